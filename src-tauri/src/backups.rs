@@ -0,0 +1,425 @@
+//! Database backups. Copies are taken with SQLite's online backup API into a
+//! `backups` folder next to the database, either on demand or automatically
+//! (on startup, on a schedule, and before migrations, restores and repairs).
+//! Startup and scheduled copies rotate according to the retention setting;
+//! manual copies and the safety copies taken before migrations, restores and
+//! repairs are kept until deleted.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, UNIX_EPOCH};
+
+use rusqlite::backup::Backup;
+use rusqlite::{Connection, DatabaseName, OpenFlags, OptionalExtension};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "app")]
+use tauri::State;
+
+use crate::migrations::{migrate_database, CURRENT_SCHEMA_VERSION};
+use crate::{chrono_now, get_setting, set_setting, Db};
+
+const BACKUP_DIR: &str = "backups";
+const FILE_PREFIX: &str = "benchmaker-";
+const FILE_EXTENSION: &str = "sqlite";
+
+const RETENTION_SETTING: &str = "backup_retention_count";
+const INTERVAL_SETTING: &str = "backup_interval_hours";
+const DEFAULT_RETENTION_COUNT: i64 = 10;
+const DEFAULT_INTERVAL_HOURS: i64 = 24;
+const HOUR_MS: i64 = 60 * 60 * 1000;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum BackupKind {
+    Manual,
+    Startup,
+    Scheduled,
+    PreMigration,
+    PreRestore,
+    PreRepair,
+}
+
+impl BackupKind {
+    const ALL: [BackupKind; 6] = [
+        BackupKind::Manual,
+        BackupKind::Startup,
+        BackupKind::Scheduled,
+        BackupKind::PreMigration,
+        BackupKind::PreRestore,
+        BackupKind::PreRepair,
+    ];
+
+    fn slug(self) -> &'static str {
+        match self {
+            BackupKind::Manual => "manual",
+            BackupKind::Startup => "startup",
+            BackupKind::Scheduled => "scheduled",
+            BackupKind::PreMigration => "pre-migration",
+            BackupKind::PreRestore => "pre-restore",
+            BackupKind::PreRepair => "pre-repair",
+        }
+    }
+
+    /// Routine copies that rotate. Startup copies are taken on every launch
+    /// and would pile up otherwise; safety copies are what an upgrade or
+    /// restore is rolled back with, so they never rotate out.
+    fn is_rotated(self) -> bool {
+        matches!(self, BackupKind::Startup | BackupKind::Scheduled)
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupInfo {
+    pub file_name: String,
+    pub path: String,
+    pub kind: BackupKind,
+    pub size_bytes: u64,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupSettings {
+    /// Number of startup and scheduled backups to keep; 0 keeps them all.
+    pub retention_count: i64,
+    /// Hours between scheduled backups; 0 disables the schedule.
+    pub interval_hours: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupListing {
+    pub backups: Vec<BackupInfo>,
+    pub settings: BackupSettings,
+}
+
+// The settings table only exists from schema v8, and pre-migration backups
+// run before that, so a missing table falls back to the defaults.
+fn setting_or(conn: &Connection, key: &str, default: i64) -> i64 {
+    get_setting(conn, key)
+        .ok()
+        .flatten()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
+
+pub fn backup_settings(conn: &Connection) -> BackupSettings {
+    BackupSettings {
+        retention_count: setting_or(conn, RETENTION_SETTING, DEFAULT_RETENTION_COUNT),
+        interval_hours: setting_or(conn, INTERVAL_SETTING, DEFAULT_INTERVAL_HOURS),
+    }
+}
+
+/// The `backups` folder next to the database file, or `None` for in-memory
+/// databases.
+fn backup_dir(conn: &Connection) -> Option<PathBuf> {
+    let path = conn.path().filter(|path| !path.is_empty())?;
+    Some(Path::new(path).parent()?.join(BACKUP_DIR))
+}
+
+fn kind_from_file_name(file_name: &str) -> Option<BackupKind> {
+    let stem = file_name
+        .strip_prefix(FILE_PREFIX)?
+        .strip_suffix(&format!(".{}", FILE_EXTENSION))?;
+    BackupKind::ALL
+        .into_iter()
+        .find(|kind| stem.ends_with(&format!("-{}", kind.slug())))
+}
+
+/// Resolves a file name from `list_backups` to a path inside the backup
+/// folder, rejecting anything that could point elsewhere.
+fn resolve_backup(conn: &Connection, file_name: &str) -> Result<PathBuf, String> {
+    if file_name.contains(['/', '\\']) || file_name.contains("..") || kind_from_file_name(file_name).is_none() {
+        return Err(format!("'{}' is not a Benchmaker backup.", file_name));
+    }
+
+    let path = backup_dir(conn)
+        .ok_or_else(|| "Backups are unavailable for in-memory databases.".to_string())?
+        .join(file_name);
+
+    if !path.is_file() {
+        return Err(format!("Backup '{}' was not found.", file_name));
+    }
+
+    Ok(path)
+}
+
+pub fn list_backups_internal(conn: &Connection) -> Result<Vec<BackupInfo>, String> {
+    let dir = match backup_dir(conn) {
+        Some(dir) if dir.is_dir() => dir,
+        _ => return Ok(Vec::new()),
+    };
+
+    let mut backups = Vec::new();
+    for entry in fs::read_dir(&dir).map_err(|err| err.to_string())? {
+        let entry = entry.map_err(|err| err.to_string())?;
+        let file_name = entry.file_name().to_string_lossy().to_string();
+        let Some(kind) = kind_from_file_name(&file_name) else {
+            continue;
+        };
+
+        let metadata = entry.metadata().map_err(|err| err.to_string())?;
+        let created_at = metadata
+            .modified()
+            .ok()
+            .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+            .map(|duration| duration.as_millis() as i64)
+            .unwrap_or(0);
+
+        backups.push(BackupInfo {
+            file_name,
+            path: entry.path().to_string_lossy().to_string(),
+            kind,
+            size_bytes: metadata.len(),
+            created_at,
+        });
+    }
+
+    // Newest first; names embed the timestamp so they break ties
+    backups.sort_by(|a, b| {
+        b.created_at
+            .cmp(&a.created_at)
+            .then_with(|| b.file_name.cmp(&a.file_name))
+    });
+
+    Ok(backups)
+}
+
+/// Copies the live database into a new timestamped file. Returns `None` for
+/// in-memory databases, which have nothing on disk to protect.
+pub fn create_backup_internal(conn: &Connection, kind: BackupKind) -> Result<Option<BackupInfo>, String> {
+    let Some(dir) = backup_dir(conn) else {
+        return Ok(None);
+    };
+    fs::create_dir_all(&dir).map_err(|err| err.to_string())?;
+
+    let file_name = format!(
+        "{}{}-{}.{}",
+        FILE_PREFIX,
+        chrono::Utc::now().format("%Y%m%d-%H%M%S-%3f"),
+        kind.slug(),
+        FILE_EXTENSION
+    );
+    let path = dir.join(&file_name);
+
+    let write_copy = || -> rusqlite::Result<()> {
+        let mut dst = Connection::open(&path)?;
+        Backup::new(conn, &mut dst)?.run_to_completion(256, Duration::from_millis(5), None)?;
+        // Backups are standalone files; don't leave -wal/-shm siblings behind
+        dst.pragma_update(None, "journal_mode", "DELETE")?;
+        Ok(())
+    };
+
+    if let Err(err) = write_copy() {
+        let _ = fs::remove_file(&path);
+        return Err(format!("Failed to write backup: {}", err));
+    }
+
+    if kind.is_rotated() {
+        prune_backups(conn)?;
+    }
+
+    let size_bytes = fs::metadata(&path).map(|metadata| metadata.len()).unwrap_or(0);
+    Ok(Some(BackupInfo {
+        file_name,
+        path: path.to_string_lossy().to_string(),
+        kind,
+        size_bytes,
+        created_at: chrono_now(),
+    }))
+}
+
+/// Deletes the oldest startup and scheduled backups beyond the retention
+/// count.
+pub fn prune_backups(conn: &Connection) -> Result<usize, String> {
+    let retention_count = backup_settings(conn).retention_count;
+    if retention_count <= 0 {
+        return Ok(0);
+    }
+
+    let expired: Vec<BackupInfo> = list_backups_internal(conn)?
+        .into_iter()
+        .filter(|backup| backup.kind.is_rotated())
+        .skip(retention_count as usize)
+        .collect();
+
+    for backup in &expired {
+        fs::remove_file(&backup.path).map_err(|err| err.to_string())?;
+    }
+
+    Ok(expired.len())
+}
+
+/// Takes a scheduled backup when the newest routine one is older than the
+/// configured interval.
+pub fn backup_if_due(conn: &Connection) -> Result<Option<BackupInfo>, String> {
+    let interval_hours = backup_settings(conn).interval_hours;
+    if interval_hours <= 0 {
+        return Ok(None);
+    }
+
+    let latest = list_backups_internal(conn)?
+        .into_iter()
+        .find(|backup| backup.kind.is_rotated())
+        .map(|backup| backup.created_at);
+
+    match latest {
+        Some(created_at) if chrono_now() - created_at < interval_hours * HOUR_MS => Ok(None),
+        _ => create_backup_internal(conn, BackupKind::Scheduled),
+    }
+}
+
+/// Checks the schedule once an hour for as long as the app runs. Backups go
+/// through their own read-only connection, so copying a large database
+/// doesn't hold up the commands sharing `Db`.
+pub fn run_schedule(db_path: PathBuf) {
+    loop {
+        std::thread::sleep(Duration::from_secs(60 * 60));
+
+        let result = Connection::open_with_flags(&db_path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .and_then(|conn| conn.busy_timeout(Duration::from_secs(5)).map(|_| conn))
+            .map_err(|err| err.to_string())
+            .and_then(|conn| backup_if_due(&conn));
+        if let Err(err) = result {
+            eprintln!("Scheduled backup failed: {}", err);
+        }
+    }
+}
+
+/// Makes sure a backup is intact and not from a newer build before anything
+/// is overwritten with it.
+fn validate_backup(path: &Path) -> Result<(), String> {
+    // Read-write because FTS5 integrity checks need a writable handle
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_WRITE)
+        .map_err(|err| format!("Unable to open backup: {}", err))?;
+
+    let check: String = conn
+        .query_row("PRAGMA quick_check", [], |row| row.get(0))
+        .map_err(|err| format!("Unable to read backup: {}", err))?;
+    if check != "ok" {
+        return Err(format!("Backup failed its integrity check: {}", check));
+    }
+
+    let version: i64 = conn
+        .query_row("SELECT version FROM schema_version WHERE id = 1", [], |row| row.get(0))
+        .optional()
+        .map_err(|err| format!("Backup is not a Benchmaker database: {}", err))?
+        .unwrap_or(0);
+    if version > CURRENT_SCHEMA_VERSION {
+        return Err(format!(
+            "Backup schema version {} is newer than this build supports ({}). Please update Benchmaker.",
+            version, CURRENT_SCHEMA_VERSION
+        ));
+    }
+
+    Ok(())
+}
+
+/// Replaces the live database with a backup, taking a safety copy first.
+/// Older backups are migrated forward once restored.
+pub fn restore_backup_internal(conn: &mut Connection, file_name: &str) -> Result<Option<BackupInfo>, String> {
+    let path = resolve_backup(conn, file_name)?;
+    validate_backup(&path)?;
+
+    let safety_copy = create_backup_internal(conn, BackupKind::PreRestore)?;
+
+    conn.restore(DatabaseName::Main, &path, None::<fn(rusqlite::backup::Progress)>)
+        .map_err(|err| format!("Failed to restore backup: {}", err))?;
+    migrate_database(conn)?;
+
+    Ok(safety_copy)
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[cfg(feature = "app")]
+#[tauri::command]
+pub fn create_backup(db: State<'_, Db>) -> Result<Option<BackupInfo>, String> {
+    let conn = db.conn()?;
+    create_backup_internal(&conn, BackupKind::Manual)
+}
+
+#[cfg(feature = "app")]
+#[tauri::command]
+pub fn list_backups(db: State<'_, Db>) -> Result<BackupListing, String> {
+    let conn = db.conn()?;
+    Ok(BackupListing {
+        backups: list_backups_internal(&conn)?,
+        settings: backup_settings(&conn),
+    })
+}
+
+/// Returns the safety copy taken before the restore.
+#[cfg(feature = "app")]
+#[tauri::command]
+pub fn restore_backup(db: State<'_, Db>, file_name: String) -> Result<Option<BackupInfo>, String> {
+    let mut conn = db.conn()?;
+    restore_backup_internal(&mut conn, &file_name)
+}
+
+#[cfg(feature = "app")]
+#[tauri::command]
+pub fn delete_backup(db: State<'_, Db>, file_name: String) -> Result<(), String> {
+    let conn = db.conn()?;
+    let path = resolve_backup(&conn, &file_name)?;
+    fs::remove_file(path).map_err(|err| err.to_string())
+}
+
+#[cfg(feature = "app")]
+#[tauri::command]
+pub fn set_backup_settings(db: State<'_, Db>, settings: BackupSettings) -> Result<(), String> {
+    if settings.retention_count < 0 || settings.interval_hours < 0 {
+        return Err("Backup retention and interval must be zero or positive.".to_string());
+    }
+
+    let conn = db.conn()?;
+    set_setting(&conn, RETENTION_SETTING, &settings.retention_count.to_string())?;
+    set_setting(&conn, INTERVAL_SETTING, &settings.interval_hours.to_string())?;
+    prune_backups(&conn)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::open_connection;
+
+    fn kinds(conn: &Connection) -> Vec<BackupKind> {
+        list_backups_internal(conn).unwrap().into_iter().map(|backup| backup.kind).collect()
+    }
+
+    #[test]
+    fn rotation_keeps_manual_and_safety_copies() {
+        let dir = tempfile::tempdir().unwrap();
+        let conn = open_connection(&dir.path().join("benchmaker.sqlite")).unwrap();
+        set_setting(&conn, RETENTION_SETTING, "2").unwrap();
+
+        for kind in [BackupKind::PreMigration, BackupKind::Manual, BackupKind::PreRestore, BackupKind::PreRepair] {
+            create_backup_internal(&conn, kind).unwrap();
+        }
+        for kind in [BackupKind::Startup, BackupKind::Scheduled, BackupKind::Scheduled, BackupKind::Scheduled] {
+            // File names and times are millisecond-precise
+            std::thread::sleep(Duration::from_millis(5));
+            create_backup_internal(&conn, kind).unwrap();
+        }
+
+        let kept = kinds(&conn);
+        assert_eq!(kept.iter().filter(|kind| kind.is_rotated()).count(), 2);
+        for kind in [BackupKind::PreMigration, BackupKind::Manual, BackupKind::PreRestore, BackupKind::PreRepair] {
+            assert!(kept.contains(&kind), "{:?} was pruned", kind);
+        }
+    }
+
+    #[test]
+    fn schedule_ignores_safety_copies() {
+        let dir = tempfile::tempdir().unwrap();
+        let conn = open_connection(&dir.path().join("benchmaker.sqlite")).unwrap();
+
+        create_backup_internal(&conn, BackupKind::PreMigration).unwrap();
+        assert!(backup_if_due(&conn).unwrap().is_some());
+        assert!(backup_if_due(&conn).unwrap().is_none());
+    }
+}
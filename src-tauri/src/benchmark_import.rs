@@ -0,0 +1,729 @@
+//! Importers for published benchmark formats:
+//!
+//! - OpenAI Evals samples: JSONL lines with chat `input` messages and an
+//!   `ideal` answer (or list of acceptable answers).
+//! - lm-evaluation-harness tasks: a YAML task definition plus a local copy of
+//!   its dataset split (JSONL or CSV). Only the `{{ ... }}` subset of Jinja is
+//!   understood; tasks that rely on `{% %}` blocks or `!function` hooks are
+//!   rejected with an explanation.
+//!
+//! Both produce ordinary test cases through the dataset importer, tagged with
+//! the source benchmark's name.
+
+use std::fs;
+use std::path::Path;
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+#[cfg(feature = "app")]
+use tauri::State;
+
+use crate::dataset_import::{
+    read_rows, save_imported_cases, DatasetFormat, DatasetRows, ImportTarget, RowError, TestCaseImportReport,
+};
+use crate::suite_bundles::SCORING_METHODS;
+use crate::{generate_id, Db, TestCase, TestCaseMetadata};
+
+const CHOICE_LETTERS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenAiEvalsImportRequest {
+    pub path: String,
+    /// Eval name for tags and a new suite's name; defaults to the file name
+    pub source_name: Option<String>,
+    pub target: ImportTarget,
+    /// Overrides the default of exact-match (one ideal) or regex-match (several)
+    pub scoring_method: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LmEvalImportRequest {
+    /// The task's YAML definition
+    pub task_path: String,
+    /// Local JSONL or CSV export of the split to import
+    pub data_path: String,
+    pub target: ImportTarget,
+    /// Import at most this many documents
+    pub limit: Option<usize>,
+}
+
+fn source_tag(framework: &str, name: &str) -> String {
+    format!("{}:{}", framework, name)
+}
+
+fn file_stem(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_else(|| "dataset".to_string())
+}
+
+/// Fills in what a new suite needs from the source: its name when none was
+/// given, and a shared system prompt.
+fn prepare_target(target: &ImportTarget, source_name: &str, system_prompt: Option<&str>) -> ImportTarget {
+    match target.clone() {
+        ImportTarget::Create { name, description, system_prompt: existing } => ImportTarget::Create {
+            name: if name.trim().is_empty() { source_name.to_string() } else { name },
+            description,
+            system_prompt: if existing.is_empty() {
+                system_prompt.unwrap_or_default().to_string()
+            } else {
+                existing
+            },
+        },
+        append => append,
+    }
+}
+
+/// Escapes text for use inside a JavaScript regex, which is how the
+/// frontend evaluates regex-match patterns.
+fn escape_js_regex(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        if "\\^$.*+?()[]{}|/".contains(ch) {
+            escaped.push('\\');
+        }
+        escaped.push(ch);
+    }
+    escaped
+}
+
+fn test_case(prompt: String, expected_output: String, scoring_method: String, tag: &str) -> TestCase {
+    TestCase {
+        id: generate_id(),
+        prompt,
+        expected_output: Some(expected_output),
+        scoring_method,
+        weight: 1.0,
+        metadata: TestCaseMetadata {
+            category: None,
+            difficulty: None,
+            tags: vec![tag.to_string()],
+        },
+    }
+}
+
+// ============================================================================
+// OpenAI Evals
+// ============================================================================
+
+struct EvalSample {
+    system: Option<String>,
+    prompt: String,
+    ideals: Vec<String>,
+}
+
+fn message_text(message: &Value) -> Option<String> {
+    match message.get("content")? {
+        Value::String(text) => Some(text.clone()),
+        // Multi-part content; keep the text parts
+        Value::Array(parts) => Some(
+            parts
+                .iter()
+                .filter_map(|part| part.get("text").and_then(Value::as_str))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        ),
+        _ => None,
+    }
+}
+
+/// Speaker label for a transcript line. Few-shot examples in Evals are
+/// system messages named `example_user` / `example_assistant`.
+fn speaker(message: &Value) -> &'static str {
+    match (
+        message.get("role").and_then(Value::as_str),
+        message.get("name").and_then(Value::as_str),
+    ) {
+        (_, Some("example_user")) | (Some("user"), _) => "User",
+        (_, Some("example_assistant")) | (Some("assistant"), _) => "Assistant",
+        _ => "System",
+    }
+}
+
+fn parse_eval_sample(row: &Value) -> Result<EvalSample, String> {
+    let ideals = match row.get("ideal") {
+        Some(Value::String(ideal)) => vec![ideal.clone()],
+        Some(Value::Array(items)) => items
+            .iter()
+            .map(|item| item.as_str().map(str::to_string).unwrap_or_else(|| item.to_string()))
+            .collect(),
+        Some(Value::Null) | None => return Err("Missing 'ideal'".to_string()),
+        Some(other) => vec![other.to_string()],
+    };
+    let ideals: Vec<String> = ideals.into_iter().filter(|ideal| !ideal.trim().is_empty()).collect();
+    if ideals.is_empty() {
+        return Err("'ideal' is empty".to_string());
+    }
+
+    let messages = match row.get("input") {
+        Some(Value::String(prompt)) => {
+            return Ok(EvalSample {
+                system: None,
+                prompt: prompt.clone(),
+                ideals,
+            })
+        }
+        Some(Value::Array(messages)) if !messages.is_empty() => messages,
+        _ => return Err("'input' must be a string or a non-empty list of messages".to_string()),
+    };
+
+    // Leading plain system messages become the system prompt; everything
+    // after is flattened into a transcript ending in the final user turn.
+    let leading_system = messages
+        .iter()
+        .take_while(|message| speaker(message) == "System")
+        .count();
+    let system = messages[..leading_system]
+        .iter()
+        .filter_map(message_text)
+        .collect::<Vec<_>>()
+        .join("\n\n");
+
+    let turns = &messages[leading_system..];
+    let prompt = match turns {
+        [] => return Err("'input' has no user message".to_string()),
+        [only] => message_text(only).ok_or("Message has no text content")?,
+        _ => turns
+            .iter()
+            .map(|message| format!("{}: {}", speaker(message), message_text(message).unwrap_or_default()))
+            .collect::<Vec<_>>()
+            .join("\n\n"),
+    };
+
+    Ok(EvalSample {
+        system: (!system.is_empty()).then_some(system),
+        prompt,
+        ideals,
+    })
+}
+
+pub fn import_openai_evals_internal(conn: &Connection, request: &OpenAiEvalsImportRequest) -> Result<TestCaseImportReport, String> {
+    if let Some(method) = &request.scoring_method {
+        if !SCORING_METHODS.contains(&method.as_str()) {
+            return Err(format!("Unknown scoring method '{}'", method));
+        }
+    }
+
+    let path = Path::new(&request.path);
+    let source_name = request.source_name.clone().unwrap_or_else(|| file_stem(path));
+    let tag = source_tag("openai-evals", &source_name);
+    let DatasetRows { rows, mut errors } = read_rows(path, DatasetFormat::Jsonl)?;
+
+    let mut samples = Vec::new();
+    for (row_number, row) in rows {
+        match parse_eval_sample(&row) {
+            Ok(sample) => samples.push(sample),
+            Err(message) => errors.push(RowError { row: row_number, message }),
+        }
+    }
+
+    // A system prompt shared by every sample moves to the suite when one is
+    // being created; otherwise it stays with each prompt.
+    let shared_system = samples.first().and_then(|first| first.system.clone()).filter(|system| {
+        samples.iter().all(|sample| sample.system.as_deref() == Some(system.as_str()))
+    });
+    let creating = matches!(request.target, ImportTarget::Create { .. });
+    let hoist_system = creating && shared_system.is_some();
+
+    let cases = samples
+        .into_iter()
+        .map(|sample| {
+            let prompt = match (&sample.system, hoist_system) {
+                (Some(system), false) => format!("{}\n\n{}", system, sample.prompt),
+                _ => sample.prompt,
+            };
+            let (expected_output, default_method) = match sample.ideals.as_slice() {
+                [ideal] => (ideal.clone(), "exact-match"),
+                ideals => (
+                    format!(
+                        "/^\\s*(?:{})\\s*$/i",
+                        ideals.iter().map(|ideal| escape_js_regex(ideal.trim())).collect::<Vec<_>>().join("|")
+                    ),
+                    "regex-match",
+                ),
+            };
+            let scoring_method = request.scoring_method.clone().unwrap_or_else(|| default_method.to_string());
+            test_case(prompt, expected_output, scoring_method, &tag)
+        })
+        .collect();
+
+    errors.sort_by_key(|error| error.row);
+    let target = prepare_target(&request.target, &source_name, shared_system.as_deref().filter(|_| hoist_system));
+    save_imported_cases(conn, &target, cases, errors)
+}
+
+// ============================================================================
+// lm-evaluation-harness
+// ============================================================================
+
+#[derive(Debug, PartialEq, Eq)]
+enum OutputType {
+    MultipleChoice,
+    GenerateUntil,
+}
+
+struct LmEvalTask {
+    name: String,
+    description: Option<String>,
+    output_type: OutputType,
+    doc_to_text: String,
+    doc_to_target: serde_yaml::Value,
+    doc_to_choice: Option<serde_yaml::Value>,
+}
+
+fn yaml_string(task: &serde_yaml::Mapping, key: &str) -> Option<String> {
+    task.get(key).and_then(|value| value.as_str()).map(str::to_string)
+}
+
+fn parse_lm_eval_task(contents: &str, fallback_name: &str) -> Result<LmEvalTask, String> {
+    let task: serde_yaml::Mapping = serde_yaml::from_str(contents).map_err(|err| format!("Invalid task YAML: {}", err))?;
+
+    if let Some(include) = yaml_string(&task, "include") {
+        return Err(format!(
+            "The task inherits from '{}'; merge it into a single YAML file before importing.",
+            include
+        ));
+    }
+    for (key, value) in &task {
+        if let serde_yaml::Value::Tagged(tagged) = value {
+            return Err(format!(
+                "'{}' uses a {} hook, which needs the Python harness and can't be imported.",
+                key.as_str().unwrap_or("?"),
+                tagged.tag
+            ));
+        }
+    }
+
+    let output_type = match yaml_string(&task, "output_type").as_deref() {
+        Some("multiple_choice") => OutputType::MultipleChoice,
+        Some("generate_until") | None => OutputType::GenerateUntil,
+        Some(other) => {
+            return Err(format!(
+                "Output type '{}' scores log-likelihoods, which chat models don't expose.",
+                other
+            ))
+        }
+    };
+
+    let doc_to_text = yaml_string(&task, "doc_to_text").ok_or("The task has no doc_to_text")?;
+    let doc_to_target = task.get("doc_to_target").cloned().ok_or("The task has no doc_to_target")?;
+    let doc_to_choice = task.get("doc_to_choice").cloned();
+    if output_type == OutputType::MultipleChoice && doc_to_choice.is_none() {
+        return Err("Multiple-choice tasks need doc_to_choice.".to_string());
+    }
+
+    Ok(LmEvalTask {
+        name: yaml_string(&task, "task").unwrap_or_else(|| fallback_name.to_string()),
+        description: yaml_string(&task, "description").filter(|description| !description.trim().is_empty()),
+        output_type,
+        doc_to_text,
+        doc_to_target,
+        doc_to_choice,
+    })
+}
+
+/// Evaluates a Jinja expression limited to paths (`a.b`, `a[0]`, `a['b']`),
+/// the `strip`/`lower`/`upper` methods and the `trim`/`lower`/`upper` filters.
+fn eval_expression(doc: &Value, expression: &str) -> Result<Value, String> {
+    let mut parts = expression.split('|');
+    let mut path = parts.next().unwrap_or_default().trim().to_string();
+    let mut transforms: Vec<String> = Vec::new();
+
+    for method in ["strip", "lower", "upper"] {
+        let call = format!(".{}()", method);
+        while let Some(stripped) = path.strip_suffix(&call) {
+            transforms.insert(0, method.to_string());
+            path = stripped.to_string();
+        }
+    }
+    for filter in parts {
+        match filter.trim() {
+            "trim" => transforms.push("strip".to_string()),
+            "lower" | "upper" => transforms.push(filter.trim().to_string()),
+            other => return Err(format!("Unsupported template filter '{}'", other)),
+        }
+    }
+
+    let mut value = doc;
+    let mut rest = path.as_str();
+    while !rest.is_empty() {
+        if let Some(after) = rest.strip_prefix('.') {
+            rest = after;
+        } else if let Some(after) = rest.strip_prefix('[') {
+            let end = after.find(']').ok_or_else(|| format!("Unclosed '[' in '{}'", expression))?;
+            let key = after[..end].trim().trim_matches(['\'', '"']);
+            value = match value {
+                Value::Array(items) => key.parse::<usize>().ok().and_then(|index| items.get(index)),
+                _ => value.get(key),
+            }
+            .ok_or_else(|| format!("'{}' not found in document", expression.trim()))?;
+            rest = &after[end + 1..];
+        } else {
+            let end = rest.find(['.', '[']).unwrap_or(rest.len());
+            let field = &rest[..end];
+            if !field.chars().all(|ch| ch.is_alphanumeric() || ch == '_') {
+                return Err(format!("Unsupported template expression '{}'", expression.trim()));
+            }
+            value = value.get(field).ok_or_else(|| format!("Field '{}' not found in document", field))?;
+            rest = &rest[end..];
+        }
+    }
+
+    let mut value = value.clone();
+    for transform in transforms {
+        if let Value::String(text) = &value {
+            value = Value::String(match transform.as_str() {
+                "strip" => text.trim().to_string(),
+                "lower" => text.to_lowercase(),
+                _ => text.to_uppercase(),
+            });
+        }
+    }
+    Ok(value)
+}
+
+fn value_text(value: &Value) -> String {
+    match value {
+        Value::String(text) => text.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Renders a template, or reads a plain field when `template` names one.
+fn render(doc: &Value, template: &str) -> Result<String, String> {
+    if !template.contains("{{") {
+        return Ok(match doc.get(template) {
+            Some(value) => value_text(value),
+            None => template.to_string(),
+        });
+    }
+    if template.contains("{%") {
+        return Err("Jinja control blocks ({% ... %}) are not supported".to_string());
+    }
+
+    let mut output = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+        let end = rest[start..].find("}}").ok_or("Unclosed '{{' in template")? + start;
+        output.push_str(&value_text(&eval_expression(doc, &rest[start + 2..end])?));
+        rest = &rest[end + 2..];
+    }
+    output.push_str(rest);
+    Ok(output)
+}
+
+/// Resolves `doc_to_target`/`doc_to_choice`: literal values pass through,
+/// strings name a field or are a template. A template that is a single
+/// expression keeps its JSON type so lists and indices survive.
+fn resolve(doc: &Value, spec: &serde_yaml::Value) -> Result<Value, String> {
+    match spec {
+        serde_yaml::Value::String(template) => {
+            let trimmed = template.trim();
+            if let Some(expression) = trimmed.strip_prefix("{{").and_then(|rest| rest.strip_suffix("}}")) {
+                if !expression.contains("{{") {
+                    return eval_expression(doc, expression);
+                }
+            }
+            if !trimmed.contains("{{") {
+                if let Some(value) = doc.get(trimmed) {
+                    return Ok(value.clone());
+                }
+            }
+            render(doc, template).map(Value::String)
+        }
+        other => serde_json::to_value(other).map_err(|err| err.to_string()),
+    }
+}
+
+fn choice_index(target: &Value, choices: &[String]) -> Result<usize, String> {
+    let index = match target {
+        Value::Number(number) => number.as_u64().map(|index| index as usize),
+        // Same rule as the harness: digit strings are indices
+        Value::String(text) if !text.is_empty() && text.trim().chars().all(|ch| ch.is_ascii_digit()) => text.trim().parse().ok(),
+        Value::String(text) => choices
+            .iter()
+            .position(|choice| choice.trim() == text.trim())
+            .or_else(|| {
+                let letter = text.trim();
+                (letter.len() == 1).then(|| CHOICE_LETTERS.find(&letter.to_ascii_uppercase())).flatten()
+            }),
+        _ => None,
+    };
+
+    index
+        .filter(|index| *index < choices.len())
+        .ok_or_else(|| format!("Target {} doesn't match any of the {} choices", target, choices.len()))
+}
+
+fn lm_eval_case(task: &LmEvalTask, doc: &Value, tag: &str) -> Result<TestCase, String> {
+    let text = render(doc, &task.doc_to_text)?.trim_end().to_string();
+    let target = resolve(doc, &task.doc_to_target)?;
+
+    match task.output_type {
+        OutputType::GenerateUntil => {
+            let expected = value_text(&target).trim().to_string();
+            if expected.is_empty() {
+                return Err("Target is empty".to_string());
+            }
+            let scoring_method = if expected.parse::<f64>().is_ok() { "numeric-tolerance" } else { "boolean" };
+            Ok(test_case(text, expected, scoring_method.to_string(), tag))
+        }
+        OutputType::MultipleChoice => {
+            let spec = task.doc_to_choice.as_ref().ok_or("Multiple-choice tasks need doc_to_choice")?;
+            let choices: Vec<String> = match resolve(doc, spec)? {
+                Value::Array(items) => items.iter().map(value_text).collect(),
+                other => return Err(format!("doc_to_choice produced {} instead of a list", other)),
+            };
+            if choices.len() < 2 || choices.len() > CHOICE_LETTERS.len() {
+                return Err(format!("Expected 2-{} choices, got {}", CHOICE_LETTERS.len(), choices.len()));
+            }
+            let answer = &CHOICE_LETTERS[choice_index(&target, &choices)?..][..1];
+
+            // Harness prompts end in a completion cue ("Answer:") that reads
+            // oddly before the chat instruction, so it is dropped.
+            let text = text
+                .strip_suffix("Answer:")
+                .map(|text| text.trim_end().to_string())
+                .unwrap_or(text);
+
+            // Tasks like MMLU already list lettered options in the text and
+            // use the letters as choices; others get their options listed.
+            let lettered = choices
+                .iter()
+                .zip(CHOICE_LETTERS.chars())
+                .all(|(choice, letter)| choice.trim().len() == 1 && choice.trim().starts_with(letter));
+            let prompt = if lettered {
+                format!("{}\n\nAnswer with the letter of the correct choice only.", text)
+            } else {
+                let options = choices
+                    .iter()
+                    .zip(CHOICE_LETTERS.chars())
+                    .map(|(choice, letter)| format!("{}. {}", letter, choice.trim()))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                format!("{}\n\n{}\n\nAnswer with the letter of the correct choice only.", text, options)
+            };
+
+            Ok(test_case(prompt, format!("/^\\W*{}\\b/i", answer), "regex-match".to_string(), tag))
+        }
+    }
+}
+
+pub fn import_lm_eval_task_internal(conn: &Connection, request: &LmEvalImportRequest) -> Result<TestCaseImportReport, String> {
+    let task_path = Path::new(&request.task_path);
+    let contents = fs::read_to_string(task_path).map_err(|err| format!("Failed to read {}: {}", task_path.display(), err))?;
+    let task = parse_lm_eval_task(&contents, &file_stem(task_path))?;
+    let tag = source_tag("lm-eval", &task.name);
+
+    let data_path = Path::new(&request.data_path);
+    let format = match data_path.extension().and_then(|ext| ext.to_str()) {
+        Some("csv") => DatasetFormat::Csv,
+        _ => DatasetFormat::Jsonl,
+    };
+    let DatasetRows { mut rows, mut errors } = read_rows(data_path, format)?;
+    if let Some(limit) = request.limit {
+        rows.truncate(limit);
+    }
+
+    let mut cases = Vec::new();
+    for (row_number, doc) in rows {
+        match lm_eval_case(&task, &doc, &tag) {
+            Ok(test_case) => cases.push(test_case),
+            Err(message) => errors.push(RowError { row: row_number, message }),
+        }
+    }
+
+    errors.sort_by_key(|error| error.row);
+    let target = prepare_target(&request.target, &task.name, task.description.as_deref());
+    save_imported_cases(conn, &target, cases, errors)
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[cfg(feature = "app")]
+#[tauri::command]
+pub fn import_openai_evals(db: State<'_, Db>, request: OpenAiEvalsImportRequest) -> Result<TestCaseImportReport, String> {
+    let mut conn = db.conn()?;
+    let tx = conn.transaction().map_err(|err| err.to_string())?;
+    let report = import_openai_evals_internal(&tx, &request)?;
+    tx.commit().map_err(|err| err.to_string())?;
+    Ok(report)
+}
+
+#[cfg(feature = "app")]
+#[tauri::command]
+pub fn import_lm_eval_task(db: State<'_, Db>, request: LmEvalImportRequest) -> Result<TestCaseImportReport, String> {
+    let mut conn = db.conn()?;
+    let tx = conn.transaction().map_err(|err| err.to_string())?;
+    let report = import_lm_eval_task_internal(&tx, &request)?;
+    tx.commit().map_err(|err| err.to_string())?;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn task(yaml: &str) -> LmEvalTask {
+        parse_lm_eval_task(yaml, "fallback").unwrap()
+    }
+
+    #[test]
+    fn templates_support_paths_methods_and_filters() {
+        let doc = json!({ "question": "  Why? ", "choices": { "text": ["sky", "sea"] } });
+
+        let rendered = render(&doc, "Q: {{ question.strip() }} {{choices.text[1]|upper}} {{ choices['text'][0] }}").unwrap();
+        assert_eq!(rendered, "Q: Why? SEA sky");
+        // A bare field name reads the field
+        assert_eq!(render(&doc, "question").unwrap(), "  Why? ");
+
+        assert!(render(&doc, "{% if question %}{{ question }}{% endif %}").is_err());
+        assert!(render(&doc, "{{ question|title }}").is_err());
+        assert!(render(&doc, "{{ missing }}").is_err());
+    }
+
+    #[test]
+    fn single_expression_targets_keep_their_type() {
+        let doc = json!({ "label": 2, "options": ["a", "b", "c"] });
+
+        assert_eq!(resolve(&doc, &serde_yaml::Value::from("{{label}}")).unwrap(), json!(2));
+        assert_eq!(resolve(&doc, &serde_yaml::Value::from("options")).unwrap(), json!(["a", "b", "c"]));
+        assert_eq!(resolve(&doc, &serde_yaml::Value::from(1)).unwrap(), json!(1));
+        assert_eq!(resolve(&doc, &serde_yaml::Value::from("Label {{label}}")).unwrap(), json!("Label 2"));
+    }
+
+    #[test]
+    fn targets_map_to_choice_indices() {
+        let choices = vec!["sky".to_string(), "sea".to_string(), "land".to_string()];
+
+        assert_eq!(choice_index(&json!(1), &choices).unwrap(), 1);
+        assert_eq!(choice_index(&json!("2"), &choices).unwrap(), 2);
+        assert_eq!(choice_index(&json!(" sea "), &choices).unwrap(), 1);
+        assert_eq!(choice_index(&json!("c"), &choices).unwrap(), 2);
+        assert!(choice_index(&json!(3), &choices).is_err());
+        assert!(choice_index(&json!("river"), &choices).is_err());
+    }
+
+    #[test]
+    fn multiple_choice_prompts_list_options_unless_lettered() {
+        let listed = task(
+            "task: colors
+output_type: multiple_choice
+doc_to_text: \"Question: {{question}}\\nAnswer:\"
+doc_to_choice: \"{{choices.text}}\"
+doc_to_target: label",
+        );
+        let doc = json!({ "question": "What is blue?", "choices": { "text": ["sky", "grass"] }, "label": 0 });
+        let case = lm_eval_case(&listed, &doc, "lm-eval:colors").unwrap();
+        assert_eq!(
+            case.prompt,
+            "Question: What is blue?\n\nA. sky\nB. grass\n\nAnswer with the letter of the correct choice only."
+        );
+        assert_eq!(case.expected_output.as_deref(), Some("/^\\W*A\\b/i"));
+        assert_eq!(case.scoring_method, "regex-match");
+        assert_eq!(case.metadata.tags, ["lm-eval:colors"]);
+
+        let lettered = task(
+            "output_type: multiple_choice
+doc_to_text: \"{{question}}\\nA. sky\\nB. grass\\nAnswer:\"
+doc_to_choice: [\"A\", \"B\"]
+doc_to_target: answer",
+        );
+        assert_eq!(lettered.name, "fallback");
+        let doc = json!({ "question": "What is green?", "answer": "B" });
+        let case = lm_eval_case(&lettered, &doc, "lm-eval:fallback").unwrap();
+        assert_eq!(
+            case.prompt,
+            "What is green?\nA. sky\nB. grass\n\nAnswer with the letter of the correct choice only."
+        );
+        assert_eq!(case.expected_output.as_deref(), Some("/^\\W*B\\b/i"));
+    }
+
+    #[test]
+    fn generated_targets_pick_a_scoring_method() {
+        let math = task("doc_to_text: question\ndoc_to_target: \"{{answer}}\"");
+        let case = lm_eval_case(&math, &json!({ "question": "2 + 2?", "answer": 4 }), "t").unwrap();
+        assert_eq!(case.expected_output.as_deref(), Some("4"));
+        assert_eq!(case.scoring_method, "numeric-tolerance");
+
+        let case = lm_eval_case(&math, &json!({ "question": "Capital of France?", "answer": "Paris" }), "t").unwrap();
+        assert_eq!(case.scoring_method, "boolean");
+        assert!(lm_eval_case(&math, &json!({ "question": "?", "answer": " " }), "t").is_err());
+    }
+
+    #[test]
+    fn new_suites_take_the_source_name_and_system_prompt() {
+        let create = ImportTarget::Create {
+            name: " ".to_string(),
+            description: None,
+            system_prompt: String::new(),
+        };
+        match prepare_target(&create, "colors", Some("Answer briefly.")) {
+            ImportTarget::Create { name, system_prompt, .. } => {
+                assert_eq!(name, "colors");
+                assert_eq!(system_prompt, "Answer briefly.");
+            }
+            other => panic!("unexpected target {:?}", other),
+        }
+
+        let named = ImportTarget::Create {
+            name: "Mine".to_string(),
+            description: None,
+            system_prompt: "Keep it.".to_string(),
+        };
+        match prepare_target(&named, "colors", Some("Answer briefly.")) {
+            ImportTarget::Create { name, system_prompt, .. } => {
+                assert_eq!(name, "Mine");
+                assert_eq!(system_prompt, "Keep it.");
+            }
+            other => panic!("unexpected target {:?}", other),
+        }
+
+        let append = ImportTarget::Append { test_suite_id: "s1".to_string() };
+        assert!(matches!(
+            prepare_target(&append, "colors", Some("Answer briefly.")),
+            ImportTarget::Append { test_suite_id } if test_suite_id == "s1"
+        ));
+    }
+
+    #[test]
+    fn unsupported_tasks_are_rejected() {
+        for yaml in [
+            "include: _default.yaml\ndoc_to_text: q\ndoc_to_target: a",
+            "doc_to_text: !function utils.doc_to_text\ndoc_to_target: a",
+            "output_type: loglikelihood\ndoc_to_text: q\ndoc_to_target: a",
+            "output_type: multiple_choice\ndoc_to_text: q\ndoc_to_target: a",
+            "doc_to_target: a",
+        ] {
+            assert!(parse_lm_eval_task(yaml, "t").is_err(), "{}", yaml);
+        }
+    }
+
+    #[test]
+    fn eval_samples_split_system_prompt_and_transcript() {
+        let sample = parse_eval_sample(&json!({
+            "input": [
+                { "role": "system", "content": "Answer in one word." },
+                { "role": "system", "name": "example_user", "content": "Sky color?" },
+                { "role": "system", "name": "example_assistant", "content": "Blue" },
+                { "role": "user", "content": "Grass color?" }
+            ],
+            "ideal": ["Green", " "]
+        }))
+        .unwrap();
+
+        assert_eq!(sample.system.as_deref(), Some("Answer in one word."));
+        assert_eq!(sample.prompt, "User: Sky color?\n\nAssistant: Blue\n\nUser: Grass color?");
+        assert_eq!(sample.ideals, ["Green"]);
+
+        assert!(parse_eval_sample(&json!({ "input": "Hi" })).is_err());
+        assert!(parse_eval_sample(&json!({ "input": [], "ideal": "x" })).is_err());
+    }
+}
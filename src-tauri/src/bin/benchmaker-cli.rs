@@ -0,0 +1,863 @@
+//! Headless entry point for terminals and CI jobs. Works on the same SQLite
+//! database as the app (or any file passed with `--db`) through the shared
+//! library crate, so suites, runs and reports stay interchangeable.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::atomic::AtomicBool;
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use rusqlite::Connection;
+
+use benchmaker_lib::cassette::{Cassette, CassetteMode};
+use benchmaker_lib::execution::{execute_run, RunEvent, RunRequest};
+use benchmaker_lib::providers::{delete_provider_config, get_provider_configs, save_provider_config, ProviderConfig, ProviderKind, Providers, OPENROUTER};
+use benchmaker_lib::regression_check::{check_run_internal, get_baseline, set_baseline, write_check_outputs, CheckOptions, CheckStatus};
+use benchmaker_lib::reports::{generate_report_internal, leaderboard, ReportFormat};
+use benchmaker_lib::response_cache::{cache_settings, cache_status, clear_response_cache, save_cache_settings, ResponseCache};
+use benchmaker_lib::run_export::{export_runs_internal, ExportFormat, RunExportRequest};
+use benchmaker_lib::run_queries::{list_runs_internal, RunListQuery};
+use benchmaker_lib::scheduler::{rate_limits, save_rate_limits, RateLimit};
+use benchmaker_lib::suite_bundles::{build_bundle, import_bundle, read_bundle, write_bundle, BundleFormat, CollisionStrategy};
+use benchmaker_lib::{default_db_path, get_all_test_suites_internal, get_run_internal, open_connection, ModelParameters, RunResult, TestSuite};
+
+#[derive(Parser)]
+#[command(name = "benchmaker-cli", version, about = "Run and inspect Benchmaker benchmarks without the app")]
+struct Cli {
+    /// Database file; defaults to the one the app uses
+    #[arg(long, global = true, env = "BENCHMAKER_DB")]
+    db: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Manage test suites
+    #[command(alias = "suites")]
+    Suite {
+        #[command(subcommand)]
+        command: SuiteCommand,
+    },
+    /// Run a suite against one or more models
+    Run(RunArgs),
+    /// Browse past runs
+    Runs {
+        #[command(subcommand)]
+        command: RunsCommand,
+    },
+    /// Write an HTML or Markdown report for a run
+    Report(ReportArgs),
+    /// Export run results as CSV, JSONL or Parquet
+    Export(ExportArgs),
+    /// Run a suite (or take an existing run) and fail on score regressions
+    Check(CheckArgs),
+    /// List models a provider offers, with ids ready for --models
+    Models {
+        /// Only models whose id or name contains this text
+        search: Option<String>,
+        #[arg(long, default_value = OPENROUTER)]
+        provider: String,
+        #[arg(long, env = "OPENROUTER_API_KEY", hide_env_values = true)]
+        api_key: Option<String>,
+    },
+    /// Configure model providers
+    Providers {
+        #[command(subcommand)]
+        command: ProvidersCommand,
+    },
+    /// Configure or clear the response cache
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommand,
+    },
+    /// Configure request and token rate limits
+    Limits {
+        #[command(subcommand)]
+        command: LimitsCommand,
+    },
+}
+
+#[derive(Subcommand)]
+enum LimitsCommand {
+    /// List the configured limits
+    List,
+    /// Limit a provider, or a model with --model; options left out lift
+    /// that limit
+    Set {
+        /// Provider id, or a model id as passed to --models
+        target: String,
+        #[arg(long)]
+        model: bool,
+        /// Requests per minute
+        #[arg(long)]
+        rpm: Option<u32>,
+        /// Tokens per minute
+        #[arg(long)]
+        tpm: Option<u32>,
+    },
+    /// Remove the limits of a provider, or a model with --model
+    Remove {
+        target: String,
+        #[arg(long)]
+        model: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum CacheCommand {
+    /// Show the cache settings and size
+    Status,
+    /// Change cache settings; options left out keep their value
+    Set {
+        #[arg(long)]
+        enabled: Option<bool>,
+        /// Hours a response stays valid; 0 never expires
+        #[arg(long)]
+        ttl_hours: Option<i64>,
+        /// Size limit of the stored responses; 0 is unlimited
+        #[arg(long)]
+        max_size_mb: Option<i64>,
+    },
+    /// Remove every cached response
+    Clear,
+}
+
+#[derive(Subcommand)]
+enum ProvidersCommand {
+    /// List configured providers
+    List,
+    /// Add a provider, or replace the one with the same id
+    Add {
+        /// Namespace for model ids, as in `<id>:<model>`
+        id: String,
+        #[arg(long, value_enum)]
+        kind: ProviderKindArg,
+        /// API root, e.g. http://localhost:8000/v1 for vLLM
+        #[arg(long)]
+        base_url: Option<String>,
+        #[arg(long)]
+        api_key: Option<String>,
+        /// Fixture file for the mock kind (JSON or YAML)
+        #[arg(long)]
+        fixture: Option<String>,
+    },
+    /// Remove a provider; built-in ones return to their defaults
+    Remove { id: String },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ProviderKindArg {
+    Openrouter,
+    OpenaiCompatible,
+    Ollama,
+    Anthropic,
+    Mock,
+}
+
+#[derive(Subcommand)]
+enum SuiteCommand {
+    /// List test suites
+    List,
+    /// Import a suite bundle (JSON or YAML)
+    Import {
+        path: PathBuf,
+        /// What to do when the suite already exists
+        #[arg(long, value_enum, default_value_t = OnConflict::Rename)]
+        on_conflict: OnConflict,
+    },
+    /// Export a suite as a bundle; the format follows the file extension
+    Export {
+        /// Suite id or name
+        suite: String,
+        path: PathBuf,
+    },
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum OnConflict {
+    Keep,
+    Rename,
+    Overwrite,
+}
+
+#[derive(Args)]
+struct RunArgs {
+    /// Suite id or name
+    #[arg(long)]
+    suite: String,
+    /// Comma-separated model ids
+    #[arg(long, value_delimiter = ',', required = true)]
+    models: Vec<String>,
+    /// Judge model for llm-judge cases
+    #[arg(long)]
+    judge: Option<String>,
+    /// Needed for OpenRouter models
+    #[arg(long, env = "OPENROUTER_API_KEY", hide_env_values = true)]
+    api_key: Option<String>,
+    /// Sampling temperature [default: 0.7]
+    #[arg(long)]
+    temperature: Option<f64>,
+    /// Nucleus sampling [default: 1]
+    #[arg(long)]
+    top_p: Option<f64>,
+    /// Completion token limit [default: 1024]
+    #[arg(long)]
+    max_tokens: Option<i64>,
+    /// [default: 0]
+    #[arg(long)]
+    frequency_penalty: Option<f64>,
+    /// [default: 0]
+    #[arg(long)]
+    presence_penalty: Option<f64>,
+    /// Concurrent requests
+    #[arg(long, default_value_t = 5)]
+    concurrency: usize,
+    #[command(flatten)]
+    cassette: CassetteArgs,
+    /// Ignore cached responses; fresh ones still refresh the cache
+    #[arg(long)]
+    bypass_cache: bool,
+}
+
+#[derive(Args)]
+struct CassetteArgs {
+    /// Store every model call and its response in the cassette
+    #[arg(long, conflicts_with = "replay")]
+    record: bool,
+    /// Serve model calls from the cassette; fails if any is missing
+    #[arg(long)]
+    replay: bool,
+    /// With --replay, ask the judge live for grades that were not recorded
+    #[arg(long, requires = "replay")]
+    allow_live_judge: bool,
+    /// Cassette database; defaults to --db
+    #[arg(long)]
+    cassette: Option<PathBuf>,
+}
+
+#[derive(Subcommand)]
+enum RunsCommand {
+    /// List runs, newest first
+    List {
+        /// Suite id or name
+        #[arg(long)]
+        suite: Option<String>,
+        #[arg(long)]
+        model: Option<String>,
+        #[arg(long)]
+        status: Option<String>,
+        #[arg(long, default_value_t = 20)]
+        limit: i64,
+    },
+}
+
+#[derive(Args)]
+struct ReportArgs {
+    run_id: String,
+    path: PathBuf,
+    /// Defaults to the file extension
+    #[arg(long, value_enum)]
+    format: Option<ReportKind>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ReportKind {
+    Html,
+    Markdown,
+}
+
+#[derive(Args)]
+struct ExportArgs {
+    path: PathBuf,
+    /// Comma-separated run ids
+    #[arg(long, value_delimiter = ',')]
+    runs: Option<Vec<String>>,
+    /// Suite id or name
+    #[arg(long)]
+    suite: Option<String>,
+    #[arg(long)]
+    model: Option<String>,
+    #[arg(long)]
+    status: Option<String>,
+    /// Defaults to the file extension
+    #[arg(long, value_enum)]
+    format: Option<ExportKind>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ExportKind {
+    Csv,
+    Jsonl,
+    Parquet,
+}
+
+#[derive(Args)]
+struct CheckArgs {
+    /// Check an existing run instead of starting a new one
+    #[arg(long, conflicts_with_all = ["models", "judge"])]
+    run: Option<String>,
+    /// Suite id or name; defaults to the baseline's suite
+    #[arg(long)]
+    suite: Option<String>,
+    /// Baseline run id; defaults to the suite's stored baseline
+    #[arg(long)]
+    baseline: Option<String>,
+    /// Comma-separated model ids; defaults to the baseline's models
+    #[arg(long, value_delimiter = ',')]
+    models: Option<Vec<String>>,
+    /// Judge model; defaults to the baseline's judge
+    #[arg(long)]
+    judge: Option<String>,
+    #[arg(long, env = "OPENROUTER_API_KEY", hide_env_values = true)]
+    api_key: Option<String>,
+    /// Concurrent requests
+    #[arg(long, default_value_t = 5)]
+    concurrency: usize,
+    #[command(flatten)]
+    cassette: CassetteArgs,
+    /// Ignore cached responses; fresh ones still refresh the cache
+    #[arg(long)]
+    bypass_cache: bool,
+    /// Allowed drop of a model's aggregate score (0-1, 0.02 = 2 points)
+    #[arg(long, default_value_t = CheckOptions::default().tolerance)]
+    tolerance: f64,
+    /// Allowed drop of a single case score (0-1)
+    #[arg(long, default_value_t = CheckOptions::default().case_tolerance)]
+    case_tolerance: f64,
+    /// Minimum aggregate score per model (0-1)
+    #[arg(long)]
+    min_score: Option<f64>,
+    /// Minimum score per case (0-1)
+    #[arg(long)]
+    min_case_score: Option<f64>,
+    /// Write a JUnit XML report here
+    #[arg(long)]
+    junit: Option<PathBuf>,
+    /// Write a Markdown summary here
+    #[arg(long)]
+    summary: Option<PathBuf>,
+    /// Store the checked run as the suite's baseline if the check passes
+    #[arg(long)]
+    update_baseline: bool,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(cli: Cli) -> Result<(), String> {
+    let db_path = match cli.db {
+        Some(path) => path,
+        None => default_db_path().ok_or_else(|| "Unable to resolve the app data directory; pass --db.".to_string())?,
+    };
+    if !db_path.exists() {
+        if let Some(parent) = db_path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent).map_err(|err| err.to_string())?;
+        }
+    }
+    let mut conn = open_connection(&db_path)?;
+
+    match cli.command {
+        Command::Suite { command } => suite_command(&mut conn, command),
+        Command::Run(args) => run_suite(&conn, &db_path, args),
+        Command::Runs { command: RunsCommand::List { suite, model, status, limit } } => {
+            let test_suite_id = suite.map(|suite| find_suite(&conn, &suite).map(|suite| suite.id)).transpose()?;
+            list_runs(&conn, RunListQuery { limit: Some(limit), test_suite_id, model_id: model, status, ..Default::default() })
+        }
+        Command::Report(args) => {
+            let format = match args.format {
+                Some(ReportKind::Html) => ReportFormat::Html,
+                Some(ReportKind::Markdown) => ReportFormat::Markdown,
+                None => ReportFormat::from_path(&args.path),
+            };
+            generate_report_internal(&conn, &args.run_id, format, &args.path)?;
+            println!("Wrote {}", args.path.display());
+            Ok(())
+        }
+        Command::Export(args) => export(&conn, args),
+        Command::Check(args) => check(&conn, &db_path, args),
+        Command::Models { search, provider, api_key } => {
+            let providers = Providers::load(&conn, api_key.as_deref())?;
+            list_models(&providers, &provider, search.as_deref())
+        }
+        Command::Providers { command } => providers_command(&conn, command),
+        Command::Cache { command } => cache_command(&conn, command),
+        Command::Limits { command } => limits_command(&conn, command),
+    }
+}
+
+// ============================================================================
+// Suites
+// ============================================================================
+
+/// Matches a suite by id, then by name (case-insensitive).
+fn find_suite(conn: &Connection, key: &str) -> Result<TestSuite, String> {
+    let suites = get_all_test_suites_internal(conn)?;
+    if let Some(suite) = suites.iter().find(|suite| suite.id == key) {
+        return Ok(suite.clone());
+    }
+
+    let mut matches: Vec<TestSuite> = suites.into_iter().filter(|suite| suite.name.eq_ignore_ascii_case(key)).collect();
+    match matches.len() {
+        0 => Err(format!("No test suite with id or name '{}'.", key)),
+        1 => Ok(matches.remove(0)),
+        count => Err(format!("{} test suites are named '{}'; use the id instead.", count, key)),
+    }
+}
+
+fn suite_command(conn: &mut Connection, command: SuiteCommand) -> Result<(), String> {
+    match command {
+        SuiteCommand::List => {
+            let rows = get_all_test_suites_internal(conn)?
+                .into_iter()
+                .map(|suite| vec![suite.id, suite.name, suite.test_cases.len().to_string(), format_time(suite.updated_at)])
+                .collect();
+            print_table(&["ID", "NAME", "CASES", "UPDATED"], rows);
+            Ok(())
+        }
+        SuiteCommand::Import { path, on_conflict } => {
+            let bundle = read_bundle(&path)?;
+            let on_conflict = match on_conflict {
+                OnConflict::Keep => CollisionStrategy::Keep,
+                OnConflict::Rename => CollisionStrategy::Rename,
+                OnConflict::Overwrite => CollisionStrategy::Overwrite,
+            };
+
+            let tx = conn.transaction().map_err(|err| err.to_string())?;
+            let report = import_bundle(&tx, bundle, on_conflict)?;
+            tx.commit().map_err(|err| err.to_string())?;
+
+            println!("{:?}: {} ({})", report.outcome, report.test_suite_name, report.test_suite_id);
+            for warning in &report.warnings {
+                eprintln!("warning: {}", warning);
+            }
+            Ok(())
+        }
+        SuiteCommand::Export { suite, path } => {
+            let suite = find_suite(conn, &suite)?;
+            write_bundle(&build_bundle(conn, &suite.id)?, &path, BundleFormat::from_path(&path))?;
+            println!("Wrote {}", path.display());
+            Ok(())
+        }
+    }
+}
+
+// ============================================================================
+// Runs
+// ============================================================================
+
+/// Providers for a run, with the cassette the flags ask for.
+fn load_providers(
+    conn: &Connection,
+    db_path: &Path,
+    api_key: Option<&str>,
+    cassette: &CassetteArgs,
+    bypass_cache: bool,
+) -> Result<Providers, String> {
+    let mut providers = Providers::load(conn, api_key)?;
+    if let Some(cache) = ResponseCache::open(db_path, bypass_cache)? {
+        providers = providers.with_response_cache(cache);
+    }
+    let mode = match (cassette.record, cassette.replay) {
+        (true, _) => CassetteMode::Record,
+        (_, true) => CassetteMode::Replay,
+        _ => return Ok(providers),
+    };
+    let path = cassette.cassette.as_deref().unwrap_or(db_path);
+    Ok(providers.with_cassette(Cassette::open(path, mode)?.with_live_judge(cassette.allow_live_judge)))
+}
+
+fn run_suite(conn: &Connection, db_path: &Path, args: RunArgs) -> Result<(), String> {
+    let suite = find_suite(conn, &args.suite)?;
+    let providers = load_providers(conn, db_path, args.api_key.as_deref(), &args.cassette, args.bypass_cache)?;
+
+    let defaults = ModelParameters::default();
+    let request = RunRequest {
+        test_suite_id: suite.id.clone(),
+        models: args.models,
+        judge_model: args.judge,
+        parameters: ModelParameters {
+            temperature: args.temperature.unwrap_or(defaults.temperature),
+            top_p: args.top_p.unwrap_or(defaults.top_p),
+            max_tokens: args.max_tokens.unwrap_or(defaults.max_tokens),
+            frequency_penalty: args.frequency_penalty.unwrap_or(defaults.frequency_penalty),
+            presence_penalty: args.presence_penalty.unwrap_or(defaults.presence_penalty),
+        },
+        concurrency: Some(args.concurrency),
+        bypass_cache: args.bypass_cache,
+    };
+
+    let run = execute_with_progress(conn, &providers, &suite, &request)?;
+
+    let rows = leaderboard(&run, &suite.test_cases)
+        .into_iter()
+        .map(|standing| {
+            vec![
+                standing.rank.to_string(),
+                standing.model_id,
+                format_score(standing.score),
+                standing.failed.to_string(),
+                standing.total_cost.map(|cost| format!("${:.4}", cost)).unwrap_or_else(|| "-".to_string()),
+            ]
+        })
+        .collect();
+    print_table(&["#", "MODEL", "SCORE", "FAILED", "COST"], rows);
+    println!("Run {}", run.id);
+    Ok(())
+}
+
+/// Executes a run, logging each result to stderr as it lands.
+fn execute_with_progress(conn: &Connection, providers: &Providers, suite: &TestSuite, request: &RunRequest) -> Result<RunResult, String> {
+    let total = suite.test_cases.len() * request.models.len();
+    let mut done = 0;
+    eprintln!("Running '{}': {} case(s) x {} model(s)", suite.name, suite.test_cases.len(), request.models.len());
+
+    let mut retries = HashMap::new();
+    execute_run(conn, providers, request, &AtomicBool::new(false), |event| {
+        let result = match event {
+            RunEvent::Result(result) => result,
+            RunEvent::Queue(queue) => {
+                for model in &queue.models {
+                    let previous = retries.insert(model.model_id.clone(), model.retries).unwrap_or(0);
+                    if model.retries > previous {
+                        eprintln!("{} · throttled, retrying ({} retries so far)", model.model_id, model.retries);
+                    }
+                }
+                return;
+            }
+            RunEvent::Progress { .. } => return,
+        };
+        done += 1;
+        let outcome = match (&result.error, &result.score) {
+            (Some(error), _) => format!("failed: {}", error),
+            (None, Some(score)) => format!("{:.1}%", score.score * 100.0),
+            (None, None) => result.status.clone(),
+        };
+        let cached = if result.cached { " (cached)" } else { "" };
+        eprintln!("[{}/{}] {} · {} · {}{}", done, total, result.model_id, result.test_case_id, outcome, cached);
+    })
+}
+
+fn list_models(providers: &Providers, provider_id: &str, search: Option<&str>) -> Result<(), String> {
+    let search = search.map(str::to_lowercase);
+    let mut models = providers.list_models(provider_id)?;
+    models.retain(|model| {
+        search.as_deref().is_none_or(|search| model.id.to_lowercase().contains(search) || model.name.to_lowercase().contains(search))
+    });
+    models.sort_by(|a, b| a.id.cmp(&b.id));
+
+    // Prices are USD per token; show them per million
+    let per_million = |price: &str| price.parse::<f64>().map(|price| format!("${:.2}", price * 1_000_000.0)).unwrap_or_else(|_| "-".to_string());
+    let rows = models
+        .into_iter()
+        .map(|model| {
+            vec![
+                model.id,
+                model.context_length.map(|length| length.to_string()).unwrap_or_else(|| "-".to_string()),
+                model.pricing.as_ref().map(|pricing| per_million(&pricing.prompt)).unwrap_or_else(|| "-".to_string()),
+                model.pricing.as_ref().map(|pricing| per_million(&pricing.completion)).unwrap_or_else(|| "-".to_string()),
+            ]
+        })
+        .collect();
+    print_table(&["ID", "CONTEXT", "PROMPT/M", "COMPLETION/M"], rows);
+    Ok(())
+}
+
+fn providers_command(conn: &Connection, command: ProvidersCommand) -> Result<(), String> {
+    match command {
+        ProvidersCommand::List => {
+            let rows = get_provider_configs(conn)?
+                .into_iter()
+                .map(|config| {
+                    vec![
+                        config.id,
+                        config.kind.as_str().to_string(),
+                        config.base_url.or(config.fixture).unwrap_or_else(|| "-".to_string()),
+                        if config.api_key.is_some() { "set" } else { "-" }.to_string(),
+                    ]
+                })
+                .collect();
+            print_table(&["ID", "KIND", "BASE URL / FIXTURE", "API KEY"], rows);
+            Ok(())
+        }
+        ProvidersCommand::Add { id, kind, base_url, api_key, fixture } => {
+            let kind = match kind {
+                ProviderKindArg::Openrouter => ProviderKind::OpenRouter,
+                ProviderKindArg::OpenaiCompatible => ProviderKind::OpenAiCompatible,
+                ProviderKindArg::Ollama => ProviderKind::Ollama,
+                ProviderKindArg::Anthropic => ProviderKind::Anthropic,
+                ProviderKindArg::Mock => ProviderKind::Mock,
+            };
+            save_provider_config(conn, &ProviderConfig { id: id.clone(), kind, base_url, api_key, fixture })?;
+            println!("Saved provider '{}'; use its models as {}:<model>.", id, id);
+            Ok(())
+        }
+        ProvidersCommand::Remove { id } => {
+            if !delete_provider_config(conn, &id)? {
+                return Err(format!("No stored provider '{}'.", id));
+            }
+            println!("Removed provider '{}'.", id);
+            Ok(())
+        }
+    }
+}
+
+fn cache_command(conn: &Connection, command: CacheCommand) -> Result<(), String> {
+    match command {
+        CacheCommand::Status => {
+            let status = cache_status(conn)?;
+            let settings = status.settings;
+            println!("Enabled: {}", if settings.enabled { "yes" } else { "no" });
+            println!("TTL: {}", if settings.ttl_hours > 0 { format!("{} h", settings.ttl_hours) } else { "none".to_string() });
+            println!("Size limit: {}", if settings.max_size_mb > 0 { format!("{} MB", settings.max_size_mb) } else { "none".to_string() });
+            println!("Entries: {} ({:.1} MB)", status.entries, status.size_bytes as f64 / (1024.0 * 1024.0));
+            Ok(())
+        }
+        CacheCommand::Set { enabled, ttl_hours, max_size_mb } => {
+            let mut settings = cache_settings(conn);
+            settings.enabled = enabled.unwrap_or(settings.enabled);
+            settings.ttl_hours = ttl_hours.unwrap_or(settings.ttl_hours);
+            settings.max_size_mb = max_size_mb.unwrap_or(settings.max_size_mb);
+            save_cache_settings(conn, &settings)?;
+            cache_command(conn, CacheCommand::Status)
+        }
+        CacheCommand::Clear => {
+            println!("Removed {} cached response(s).", clear_response_cache(conn)?);
+            Ok(())
+        }
+    }
+}
+
+fn limits_command(conn: &Connection, command: LimitsCommand) -> Result<(), String> {
+    let mut limits = rate_limits(conn)?;
+    match command {
+        LimitsCommand::List => {
+            let per_minute = |limit: Option<u32>| limit.map(|limit| limit.to_string()).unwrap_or_else(|| "-".to_string());
+            let rows = limits
+                .providers
+                .iter()
+                .map(|(target, limit)| ("provider", target, limit))
+                .chain(limits.models.iter().map(|(target, limit)| ("model", target, limit)))
+                .map(|(kind, target, limit)| {
+                    vec![kind.to_string(), target.clone(), per_minute(limit.requests_per_minute), per_minute(limit.tokens_per_minute)]
+                })
+                .collect();
+            print_table(&["KIND", "ID", "REQUESTS/MIN", "TOKENS/MIN"], rows);
+            Ok(())
+        }
+        LimitsCommand::Set { target, model, rpm, tpm } => {
+            let limit = RateLimit { requests_per_minute: rpm, tokens_per_minute: tpm };
+            if model {
+                limits.models.insert(target, limit);
+            } else {
+                limits.providers.insert(target, limit);
+            }
+            save_rate_limits(conn, &limits)?;
+            limits_command(conn, LimitsCommand::List)
+        }
+        LimitsCommand::Remove { target, model } => {
+            let removed = if model { limits.models.remove(&target) } else { limits.providers.remove(&target) };
+            if removed.is_none() {
+                return Err(format!("No rate limits for '{}'.", target));
+            }
+            save_rate_limits(conn, &limits)?;
+            println!("Removed the rate limits for '{}'.", target);
+            Ok(())
+        }
+    }
+}
+
+fn list_runs(conn: &Connection, query: RunListQuery) -> Result<(), String> {
+    let page = list_runs_internal(conn, &query)?;
+    let rows = page
+        .runs
+        .into_iter()
+        .map(|run| {
+            vec![
+                run.id,
+                run.test_suite_name,
+                run.models.join(","),
+                run.status,
+                format_time(run.started_at),
+                format_score(run.mean_score),
+            ]
+        })
+        .collect();
+    print_table(&["ID", "SUITE", "MODELS", "STATUS", "STARTED", "MEAN"], rows);
+    if page.total > page.limit {
+        eprintln!("Showing {} of {} runs; use --limit to see more.", page.limit, page.total);
+    }
+    Ok(())
+}
+
+fn export(conn: &Connection, args: ExportArgs) -> Result<(), String> {
+    let test_suite_id = args.suite.map(|suite| find_suite(conn, &suite).map(|suite| suite.id)).transpose()?;
+    let request = RunExportRequest {
+        path: path_string(&args.path),
+        format: args.format.map(|format| match format {
+            ExportKind::Csv => ExportFormat::Csv,
+            ExportKind::Jsonl => ExportFormat::Jsonl,
+            ExportKind::Parquet => ExportFormat::Parquet,
+        }),
+        run_ids: args.runs,
+        filter: Some(RunListQuery { test_suite_id, model_id: args.model, status: args.status, ..Default::default() }),
+    };
+
+    let report = export_runs_internal(conn, &request)?;
+    println!("Wrote {} row(s) from {} run(s) to {}", report.rows, report.runs, report.path);
+    Ok(())
+}
+
+// ============================================================================
+// Check
+// ============================================================================
+
+fn find_run(conn: &Connection, run_id: &str) -> Result<RunResult, String> {
+    get_run_internal(conn, run_id)?.ok_or_else(|| format!("Run {} not found.", run_id))
+}
+
+fn check(conn: &Connection, db_path: &Path, args: CheckArgs) -> Result<(), String> {
+    let checked_run = args.run.as_deref().map(|run_id| find_run(conn, run_id)).transpose()?;
+    let mut test_suite_id = match (&args.suite, &checked_run) {
+        (Some(suite), _) => Some(find_suite(conn, suite)?.id),
+        (None, Some(run)) => Some(run.test_suite_id.clone()),
+        (None, None) => None,
+    };
+
+    let baseline_run_id = match (&args.baseline, &test_suite_id) {
+        (Some(baseline), _) => Some(baseline.clone()),
+        (None, Some(test_suite_id)) => get_baseline(conn, test_suite_id)?,
+        (None, None) => None,
+    };
+    let baseline = baseline_run_id.as_deref().map(|run_id| find_run(conn, run_id)).transpose()?;
+    if test_suite_id.is_none() {
+        test_suite_id = baseline.as_ref().map(|baseline| baseline.test_suite_id.clone());
+    }
+    let test_suite_id = test_suite_id.ok_or_else(|| "Pass --suite, --run or --baseline.".to_string())?;
+    if let Some(baseline) = baseline.as_ref().filter(|baseline| baseline.test_suite_id != test_suite_id) {
+        return Err(format!("Baseline run {} is for suite '{}', not this one.", baseline.id, baseline.test_suite_name));
+    }
+
+    let options = CheckOptions {
+        tolerance: args.tolerance,
+        case_tolerance: args.case_tolerance,
+        min_score: args.min_score,
+        min_case_score: args.min_case_score,
+    };
+    let has_threshold = options.min_score.is_some() || options.min_case_score.is_some();
+    // Fail before paying for a run that could not be checked
+    if baseline.is_none() && !has_threshold && !args.update_baseline {
+        return Err(
+            "Nothing to check against: pass --baseline, --min-score or --min-case-score, or --update-baseline to store a first baseline."
+                .to_string(),
+        );
+    }
+
+    let run = match checked_run {
+        Some(run) => run,
+        None => {
+            let suite = find_suite(conn, &test_suite_id)?;
+            let models = args
+                .models
+                .or_else(|| baseline.as_ref().map(|baseline| baseline.models.clone()))
+                .ok_or_else(|| "Pass --models; there is no baseline to take them from.".to_string())?;
+            let request = RunRequest {
+                test_suite_id: suite.id.clone(),
+                models,
+                judge_model: args.judge.or_else(|| baseline.as_ref().and_then(|baseline| baseline.judge_model.clone())),
+                // Same sampling settings as the baseline so scores are comparable
+                parameters: baseline.as_ref().map(|baseline| baseline.parameters.clone()).unwrap_or_default(),
+                concurrency: Some(args.concurrency),
+                bypass_cache: args.bypass_cache,
+            };
+            let providers = load_providers(conn, db_path, args.api_key.as_deref(), &args.cassette, args.bypass_cache)?;
+            execute_with_progress(conn, &providers, &suite, &request)?
+        }
+    };
+
+    if baseline.is_none() && !has_threshold {
+        set_baseline(conn, &test_suite_id, &run.id)?;
+        println!("No baseline yet; stored run {} as the baseline for '{}'.", run.id, run.test_suite_name);
+        return Ok(());
+    }
+
+    let report = check_run_internal(conn, &run.id, baseline.as_ref().map(|baseline| baseline.id.as_str()), &options)?;
+    write_check_outputs(&report, args.junit.as_deref(), args.summary.as_deref())?;
+
+    let rows = report
+        .models
+        .iter()
+        .map(|model| {
+            let failing = report.failures().filter(|check| check.test_case_id.is_some() && check.model_id == model.model_id).count();
+            vec![
+                model.model_id.clone(),
+                format_score(model.score),
+                format_score(model.baseline),
+                failing.to_string(),
+                format!("{:?}", model.status),
+            ]
+        })
+        .collect();
+    print_table(&["MODEL", "SCORE", "BASELINE", "FAILING CASES", "RESULT"], rows);
+    for failure in report.failures() {
+        let marker = if failure.status == CheckStatus::Errored { "ERROR" } else { "FAIL" };
+        eprintln!("{}: {} · {} · {}", marker, failure.model_id, failure.label, failure.message.as_deref().unwrap_or_default());
+    }
+
+    let failures = report.failures().count();
+    if failures > 0 {
+        return Err(format!("check failed: {} regression(s) or error(s) in run {}", failures, run.id));
+    }
+    if args.update_baseline {
+        set_baseline(conn, &test_suite_id, &run.id)?;
+        println!("Stored run {} as the new baseline.", run.id);
+    }
+    println!("Check passed for run {}.", run.id);
+    Ok(())
+}
+
+// ============================================================================
+// Output
+// ============================================================================
+
+fn path_string(path: &Path) -> String {
+    path.to_string_lossy().to_string()
+}
+
+fn format_score(score: Option<f64>) -> String {
+    score.map(|score| format!("{:.1}%", score * 100.0)).unwrap_or_else(|| "-".to_string())
+}
+
+fn format_time(timestamp_ms: i64) -> String {
+    chrono::DateTime::from_timestamp_millis(timestamp_ms)
+        .map(|time| time.format("%Y-%m-%d %H:%M").to_string())
+        .unwrap_or_default()
+}
+
+fn print_table(headers: &[&str], rows: Vec<Vec<String>>) {
+    let mut widths: Vec<usize> = headers.iter().map(|header| header.len()).collect();
+    for row in &rows {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let line = |cells: Vec<&str>| {
+        let padded: Vec<String> = cells.iter().zip(&widths).map(|(cell, width)| format!("{:<width$}", cell, width = width)).collect();
+        println!("{}", padded.join("  ").trim_end());
+    };
+    line(headers.to_vec());
+    for row in &rows {
+        line(row.iter().map(String::as_str).collect());
+    }
+}
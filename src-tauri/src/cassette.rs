@@ -0,0 +1,263 @@
+//! Record and replay of model calls. In record mode every provider request
+//! (provider, model, messages and parameters) is stored with its response,
+//! keyed by a hash of the request. In replay mode runs are served from those
+//! recordings instead of the provider: old responses can be re-scored after
+//! a scorer changes, and integration tests get identical answers every time.
+//!
+//! Replay is strict: a run whose requests, judge calls included, are not all
+//! recorded fails before it starts. Grading recorded answers with a new judge
+//! takes an explicit opt-in (`with_live_judge`), which sends unrecorded judge
+//! calls to the judge's provider.
+//!
+//! A cassette lives in the app database by default, or in any other
+//! Benchmaker database file (handy to check into a repository for tests).
+
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::providers::{ChatCompletionRequest, Completion};
+use crate::{chrono_now, open_connection};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum CassetteMode {
+    /// Call the provider and store each response
+    Record,
+    /// Serve responses from the cassette only
+    Replay,
+}
+
+pub struct Cassette {
+    conn: Mutex<Connection>,
+    mode: CassetteMode,
+    live_judge: bool,
+}
+
+impl Cassette {
+    /// Opens (and migrates) the database at `path` as a cassette.
+    pub fn open(path: &Path, mode: CassetteMode) -> Result<Cassette, String> {
+        Ok(Cassette::with_connection(open_connection(path)?, mode))
+    }
+
+    pub fn with_connection(conn: Connection, mode: CassetteMode) -> Cassette {
+        Cassette { conn: Mutex::new(conn), mode, live_judge: false }
+    }
+
+    /// Lets a replay ask the judge live for answers that were never recorded.
+    pub fn with_live_judge(mut self, allow: bool) -> Cassette {
+        self.live_judge = allow;
+        self
+    }
+
+    pub fn mode(&self) -> CassetteMode {
+        self.mode
+    }
+
+    pub fn allows_live_judge(&self) -> bool {
+        self.live_judge
+    }
+
+    fn conn(&self) -> Result<MutexGuard<'_, Connection>, String> {
+        self.conn.lock().map_err(|_| "Cassette is unavailable after a previous failure.".to_string())
+    }
+
+    pub fn lookup(&self, provider: &str, request: &ChatCompletionRequest) -> Result<Option<Completion>, String> {
+        find_response(&*self.conn()?, &request_key(provider, request))
+    }
+
+    pub fn contains(&self, provider: &str, request: &ChatCompletionRequest) -> Result<bool, String> {
+        Ok(self.lookup(provider, request)?.is_some())
+    }
+
+    pub fn record(&self, provider: &str, request: &ChatCompletionRequest, completion: &Completion) -> Result<(), String> {
+        record_response(&*self.conn()?, provider, request, completion)
+    }
+}
+
+/// SHA-256 over the provider and the request as it would be sent. The test
+/// case id is not part of the request, so identical prompts share an entry.
+pub fn request_key(provider: &str, request: &ChatCompletionRequest) -> String {
+    let json = serde_json::to_vec(&serde_json::json!({ "provider": provider, "request": request })).unwrap_or_default();
+    hex::encode(Sha256::digest(&json))
+}
+
+/// Stores a response, replacing an earlier recording of the same request.
+pub fn record_response(conn: &Connection, provider: &str, request: &ChatCompletionRequest, completion: &Completion) -> Result<(), String> {
+    let request_json = serde_json::to_string(request).map_err(|err| err.to_string())?;
+    let response_json = serde_json::to_string(completion).map_err(|err| err.to_string())?;
+
+    conn.execute(
+        "INSERT INTO cassette_entries (key, provider, model, request, response, recorded_at)
+         VALUES (?, ?, ?, ?, ?, ?)
+         ON CONFLICT(key) DO UPDATE SET response = excluded.response, recorded_at = excluded.recorded_at",
+        params![request_key(provider, request), provider, request.model, request_json, response_json, chrono_now()],
+    )
+    .map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+pub fn find_response(conn: &Connection, key: &str) -> Result<Option<Completion>, String> {
+    let response: Option<String> = conn
+        .query_row("SELECT response FROM cassette_entries WHERE key = ?", params![key], |row| row.get(0))
+        .optional()
+        .map_err(|err| err.to_string())?;
+
+    response
+        .map(|json| serde_json::from_str(&json).map_err(|err| format!("Corrupt cassette entry {}: {}", key, err)))
+        .transpose()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicBool;
+
+    use crate::execution::{execute_run, prepare_run, RunRequest};
+    use crate::providers::{save_provider_config, ProviderConfig, ProviderKind, Providers};
+    use crate::test_support::{memory_db, suite};
+    use crate::{write_test_suite, ModelParameters, RunResult, TestSuite};
+
+    /// Answers every case with "Answer for a" and grades everything 80.
+    const RECORDED: &str = r#"{"responses": [
+        {"model": "good", "response": "Answer for a"},
+        {"model": "judge", "response": "{\"score\": 80, \"reasoning\": \"Scripted\"}"}
+    ]}"#;
+
+    /// Fails every live call, so a replay that reaches the provider shows.
+    const OFFLINE: &str = r#"{"responses": [{"error": "server"}]}"#;
+
+    /// The `mock` provider answering from `fixture`, plus a cassette file.
+    fn providers(conn: &Connection, dir: &Path, fixture: &str, mode: CassetteMode, live_judge: bool) -> Providers {
+        let path = dir.join("fixture.json");
+        std::fs::write(&path, fixture).unwrap();
+        let config = ProviderConfig {
+            id: "mock".to_string(),
+            kind: ProviderKind::Mock,
+            base_url: None,
+            api_key: None,
+            fixture: Some(path.to_string_lossy().into_owned()),
+        };
+        save_provider_config(conn, &config).unwrap();
+
+        let cassette = Cassette::open(&dir.join("cassette.db"), mode).unwrap().with_live_judge(live_judge);
+        Providers::load(conn, None).unwrap().with_cassette(cassette)
+    }
+
+    fn judged_suite(conn: &Connection) -> TestSuite {
+        let mut suite = suite("s1", &["a"]);
+        suite.test_cases[0].scoring_method = "llm-judge".to_string();
+        write_test_suite(conn, &suite).unwrap();
+        suite
+    }
+
+    fn request(judge_model: Option<&str>) -> RunRequest {
+        RunRequest {
+            test_suite_id: "s1".to_string(),
+            models: vec!["mock:good".to_string()],
+            judge_model: judge_model.map(str::to_string),
+            parameters: ModelParameters::default(),
+            concurrency: None,
+            bypass_cache: false,
+        }
+    }
+
+    fn answers(run: &RunResult) -> Vec<(String, Option<f64>)> {
+        run.results
+            .iter()
+            .map(|result| (result.response.clone(), result.score.as_ref().map(|score| score.score)))
+            .collect()
+    }
+
+    fn run_with(conn: &Connection, providers: &Providers, request: &RunRequest) -> RunResult {
+        execute_run(conn, providers, request, &AtomicBool::new(false), |_| {}).unwrap()
+    }
+
+    #[test]
+    fn replay_serves_the_recorded_answers() {
+        let conn = memory_db();
+        let dir = tempfile::tempdir().unwrap();
+        write_test_suite(&conn, &suite("s1", &["a", "b"])).unwrap();
+
+        let recording = providers(&conn, dir.path(), RECORDED, CassetteMode::Record, false);
+        let recorded = run_with(&conn, &recording, &request(None));
+        drop(recording);
+
+        let replaying = providers(&conn, dir.path(), OFFLINE, CassetteMode::Replay, false);
+        let replayed = run_with(&conn, &replaying, &request(None));
+        assert_eq!(answers(&replayed), answers(&recorded));
+        assert_eq!(answers(&replayed)[0], ("Answer for a".to_string(), Some(1.0)));
+        assert!(replayed.results.iter().all(|result| result.status == "completed"));
+    }
+
+    #[test]
+    fn replaying_an_unrecorded_case_fails_before_the_run() {
+        let conn = memory_db();
+        let dir = tempfile::tempdir().unwrap();
+        write_test_suite(&conn, &suite("s1", &["a", "b"])).unwrap();
+        let recording = providers(&conn, dir.path(), RECORDED, CassetteMode::Record, false);
+        run_with(&conn, &recording, &request(None));
+        drop(recording);
+
+        write_test_suite(&conn, &suite("s1", &["a", "b", "c"])).unwrap();
+        let replaying = providers(&conn, dir.path(), OFFLINE, CassetteMode::Replay, false);
+        let err = prepare_run(&conn, &replaying, &request(None)).unwrap_err();
+        assert_eq!(
+            err,
+            "Cannot replay: 1 of 3 request(s) are not in the cassette (mock:good · c). Record the run first."
+        );
+        let runs: i64 = conn.query_row("SELECT COUNT(*) FROM runs", [], |row| row.get(0)).unwrap();
+        assert_eq!(runs, 1);
+    }
+
+    #[test]
+    fn judge_grades_are_replayed_too() {
+        let conn = memory_db();
+        let dir = tempfile::tempdir().unwrap();
+        judged_suite(&conn);
+        let recording = providers(&conn, dir.path(), RECORDED, CassetteMode::Record, false);
+        run_with(&conn, &recording, &request(Some("mock:judge")));
+        drop(recording);
+
+        let replaying = providers(&conn, dir.path(), OFFLINE, CassetteMode::Replay, false);
+        let replayed = run_with(&conn, &replaying, &request(Some("mock:judge")));
+        assert_eq!(answers(&replayed), vec![("Answer for a".to_string(), Some(0.8))]);
+    }
+
+    #[test]
+    fn replay_fails_when_a_judge_grade_is_missing() {
+        let conn = memory_db();
+        let dir = tempfile::tempdir().unwrap();
+        judged_suite(&conn);
+        // Recorded without a judge, so only the answer is on the cassette
+        let recording = providers(&conn, dir.path(), RECORDED, CassetteMode::Record, false);
+        run_with(&conn, &recording, &request(None));
+        drop(recording);
+
+        let replaying = providers(&conn, dir.path(), OFFLINE, CassetteMode::Replay, false);
+        let err = prepare_run(&conn, &replaying, &request(Some("mock:judge"))).unwrap_err();
+        assert!(err.contains("(mock:judge grading mock:good · a)"), "{}", err);
+    }
+
+    #[test]
+    fn live_judge_grades_unrecorded_answers() {
+        let conn = memory_db();
+        let dir = tempfile::tempdir().unwrap();
+        judged_suite(&conn);
+        let recording = providers(&conn, dir.path(), RECORDED, CassetteMode::Record, false);
+        run_with(&conn, &recording, &request(None));
+        drop(recording);
+
+        // The judge answers live; the model would fail if it were called
+        let fixture = r#"{"responses": [
+            {"model": "good", "error": "server"},
+            {"model": "judge", "response": "{\"score\": 60, \"reasoning\": \"Live\"}"}
+        ]}"#;
+        let replaying = providers(&conn, dir.path(), fixture, CassetteMode::Replay, true);
+        let replayed = run_with(&conn, &replaying, &request(Some("mock:judge")));
+        assert_eq!(answers(&replayed), vec![("Answer for a".to_string(), Some(0.6))]);
+    }
+}
@@ -0,0 +1,412 @@
+//! Bulk test case import from tabular datasets. CSV, JSONL and JSON array rows are mapped
+//! onto `TestCase` fields by column name, validated one by one, and appended
+//! to an existing suite or a new one. Rows that fail validation are skipped
+//! and listed in the report instead of aborting the whole import.
+
+use std::fs::File;
+use std::io::{BufRead, BufReader, Seek};
+use std::path::Path;
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+#[cfg(feature = "app")]
+use tauri::State;
+
+use crate::suite_bundles::SCORING_METHODS;
+use crate::suite_revisions::latest_revision;
+use crate::{chrono_now, generate_id, get_test_suite_internal, write_test_suite, Db, TestCase, TestCaseMetadata, TestSuite};
+
+const DIFFICULTIES: [&str; 3] = ["easy", "medium", "hard"];
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum DatasetFormat {
+    Csv,
+    Jsonl,
+    /// A single JSON array of row objects
+    Json,
+}
+
+impl DatasetFormat {
+    /// JSONL for `.jsonl`/`.ndjson` files, a JSON array for `.json`, CSV for
+    /// everything else.
+    pub fn from_path(path: &Path) -> DatasetFormat {
+        match path.extension().and_then(|ext| ext.to_str()).map(str::to_ascii_lowercase).as_deref() {
+            Some("jsonl") | Some("ndjson") => DatasetFormat::Jsonl,
+            Some("json") => DatasetFormat::Json,
+            _ => DatasetFormat::Csv,
+        }
+    }
+}
+
+/// Source column for each `TestCase` field. JSONL columns may use dotted
+/// paths (`input.question`) to reach nested values.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ColumnMapping {
+    pub prompt: String,
+    pub expected_output: Option<String>,
+    pub scoring_method: Option<String>,
+    pub weight: Option<String>,
+    pub category: Option<String>,
+    pub difficulty: Option<String>,
+    pub tags: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase", rename_all_fields = "camelCase", tag = "mode")]
+pub enum ImportTarget {
+    /// Append to an existing suite
+    Append { test_suite_id: String },
+    /// Create a new suite to hold the cases
+    Create {
+        name: String,
+        description: Option<String>,
+        #[serde(default)]
+        system_prompt: String,
+    },
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TestCaseImportRequest {
+    pub path: String,
+    /// Defaults to the file extension
+    pub format: Option<DatasetFormat>,
+    pub mapping: ColumnMapping,
+    pub target: ImportTarget,
+    /// Used when the scoring method column is unmapped or empty. Falls back
+    /// to exact-match for rows with an expected output and llm-judge otherwise.
+    pub default_scoring_method: Option<String>,
+    /// Added to every imported case, e.g. the dataset name
+    #[serde(default)]
+    pub extra_tags: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RowError {
+    /// 1-based data row (CSV, excluding the header), line number (JSONL) or
+    /// array index (JSON)
+    pub row: usize,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TestCaseImportReport {
+    pub test_suite_id: Option<String>,
+    pub test_suite_name: Option<String>,
+    pub created_suite: bool,
+    pub imported: usize,
+    pub skipped: usize,
+    /// Suite revision after the import
+    pub revision: Option<i64>,
+    pub errors: Vec<RowError>,
+}
+
+// ============================================================================
+// Reading
+// ============================================================================
+
+/// Rows of a dataset file as JSON objects, keyed by their 1-based row number.
+pub struct DatasetRows {
+    pub rows: Vec<(usize, Value)>,
+    pub errors: Vec<RowError>,
+}
+
+/// Reads every row as a JSON object. CSV cells become strings; a row that
+/// can't be parsed at all is reported and left out. JSON input is read as an
+/// array when it starts with `[` and as JSONL otherwise, whatever `format`
+/// says, since `.json` files come both ways.
+pub fn read_rows(path: &Path, format: DatasetFormat) -> Result<DatasetRows, String> {
+    let mut file = File::open(path).map_err(|err| format!("Failed to open {}: {}", path.display(), err))?;
+    let mut rows = Vec::new();
+    let mut errors = Vec::new();
+
+    let format = match format {
+        DatasetFormat::Csv => DatasetFormat::Csv,
+        DatasetFormat::Jsonl | DatasetFormat::Json if starts_with_array(&mut file)? => DatasetFormat::Json,
+        DatasetFormat::Jsonl | DatasetFormat::Json => DatasetFormat::Jsonl,
+    };
+
+    match format {
+        DatasetFormat::Csv => {
+            let mut reader = csv::ReaderBuilder::new().flexible(true).from_reader(file);
+            let headers = reader.headers().map_err(|err| format!("Failed to read CSV header: {}", err))?.clone();
+
+            for (index, record) in reader.records().enumerate() {
+                match record {
+                    Ok(record) => {
+                        let object = headers
+                            .iter()
+                            .zip(record.iter())
+                            .map(|(header, cell)| (header.trim().to_string(), Value::String(cell.to_string())))
+                            .collect();
+                        rows.push((index + 1, Value::Object(object)));
+                    }
+                    Err(err) => errors.push(RowError { row: index + 1, message: err.to_string() }),
+                }
+            }
+        }
+        DatasetFormat::Jsonl => {
+            for (index, line) in BufReader::new(file).lines().enumerate() {
+                let line = line.map_err(|err| err.to_string())?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                match serde_json::from_str::<Value>(&line) {
+                    Ok(value @ Value::Object(_)) => rows.push((index + 1, value)),
+                    Ok(_) => errors.push(RowError { row: index + 1, message: "Line is not a JSON object".to_string() }),
+                    Err(err) => errors.push(RowError { row: index + 1, message: format!("Invalid JSON: {}", err) }),
+                }
+            }
+        }
+        DatasetFormat::Json => {
+            let items: Vec<Value> =
+                serde_json::from_reader(BufReader::new(file)).map_err(|err| format!("Invalid JSON array: {}", err))?;
+            for (index, item) in items.into_iter().enumerate() {
+                match item {
+                    value @ Value::Object(_) => rows.push((index + 1, value)),
+                    _ => errors.push(RowError { row: index + 1, message: "Item is not a JSON object".to_string() }),
+                }
+            }
+        }
+    }
+
+    Ok(DatasetRows { rows, errors })
+}
+
+/// Whether the first non-blank byte is `[`; leaves the file at its start.
+fn starts_with_array(file: &mut File) -> Result<bool, String> {
+    let mut reader = BufReader::new(&mut *file);
+    let first = loop {
+        let buffer = reader.fill_buf().map_err(|err| err.to_string())?;
+        let Some(&byte) = buffer.first() else { break None };
+        if byte.is_ascii_whitespace() {
+            reader.consume(1);
+        } else {
+            break Some(byte);
+        }
+    };
+    file.rewind().map_err(|err| err.to_string())?;
+    Ok(first == Some(b'['))
+}
+
+/// Looks up `column` in a row, trying the literal key before a dotted path.
+fn lookup<'a>(row: &'a Value, column: &str) -> Option<&'a Value> {
+    if let Some(value) = row.get(column) {
+        return Some(value);
+    }
+    column.split('.').try_fold(row, |value, key| match value {
+        Value::Array(items) => key.parse::<usize>().ok().and_then(|index| items.get(index)),
+        _ => value.get(key),
+    })
+}
+
+/// Text of a cell; empty strings and nulls count as missing.
+fn text(row: &Value, column: Option<&str>) -> Option<String> {
+    let value = lookup(row, column?)?;
+    let text = match value {
+        Value::Null => return None,
+        Value::String(text) => text.trim().to_string(),
+        other => other.to_string(),
+    };
+    (!text.is_empty()).then_some(text)
+}
+
+fn tags(row: &Value, column: Option<&str>) -> Vec<String> {
+    let Some(value) = column.and_then(|column| lookup(row, column)) else {
+        return Vec::new();
+    };
+    let tags: Vec<String> = match value {
+        Value::Array(items) => items
+            .iter()
+            .map(|item| item.as_str().map(str::to_string).unwrap_or_else(|| item.to_string()))
+            .collect(),
+        Value::String(text) => text.split([',', ';', '|']).map(str::to_string).collect(),
+        Value::Null => Vec::new(),
+        other => vec![other.to_string()],
+    };
+    tags.into_iter()
+        .map(|tag| tag.trim().to_string())
+        .filter(|tag| !tag.is_empty())
+        .collect()
+}
+
+/// Builds a test case from one row, or explains why the row was rejected.
+pub fn row_to_test_case(row: &Value, mapping: &ColumnMapping, default_scoring_method: Option<&str>) -> Result<TestCase, String> {
+    let prompt = text(row, Some(&mapping.prompt)).ok_or_else(|| format!("Missing prompt in column '{}'", mapping.prompt))?;
+    let expected_output = text(row, mapping.expected_output.as_deref());
+
+    let scoring_method = text(row, mapping.scoring_method.as_deref())
+        .map(|method| method.to_ascii_lowercase())
+        .or_else(|| default_scoring_method.map(str::to_string))
+        .unwrap_or_else(|| {
+            if expected_output.is_some() { "exact-match" } else { "llm-judge" }.to_string()
+        });
+    if !SCORING_METHODS.contains(&scoring_method.as_str()) {
+        return Err(format!("Unknown scoring method '{}'", scoring_method));
+    }
+    if scoring_method != "llm-judge" && expected_output.is_none() {
+        return Err(format!("Scoring method '{}' needs an expected output", scoring_method));
+    }
+
+    let weight = match text(row, mapping.weight.as_deref()) {
+        Some(weight) => weight
+            .parse::<f64>()
+            .ok()
+            .filter(|weight| weight.is_finite() && *weight >= 0.0)
+            .ok_or_else(|| format!("Invalid weight '{}'", weight))?,
+        None => 1.0,
+    };
+
+    let difficulty = match text(row, mapping.difficulty.as_deref()) {
+        Some(difficulty) => {
+            let difficulty = difficulty.to_ascii_lowercase();
+            if !DIFFICULTIES.contains(&difficulty.as_str()) {
+                return Err(format!("Difficulty must be easy, medium or hard, not '{}'", difficulty));
+            }
+            Some(difficulty)
+        }
+        None => None,
+    };
+
+    Ok(TestCase {
+        id: generate_id(),
+        prompt,
+        expected_output,
+        scoring_method,
+        weight,
+        metadata: TestCaseMetadata {
+            category: text(row, mapping.category.as_deref()),
+            difficulty,
+            tags: tags(row, mapping.tags.as_deref()),
+        },
+    })
+}
+
+// ============================================================================
+// Saving
+// ============================================================================
+
+/// Adds `cases` to the target suite, creating it if asked. Nothing is written
+/// when there are no cases.
+pub fn save_imported_cases(
+    conn: &Connection,
+    target: &ImportTarget,
+    cases: Vec<TestCase>,
+    errors: Vec<RowError>,
+) -> Result<TestCaseImportReport, String> {
+    let mut report = TestCaseImportReport {
+        test_suite_id: None,
+        test_suite_name: None,
+        created_suite: false,
+        imported: cases.len(),
+        skipped: errors.len(),
+        revision: None,
+        errors,
+    };
+
+    let mut suite = match target {
+        ImportTarget::Append { test_suite_id } => get_test_suite_internal(conn, test_suite_id)?
+            .ok_or_else(|| format!("Test suite {} not found.", test_suite_id))?,
+        ImportTarget::Create { name, description, system_prompt } => {
+            if name.trim().is_empty() {
+                return Err("A name is required for the new test suite.".to_string());
+            }
+            report.created_suite = true;
+            TestSuite {
+                id: generate_id(),
+                name: name.trim().to_string(),
+                description: description.clone(),
+                system_prompt: system_prompt.clone(),
+                judge_system_prompt: None,
+                test_cases: Vec::new(),
+                created_at: chrono_now(),
+                updated_at: chrono_now(),
+            }
+        }
+    };
+
+    if cases.is_empty() {
+        report.created_suite = false;
+        return Ok(report);
+    }
+
+    suite.test_cases.extend(cases);
+    suite.updated_at = chrono_now();
+    write_test_suite(conn, &suite)?;
+
+    report.revision = latest_revision(conn, &suite.id)?;
+    report.test_suite_id = Some(suite.id);
+    report.test_suite_name = Some(suite.name);
+    Ok(report)
+}
+
+pub fn import_test_cases_internal(conn: &Connection, request: &TestCaseImportRequest) -> Result<TestCaseImportReport, String> {
+    if request.mapping.prompt.trim().is_empty() {
+        return Err("Map a column to the prompt field.".to_string());
+    }
+
+    let path = Path::new(&request.path);
+    let format = request.format.unwrap_or_else(|| DatasetFormat::from_path(path));
+    let DatasetRows { rows, mut errors } = read_rows(path, format)?;
+
+    let mut cases = Vec::new();
+    for (row_number, row) in rows {
+        match row_to_test_case(&row, &request.mapping, request.default_scoring_method.as_deref()) {
+            Ok(mut test_case) => {
+                test_case.metadata.tags.extend(request.extra_tags.iter().cloned());
+                cases.push(test_case);
+            }
+            Err(message) => errors.push(RowError { row: row_number, message }),
+        }
+    }
+    errors.sort_by_key(|error| error.row);
+
+    save_imported_cases(conn, &request.target, cases, errors)
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[cfg(feature = "app")]
+#[tauri::command]
+pub fn import_test_cases(db: State<'_, Db>, request: TestCaseImportRequest) -> Result<TestCaseImportReport, String> {
+    let mut conn = db.conn()?;
+    let tx = conn.transaction().map_err(|err| err.to_string())?;
+    let report = import_test_cases_internal(&tx, &request)?;
+    tx.commit().map_err(|err| err.to_string())?;
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prompts(rows: &DatasetRows) -> Vec<&str> {
+        rows.rows.iter().map(|(_, row)| row["prompt"].as_str().unwrap()).collect()
+    }
+
+    #[test]
+    fn json_files_are_read_as_arrays_or_lines() {
+        let dir = tempfile::tempdir().unwrap();
+        let array = dir.path().join("array.json");
+        std::fs::write(&array, "\n  [{\"prompt\": \"one\"}, 2, {\"prompt\": \"three\"}]").unwrap();
+        let lines = dir.path().join("lines.json");
+        std::fs::write(&lines, "{\"prompt\": \"one\"}\n{\"prompt\": \"two\"}\n").unwrap();
+
+        let rows = read_rows(&array, DatasetFormat::from_path(&array)).unwrap();
+        assert_eq!(prompts(&rows), ["one", "three"]);
+        assert_eq!(rows.errors.len(), 1);
+        assert_eq!(rows.errors[0].row, 2);
+
+        let rows = read_rows(&lines, DatasetFormat::from_path(&lines)).unwrap();
+        assert_eq!(prompts(&rows), ["one", "two"]);
+        // An explicit JSONL format still takes an array
+        assert_eq!(prompts(&read_rows(&array, DatasetFormat::Jsonl).unwrap()), ["one", "three"]);
+    }
+}
@@ -0,0 +1,733 @@
+//! Runs a suite against models from the backend. Worker threads make the
+//! model and judge calls; the calling thread owns the connection and
+//! persists each result as it arrives, so a crash loses at most the
+//! in-flight requests.
+//!
+//! The app starts runs with `start_run`, which executes on a background
+//! thread with its own connection and reports progress through events, so a
+//! run survives a reload of the webview. Which case runs next, and when each
+//! call may go out, is up to the `Scheduler`.
+
+use std::collections::{BTreeSet, HashMap};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "app")]
+use tauri::{AppHandle, Manager, State};
+
+use crate::cassette::{Cassette, CassetteMode};
+use crate::providers::{ChatCompletionRequest, ChatMessage, Completion, Provider, Providers};
+use crate::response_cache::{cache_key, ResponseCache};
+use crate::scheduler::{rate_limits, QueueState, Scheduler};
+use crate::scoring::{judge_request, score_response, Judge};
+#[cfg(feature = "app")]
+use crate::db_path;
+use crate::{chrono_now, generate_id, get_run_internal, get_test_suite_internal, open_connection, upsert_result};
+use crate::{write_run, write_run_with_results, Db, ModelParameters, RunResult, TestCase, TestCaseResult, TestSuite};
+
+const DEFAULT_CONCURRENCY: usize = 5;
+const MAX_EMPTY_RESPONSE_RETRIES: u32 = 2;
+const EMPTY_RESPONSE_BACKOFF: Duration = Duration::from_millis(400);
+/// Misses named in the error of a replay that can't run
+const MAX_LISTED_MISSES: usize = 5;
+/// Minimum gap between progress updates of one streaming response
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+/// Minimum gap between queue state updates
+const QUEUE_INTERVAL: Duration = Duration::from_millis(500);
+
+pub const RUN_PROGRESS_EVENT: &str = "run-progress";
+pub const RUN_RESULT_EVENT: &str = "run-result";
+pub const RUN_FINISHED_EVENT: &str = "run-finished";
+pub const RUN_QUEUE_EVENT: &str = "run-queue";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RunRequest {
+    pub test_suite_id: String,
+    pub models: Vec<String>,
+    pub judge_model: Option<String>,
+    #[serde(default)]
+    pub parameters: ModelParameters,
+    /// Concurrent requests; defaults to 5 like the app. A provider drops
+    /// below it while it returns errors.
+    pub concurrency: Option<usize>,
+    /// Ignore cached responses; fresh answers still refresh the cache
+    #[serde(default)]
+    pub bypass_cache: bool,
+}
+
+/// What `execute_run` reports while it works.
+pub enum RunEvent<'a> {
+    /// The response streamed so far for a case that is still running. Sent
+    /// with empty content when the case starts.
+    Progress { test_case_id: &'a str, model_id: &'a str, content: &'a str },
+    /// A finished result, right after it was saved
+    Result(&'a TestCaseResult),
+    /// Queued, running and throttled calls, sent when they change
+    Queue(&'a QueueState),
+}
+
+enum WorkerMessage {
+    Progress(usize, String),
+    Done(usize, Box<TestCaseResult>),
+}
+
+fn pending_result(test_case: &TestCase, model_id: &str) -> TestCaseResult {
+    TestCaseResult {
+        test_case_id: test_case.id.clone(),
+        model_id: model_id.to_string(),
+        provider: None,
+        attempt: 0,
+        response: String::new(),
+        token_count: None,
+        prompt_tokens: None,
+        completion_tokens: None,
+        cost: None,
+        latency_ms: None,
+        status: "idle".to_string(),
+        error: None,
+        score: None,
+        streamed_content: None,
+        cached: false,
+    }
+}
+
+/// What every case of a run shares
+struct RunContext<'a> {
+    providers: &'a Providers,
+    scheduler: &'a Scheduler<'a>,
+    suite: &'a TestSuite,
+    parameters: &'a ModelParameters,
+    judge: Option<Judge<'a>>,
+}
+
+/// Streams the completion, falling back to a plain request when the stream
+/// comes back empty and retrying empty answers a couple of times, like the
+/// frontend does. Every call goes through the scheduler, which retries
+/// errors worth retrying.
+fn complete_with_retries(
+    scheduler: &Scheduler,
+    provider: &dyn Provider,
+    namespace: &str,
+    model_id: &str,
+    request: &ChatCompletionRequest,
+    mut on_progress: impl FnMut(&str),
+) -> Result<Completion, String> {
+    let mut attempt = 0;
+    loop {
+        let completion = scheduler.call(namespace, model_id, request, || {
+            let mut streamed = String::new();
+            let mut last_update = Instant::now();
+            on_progress("");
+
+            provider.chat_completion_stream(request, &mut |delta| {
+                streamed.push_str(delta);
+                if last_update.elapsed() >= PROGRESS_INTERVAL {
+                    last_update = Instant::now();
+                    on_progress(&streamed);
+                }
+            })
+        })?;
+        if !completion.content.trim().is_empty() {
+            return Ok(completion);
+        }
+
+        // A failed fallback just counts as another empty answer
+        if let Ok(fallback) = scheduler.call(namespace, model_id, request, || provider.chat_completion(request)) {
+            if !fallback.content.trim().is_empty() {
+                return Ok(fallback);
+            }
+        }
+
+        if attempt >= MAX_EMPTY_RESPONSE_RETRIES {
+            return Ok(completion);
+        }
+        attempt += 1;
+        thread::sleep(EMPTY_RESPONSE_BACKOFF * attempt);
+    }
+}
+
+/// An answer to a model call and where it came from
+struct Served {
+    completion: Completion,
+    /// For a cache hit, the latency of the call that produced the answer
+    latency_ms: i64,
+    cached: bool,
+}
+
+fn elapsed_ms(started: Instant) -> i64 {
+    started.elapsed().as_millis() as i64
+}
+
+/// Serves a model call from the cassette when replaying, or from the response
+/// cache under `cache_key`. Live answers are recorded when recording and
+/// cached when the cache is on.
+fn complete(
+    providers: &Providers,
+    scheduler: &Scheduler,
+    model_id: &str,
+    request: &ChatCompletionRequest,
+    cache_key: Option<&str>,
+    mut on_progress: impl FnMut(&str),
+) -> Result<Served, String> {
+    let (namespace, model) = providers.split_model_id(model_id);
+    let cassette = providers.cassette();
+    let started = Instant::now();
+
+    if let Some(cassette) = cassette.filter(|cassette| cassette.mode() == CassetteMode::Replay) {
+        let completion = cassette
+            .lookup(namespace, request)?
+            .ok_or_else(|| format!("Not in the cassette: {} · {}", model_id, request.test_case_id.as_deref().unwrap_or_default()))?;
+        on_progress(&completion.content);
+        return Ok(Served { completion, latency_ms: elapsed_ms(started), cached: false });
+    }
+
+    let cache: Option<(&ResponseCache, &str)> = providers.response_cache().zip(cache_key);
+    if let Some((cache, key)) = cache {
+        // The cache only saves money; a broken entry is just a miss
+        match cache.lookup(key) {
+            Ok(Some(hit)) => {
+                on_progress(&hit.completion.content);
+                if let Some(cassette) = cassette {
+                    cassette.record(namespace, request, &hit.completion)?;
+                }
+                let latency_ms = hit.latency_ms.unwrap_or_else(|| elapsed_ms(started));
+                return Ok(Served { completion: hit.completion, latency_ms, cached: true });
+            }
+            Ok(None) => {}
+            Err(err) => eprintln!("Failed to read the response cache: {}", err),
+        }
+    }
+
+    let (provider, _, _) = providers.resolve(model_id)?;
+    let completion = complete_with_retries(scheduler, provider, namespace, model_id, request, on_progress)?;
+    let latency_ms = elapsed_ms(started);
+    if let Some(cassette) = cassette {
+        cassette.record(namespace, request, &completion)?;
+    }
+    // Empty answers are retried next time rather than cached
+    if let Some((cache, key)) = cache.filter(|_| !completion.content.trim().is_empty()) {
+        if let Err(err) = cache.store(key, namespace, model, &completion, Some(latency_ms)) {
+            eprintln!("Failed to cache response: {}", err);
+        }
+    }
+    Ok(Served { completion, latency_ms, cached: false })
+}
+
+/// The request sent for a case; `model` is the provider's own id.
+fn case_request(suite: &TestSuite, test_case: &TestCase, model: &str, parameters: &ModelParameters) -> ChatCompletionRequest {
+    let mut messages = Vec::new();
+    if !suite.system_prompt.is_empty() {
+        messages.push(ChatMessage::system(&suite.system_prompt));
+    }
+    messages.push(ChatMessage::user(&test_case.prompt));
+
+    let mut request = ChatCompletionRequest::new(model, messages, parameters);
+    request.test_case_id = Some(test_case.id.clone());
+    request
+}
+
+fn execute_case(context: &RunContext, test_case: &TestCase, model_id: &str, on_progress: impl FnMut(&str)) -> TestCaseResult {
+    let RunContext { providers, scheduler, suite, parameters, .. } = context;
+    let mut result = pending_result(test_case, model_id);
+
+    let (namespace, model) = providers.split_model_id(model_id);
+    result.provider = Some(namespace.to_string());
+    let request = case_request(suite, test_case, model, parameters);
+    let key = providers
+        .response_cache()
+        .map(|_| cache_key(namespace, model, &suite.system_prompt, &test_case.prompt, parameters));
+
+    match complete(providers, scheduler, model_id, &request, key.as_deref(), on_progress) {
+        Ok(Served { completion, latency_ms, cached }) => {
+            result.latency_ms = Some(latency_ms);
+            result.cached = cached;
+            if let Some(usage) = &completion.usage {
+                result.prompt_tokens = Some(usage.prompt_tokens);
+                result.completion_tokens = Some(usage.completion_tokens);
+                result.token_count = Some(usage.total_tokens);
+                // A hit is free; the cost was paid by the call that filled the cache
+                result.cost = if cached { Some(0.0) } else { usage.cost };
+            }
+            result.score =
+                Some(score_response(test_case, &completion.content, context.judge.as_ref(), suite.judge_system_prompt.as_deref()));
+            result.response = completion.content;
+            result.status = "completed".to_string();
+        }
+        Err(err) => {
+            result.status = "failed".to_string();
+            result.error = Some(err);
+        }
+    }
+
+    result
+}
+
+/// Fails unless every case and model of a replayed run is in the cassette,
+/// along with the judge's grade of every recorded llm-judge answer when live
+/// judging is not allowed.
+fn check_cassette(providers: &Providers, cassette: &Cassette, suite: &TestSuite, request: &RunRequest) -> Result<(), String> {
+    let judge = request
+        .judge_model
+        .as_deref()
+        .filter(|_| !cassette.allows_live_judge())
+        .map(|judge_model| (judge_model, providers.split_model_id(judge_model)));
+
+    let mut missing = Vec::new();
+    let mut total = 0;
+    for test_case in &suite.test_cases {
+        for model_id in &request.models {
+            total += 1;
+            let (namespace, model) = providers.split_model_id(model_id);
+            let Some(recorded) = cassette.lookup(namespace, &case_request(suite, test_case, model, &request.parameters))? else {
+                missing.push(format!("{} · {}", model_id, test_case.id));
+                continue;
+            };
+
+            let Some((judge_model, (judge_namespace, judge_id))) = judge else { continue };
+            // Empty answers are scored without asking the judge
+            if test_case.scoring_method != "llm-judge" || recorded.content.trim().is_empty() {
+                continue;
+            }
+            total += 1;
+            let grade = judge_request(
+                judge_id,
+                &test_case.prompt,
+                &recorded.content,
+                test_case.expected_output.as_deref(),
+                suite.judge_system_prompt.as_deref(),
+            );
+            if !cassette.contains(judge_namespace, &grade)? {
+                missing.push(format!("{} grading {} · {}", judge_model, model_id, test_case.id));
+            }
+        }
+    }
+    if missing.is_empty() {
+        return Ok(());
+    }
+
+    let shown: Vec<&str> = missing.iter().take(MAX_LISTED_MISSES).map(String::as_str).collect();
+    let more = missing.len().saturating_sub(MAX_LISTED_MISSES);
+    Err(format!(
+        "Cannot replay: {} of {} request(s) are not in the cassette ({}{}). Record the run first.",
+        missing.len(),
+        total,
+        shown.join(", "),
+        if more > 0 { format!(" and {} more", more) } else { String::new() }
+    ))
+}
+
+/// Validates the request and saves the run with a pending result for every
+/// case and model. Fails before anything is saved when a model or the judge
+/// belongs to a provider that is not set up, or when a replayed run is not
+/// fully recorded (judge answers included, unless live judging is allowed).
+pub fn prepare_run(conn: &Connection, providers: &Providers, request: &RunRequest) -> Result<(TestSuite, RunResult), String> {
+    let suite = get_test_suite_internal(conn, &request.test_suite_id)?
+        .ok_or_else(|| format!("Test suite {} not found.", request.test_suite_id))?;
+    if request.models.is_empty() {
+        return Err("Select at least one model.".to_string());
+    }
+    if suite.test_cases.is_empty() {
+        return Err(format!("Test suite '{}' has no test cases.", suite.name));
+    }
+
+    match providers.cassette() {
+        // Replayed calls need no provider; the judge does when it may be asked live
+        Some(cassette) if cassette.mode() == CassetteMode::Replay => {
+            check_cassette(providers, cassette, &suite, request)?;
+            if cassette.allows_live_judge() {
+                providers.check_models(request.judge_model.as_deref())?;
+            }
+        }
+        _ => providers.check_models(request.models.iter().chain(&request.judge_model).map(String::as_str))?,
+    }
+
+    let run = RunResult {
+        id: generate_id(),
+        test_suite_id: suite.id.clone(),
+        test_suite_name: suite.name.clone(),
+        test_suite_revision: None,
+        models: request.models.clone(),
+        parameters: request.parameters.clone(),
+        results: suite
+            .test_cases
+            .iter()
+            .flat_map(|test_case| request.models.iter().map(move |model_id| pending_result(test_case, model_id)))
+            .collect(),
+        status: "running".to_string(),
+        started_at: chrono_now(),
+        completed_at: None,
+        judge_model: request.judge_model.clone(),
+        error_count: None,
+        error_summary: None,
+    };
+    write_run_with_results(conn, &run)?;
+
+    // Pick up the suite revision assigned on save
+    let run = get_run_internal(conn, &run.id)?.unwrap_or(run);
+    Ok((suite, run))
+}
+
+/// Executes the pending results of a prepared run and saves the outcome.
+/// Once `cancel` is set no new case starts; requests already in flight
+/// finish, calls waiting for a rate limit give up, and the rest are marked
+/// cancelled. A result that fails to save sets `cancel` and ends the run
+/// with the error.
+pub fn execute_prepared_run(
+    conn: &Connection,
+    providers: &Providers,
+    suite: &TestSuite,
+    mut run: RunResult,
+    concurrency: Option<usize>,
+    cancel: &AtomicBool,
+    mut on_event: impl FnMut(RunEvent),
+) -> Result<RunResult, String> {
+    let cases: HashMap<&str, &TestCase> = suite.test_cases.iter().map(|test_case| (test_case.id.as_str(), test_case)).collect();
+    let jobs: Vec<(usize, &TestCase, String)> = run
+        .results
+        .iter()
+        .enumerate()
+        .filter(|(_, result)| result.status == "idle")
+        .filter_map(|(index, result)| Some((index, *cases.get(result.test_case_id.as_str())?, result.model_id.clone())))
+        .collect();
+
+    let concurrency = concurrency.unwrap_or(DEFAULT_CONCURRENCY).max(1);
+    let scheduler = Scheduler::new(rate_limits(conn)?, concurrency, cancel);
+    for (job, (_, _, model_id)) in jobs.iter().enumerate() {
+        scheduler.push_job(job, providers.split_model_id(model_id).0, model_id);
+    }
+    let workers = concurrency.clamp(1, jobs.len().max(1));
+    let (sender, receiver) = mpsc::channel();
+    let parameters = run.parameters.clone();
+    let context = RunContext {
+        providers,
+        scheduler: &scheduler,
+        suite,
+        parameters: &parameters,
+        judge: run.judge_model.as_deref().map(|model| Judge { providers, scheduler: &scheduler, model }),
+    };
+
+    thread::scope(|scope| -> Result<(), String> {
+        for _ in 0..workers {
+            let sender = sender.clone();
+            let (jobs, context) = (&jobs, &context);
+            scope.spawn(move || {
+                while let Some(job) = context.scheduler.next_job() {
+                    let (index, test_case, model_id) = &jobs[job];
+                    let on_progress = |content: &str| {
+                        let _ = sender.send(WorkerMessage::Progress(*index, content.to_string()));
+                    };
+                    let result = execute_case(context, test_case, model_id, on_progress);
+                    context.scheduler.finish_job(providers.split_model_id(model_id).0, model_id);
+                    if sender.send(WorkerMessage::Done(*index, Box::new(result))).is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+        drop(sender);
+
+        let mut queue_version = None;
+        let mut queue_sent: Option<Instant> = None;
+        loop {
+            match receiver.recv_timeout(QUEUE_INTERVAL) {
+                Ok(WorkerMessage::Progress(index, content)) => {
+                    let result = &run.results[index];
+                    on_event(RunEvent::Progress { test_case_id: &result.test_case_id, model_id: &result.model_id, content: &content });
+                }
+                Ok(WorkerMessage::Done(index, result)) => {
+                    if let Err(err) = upsert_result(conn, &run.id, &result) {
+                        // Nothing more can be saved, so stop paying for calls:
+                        // workers finish their current case and take no new one
+                        cancel.store(true, Ordering::Relaxed);
+                        drop(receiver);
+                        return Err(err);
+                    }
+                    on_event(RunEvent::Result(&result));
+                    run.results[index] = *result;
+                }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
+            }
+
+            let version = scheduler.version();
+            if queue_version != Some(version) && queue_sent.is_none_or(|sent| sent.elapsed() >= QUEUE_INTERVAL) {
+                on_event(RunEvent::Queue(&scheduler.queue_state()));
+                queue_version = Some(version);
+                queue_sent = Some(Instant::now());
+            }
+        }
+        Ok(())
+    })?;
+
+    let cancelled = cancel.load(Ordering::Relaxed);
+    if cancelled {
+        for result in run.results.iter_mut().filter(|result| result.status == "idle") {
+            result.status = "cancelled".to_string();
+            upsert_result(conn, &run.id, result)?;
+            on_event(RunEvent::Result(result));
+        }
+    }
+
+    let errors: Vec<&str> = run.results.iter().filter_map(|result| result.error.as_deref()).collect();
+    if !errors.is_empty() {
+        let unique: BTreeSet<&str> = errors.iter().copied().collect();
+        run.error_count = Some(errors.len() as i64);
+        run.error_summary = Some(format!(
+            "{} task(s) failed: {}",
+            errors.len(),
+            unique.into_iter().collect::<Vec<_>>().join("; ")
+        ));
+    }
+    run.status = if cancelled { "cancelled" } else { "completed" }.to_string();
+    run.completed_at = Some(chrono_now());
+    write_run(conn, &run)?;
+
+    Ok(run)
+}
+
+/// Executes every test case against every model and saves the run.
+pub fn execute_run(
+    conn: &Connection,
+    providers: &Providers,
+    request: &RunRequest,
+    cancel: &AtomicBool,
+    on_event: impl FnMut(RunEvent),
+) -> Result<RunResult, String> {
+    let (suite, run) = prepare_run(conn, providers, request)?;
+    execute_prepared_run(conn, providers, &suite, run, request.concurrency, cancel, on_event)
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Cancellation flags of the runs executing in the backend, by run id
+#[derive(Default)]
+pub struct ActiveRuns(Mutex<HashMap<String, Arc<AtomicBool>>>);
+
+impl ActiveRuns {
+    fn flags(&self) -> Result<std::sync::MutexGuard<'_, HashMap<String, Arc<AtomicBool>>>, String> {
+        self.0.lock().map_err(|_| "Run registry is unavailable after a previous failure.".to_string())
+    }
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct RunProgressPayload<'a> {
+    run_id: &'a str,
+    test_case_id: &'a str,
+    model_id: &'a str,
+    content: &'a str,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct RunResultPayload<'a> {
+    run_id: &'a str,
+    result: &'a TestCaseResult,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct RunQueuePayload<'a> {
+    run_id: &'a str,
+    queue: &'a QueueState,
+}
+
+#[derive(Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+struct RunFinishedPayload<'a> {
+    run_id: &'a str,
+    /// The saved run, or `None` when execution stopped on an error
+    run: Option<&'a RunResult>,
+    error: Option<&'a str>,
+}
+
+#[cfg(feature = "app")]
+fn emit_run_event(app: &AppHandle, run_id: &str, event: RunEvent) {
+    let sent = match event {
+        RunEvent::Progress { test_case_id, model_id, content } => {
+            app.emit_all(RUN_PROGRESS_EVENT, RunProgressPayload { run_id, test_case_id, model_id, content })
+        }
+        RunEvent::Result(result) => app.emit_all(RUN_RESULT_EVENT, RunResultPayload { run_id, result }),
+        RunEvent::Queue(queue) => app.emit_all(RUN_QUEUE_EVENT, RunQueuePayload { run_id, queue }),
+    };
+    if let Err(err) = sent {
+        eprintln!("Failed to emit run event: {}", err);
+    }
+}
+
+/// Saves the run and executes it on a background thread, returning the
+/// pending run right away. Progress arrives as `run-progress`, `run-result`,
+/// `run-queue` and `run-finished` events.
+/// `cassette` records the run's model calls in the app database, or replays
+/// them from it; `allow_live_judge` lets a replay grade with a judge whose
+/// answers were not recorded. The response cache is used when enabled in the
+/// settings.
+#[cfg(feature = "app")]
+#[tauri::command]
+pub fn start_run(
+    app: AppHandle,
+    db: State<'_, Db>,
+    active_runs: State<'_, ActiveRuns>,
+    request: RunRequest,
+    api_key: Option<String>,
+    cassette: Option<CassetteMode>,
+    allow_live_judge: Option<bool>,
+) -> Result<RunResult, String> {
+    let path = db_path(&app)?;
+    let conn = db.conn()?;
+    let mut providers = Providers::load(&conn, api_key.as_deref())?;
+    if let Some(mode) = cassette {
+        providers = providers.with_cassette(Cassette::open(&path, mode)?.with_live_judge(allow_live_judge.unwrap_or(false)));
+    }
+    if let Some(cache) = ResponseCache::open(&path, request.bypass_cache)? {
+        providers = providers.with_response_cache(cache);
+    }
+    let (suite, run) = prepare_run(&conn, &providers, &request)?;
+    drop(conn);
+
+    let cancel = Arc::new(AtomicBool::new(false));
+    active_runs.flags()?.insert(run.id.clone(), cancel.clone());
+
+    let pending = run.clone();
+    thread::spawn(move || {
+        let run_id = run.id.clone();
+        // A connection of its own, so the run doesn't hold the shared one
+        let outcome = open_connection(&path).and_then(|conn| {
+            execute_prepared_run(&conn, &providers, &suite, run, request.concurrency, &cancel, |event| {
+                emit_run_event(&app, &run_id, event)
+            })
+        });
+
+        if let Ok(mut flags) = app.state::<ActiveRuns>().flags() {
+            flags.remove(&run_id);
+        }
+        let payload = match &outcome {
+            Ok(run) => RunFinishedPayload { run_id: &run_id, run: Some(run), error: None },
+            Err(err) => RunFinishedPayload { run_id: &run_id, run: None, error: Some(err) },
+        };
+        if let Err(err) = app.emit_all(RUN_FINISHED_EVENT, payload) {
+            eprintln!("Failed to emit run event: {}", err);
+        }
+    });
+
+    Ok(pending)
+}
+
+/// Stops a backend run from starting new cases. Returns false when the run
+/// is not executing.
+#[cfg(feature = "app")]
+#[tauri::command]
+pub fn cancel_run(active_runs: State<'_, ActiveRuns>, run_id: String) -> Result<bool, String> {
+    let flags = active_runs.flags()?;
+    match flags.get(&run_id) {
+        Some(cancel) => {
+            cancel.store(true, Ordering::Relaxed);
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+/// Ids of the runs currently executing in the backend
+#[cfg(feature = "app")]
+#[tauri::command]
+pub fn list_active_runs(active_runs: State<'_, ActiveRuns>) -> Result<Vec<String>, String> {
+    Ok(active_runs.flags()?.keys().cloned().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    use crate::providers::{save_provider_config, ProviderConfig, ProviderKind};
+    use crate::response_cache::{save_cache_settings, CacheSettings};
+    use crate::test_support::{memory_db, suite};
+    use crate::write_test_suite;
+
+    fn request() -> RunRequest {
+        RunRequest {
+            test_suite_id: "s1".to_string(),
+            models: vec!["mock:good".to_string()],
+            judge_model: Some("mock:judge".to_string()),
+            parameters: ModelParameters::default(),
+            concurrency: None,
+            bypass_cache: false,
+        }
+    }
+
+    /// Points the `mock` provider at a fixture written to `dir`.
+    fn mock_fixture(conn: &Connection, dir: &Path, json: &str) {
+        let fixture = dir.join("fixture.json");
+        std::fs::write(&fixture, json).unwrap();
+        let config = ProviderConfig {
+            id: "mock".to_string(),
+            kind: ProviderKind::Mock,
+            base_url: None,
+            api_key: None,
+            fixture: Some(fixture.to_string_lossy().into_owned()),
+        };
+        save_provider_config(conn, &config).unwrap();
+    }
+
+    #[test]
+    fn cache_hits_cost_nothing() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("app.db");
+        let conn = open_connection(&path).unwrap();
+        mock_fixture(&conn, dir.path(), r#"{"defaults": {"cost": 0.25}}"#);
+        save_cache_settings(&conn, &CacheSettings { enabled: true, ttl_hours: 0, max_size_mb: 0 }).unwrap();
+        write_test_suite(&conn, &suite("s1", &["a"])).unwrap();
+        let request = RunRequest { judge_model: None, ..request() };
+
+        let mut costs = Vec::new();
+        for _ in 0..2 {
+            let cache = ResponseCache::open(&path, false).unwrap().unwrap();
+            let providers = Providers::load(&conn, None).unwrap().with_response_cache(cache);
+            let run = execute_run(&conn, &providers, &request, &AtomicBool::new(false), |_| {}).unwrap();
+            costs.push((run.results[0].cached, run.results[0].cost));
+        }
+        assert_eq!(costs, vec![(false, Some(0.25)), (true, Some(0.0))]);
+    }
+
+    #[test]
+    fn a_failed_save_stops_the_workers() {
+        let conn = memory_db();
+        let dir = tempfile::tempdir().unwrap();
+        mock_fixture(&conn, dir.path(), r#"{"defaults": {"latencyMs": 20}}"#);
+
+        let case_ids: Vec<String> = (0..20).map(|index| format!("c{}", index)).collect();
+        let case_ids: Vec<&str> = case_ids.iter().map(String::as_str).collect();
+        write_test_suite(&conn, &suite("s1", &case_ids)).unwrap();
+
+        // Every model call lands in the cassette, so it counts the calls made
+        let cassette_path = dir.path().join("cassette.db");
+        let cassette = Cassette::open(&cassette_path, CassetteMode::Record).unwrap();
+        let providers = Providers::load(&conn, None).unwrap().with_cassette(cassette);
+        let request = RunRequest { judge_model: None, concurrency: Some(1), ..request() };
+        let (suite, run) = prepare_run(&conn, &providers, &request).unwrap();
+
+        conn.execute_batch(
+            "CREATE TRIGGER fail_results BEFORE UPDATE ON test_case_results BEGIN SELECT RAISE(FAIL, 'disk full'); END;",
+        )
+        .unwrap();
+        let cancel = AtomicBool::new(false);
+        let err = execute_prepared_run(&conn, &providers, &suite, run, request.concurrency, &cancel, |_| {}).unwrap_err();
+        assert!(err.contains("disk full"), "{}", err);
+        assert!(cancel.load(Ordering::Relaxed));
+
+        let calls: i64 = open_connection(&cassette_path)
+            .unwrap()
+            .query_row("SELECT COUNT(*) FROM cassette_entries", [], |row| row.get(0))
+            .unwrap();
+        assert!(calls <= 2, "{} calls after the failed save", calls);
+    }
+}
@@ -0,0 +1,723 @@
+//! Database health checks. `check_database` looks for the damage an
+//! interrupted write can leave behind; `repair_database` backs up first, then
+//! fixes what it can and moves anything it has to drop into `quarantine`,
+//! from where `restore_quarantined` can put it back.
+
+use rusqlite::types::{Value, ValueRef};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "app")]
+use tauri::State;
+
+use crate::backups::{create_backup_internal, BackupInfo, BackupKind};
+use crate::{chrono_now, Db, ModelParameters, ScoringResult, TestCase};
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum IssueKind {
+    /// Reported by `PRAGMA integrity_check`
+    Integrity,
+    /// Row whose parent (suite or run) no longer exists
+    MissingParent,
+    /// Result for a test case missing from the suite revision its run is
+    /// pinned to
+    OrphanedResult,
+    /// JSON column that the readers would silently replace with a default
+    InvalidJson,
+    /// `app_state` pointing at a missing or trashed item
+    DanglingReference,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseIssue {
+    pub kind: IssueKind,
+    pub table: String,
+    pub row_id: Option<i64>,
+    pub column: Option<String>,
+    pub detail: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckReport {
+    pub checked_at: i64,
+    pub issues: Vec<DatabaseIssue>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum RepairOutcome {
+    /// Row moved to the quarantine table and deleted
+    Quarantined,
+    /// Bad value kept in quarantine and the column reset
+    Reset,
+    /// Dangling id cleared
+    Cleared,
+    /// Indexes rebuilt
+    Rebuilt,
+    /// Reported only; results are run history and are never deleted
+    Kept,
+    /// Left alone; restoring a backup is the only fix
+    Unrepaired,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RepairAction {
+    pub issue: DatabaseIssue,
+    pub outcome: RepairOutcome,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RepairReport {
+    /// Backup taken before anything was changed
+    pub backup: Option<BackupInfo>,
+    pub actions: Vec<RepairAction>,
+    /// Fresh check after the repair
+    pub after: CheckReport,
+}
+
+fn issue(kind: IssueKind, table: &str, row_id: Option<i64>, column: Option<&str>, detail: String) -> DatabaseIssue {
+    DatabaseIssue {
+        kind,
+        table: table.to_string(),
+        row_id,
+        column: column.map(str::to_string),
+        detail,
+    }
+}
+
+// ============================================================================
+// Checks
+// ============================================================================
+
+fn check_integrity(conn: &Connection, issues: &mut Vec<DatabaseIssue>) -> Result<(), String> {
+    let mut stmt = conn.prepare("PRAGMA integrity_check").map_err(|err| err.to_string())?;
+    let rows = stmt
+        .query_map([], |row| row.get::<_, String>(0))
+        .map_err(|err| err.to_string())?;
+
+    for row in rows {
+        let message = row.map_err(|err| err.to_string())?;
+        if message != "ok" {
+            issues.push(issue(IssueKind::Integrity, "", None, None, message));
+        }
+    }
+
+    Ok(())
+}
+
+fn check_foreign_keys(conn: &Connection, issues: &mut Vec<DatabaseIssue>) -> Result<(), String> {
+    let mut stmt = conn.prepare("PRAGMA foreign_key_check").map_err(|err| err.to_string())?;
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, Option<i64>>(1)?, row.get::<_, String>(2)?))
+        })
+        .map_err(|err| err.to_string())?;
+
+    for row in rows {
+        let (table, row_id, parent) = row.map_err(|err| err.to_string())?;
+        issues.push(issue(
+            IssueKind::MissingParent,
+            &table,
+            row_id,
+            None,
+            format!("References a row in {} that does not exist", parent),
+        ));
+    }
+
+    Ok(())
+}
+
+fn check_orphaned_results(conn: &Connection, issues: &mut Vec<DatabaseIssue>) -> Result<(), String> {
+    // Revisions only go back to schema v7, and runs keep results for cases
+    // removed before then or for suites deleted outright. Only a run pinned to
+    // a stored revision says which cases it had, so only those are checked.
+    let mut stmt = conn
+        .prepare(
+            "SELECT r.id, r.run_id, r.test_case_id
+             FROM test_case_results r
+             JOIN runs ON runs.id = r.run_id
+             JOIN test_suite_revisions v
+               ON v.test_suite_id = runs.test_suite_id AND v.revision = runs.test_suite_revision
+             WHERE json_valid(v.test_cases)
+             AND NOT EXISTS (
+                 SELECT 1 FROM json_each(v.test_cases) j
+                 WHERE json_extract(j.value, '$.id') = r.test_case_id
+             )
+             AND NOT EXISTS (
+                 SELECT 1 FROM test_cases tc
+                 WHERE tc.id = r.test_case_id AND tc.test_suite_id = runs.test_suite_id
+             )",
+        )
+        .map_err(|err| err.to_string())?;
+
+    let rows = stmt
+        .query_map([], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?))
+        })
+        .map_err(|err| err.to_string())?;
+
+    for row in rows {
+        let (row_id, run_id, test_case_id) = row.map_err(|err| err.to_string())?;
+        issues.push(issue(
+            IssueKind::OrphanedResult,
+            "test_case_results",
+            Some(row_id),
+            None,
+            format!("Run {} has a result for test case {}, which its suite revision doesn't contain", run_id, test_case_id),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Flags values in `table.column` that don't deserialize as `T`, which is
+/// exactly what the readers' `unwrap_or_default` would hide.
+fn check_json_column<T: DeserializeOwned>(
+    conn: &Connection,
+    table: &str,
+    column: &str,
+    issues: &mut Vec<DatabaseIssue>,
+) -> Result<(), String> {
+    let mut stmt = conn
+        .prepare(&format!("SELECT rowid, {} FROM {} WHERE {} IS NOT NULL", column, table, column))
+        .map_err(|err| err.to_string())?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?)))
+        .map_err(|err| err.to_string())?;
+
+    for row in rows {
+        let (row_id, value) = row.map_err(|err| err.to_string())?;
+        if let Err(err) = serde_json::from_str::<T>(&value) {
+            issues.push(issue(IssueKind::InvalidJson, table, Some(row_id), Some(column), err.to_string()));
+        }
+    }
+
+    Ok(())
+}
+
+const APP_STATE_REFERENCES: [(&str, &str); 3] = [
+    ("active_test_suite_id", "test_suites"),
+    ("current_run_id", "runs"),
+    ("current_code_arena_run_id", "code_arena_runs"),
+];
+
+fn check_app_state(conn: &Connection, issues: &mut Vec<DatabaseIssue>) -> Result<(), String> {
+    for (column, target) in APP_STATE_REFERENCES {
+        let dangling: Option<String> = conn
+            .query_row(
+                &format!(
+                    "SELECT {column} FROM app_state
+                     WHERE id = 1 AND {column} IS NOT NULL
+                       AND NOT EXISTS (SELECT 1 FROM {target} WHERE id = app_state.{column} AND deleted_at IS NULL)"
+                ),
+                [],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(|err| err.to_string())?;
+
+        if let Some(id) = dangling {
+            issues.push(issue(
+                IssueKind::DanglingReference,
+                "app_state",
+                Some(1),
+                Some(column),
+                format!("Points at {} {} which does not exist", target, id),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+pub fn check_database_internal(conn: &Connection) -> Result<CheckReport, String> {
+    let mut issues = Vec::new();
+
+    check_integrity(conn, &mut issues)?;
+    check_foreign_keys(conn, &mut issues)?;
+    check_orphaned_results(conn, &mut issues)?;
+    check_json_column::<Vec<String>>(conn, "test_cases", "tags", &mut issues)?;
+    check_json_column::<Vec<String>>(conn, "runs", "models", &mut issues)?;
+    check_json_column::<ModelParameters>(conn, "runs", "parameters", &mut issues)?;
+    check_json_column::<ScoringResult>(conn, "test_case_results", "score", &mut issues)?;
+    check_json_column::<Vec<String>>(conn, "code_arena_runs", "models", &mut issues)?;
+    check_json_column::<ModelParameters>(conn, "code_arena_runs", "parameters", &mut issues)?;
+    check_json_column::<ScoringResult>(conn, "code_arena_outputs", "score", &mut issues)?;
+    check_json_column::<Vec<TestCase>>(conn, "test_suite_revisions", "test_cases", &mut issues)?;
+    check_app_state(conn, &mut issues)?;
+
+    Ok(CheckReport {
+        checked_at: chrono_now(),
+        issues,
+    })
+}
+
+// ============================================================================
+// Repair
+// ============================================================================
+
+fn row_as_json(conn: &Connection, table: &str, row_id: i64) -> Result<Option<serde_json::Value>, String> {
+    let mut stmt = conn
+        .prepare(&format!("SELECT * FROM {} WHERE rowid = ?", table))
+        .map_err(|err| err.to_string())?;
+    let columns: Vec<String> = stmt.column_names().iter().map(|name| name.to_string()).collect();
+
+    let mut rows = stmt.query(params![row_id]).map_err(|err| err.to_string())?;
+    let Some(row) = rows.next().map_err(|err| err.to_string())? else {
+        return Ok(None);
+    };
+
+    let mut object = serde_json::Map::new();
+    for (index, name) in columns.iter().enumerate() {
+        let value = match row.get_ref(index).map_err(|err| err.to_string())? {
+            ValueRef::Null => serde_json::Value::Null,
+            ValueRef::Integer(value) => value.into(),
+            ValueRef::Real(value) => value.into(),
+            ValueRef::Text(value) => String::from_utf8_lossy(value).into(),
+            ValueRef::Blob(value) => hex::encode(value).into(),
+        };
+        object.insert(name.clone(), value);
+    }
+
+    Ok(Some(serde_json::Value::Object(object)))
+}
+
+fn quarantine(conn: &Connection, issue: &DatabaseIssue, payload: &str) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO quarantine (source_table, source_rowid, column_name, reason, payload, quarantined_at)
+         VALUES (?, ?, ?, ?, ?, ?)",
+        params![issue.table, issue.row_id, issue.column, issue.detail, payload, chrono_now()],
+    ).map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+fn quarantine_row(conn: &Connection, issue: &DatabaseIssue) -> Result<RepairOutcome, String> {
+    let Some(row_id) = issue.row_id else {
+        return Ok(RepairOutcome::Unrepaired);
+    };
+
+    // Already gone, e.g. removed along with an orphaned parent
+    let Some(row) = row_as_json(conn, &issue.table, row_id)? else {
+        return Ok(RepairOutcome::Quarantined);
+    };
+
+    quarantine(conn, issue, &row.to_string())?;
+    conn.execute(&format!("DELETE FROM {} WHERE rowid = ?", issue.table), params![row_id])
+        .map_err(|err| err.to_string())?;
+
+    Ok(RepairOutcome::Quarantined)
+}
+
+/// Replacement for an unparsable JSON column, or `None` when there is no
+/// sensible default (suite revisions are history and stay as they are).
+fn replacement_json(conn: &Connection, table: &str, column: &str, row_id: i64) -> Result<Option<Option<String>>, String> {
+    let models_from = |child: &str| -> Result<Option<Option<String>>, String> {
+        // Recover the model list from the rows that belong to the run
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT model_id FROM {child}
+                 WHERE run_id = (SELECT id FROM {table} WHERE rowid = ?)
+                 GROUP BY model_id ORDER BY MIN(id)"
+            ))
+            .map_err(|err| err.to_string())?;
+        let models = stmt
+            .query_map(params![row_id], |row| row.get::<_, String>(0))
+            .map_err(|err| err.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|err| err.to_string())?;
+        Ok(Some(Some(serde_json::to_string(&models).map_err(|err| err.to_string())?)))
+    };
+
+    match (table, column) {
+        ("runs", "models") => models_from("test_case_results"),
+        ("code_arena_runs", "models") => models_from("code_arena_outputs"),
+        (_, "parameters") => Ok(Some(Some(
+            serde_json::to_string(&ModelParameters::default()).map_err(|err| err.to_string())?,
+        ))),
+        (_, "tags") => Ok(Some(Some("[]".to_string()))),
+        (_, "score") => Ok(Some(None)),
+        _ => Ok(None),
+    }
+}
+
+fn reset_json(conn: &Connection, issue: &DatabaseIssue) -> Result<RepairOutcome, String> {
+    let (Some(row_id), Some(column)) = (issue.row_id, issue.column.as_deref()) else {
+        return Ok(RepairOutcome::Unrepaired);
+    };
+    let Some(replacement) = replacement_json(conn, &issue.table, column, row_id)? else {
+        return Ok(RepairOutcome::Unrepaired);
+    };
+
+    let original: String = conn
+        .query_row(&format!("SELECT {} FROM {} WHERE rowid = ?", column, issue.table), params![row_id], |row| row.get(0))
+        .map_err(|err| err.to_string())?;
+    quarantine(conn, issue, &original)?;
+
+    conn.execute(
+        &format!("UPDATE {} SET {} = ? WHERE rowid = ?", issue.table, column),
+        params![replacement, row_id],
+    ).map_err(|err| err.to_string())?;
+
+    Ok(RepairOutcome::Reset)
+}
+
+fn rebuild_indexes(conn: &Connection) -> Result<RepairOutcome, String> {
+    conn.execute_batch(
+        "REINDEX;
+         INSERT INTO test_cases_fts(test_cases_fts) VALUES ('rebuild');
+         INSERT INTO results_fts(results_fts) VALUES ('rebuild');",
+    ).map_err(|err| err.to_string())?;
+    Ok(RepairOutcome::Rebuilt)
+}
+
+pub fn repair_database_internal(conn: &mut Connection) -> Result<RepairReport, String> {
+    let report = check_database_internal(conn)?;
+    if report.issues.is_empty() {
+        return Ok(RepairReport {
+            backup: None,
+            actions: Vec::new(),
+            after: report,
+        });
+    }
+
+    let backup = create_backup_internal(conn, BackupKind::PreRepair)?;
+
+    let tx = conn.transaction().map_err(|err| err.to_string())?;
+    let mut rebuilt = false;
+    let mut actions = Vec::with_capacity(report.issues.len());
+
+    for issue in report.issues {
+        let outcome = match issue.kind {
+            // Index damage is the one kind a rebuild can fix; anything else
+            // needs a backup restored.
+            IssueKind::Integrity if !rebuilt => {
+                rebuilt = true;
+                rebuild_indexes(&tx)?
+            }
+            IssueKind::Integrity => RepairOutcome::Rebuilt,
+            IssueKind::MissingParent => quarantine_row(&tx, &issue)?,
+            IssueKind::OrphanedResult => RepairOutcome::Kept,
+            IssueKind::InvalidJson => reset_json(&tx, &issue)?,
+            IssueKind::DanglingReference => {
+                let column = issue.column.as_deref().unwrap_or_default();
+                tx.execute(&format!("UPDATE app_state SET {} = NULL WHERE id = 1", column), [])
+                    .map_err(|err| err.to_string())?;
+                RepairOutcome::Cleared
+            }
+        };
+        actions.push(RepairAction { issue, outcome });
+    }
+
+    tx.commit().map_err(|err| err.to_string())?;
+
+    // Anything the rebuild couldn't fix shows up again here
+    let after = check_database_internal(conn)?;
+    if after.issues.iter().any(|issue| issue.kind == IssueKind::Integrity) {
+        for action in actions.iter_mut().filter(|action| action.issue.kind == IssueKind::Integrity) {
+            action.outcome = RepairOutcome::Unrepaired;
+        }
+    }
+
+    Ok(RepairReport {
+        backup,
+        actions,
+        after,
+    })
+}
+
+// ============================================================================
+// Quarantine
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct QuarantinedRow {
+    pub id: i64,
+    pub source_table: String,
+    pub source_rowid: Option<i64>,
+    /// Set when only this column's value was replaced; `None` for whole rows
+    pub column_name: Option<String>,
+    pub reason: String,
+    pub payload: String,
+    pub quarantined_at: i64,
+}
+
+pub fn list_quarantine_internal(conn: &Connection) -> Result<Vec<QuarantinedRow>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, source_table, source_rowid, column_name, reason, payload, quarantined_at
+             FROM quarantine ORDER BY quarantined_at DESC, id DESC",
+        )
+        .map_err(|err| err.to_string())?;
+
+    let rows = stmt
+        .query_map([], |row| {
+            Ok(QuarantinedRow {
+                id: row.get(0)?,
+                source_table: row.get(1)?,
+                source_rowid: row.get(2)?,
+                column_name: row.get(3)?,
+                reason: row.get(4)?,
+                payload: row.get(5)?,
+                quarantined_at: row.get(6)?,
+            })
+        })
+        .map_err(|err| err.to_string())?;
+
+    rows.collect::<Result<_, _>>().map_err(|err| err.to_string())
+}
+
+/// Column names of an existing table. Quarantine entries name their table
+/// and column, so they are checked against the schema before use in SQL.
+fn table_columns(conn: &Connection, table: &str) -> Result<Vec<String>, String> {
+    let mut stmt = conn
+        .prepare("SELECT name FROM pragma_table_info(?)")
+        .map_err(|err| err.to_string())?;
+    let columns = stmt
+        .query_map(params![table], |row| row.get::<_, String>(0))
+        .map_err(|err| err.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| err.to_string())?;
+
+    if columns.is_empty() {
+        return Err(format!("Table {} does not exist", table));
+    }
+    Ok(columns)
+}
+
+fn json_to_sql(value: &serde_json::Value) -> Value {
+    match value {
+        serde_json::Value::Null => Value::Null,
+        serde_json::Value::Bool(value) => Value::Integer(*value as i64),
+        serde_json::Value::Number(number) => match number.as_i64() {
+            Some(value) => Value::Integer(value),
+            None => Value::Real(number.as_f64().unwrap_or_default()),
+        },
+        serde_json::Value::String(value) => Value::Text(value.clone()),
+        other => Value::Text(other.to_string()),
+    }
+}
+
+fn restore_entry(conn: &Connection, entry: &QuarantinedRow) -> Result<(), String> {
+    let columns = table_columns(conn, &entry.source_table)?;
+
+    match &entry.column_name {
+        // A reset column gets its original value back
+        Some(column) => {
+            if !columns.contains(column) {
+                return Err(format!("Column {} no longer exists in {}", column, entry.source_table));
+            }
+            let updated = conn
+                .execute(
+                    &format!("UPDATE \"{}\" SET \"{}\" = ? WHERE rowid = ?", entry.source_table, column),
+                    params![entry.payload, entry.source_rowid],
+                )
+                .map_err(|err| err.to_string())?;
+            if updated == 0 {
+                return Err(format!("Row {:?} of {} no longer exists", entry.source_rowid, entry.source_table));
+            }
+        }
+        // A quarantined row is inserted again as it was
+        None => {
+            let payload: serde_json::Map<String, serde_json::Value> =
+                serde_json::from_str(&entry.payload).map_err(|err| err.to_string())?;
+            let (names, values): (Vec<&String>, Vec<Value>) = payload
+                .iter()
+                .filter(|(name, _)| columns.contains(name))
+                .map(|(name, value)| (name, json_to_sql(value)))
+                .unzip();
+
+            let column_list = names.iter().map(|name| format!("\"{}\"", name)).collect::<Vec<_>>().join(", ");
+            let placeholders = vec!["?"; names.len()].join(", ");
+            conn.execute(
+                &format!("INSERT INTO \"{}\" ({}) VALUES ({})", entry.source_table, column_list, placeholders),
+                rusqlite::params_from_iter(values),
+            )
+            .map_err(|err| err.to_string())?;
+        }
+    }
+
+    conn.execute("DELETE FROM quarantine WHERE id = ?", params![entry.id])
+        .map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+/// Puts quarantined rows and values back where they came from. All or
+/// nothing: a row whose parent is still missing fails the whole restore.
+pub fn restore_quarantined_internal(conn: &mut Connection, ids: &[i64]) -> Result<usize, String> {
+    let entries: Vec<QuarantinedRow> = list_quarantine_internal(conn)?
+        .into_iter()
+        .filter(|entry| ids.contains(&entry.id))
+        .collect();
+
+    let tx = conn.transaction().map_err(|err| err.to_string())?;
+    // Newest first, so a value reset twice ends up as it originally was
+    for entry in &entries {
+        restore_entry(&tx, entry)
+            .map_err(|err| format!("Unable to restore quarantined entry {}: {}", entry.id, err))?;
+    }
+    tx.commit().map_err(|err| err.to_string())?;
+
+    Ok(entries.len())
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[cfg(feature = "app")]
+#[tauri::command]
+pub fn check_database(db: State<'_, Db>) -> Result<CheckReport, String> {
+    let conn = db.conn()?;
+    check_database_internal(&conn)
+}
+
+#[cfg(feature = "app")]
+#[tauri::command]
+pub fn repair_database(db: State<'_, Db>) -> Result<RepairReport, String> {
+    let mut conn = db.conn()?;
+    repair_database_internal(&mut conn)
+}
+
+#[cfg(feature = "app")]
+#[tauri::command]
+pub fn list_quarantine(db: State<'_, Db>) -> Result<Vec<QuarantinedRow>, String> {
+    let conn = db.conn()?;
+    list_quarantine_internal(&conn)
+}
+
+/// Returns how many entries were restored.
+#[cfg(feature = "app")]
+#[tauri::command]
+pub fn restore_quarantined(db: State<'_, Db>, ids: Vec<i64>) -> Result<usize, String> {
+    let mut conn = db.conn()?;
+    restore_quarantined_internal(&mut conn, &ids)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::migrations::{apply_migrations, open_at_version, CURRENT_SCHEMA_VERSION};
+
+    fn parameters_json() -> String {
+        serde_json::to_string(&ModelParameters::default()).unwrap()
+    }
+
+    fn insert_run(conn: &Connection, id: &str, suite_id: &str, started_at: i64) {
+        conn.execute(
+            "INSERT INTO runs (id, test_suite_id, test_suite_name, models, parameters, status, started_at, completed_at)
+             VALUES (?, ?, 'Suite', '[\"m\"]', ?, 'completed', ?, ?)",
+            params![id, suite_id, parameters_json(), started_at, started_at + 10],
+        ).unwrap();
+    }
+
+    fn insert_result(conn: &Connection, run_id: &str, test_case_id: &str) {
+        conn.execute(
+            "INSERT INTO test_case_results (run_id, test_case_id, model_id, response, status)
+             VALUES (?, ?, 'm', 'answer', 'completed')",
+            params![run_id, test_case_id],
+        ).unwrap();
+    }
+
+    fn result_count(conn: &Connection) -> i64 {
+        conn.query_row("SELECT COUNT(*) FROM test_case_results", [], |row| row.get(0)).unwrap()
+    }
+
+    /// A database from before suite revisions: case `c` was removed from the
+    /// suite after `r-old` ran, and the suite of `r-gone` was hard-deleted.
+    fn pre_revision_fixture() -> Connection {
+        let conn = open_at_version(6);
+        conn.execute(
+            "INSERT INTO test_suites (id, name, system_prompt, created_at, updated_at) VALUES ('s1', 'Suite', '', 1, 100)",
+            [],
+        ).unwrap();
+        for (sort_order, id) in ["a", "b"].into_iter().enumerate() {
+            conn.execute(
+                "INSERT INTO test_cases (id, test_suite_id, prompt, scoring_method, sort_order) VALUES (?, 's1', 'Prompt', 'exact-match', ?)",
+                params![id, sort_order as i64],
+            ).unwrap();
+        }
+
+        insert_run(&conn, "r-old", "s1", 50);
+        insert_result(&conn, "r-old", "a");
+        insert_result(&conn, "r-old", "c");
+        insert_run(&conn, "r-gone", "deleted-suite", 10);
+        insert_result(&conn, "r-gone", "x");
+        insert_run(&conn, "r-new", "s1", 200);
+        insert_result(&conn, "r-new", "a");
+        insert_result(&conn, "r-new", "b");
+
+        apply_migrations(&conn, CURRENT_SCHEMA_VERSION).unwrap();
+        conn
+    }
+
+    #[test]
+    fn results_older_than_revisions_are_not_flagged() {
+        let mut conn = pre_revision_fixture();
+
+        let report = check_database_internal(&conn).unwrap();
+        assert!(report.issues.is_empty(), "{:?}", report.issues);
+
+        repair_database_internal(&mut conn).unwrap();
+        assert_eq!(result_count(&conn), 5);
+    }
+
+    #[test]
+    fn orphaned_results_are_reported_but_kept() {
+        let mut conn = pre_revision_fixture();
+        insert_result(&conn, "r-new", "unknown");
+
+        let report = check_database_internal(&conn).unwrap();
+        assert_eq!(report.issues.len(), 1);
+        assert_eq!(report.issues[0].kind, IssueKind::OrphanedResult);
+
+        let repair = repair_database_internal(&mut conn).unwrap();
+        assert_eq!(repair.actions[0].outcome, RepairOutcome::Kept);
+        assert_eq!(result_count(&conn), 6);
+    }
+
+    #[test]
+    fn reset_values_can_be_restored() {
+        let mut conn = pre_revision_fixture();
+        conn.execute("UPDATE runs SET models = 'not json' WHERE id = 'r-new'", []).unwrap();
+
+        let repair = repair_database_internal(&mut conn).unwrap();
+        assert_eq!(repair.actions[0].outcome, RepairOutcome::Reset);
+        assert!(repair.after.issues.is_empty());
+
+        let quarantined = list_quarantine_internal(&conn).unwrap();
+        assert_eq!(quarantined.len(), 1);
+        assert_eq!(restore_quarantined_internal(&mut conn, &[quarantined[0].id]).unwrap(), 1);
+
+        let models: String = conn
+            .query_row("SELECT models FROM runs WHERE id = 'r-new'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(models, "not json");
+        assert!(list_quarantine_internal(&conn).unwrap().is_empty());
+    }
+
+    #[test]
+    fn quarantined_rows_restore_once_their_parent_is_back() {
+        let mut conn = pre_revision_fixture();
+        conn.execute("PRAGMA foreign_keys = OFF", []).unwrap();
+        insert_result(&conn, "r-missing", "a");
+        conn.execute("PRAGMA foreign_keys = ON", []).unwrap();
+
+        let repair = repair_database_internal(&mut conn).unwrap();
+        assert_eq!(repair.actions[0].outcome, RepairOutcome::Quarantined);
+        assert_eq!(result_count(&conn), 5);
+
+        let id = list_quarantine_internal(&conn).unwrap()[0].id;
+        assert!(restore_quarantined_internal(&mut conn, &[id]).is_err());
+        assert_eq!(list_quarantine_internal(&conn).unwrap().len(), 1);
+
+        insert_run(&conn, "r-missing", "s1", 300);
+        assert_eq!(restore_quarantined_internal(&mut conn, &[id]).unwrap(), 1);
+        assert_eq!(result_count(&conn), 6);
+    }
+}
@@ -0,0 +1,1573 @@
+// Headless builds (no `app` feature) compile the command layer out, which
+// leaves imports and helpers that only the commands use.
+#![cfg_attr(not(feature = "app"), allow(unused_imports, dead_code))]
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+#[cfg(feature = "app")]
+use std::process::Command;
+use std::sync::{Mutex, MutexGuard};
+#[cfg(feature = "app")]
+use tauri::{AppHandle, Manager, State};
+
+pub mod backups;
+pub mod benchmark_import;
+pub mod cassette;
+pub mod dataset_import;
+pub mod execution;
+pub mod integrity;
+pub mod migrations;
+pub mod providers;
+pub mod regression_check;
+pub mod reports;
+pub mod response_cache;
+pub mod run_export;
+pub mod run_queries;
+pub mod scheduler;
+pub mod scoring;
+pub mod search;
+pub mod suite_bundles;
+pub mod suite_revisions;
+pub mod trash;
+
+#[cfg(test)]
+mod test_support;
+
+use migrations::{migrate_database, CURRENT_SCHEMA_VERSION};
+use suite_revisions::record_suite_revision;
+use trash::{move_to_trash, trash_deleted, TrashKind, TrashRef};
+
+// ============================================================================
+// Data Types
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TestCaseMetadata {
+    pub category: Option<String>,
+    pub difficulty: Option<String>,
+    pub tags: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TestCase {
+    pub id: String,
+    pub prompt: String,
+    pub expected_output: Option<String>,
+    pub scoring_method: String,
+    pub weight: f64,
+    pub metadata: TestCaseMetadata,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TestSuite {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub system_prompt: String,
+    pub judge_system_prompt: Option<String>,
+    pub test_cases: Vec<TestCase>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ScoringResult {
+    pub score: f64,
+    pub confidence: Option<f64>,
+    pub notes: Option<String>,
+    pub raw_score: Option<f64>,
+    pub max_score: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelParameters {
+    pub temperature: f64,
+    pub top_p: f64,
+    pub max_tokens: i64,
+    pub frequency_penalty: f64,
+    pub presence_penalty: f64,
+}
+
+impl Default for ModelParameters {
+    fn default() -> Self {
+        ModelParameters {
+            temperature: 0.7,
+            top_p: 1.0,
+            max_tokens: 1024,
+            frequency_penalty: 0.0,
+            presence_penalty: 0.0,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TestCaseResult {
+    pub test_case_id: String,
+    pub model_id: String,
+    /// Provider namespace of `model_id` that served the request
+    #[serde(default)]
+    pub provider: Option<String>,
+    #[serde(default)]
+    pub attempt: i64,
+    pub response: String,
+    pub token_count: Option<i64>,
+    pub prompt_tokens: Option<i64>,
+    pub completion_tokens: Option<i64>,
+    pub cost: Option<f64>,
+    pub latency_ms: Option<i64>,
+    pub status: String,
+    pub error: Option<String>,
+    pub score: Option<ScoringResult>,
+    pub streamed_content: Option<String>,
+    /// Served from the response cache rather than the provider
+    #[serde(default)]
+    pub cached: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RunResult {
+    pub id: String,
+    pub test_suite_id: String,
+    pub test_suite_name: String,
+    /// Suite revision the run was scored against; filled in on first save.
+    #[serde(default)]
+    pub test_suite_revision: Option<i64>,
+    pub models: Vec<String>,
+    pub parameters: ModelParameters,
+    pub results: Vec<TestCaseResult>,
+    pub status: String,
+    pub started_at: i64,
+    pub completed_at: Option<i64>,
+    pub judge_model: Option<String>,
+    pub error_count: Option<i64>,
+    pub error_summary: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CodeArenaOutput {
+    pub model_id: String,
+    pub raw_response: String,
+    pub extracted_code: String,
+    pub status: String,
+    pub error: Option<String>,
+    pub latency_ms: Option<i64>,
+    pub prompt_tokens: Option<i64>,
+    pub completion_tokens: Option<i64>,
+    pub cost: Option<f64>,
+    pub streamed_content: Option<String>,
+    pub score: Option<ScoringResult>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CodeArenaRun {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub run_type: String,
+    pub prompt: String,
+    pub system_prompt: String,
+    pub models: Vec<String>,
+    pub parameters: ModelParameters,
+    pub outputs: Vec<CodeArenaOutput>,
+    pub status: String,
+    pub started_at: i64,
+    pub completed_at: Option<i64>,
+    pub judge_model_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AppState {
+    pub active_test_suite_id: Option<String>,
+    pub current_run_id: Option<String>,
+    #[serde(default)]
+    pub current_code_arena_run_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BenchmakerDb {
+    pub version: i64,
+    pub updated_at: i64,
+    pub test_suites: Vec<TestSuite>,
+    pub runs: Vec<RunResult>,
+    #[serde(default)]
+    pub code_arena_runs: Vec<CodeArenaRun>,
+    pub active_test_suite_id: Option<String>,
+    pub current_run_id: Option<String>,
+    #[serde(default)]
+    pub current_code_arena_run_id: Option<String>,
+    /// Items the frontend deleted since its last write. Anything not listed
+    /// here stays, even if the snapshot doesn't carry it.
+    #[serde(default)]
+    pub deleted: Vec<TrashRef>,
+}
+
+// ============================================================================
+// Database Setup & Migration
+// ============================================================================
+
+/// Bundle identifier from tauri.conf.json, which names the app data folder.
+const APP_IDENTIFIER: &str = "com.benchmaker.app";
+const DB_FILE_NAME: &str = "benchmaker.sqlite";
+
+#[cfg(feature = "app")]
+fn db_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let data_dir = app
+        .path_resolver()
+        .app_data_dir()
+        .ok_or_else(|| "Unable to resolve app data directory.".to_string())?;
+    fs::create_dir_all(&data_dir).map_err(|err| err.to_string())?;
+    Ok(data_dir.join(DB_FILE_NAME))
+}
+
+/// The database the app uses, resolved without a running app so the CLI
+/// opens the same file.
+pub fn default_db_path() -> Option<PathBuf> {
+    dirs_next::data_dir().map(|dir| dir.join(APP_IDENTIFIER).join(DB_FILE_NAME))
+}
+
+/// Opens the database with the app's pragmas and brings the schema up to
+/// date. Shared by the app and the CLI.
+pub fn open_connection(path: &Path) -> Result<Connection, String> {
+    let conn = Connection::open(path).map_err(|err| err.to_string())?;
+
+    // WAL lets reads proceed while a snapshot write is in flight, and the
+    // busy timeout covers the occasional overlap instead of failing fast.
+    conn.pragma_update(None, "journal_mode", "WAL")
+        .map_err(|err| err.to_string())?;
+    conn.pragma_update(None, "synchronous", "NORMAL")
+        .map_err(|err| err.to_string())?;
+    conn.busy_timeout(std::time::Duration::from_secs(5))
+        .map_err(|err| err.to_string())?;
+
+    // Enable foreign keys
+    conn.execute("PRAGMA foreign_keys = ON", [])
+        .map_err(|err| err.to_string())?;
+
+    // Room for every insert/upsert statement the writers cache
+    conn.set_prepared_statement_cache_capacity(32);
+
+    // Run migrations
+    migrate_database(&conn)?;
+
+    Ok(conn)
+}
+
+/// Long-lived connection shared by every command. Opened once at startup so
+/// pragmas and migrations run a single time instead of on every invoke.
+pub struct Db(Mutex<Connection>);
+
+impl Db {
+    pub fn open(path: &Path) -> Result<Db, String> {
+        let conn = open_connection(path)?;
+
+        // Housekeeping; a failure here shouldn't keep the app from starting
+        if let Err(err) = backups::create_backup_internal(&conn, backups::BackupKind::Startup) {
+            eprintln!("Failed to back up database on startup: {}", err);
+        }
+        if let Err(err) = trash::purge_expired(&conn) {
+            eprintln!("Failed to purge expired trash: {}", err);
+        }
+
+        Ok(Db(Mutex::new(conn)))
+    }
+
+    pub fn conn(&self) -> Result<MutexGuard<'_, Connection>, String> {
+        self.0
+            .lock()
+            .map_err(|_| "Database connection is unavailable after a previous failure.".to_string())
+    }
+}
+
+// ============================================================================
+// Tauri Commands - Test Suites
+// ============================================================================
+
+#[cfg(feature = "app")]
+#[tauri::command]
+fn get_all_test_suites(db: State<'_, Db>) -> Result<Vec<TestSuite>, String> {
+    let conn = db.conn()?;
+
+    let mut stmt = conn
+        .prepare("SELECT id, name, description, system_prompt, judge_system_prompt, created_at, updated_at FROM test_suites WHERE deleted_at IS NULL ORDER BY updated_at DESC")
+        .map_err(|err| err.to_string())?;
+
+    let suite_rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, Option<String>>(4)?,
+                row.get::<_, i64>(5)?,
+                row.get::<_, i64>(6)?,
+            ))
+        })
+        .map_err(|err| err.to_string())?;
+
+    let mut suites = Vec::new();
+    for row in suite_rows {
+        let (id, name, description, system_prompt, judge_system_prompt, created_at, updated_at) = row.map_err(|err| err.to_string())?;
+
+        // Get test cases for this suite
+        let test_cases = get_test_cases_for_suite(&conn, &id)?;
+
+        suites.push(TestSuite {
+            id,
+            name,
+            description,
+            system_prompt,
+            judge_system_prompt,
+            test_cases,
+            created_at,
+            updated_at,
+        });
+    }
+
+    Ok(suites)
+}
+
+fn get_test_cases_for_suite(conn: &Connection, suite_id: &str) -> Result<Vec<TestCase>, String> {
+    let mut stmt = conn
+        .prepare("SELECT id, prompt, expected_output, scoring_method, weight, category, difficulty, tags FROM test_cases WHERE test_suite_id = ? ORDER BY sort_order")
+        .map_err(|err| err.to_string())?;
+
+    let rows = stmt
+        .query_map(params![suite_id], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, f64>(4)?,
+                row.get::<_, Option<String>>(5)?,
+                row.get::<_, Option<String>>(6)?,
+                row.get::<_, String>(7)?,
+            ))
+        })
+        .map_err(|err| err.to_string())?;
+
+    let mut test_cases = Vec::new();
+    for row in rows {
+        let (id, prompt, expected_output, scoring_method, weight, category, difficulty, tags_json) = row.map_err(|err| err.to_string())?;
+        let tags: Vec<String> = serde_json::from_str(&tags_json).unwrap_or_default();
+
+        test_cases.push(TestCase {
+            id,
+            prompt,
+            expected_output,
+            scoring_method,
+            weight,
+            metadata: TestCaseMetadata {
+                category,
+                difficulty,
+                tags,
+            },
+        });
+    }
+
+    Ok(test_cases)
+}
+
+pub fn write_test_suite(conn: &Connection, suite: &TestSuite) -> Result<(), String> {
+    let written = conn.prepare_cached(
+        "INSERT INTO test_suites (id, name, description, system_prompt, judge_system_prompt, created_at, updated_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?)
+         ON CONFLICT(id) DO UPDATE SET
+           name = excluded.name,
+           description = excluded.description,
+           system_prompt = excluded.system_prompt,
+           judge_system_prompt = excluded.judge_system_prompt,
+           updated_at = excluded.updated_at
+         WHERE test_suites.deleted_at IS NULL",
+    )
+    .and_then(|mut stmt| stmt.execute(params![
+        suite.id,
+        suite.name,
+        suite.description,
+        suite.system_prompt,
+        suite.judge_system_prompt,
+        suite.created_at,
+        suite.updated_at,
+    ]))
+    .map_err(|err| err.to_string())?;
+
+    // Trashed suites stay as they are until restored; a stale save from the
+    // frontend mustn't bring them back
+    if written == 0 {
+        return Ok(());
+    }
+
+    // Upsert cases in place, leaving unchanged rows untouched, then drop the
+    // ones the suite no longer has
+    let mut upsert_case = conn
+        .prepare_cached(
+            "INSERT INTO test_cases (id, test_suite_id, prompt, expected_output, scoring_method, weight, category, difficulty, tags, sort_order)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+             ON CONFLICT(id) DO UPDATE SET
+               test_suite_id = excluded.test_suite_id,
+               prompt = excluded.prompt,
+               expected_output = excluded.expected_output,
+               scoring_method = excluded.scoring_method,
+               weight = excluded.weight,
+               category = excluded.category,
+               difficulty = excluded.difficulty,
+               tags = excluded.tags,
+               sort_order = excluded.sort_order
+             WHERE test_suite_id IS NOT excluded.test_suite_id
+               OR prompt IS NOT excluded.prompt
+               OR expected_output IS NOT excluded.expected_output
+               OR scoring_method IS NOT excluded.scoring_method
+               OR weight IS NOT excluded.weight
+               OR category IS NOT excluded.category
+               OR difficulty IS NOT excluded.difficulty
+               OR tags IS NOT excluded.tags
+               OR sort_order IS NOT excluded.sort_order",
+        )
+        .map_err(|err| err.to_string())?;
+
+    for (idx, test_case) in suite.test_cases.iter().enumerate() {
+        let tags_json = serde_json::to_string(&test_case.metadata.tags)
+            .unwrap_or_else(|_| "[]".to_string());
+
+        upsert_case.execute(params![
+            test_case.id,
+            suite.id,
+            test_case.prompt,
+            test_case.expected_output,
+            test_case.scoring_method,
+            test_case.weight,
+            test_case.metadata.category,
+            test_case.metadata.difficulty,
+            tags_json,
+            idx as i64,
+        ]).map_err(|err| err.to_string())?;
+    }
+
+    let current: HashSet<&str> = suite.test_cases.iter().map(|case| case.id.as_str()).collect();
+    let existing: Vec<String> = conn
+        .prepare_cached("SELECT id FROM test_cases WHERE test_suite_id = ?")
+        .and_then(|mut stmt| {
+            stmt.query_map(params![suite.id], |row| row.get(0))?
+                .collect::<Result<_, _>>()
+        })
+        .map_err(|err| err.to_string())?;
+
+    let mut delete_case = conn
+        .prepare_cached("DELETE FROM test_cases WHERE id = ?")
+        .map_err(|err| err.to_string())?;
+    for id in existing.iter().filter(|id| !current.contains(id.as_str())) {
+        delete_case.execute(params![id]).map_err(|err| err.to_string())?;
+    }
+
+    record_suite_revision(conn, suite)?;
+
+    Ok(())
+}
+
+#[cfg(feature = "app")]
+#[tauri::command]
+fn save_test_suite(db: State<'_, Db>, suite: TestSuite) -> Result<(), String> {
+    let mut conn = db.conn()?;
+    let tx = conn.transaction().map_err(|err| err.to_string())?;
+    write_test_suite(&tx, &suite)?;
+    tx.commit().map_err(|err| err.to_string())
+}
+
+#[cfg(feature = "app")]
+#[tauri::command]
+fn delete_test_suite(db: State<'_, Db>, id: String) -> Result<(), String> {
+    let conn = db.conn()?;
+    move_to_trash(&conn, TrashKind::TestSuite, &id)
+}
+
+// ============================================================================
+// Tauri Commands - Runs
+// ============================================================================
+
+#[cfg(feature = "app")]
+#[tauri::command]
+fn get_all_runs(db: State<'_, Db>) -> Result<Vec<RunResult>, String> {
+    let conn = db.conn()?;
+
+    let mut stmt = conn
+        .prepare("SELECT id, test_suite_id, test_suite_name, models, parameters, status, started_at, completed_at, judge_model, error_count, error_summary, test_suite_revision FROM runs WHERE deleted_at IS NULL ORDER BY started_at DESC")
+        .map_err(|err| err.to_string())?;
+
+    let run_rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, String>(4)?,
+                row.get::<_, String>(5)?,
+                row.get::<_, i64>(6)?,
+                row.get::<_, Option<i64>>(7)?,
+                row.get::<_, Option<String>>(8)?,
+                row.get::<_, Option<i64>>(9)?,
+                row.get::<_, Option<String>>(10)?,
+                row.get::<_, Option<i64>>(11)?,
+            ))
+        })
+        .map_err(|err| err.to_string())?;
+
+    let mut runs = Vec::new();
+    for row in run_rows {
+        let (id, test_suite_id, test_suite_name, models_json, params_json, status, started_at, completed_at, judge_model, error_count, error_summary, test_suite_revision) = row.map_err(|err| err.to_string())?;
+
+        let models: Vec<String> = serde_json::from_str(&models_json).unwrap_or_default();
+        let parameters: ModelParameters = serde_json::from_str(&params_json).unwrap_or_default();
+
+        let results = get_results_for_run(&conn, &id)?;
+
+        runs.push(RunResult {
+            id,
+            test_suite_id,
+            test_suite_name,
+            test_suite_revision,
+            models,
+            parameters,
+            results,
+            status,
+            started_at,
+            completed_at,
+            judge_model,
+            error_count,
+            error_summary,
+        });
+    }
+
+    Ok(runs)
+}
+
+pub fn get_results_for_run(conn: &Connection, run_id: &str) -> Result<Vec<TestCaseResult>, String> {
+    let mut stmt = conn
+        .prepare("SELECT test_case_id, model_id, attempt, response, token_count, prompt_tokens, completion_tokens, cost, latency_ms, status, error, score, streamed_content, provider, cached FROM test_case_results WHERE run_id = ? ORDER BY id")
+        .map_err(|err| err.to_string())?;
+
+    let rows = stmt
+        .query_map(params![run_id], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, i64>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, Option<i64>>(4)?,
+                row.get::<_, Option<i64>>(5)?,
+                row.get::<_, Option<i64>>(6)?,
+                row.get::<_, Option<f64>>(7)?,
+                row.get::<_, Option<i64>>(8)?,
+                row.get::<_, String>(9)?,
+                row.get::<_, Option<String>>(10)?,
+                row.get::<_, Option<String>>(11)?,
+                row.get::<_, Option<String>>(12)?,
+                row.get::<_, Option<String>>(13)?,
+                row.get::<_, bool>(14)?,
+            ))
+        })
+        .map_err(|err| err.to_string())?;
+
+    let mut results = Vec::new();
+    for row in rows {
+        let (test_case_id, model_id, attempt, response, token_count, prompt_tokens, completion_tokens, cost, latency_ms, status, error, score_json, streamed_content, provider, cached) = row.map_err(|err| err.to_string())?;
+
+        let score: Option<ScoringResult> = score_json
+            .and_then(|s| serde_json::from_str(&s).ok());
+
+        results.push(TestCaseResult {
+            test_case_id,
+            model_id,
+            provider,
+            attempt,
+            response,
+            token_count,
+            prompt_tokens,
+            completion_tokens,
+            cost,
+            latency_ms,
+            status,
+            error,
+            score,
+            streamed_content,
+            cached,
+        });
+    }
+
+    Ok(results)
+}
+
+/// Upserts run metadata. Returns `false`, writing nothing, when the run is in
+/// the trash, or when it has finished and `run` is a stale copy that still has
+/// it idle or running; status only moves forward.
+pub fn write_run(conn: &Connection, run: &RunResult) -> Result<bool, String> {
+    let models_json = serde_json::to_string(&run.models)
+        .unwrap_or_else(|_| "[]".to_string());
+    let params_json = serde_json::to_string(&run.parameters)
+        .unwrap_or_else(|_| "{}".to_string());
+
+    let written = conn.prepare_cached(
+        "INSERT INTO runs (id, test_suite_id, test_suite_name, models, parameters, status, started_at, completed_at, judge_model, error_count, error_summary, test_suite_revision)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?,
+                 COALESCE(?, (SELECT MAX(revision) FROM test_suite_revisions WHERE test_suite_id = ?)))
+         ON CONFLICT(id) DO UPDATE SET
+           status = excluded.status,
+           completed_at = excluded.completed_at,
+           error_count = excluded.error_count,
+           error_summary = excluded.error_summary,
+           test_suite_revision = COALESCE(runs.test_suite_revision, excluded.test_suite_revision)
+         WHERE runs.deleted_at IS NULL
+           AND NOT (runs.status IN ('completed', 'failed', 'cancelled') AND excluded.status IN ('idle', 'running'))",
+    )
+    .and_then(|mut stmt| stmt.execute(params![
+        run.id,
+        run.test_suite_id,
+        run.test_suite_name,
+        models_json,
+        params_json,
+        run.status,
+        run.started_at,
+        run.completed_at,
+        run.judge_model,
+        run.error_count,
+        run.error_summary,
+        run.test_suite_revision,
+        run.test_suite_id,
+    ]))
+    .map_err(|err| err.to_string())?;
+
+    Ok(written > 0)
+}
+
+/// Inserts or updates a single result keyed by (run, test case, model, attempt).
+/// Rows whose content is unchanged are left untouched so repeated snapshot
+/// writes of a finished run cost no page writes, and a finished result is never
+/// turned back into an idle or running one.
+pub fn upsert_result(conn: &Connection, run_id: &str, result: &TestCaseResult) -> Result<(), String> {
+    let score_json = result.score.as_ref()
+        .map(|s| serde_json::to_string(s).unwrap_or_else(|_| "null".to_string()));
+
+    conn.prepare_cached(
+        "INSERT INTO test_case_results (run_id, test_case_id, model_id, attempt, response, token_count, prompt_tokens, completion_tokens, cost, latency_ms, status, error, score, streamed_content, provider, cached)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+         ON CONFLICT(run_id, test_case_id, model_id, attempt) DO UPDATE SET
+           response = excluded.response,
+           token_count = excluded.token_count,
+           prompt_tokens = excluded.prompt_tokens,
+           completion_tokens = excluded.completion_tokens,
+           cost = excluded.cost,
+           latency_ms = excluded.latency_ms,
+           status = excluded.status,
+           error = excluded.error,
+           score = excluded.score,
+           streamed_content = excluded.streamed_content,
+           provider = COALESCE(excluded.provider, provider),
+           cached = excluded.cached
+         WHERE NOT (status IN ('completed', 'failed', 'cancelled') AND excluded.status IN ('idle', 'running'))
+           AND (response IS NOT excluded.response
+             OR token_count IS NOT excluded.token_count
+             OR prompt_tokens IS NOT excluded.prompt_tokens
+             OR completion_tokens IS NOT excluded.completion_tokens
+             OR cost IS NOT excluded.cost
+             OR latency_ms IS NOT excluded.latency_ms
+             OR status IS NOT excluded.status
+             OR error IS NOT excluded.error
+             OR score IS NOT excluded.score
+             OR streamed_content IS NOT excluded.streamed_content
+             OR (excluded.provider IS NOT NULL AND provider IS NOT excluded.provider)
+             OR cached IS NOT excluded.cached)",
+    )
+    .and_then(|mut stmt| stmt.execute(params![
+        run_id,
+        result.test_case_id,
+        result.model_id,
+        result.attempt,
+        result.response,
+        result.token_count,
+        result.prompt_tokens,
+        result.completion_tokens,
+        result.cost,
+        result.latency_ms,
+        result.status,
+        result.error,
+        score_json,
+        result.streamed_content,
+        result.provider,
+        result.cached,
+    ]))
+    .map_err(|err| err.to_string())?;
+
+    Ok(())
+}
+
+/// Writes run metadata and brings its stored results in line with `run.results`:
+/// changed rows are upserted and rows no longer present are removed.
+pub fn write_run_with_results(conn: &Connection, run: &RunResult) -> Result<(), String> {
+    if !write_run(conn, run)? {
+        return Ok(());
+    }
+
+    for result in &run.results {
+        upsert_result(conn, &run.id, result)?;
+    }
+
+    let current: HashSet<(&str, &str, i64)> = run.results
+        .iter()
+        .map(|r| (r.test_case_id.as_str(), r.model_id.as_str(), r.attempt))
+        .collect();
+
+    let mut existing = conn
+        .prepare_cached("SELECT id, test_case_id, model_id, attempt FROM test_case_results WHERE run_id = ?")
+        .map_err(|err| err.to_string())?;
+    let rows = existing
+        .query_map(params![run.id], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, i64>(3)?,
+            ))
+        })
+        .map_err(|err| err.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|err| err.to_string())?;
+    let stale: Vec<i64> = rows
+        .into_iter()
+        .filter(|(_, test_case_id, model_id, attempt)| {
+            !current.contains(&(test_case_id.as_str(), model_id.as_str(), *attempt))
+        })
+        .map(|(id, _, _, _)| id)
+        .collect();
+
+    let mut delete_result = conn
+        .prepare_cached("DELETE FROM test_case_results WHERE id = ?")
+        .map_err(|err| err.to_string())?;
+    for id in stale {
+        delete_result.execute(params![id]).map_err(|err| err.to_string())?;
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "app")]
+#[tauri::command]
+fn save_run(db: State<'_, Db>, run: RunResult) -> Result<(), String> {
+    let mut conn = db.conn()?;
+    let tx = conn.transaction().map_err(|err| err.to_string())?;
+    write_run(&tx, &run)?;
+    tx.commit().map_err(|err| err.to_string())
+}
+
+#[cfg(feature = "app")]
+#[tauri::command]
+fn get_run(db: State<'_, Db>, id: String) -> Result<Option<RunResult>, String> {
+    let conn = db.conn()?;
+    get_run_internal(&conn, &id)
+}
+
+#[cfg(feature = "app")]
+#[tauri::command]
+fn upsert_test_case_result(db: State<'_, Db>, run_id: String, result: TestCaseResult) -> Result<(), String> {
+    let conn = db.conn()?;
+    upsert_result(&conn, &run_id, &result)
+}
+
+#[cfg(feature = "app")]
+#[tauri::command]
+fn upsert_test_case_results(db: State<'_, Db>, run_id: String, results: Vec<TestCaseResult>) -> Result<(), String> {
+    let mut conn = db.conn()?;
+    let tx = conn.transaction().map_err(|err| err.to_string())?;
+    for result in &results {
+        upsert_result(&tx, &run_id, result)?;
+    }
+    tx.commit().map_err(|err| err.to_string())
+}
+
+#[cfg(feature = "app")]
+#[tauri::command]
+fn delete_run(db: State<'_, Db>, id: String) -> Result<(), String> {
+    let conn = db.conn()?;
+    move_to_trash(&conn, TrashKind::Run, &id)
+}
+
+// ============================================================================
+// Tauri Commands - Code Arena Runs
+// ============================================================================
+
+#[cfg(feature = "app")]
+#[tauri::command]
+fn get_all_code_arena_runs(db: State<'_, Db>) -> Result<Vec<CodeArenaRun>, String> {
+    let conn = db.conn()?;
+    get_all_code_arena_runs_internal(&conn)
+}
+
+fn get_outputs_for_code_arena_run(conn: &Connection, run_id: &str) -> Result<Vec<CodeArenaOutput>, String> {
+    let mut stmt = conn
+        .prepare("SELECT model_id, raw_response, extracted_code, status, error, latency_ms, prompt_tokens, completion_tokens, cost, streamed_content, score FROM code_arena_outputs WHERE run_id = ? ORDER BY id")
+        .map_err(|err| err.to_string())?;
+
+    let rows = stmt
+        .query_map(params![run_id], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, Option<String>>(4)?,
+                row.get::<_, Option<i64>>(5)?,
+                row.get::<_, Option<i64>>(6)?,
+                row.get::<_, Option<i64>>(7)?,
+                row.get::<_, Option<f64>>(8)?,
+                row.get::<_, Option<String>>(9)?,
+                row.get::<_, Option<String>>(10)?,
+            ))
+        })
+        .map_err(|err| err.to_string())?;
+
+    let mut outputs = Vec::new();
+    for row in rows {
+        let (model_id, raw_response, extracted_code, status, error, latency_ms, prompt_tokens, completion_tokens, cost, streamed_content, score_json) = row.map_err(|err| err.to_string())?;
+
+        let score: Option<ScoringResult> = score_json
+            .and_then(|s| serde_json::from_str(&s).ok());
+
+        outputs.push(CodeArenaOutput {
+            model_id,
+            raw_response,
+            extracted_code,
+            status,
+            error,
+            latency_ms,
+            prompt_tokens,
+            completion_tokens,
+            cost,
+            streamed_content,
+            score,
+        });
+    }
+
+    Ok(outputs)
+}
+
+fn write_code_arena_run(conn: &Connection, run: &CodeArenaRun) -> Result<(), String> {
+    let models_json = serde_json::to_string(&run.models)
+        .unwrap_or_else(|_| "[]".to_string());
+    let params_json = serde_json::to_string(&run.parameters)
+        .unwrap_or_else(|_| "{}".to_string());
+
+    let written = conn.prepare_cached(
+        "INSERT INTO code_arena_runs (id, prompt, system_prompt, models, parameters, status, started_at, completed_at, judge_model_id)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)
+         ON CONFLICT(id) DO UPDATE SET
+           status = excluded.status,
+           completed_at = excluded.completed_at,
+           judge_model_id = excluded.judge_model_id
+         WHERE code_arena_runs.deleted_at IS NULL",
+    )
+    .and_then(|mut stmt| stmt.execute(params![
+        run.id,
+        run.prompt,
+        run.system_prompt,
+        models_json,
+        params_json,
+        run.status,
+        run.started_at,
+        run.completed_at,
+        run.judge_model_id,
+    ]))
+    .map_err(|err| err.to_string())?;
+
+    if written == 0 {
+        return Ok(());
+    }
+
+    // Delete existing outputs and re-insert
+    conn.prepare_cached("DELETE FROM code_arena_outputs WHERE run_id = ?")
+        .and_then(|mut stmt| stmt.execute(params![run.id]))
+        .map_err(|err| err.to_string())?;
+
+    let mut insert_output = conn
+        .prepare_cached(
+            "INSERT INTO code_arena_outputs (run_id, model_id, raw_response, extracted_code, status, error, latency_ms, prompt_tokens, completion_tokens, cost, streamed_content, score)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        )
+        .map_err(|err| err.to_string())?;
+
+    for output in &run.outputs {
+        let score_json = output.score.as_ref()
+            .map(|s| serde_json::to_string(s).unwrap_or_else(|_| "null".to_string()));
+
+        insert_output.execute(params![
+            run.id,
+            output.model_id,
+            output.raw_response,
+            output.extracted_code,
+            output.status,
+            output.error,
+            output.latency_ms,
+            output.prompt_tokens,
+            output.completion_tokens,
+            output.cost,
+            output.streamed_content,
+            score_json,
+        ]).map_err(|err| err.to_string())?;
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "app")]
+#[tauri::command]
+fn save_code_arena_run(db: State<'_, Db>, run: CodeArenaRun) -> Result<(), String> {
+    let mut conn = db.conn()?;
+    let tx = conn.transaction().map_err(|err| err.to_string())?;
+    write_code_arena_run(&tx, &run)?;
+    tx.commit().map_err(|err| err.to_string())
+}
+
+#[cfg(feature = "app")]
+#[tauri::command]
+fn delete_code_arena_run(db: State<'_, Db>, id: String) -> Result<(), String> {
+    let conn = db.conn()?;
+    move_to_trash(&conn, TrashKind::CodeArenaRun, &id)
+}
+
+// ============================================================================
+// Tauri Commands - App State
+// ============================================================================
+
+#[cfg(feature = "app")]
+#[tauri::command]
+fn get_app_state(db: State<'_, Db>) -> Result<AppState, String> {
+    let conn = db.conn()?;
+
+    let state = conn
+        .query_row(
+            "SELECT active_test_suite_id, current_run_id, current_code_arena_run_id FROM app_state WHERE id = 1",
+            [],
+            |row| Ok(AppState {
+                active_test_suite_id: row.get(0)?,
+                current_run_id: row.get(1)?,
+                current_code_arena_run_id: row.get(2)?,
+            }),
+        )
+        .optional()
+        .map_err(|err| err.to_string())?
+        .unwrap_or(AppState {
+            active_test_suite_id: None,
+            current_run_id: None,
+            current_code_arena_run_id: None,
+        });
+
+    Ok(state)
+}
+
+#[cfg(feature = "app")]
+#[tauri::command]
+fn save_app_state(db: State<'_, Db>, state: AppState) -> Result<(), String> {
+    let conn = db.conn()?;
+
+    conn.execute(
+        "INSERT INTO app_state (id, active_test_suite_id, current_run_id, current_code_arena_run_id)
+         VALUES (1, ?, ?, ?)
+         ON CONFLICT(id) DO UPDATE SET
+           active_test_suite_id = excluded.active_test_suite_id,
+           current_run_id = excluded.current_run_id,
+           current_code_arena_run_id = excluded.current_code_arena_run_id",
+        params![state.active_test_suite_id, state.current_run_id, state.current_code_arena_run_id],
+    ).map_err(|err| err.to_string())?;
+
+    Ok(())
+}
+
+// ============================================================================
+// Legacy Command (for backwards compatibility during transition)
+// ============================================================================
+
+#[cfg(feature = "app")]
+#[tauri::command]
+fn read_snapshot(db: State<'_, Db>) -> Result<Option<BenchmakerDb>, String> {
+    let conn = db.conn()?;
+
+    // Build snapshot from normalized tables
+    let test_suites = get_all_test_suites_internal(&conn)?;
+    let runs = get_all_runs_internal(&conn)?;
+    let code_arena_runs = get_all_code_arena_runs_internal(&conn)?;
+    let state = conn
+        .query_row(
+            "SELECT active_test_suite_id, current_run_id, current_code_arena_run_id FROM app_state WHERE id = 1",
+            [],
+            |row| Ok((
+                row.get::<_, Option<String>>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, Option<String>>(2)?,
+            )),
+        )
+        .optional()
+        .map_err(|err| err.to_string())?
+        .unwrap_or((None, None, None));
+
+    Ok(Some(BenchmakerDb {
+        version: CURRENT_SCHEMA_VERSION,
+        updated_at: chrono_now(),
+        test_suites,
+        runs,
+        code_arena_runs,
+        active_test_suite_id: state.0,
+        current_run_id: state.1,
+        current_code_arena_run_id: state.2,
+        deleted: Vec::new(),
+    }))
+}
+
+#[cfg(feature = "app")]
+#[tauri::command]
+fn write_snapshot(db: State<'_, Db>, snapshot: BenchmakerDb, allow_mass_delete: Option<bool>) -> Result<(), String> {
+    let allow_mass_delete = allow_mass_delete.unwrap_or(false);
+    let mut conn = db.conn()?;
+
+    // Everything below commits together; any error drops `tx` and rolls back
+    let tx = conn.transaction().map_err(|err| err.to_string())?;
+    let conn = &tx;
+
+    // Trash what the frontend deleted before writing, so a deleted item that
+    // is still in the snapshot doesn't get saved once more
+    trash_deleted(conn, &snapshot.deleted, allow_mass_delete)?;
+
+    // Write test suites; the frontend sends only the ones it changed
+    for suite in &snapshot.test_suites {
+        write_test_suite(conn, suite)?;
+    }
+
+    // Write runs; only changed ones whose results the frontend has loaded
+    for run in &snapshot.runs {
+        write_run_with_results(conn, run)?;
+    }
+
+    // Write code arena runs
+    for run in &snapshot.code_arena_runs {
+        write_code_arena_run(conn, run)?;
+    }
+
+    // Update app state
+    conn.execute(
+        "UPDATE app_state SET active_test_suite_id = ?, current_run_id = ?, current_code_arena_run_id = ? WHERE id = 1",
+        params![snapshot.active_test_suite_id, snapshot.current_run_id, snapshot.current_code_arena_run_id],
+    ).map_err(|err| err.to_string())?;
+
+    tx.commit().map_err(|err| err.to_string())
+}
+
+// Helper functions for internal use
+pub fn get_all_test_suites_internal(conn: &Connection) -> Result<Vec<TestSuite>, String> {
+    let mut stmt = conn
+        .prepare("SELECT id, name, description, system_prompt, judge_system_prompt, created_at, updated_at FROM test_suites WHERE deleted_at IS NULL ORDER BY updated_at DESC")
+        .map_err(|err| err.to_string())?;
+
+    let suite_rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<String>>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, Option<String>>(4)?,
+                row.get::<_, i64>(5)?,
+                row.get::<_, i64>(6)?,
+            ))
+        })
+        .map_err(|err| err.to_string())?;
+
+    let mut suites = Vec::new();
+    for row in suite_rows {
+        let (id, name, description, system_prompt, judge_system_prompt, created_at, updated_at) = row.map_err(|err| err.to_string())?;
+        let test_cases = get_test_cases_for_suite(conn, &id)?;
+
+        suites.push(TestSuite {
+            id,
+            name,
+            description,
+            system_prompt,
+            judge_system_prompt,
+            test_cases,
+            created_at,
+            updated_at,
+        });
+    }
+
+    Ok(suites)
+}
+
+pub fn get_test_suite_internal(conn: &Connection, id: &str) -> Result<Option<TestSuite>, String> {
+    let suite = conn
+        .query_row(
+            "SELECT id, name, description, system_prompt, judge_system_prompt, created_at, updated_at FROM test_suites WHERE id = ? AND deleted_at IS NULL",
+            params![id],
+            |row| {
+                Ok(TestSuite {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    description: row.get(2)?,
+                    system_prompt: row.get(3)?,
+                    judge_system_prompt: row.get(4)?,
+                    test_cases: Vec::new(),
+                    created_at: row.get(5)?,
+                    updated_at: row.get(6)?,
+                })
+            },
+        )
+        .optional()
+        .map_err(|err| err.to_string())?;
+
+    let Some(mut suite) = suite else {
+        return Ok(None);
+    };
+    suite.test_cases = get_test_cases_for_suite(conn, &suite.id)?;
+    Ok(Some(suite))
+}
+
+pub fn get_all_runs_internal(conn: &Connection) -> Result<Vec<RunResult>, String> {
+    let mut stmt = conn
+        .prepare("SELECT id, test_suite_id, test_suite_name, models, parameters, status, started_at, completed_at, judge_model, error_count, error_summary, test_suite_revision FROM runs WHERE deleted_at IS NULL ORDER BY started_at DESC")
+        .map_err(|err| err.to_string())?;
+
+    let run_rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, String>(4)?,
+                row.get::<_, String>(5)?,
+                row.get::<_, i64>(6)?,
+                row.get::<_, Option<i64>>(7)?,
+                row.get::<_, Option<String>>(8)?,
+                row.get::<_, Option<i64>>(9)?,
+                row.get::<_, Option<String>>(10)?,
+                row.get::<_, Option<i64>>(11)?,
+            ))
+        })
+        .map_err(|err| err.to_string())?;
+
+    let mut runs = Vec::new();
+    for row in run_rows {
+        let (id, test_suite_id, test_suite_name, models_json, params_json, status, started_at, completed_at, judge_model, error_count, error_summary, test_suite_revision) = row.map_err(|err| err.to_string())?;
+
+        let models: Vec<String> = serde_json::from_str(&models_json).unwrap_or_default();
+        let parameters: ModelParameters = serde_json::from_str(&params_json).unwrap_or_default();
+
+        let results = get_results_for_run(conn, &id)?;
+
+        runs.push(RunResult {
+            id,
+            test_suite_id,
+            test_suite_name,
+            test_suite_revision,
+            models,
+            parameters,
+            results,
+            status,
+            started_at,
+            completed_at,
+            judge_model,
+            error_count,
+            error_summary,
+        });
+    }
+
+    Ok(runs)
+}
+
+pub fn get_run_internal(conn: &Connection, id: &str) -> Result<Option<RunResult>, String> {
+    let row = conn
+        .query_row(
+            "SELECT id, test_suite_id, test_suite_name, models, parameters, status, started_at, completed_at, judge_model, error_count, error_summary, test_suite_revision FROM runs WHERE id = ? AND deleted_at IS NULL",
+            params![id],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, String>(2)?,
+                    row.get::<_, String>(3)?,
+                    row.get::<_, String>(4)?,
+                    row.get::<_, String>(5)?,
+                    row.get::<_, i64>(6)?,
+                    row.get::<_, Option<i64>>(7)?,
+                    row.get::<_, Option<String>>(8)?,
+                    row.get::<_, Option<i64>>(9)?,
+                    row.get::<_, Option<String>>(10)?,
+                    row.get::<_, Option<i64>>(11)?,
+                ))
+            },
+        )
+        .optional()
+        .map_err(|err| err.to_string())?;
+
+    let Some((id, test_suite_id, test_suite_name, models_json, params_json, status, started_at, completed_at, judge_model, error_count, error_summary, test_suite_revision)) = row else {
+        return Ok(None);
+    };
+
+    let models: Vec<String> = serde_json::from_str(&models_json).unwrap_or_default();
+    let parameters: ModelParameters = serde_json::from_str(&params_json).unwrap_or_default();
+    let results = get_results_for_run(conn, &id)?;
+
+    Ok(Some(RunResult {
+        id,
+        test_suite_id,
+        test_suite_name,
+        test_suite_revision,
+        models,
+        parameters,
+        results,
+        status,
+        started_at,
+        completed_at,
+        judge_model,
+        error_count,
+        error_summary,
+    }))
+}
+
+fn get_all_code_arena_runs_internal(conn: &Connection) -> Result<Vec<CodeArenaRun>, String> {
+    let mut stmt = conn
+        .prepare("SELECT id, prompt, system_prompt, models, parameters, status, started_at, completed_at, judge_model_id FROM code_arena_runs WHERE deleted_at IS NULL ORDER BY started_at DESC")
+        .map_err(|err| err.to_string())?;
+
+    let run_rows = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?,
+                row.get::<_, String>(4)?,
+                row.get::<_, String>(5)?,
+                row.get::<_, i64>(6)?,
+                row.get::<_, Option<i64>>(7)?,
+                row.get::<_, Option<String>>(8)?,
+            ))
+        })
+        .map_err(|err| err.to_string())?;
+
+    let mut runs = Vec::new();
+    for row in run_rows {
+        let (id, prompt, system_prompt, models_json, params_json, status, started_at, completed_at, judge_model_id) = row.map_err(|err| err.to_string())?;
+
+        let models: Vec<String> = serde_json::from_str(&models_json).unwrap_or_default();
+        let parameters: ModelParameters = serde_json::from_str(&params_json).unwrap_or_default();
+
+        let outputs = get_outputs_for_code_arena_run(conn, &id)?;
+
+        runs.push(CodeArenaRun {
+            id,
+            run_type: "code-arena".to_string(),
+            prompt,
+            system_prompt,
+            models,
+            parameters,
+            outputs,
+            status,
+            started_at,
+            completed_at,
+            judge_model_id,
+        });
+    }
+
+    Ok(runs)
+}
+
+pub fn get_setting(conn: &Connection, key: &str) -> Result<Option<String>, String> {
+    conn.query_row("SELECT value FROM settings WHERE key = ?", params![key], |row| row.get(0))
+        .optional()
+        .map_err(|err| err.to_string())
+}
+
+pub fn set_setting(conn: &Connection, key: &str, value: &str) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO settings (key, value) VALUES (?, ?)
+         ON CONFLICT(key) DO UPDATE SET value = excluded.value",
+        params![key, value],
+    ).map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+/// Same shape as the frontend's ids (`<millis>-<random>`), for rows created
+/// on the Rust side.
+pub fn generate_id() -> String {
+    let random = uuid::Uuid::new_v4().simple().to_string();
+    format!("{}-{}", chrono_now(), &random[..9])
+}
+
+pub fn chrono_now() -> i64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+// ============================================================================
+// Updater
+// ============================================================================
+
+#[cfg(all(feature = "app", target_os = "windows"))]
+fn escape_powershell_literal(value: &str) -> String {
+    value.replace('\'', "''")
+}
+
+#[cfg(all(feature = "app", target_os = "macos"))]
+fn escape_bash_literal(value: &str) -> String {
+    value.replace('\'', "'\\''")
+}
+
+#[cfg(feature = "app")]
+#[tauri::command]
+fn apply_update(app: AppHandle, update_path: String) -> Result<(), String> {
+    if cfg!(debug_assertions) {
+        return Err("Auto-update is disabled in dev builds.".to_string());
+    }
+
+    let update_file = Path::new(&update_path);
+    if !update_file.exists() {
+        return Err("Update file not found.".to_string());
+    }
+
+    let current_exe = std::env::current_exe().map_err(|err| err.to_string())?;
+    let pid = std::process::id();
+
+    #[cfg(target_os = "windows")]
+    {
+        let script = format!(
+            "$procId = {pid}; $source = '{source}'; $target = '{target}'; while (Get-Process -Id $procId -ErrorAction SilentlyContinue) {{ Start-Sleep -Milliseconds 200 }}; Move-Item -Force $source $target; Start-Process $target",
+            pid = pid,
+            source = escape_powershell_literal(&update_file.to_string_lossy()),
+            target = escape_powershell_literal(&current_exe.to_string_lossy()),
+        );
+
+        Command::new("powershell")
+            .args(["-NoProfile", "-ExecutionPolicy", "Bypass", "-Command", &script])
+            .spawn()
+            .map_err(|err| err.to_string())?;
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        // Get the .app bundle path (current_exe is inside .app/Contents/MacOS/)
+        let app_bundle = current_exe
+            .parent()  // MacOS/
+            .and_then(|p| p.parent())  // Contents/
+            .and_then(|p| p.parent())  // .app bundle
+            .ok_or("Could not determine app bundle path")?;
+
+        let script = format!(
+            r#"
+            pid={}
+            source='{}'
+            target='{}'
+            
+            while kill -0 $pid 2>/dev/null; do sleep 0.2; done
+            rm -rf "$target"
+            mv -f "$source" "$target"
+            open "$target"
+            "#,
+            pid,
+            escape_bash_literal(&update_file.to_string_lossy()),
+            escape_bash_literal(&app_bundle.to_string_lossy()),
+        );
+
+        Command::new("bash")
+            .args(["-c", &script])
+            .spawn()
+            .map_err(|err| err.to_string())?;
+    }
+
+    #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+    {
+        return Err("Auto-update is not supported on this platform.".to_string());
+    }
+
+    app.exit(0);
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+#[cfg(feature = "app")]
+#[tauri::command]
+fn extract_app_zip(zip_path: String) -> Result<String, String> {
+    let zip_file = Path::new(&zip_path);
+    let parent = zip_file.parent().ok_or("Invalid zip path")?;
+
+    // Use ditto to extract (preserves macOS attributes and code signatures)
+    let status = Command::new("ditto")
+        .args(["-xk", &zip_path, &parent.to_string_lossy().to_string()])
+        .status()
+        .map_err(|e| e.to_string())?;
+
+    if !status.success() {
+        return Err("Failed to extract update".to_string());
+    }
+
+    // Return path to extracted .app
+    let app_path = parent.join("Benchmaker.app");
+    if !app_path.exists() {
+        return Err("Extracted app not found".to_string());
+    }
+
+    // Clean up zip file
+    std::fs::remove_file(zip_file).ok();
+
+    Ok(app_path.to_string_lossy().to_string())
+}
+
+#[cfg(not(target_os = "macos"))]
+#[cfg(feature = "app")]
+#[tauri::command]
+fn extract_app_zip(_zip_path: String) -> Result<String, String> {
+    Err("This command is only available on macOS".to_string())
+}
+
+// ============================================================================
+// Main
+// ============================================================================
+
+#[cfg(feature = "app")]
+pub fn run() {
+    tauri::Builder::default()
+        .setup(|app| {
+            let path = db_path(&app.handle())?;
+            let db = Db::open(&path)?;
+            app.manage(db);
+            app.manage(execution::ActiveRuns::default());
+
+            std::thread::spawn(move || backups::run_schedule(path));
+            Ok(())
+        })
+        .invoke_handler(tauri::generate_handler![
+            // Legacy commands (backwards compatible)
+            read_snapshot,
+            write_snapshot,
+            // New normalized commands
+            get_all_test_suites,
+            save_test_suite,
+            delete_test_suite,
+            get_all_runs,
+            run_queries::list_runs,
+            get_run,
+            save_run,
+            upsert_test_case_result,
+            upsert_test_case_results,
+            delete_run,
+            get_all_code_arena_runs,
+            save_code_arena_run,
+            delete_code_arena_run,
+            get_app_state,
+            save_app_state,
+            search::search,
+            trash::list_trash,
+            trash::restore,
+            trash::purge_trash,
+            trash::set_trash_retention_days,
+            backups::create_backup,
+            backups::list_backups,
+            backups::restore_backup,
+            backups::delete_backup,
+            backups::set_backup_settings,
+            integrity::check_database,
+            integrity::repair_database,
+            integrity::list_quarantine,
+            integrity::restore_quarantined,
+            suite_revisions::list_suite_revisions,
+            suite_revisions::get_suite_revision,
+            suite_revisions::diff_suite_revisions,
+            suite_bundles::export_test_suite,
+            suite_bundles::import_test_suite,
+            dataset_import::import_test_cases,
+            benchmark_import::import_openai_evals,
+            benchmark_import::import_lm_eval_task,
+            run_export::export_runs,
+            reports::generate_report,
+            execution::start_run,
+            execution::cancel_run,
+            execution::list_active_runs,
+            providers::list_providers,
+            providers::save_provider,
+            providers::delete_provider,
+            providers::list_provider_models,
+            response_cache::get_cache_status,
+            response_cache::set_cache_settings,
+            response_cache::clear_cache,
+            scheduler::get_rate_limits,
+            scheduler::set_rate_limits,
+            // Updater commands
+            apply_update,
+            extract_app_zip,
+        ])
+        .run(tauri::generate_context!())
+        .expect("error while running tauri application");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{memory_db, run, suite};
+
+    fn case_rowids(conn: &Connection, suite_id: &str) -> Vec<(String, i64)> {
+        let mut stmt = conn
+            .prepare("SELECT id, rowid FROM test_cases WHERE test_suite_id = ? ORDER BY sort_order")
+            .unwrap();
+        let rows = stmt.query_map(params![suite_id], |row| Ok((row.get(0)?, row.get(1)?))).unwrap();
+        rows.collect::<Result<_, _>>().unwrap()
+    }
+
+    #[test]
+    fn saving_a_suite_updates_cases_in_place() {
+        let conn = memory_db();
+        let mut suite = suite("s1", &["a", "b", "c"]);
+        write_test_suite(&conn, &suite).unwrap();
+        let before = case_rowids(&conn, "s1");
+
+        suite.test_cases.remove(1);
+        suite.test_cases[0].prompt = "Edited".to_string();
+        write_test_suite(&conn, &suite).unwrap();
+
+        let after = case_rowids(&conn, "s1");
+        assert_eq!(after, vec![before[0].clone(), before[2].clone()]);
+        let saved = get_test_suite_internal(&conn, "s1").unwrap().unwrap();
+        assert_eq!(saved.test_cases[0].prompt, "Edited");
+        assert_eq!(saved.test_cases.len(), 2);
+    }
+
+    #[test]
+    fn a_stale_save_does_not_reopen_a_finished_run() {
+        let conn = memory_db();
+        let suite = suite("s1", &["a"]);
+        write_test_suite(&conn, &suite).unwrap();
+        let mut finished = run("r1", &suite, "completed");
+        finished.completed_at = Some(20);
+        write_run_with_results(&conn, &finished).unwrap();
+
+        let stale = run("r1", &suite, "running");
+        assert!(!write_run(&conn, &stale).unwrap());
+        upsert_result(&conn, "r1", &stale.results[0]).unwrap();
+
+        let saved = get_run_internal(&conn, "r1").unwrap().unwrap();
+        assert_eq!(saved.status, "completed");
+        assert_eq!(saved.completed_at, Some(20));
+        assert_eq!(saved.results[0].status, "completed");
+    }
+}
@@ -0,0 +1,813 @@
+//! Versioned SQLite schema migrations.
+//!
+//! Each entry in [`MIGRATIONS`] runs exactly once, in order, inside its own
+//! transaction, and bumps `schema_version` when it commits. To change the
+//! schema, append a new migration; never edit one that has already shipped.
+
+use rusqlite::{params, Connection, OptionalExtension};
+
+use crate::backups::{create_backup_internal, BackupKind};
+use crate::suite_revisions::record_suite_revision;
+use crate::{
+    write_code_arena_run, write_run_with_results, write_test_suite, BenchmakerDb, TestCase,
+    TestCaseMetadata, TestSuite,
+};
+
+struct Migration {
+    version: i64,
+    description: &'static str,
+    up: fn(&Connection) -> Result<(), String>,
+}
+
+/// Ordered list of schema migrations. Version 1 was the single-row JSON
+/// snapshot table, which is imported separately by `migrate_from_snapshot`.
+const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 2,
+        description: "normalized suites, cases, runs and results",
+        up: create_normalized_tables,
+    },
+    Migration {
+        version: 3,
+        description: "code arena runs and outputs",
+        up: create_code_arena_tables,
+    },
+    Migration {
+        version: 4,
+        description: "token usage, cost and run error summary",
+        up: add_usage_and_error_columns,
+    },
+    Migration {
+        version: 5,
+        description: "natural key on test case results",
+        up: add_result_natural_key,
+    },
+    Migration {
+        version: 6,
+        description: "full-text search indexes",
+        up: create_search_indexes,
+    },
+    Migration {
+        version: 7,
+        description: "test suite revisions and run pinning",
+        up: create_suite_revisions,
+    },
+    Migration {
+        version: 8,
+        description: "soft delete and settings",
+        up: add_soft_delete,
+    },
+    Migration {
+        version: 9,
+        description: "quarantine for repaired rows",
+        up: create_quarantine,
+    },
+    Migration {
+        version: 10,
+        description: "provider on test case results",
+        up: add_result_provider,
+    },
+    Migration {
+        version: 11,
+        description: "record and replay cassette",
+        up: create_cassette,
+    },
+    Migration {
+        version: 12,
+        description: "response cache",
+        up: create_response_cache,
+    },
+];
+
+pub const CURRENT_SCHEMA_VERSION: i64 = MIGRATIONS[MIGRATIONS.len() - 1].version;
+
+pub fn migrate_database(conn: &Connection) -> Result<(), String> {
+    create_version_table(conn)?;
+
+    let current_version = schema_version(conn)?;
+
+    if current_version > CURRENT_SCHEMA_VERSION {
+        return Err(format!(
+            "Database schema version {} is newer than this build supports ({}). Please update Benchmaker.",
+            current_version, CURRENT_SCHEMA_VERSION
+        ));
+    }
+
+    apply_migrations(conn, CURRENT_SCHEMA_VERSION)?;
+
+    // Import the legacy JSON snapshot once the schema is current, so the
+    // import can use the same writers as the rest of the app.
+    if table_exists(conn, "benchmaker_snapshot")? {
+        let tx = conn.unchecked_transaction().map_err(|err| err.to_string())?;
+        migrate_from_snapshot(&tx)?;
+        tx.commit().map_err(|err| err.to_string())?;
+    }
+
+    Ok(())
+}
+
+/// Runs the pending migrations up to and including `target`. Tests use a
+/// lower target to build databases at an older schema.
+pub(crate) fn apply_migrations(conn: &Connection, target: i64) -> Result<(), String> {
+    let current_version = schema_version(conn)?;
+    let pending: Vec<&Migration> = MIGRATIONS
+        .iter()
+        .filter(|migration| migration.version > current_version && migration.version <= target)
+        .collect();
+
+    if !pending.is_empty() && current_version > 0 {
+        backup_before_migration(conn)?;
+    }
+
+    for migration in pending {
+        let tx = conn.unchecked_transaction().map_err(|err| err.to_string())?;
+
+        (migration.up)(&tx).map_err(|err| {
+            format!(
+                "Migration {} ({}) failed: {}",
+                migration.version, migration.description, err
+            )
+        })?;
+
+        tx.execute(
+            "INSERT INTO schema_version (id, version) VALUES (1, ?)
+             ON CONFLICT(id) DO UPDATE SET version = excluded.version",
+            params![migration.version],
+        ).map_err(|err| err.to_string())?;
+
+        tx.commit().map_err(|err| err.to_string())?;
+    }
+
+    Ok(())
+}
+
+/// In-memory database migrated to `version`, for tests that start from an
+/// older schema.
+#[cfg(test)]
+pub(crate) fn open_at_version(version: i64) -> Connection {
+    let conn = Connection::open_in_memory().unwrap();
+    conn.execute("PRAGMA foreign_keys = ON", []).unwrap();
+    create_version_table(&conn).unwrap();
+    apply_migrations(&conn, version).unwrap();
+    conn
+}
+
+fn create_version_table(conn: &Connection) -> Result<(), String> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS schema_version (
+            id INTEGER PRIMARY KEY CHECK (id = 1),
+            version INTEGER NOT NULL
+        )",
+        [],
+    ).map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+fn schema_version(conn: &Connection) -> Result<i64, String> {
+    Ok(conn
+        .query_row("SELECT version FROM schema_version WHERE id = 1", [], |row| row.get(0))
+        .optional()
+        .map_err(|err| err.to_string())?
+        .unwrap_or(0))
+}
+
+fn table_exists(conn: &Connection, table: &str) -> Result<bool, String> {
+    conn.query_row(
+        "SELECT COUNT(*) > 0 FROM sqlite_master WHERE type='table' AND name = ?",
+        params![table],
+        |row| row.get(0),
+    )
+    .map_err(|err| err.to_string())
+}
+
+/// Backs the database up before any migration touches it, so a failed or
+/// unwanted upgrade can be rolled back with `restore_backup`.
+fn backup_before_migration(conn: &Connection) -> Result<(), String> {
+    create_backup_internal(conn, BackupKind::PreMigration)
+        .map_err(|err| format!("Failed to back up database before migration: {}", err))?;
+    Ok(())
+}
+
+fn add_column_if_missing(conn: &Connection, table: &str, column: &str, definition: &str) -> Result<(), String> {
+    let exists: bool = conn
+        .query_row(
+            &format!("SELECT COUNT(*) > 0 FROM pragma_table_info('{}') WHERE name = ?", table),
+            params![column],
+            |row| row.get(0),
+        )
+        .map_err(|err| err.to_string())?;
+
+    if !exists {
+        conn.execute(
+            &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+            [],
+        ).map_err(|err| err.to_string())?;
+    }
+
+    Ok(())
+}
+
+// ============================================================================
+// Migrations
+// ============================================================================
+
+fn create_normalized_tables(conn: &Connection) -> Result<(), String> {
+    // Test Suites table
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS test_suites (
+            id TEXT PRIMARY KEY,
+            name TEXT NOT NULL,
+            description TEXT,
+            system_prompt TEXT NOT NULL,
+            judge_system_prompt TEXT,
+            created_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL
+        )",
+        [],
+    ).map_err(|err| err.to_string())?;
+
+    // Test Cases table
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS test_cases (
+            id TEXT PRIMARY KEY,
+            test_suite_id TEXT NOT NULL,
+            prompt TEXT NOT NULL,
+            expected_output TEXT,
+            scoring_method TEXT NOT NULL,
+            weight REAL NOT NULL DEFAULT 1.0,
+            category TEXT,
+            difficulty TEXT,
+            tags TEXT NOT NULL DEFAULT '[]',
+            sort_order INTEGER NOT NULL DEFAULT 0,
+            FOREIGN KEY (test_suite_id) REFERENCES test_suites(id) ON DELETE CASCADE
+        )",
+        [],
+    ).map_err(|err| err.to_string())?;
+
+    // Runs table
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS runs (
+            id TEXT PRIMARY KEY,
+            test_suite_id TEXT NOT NULL,
+            test_suite_name TEXT NOT NULL,
+            models TEXT NOT NULL,
+            parameters TEXT NOT NULL,
+            status TEXT NOT NULL,
+            started_at INTEGER NOT NULL,
+            completed_at INTEGER,
+            judge_model TEXT
+        )",
+        [],
+    ).map_err(|err| err.to_string())?;
+
+    // Test Case Results table
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS test_case_results (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            run_id TEXT NOT NULL,
+            test_case_id TEXT NOT NULL,
+            model_id TEXT NOT NULL,
+            response TEXT NOT NULL DEFAULT '',
+            token_count INTEGER,
+            latency_ms INTEGER,
+            status TEXT NOT NULL,
+            error TEXT,
+            score TEXT,
+            streamed_content TEXT,
+            FOREIGN KEY (run_id) REFERENCES runs(id) ON DELETE CASCADE
+        )",
+        [],
+    ).map_err(|err| err.to_string())?;
+
+    // App State table (singleton)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS app_state (
+            id INTEGER PRIMARY KEY CHECK (id = 1),
+            active_test_suite_id TEXT,
+            current_run_id TEXT
+        )",
+        [],
+    ).map_err(|err| err.to_string())?;
+
+    // Initialize app_state if empty
+    conn.execute(
+        "INSERT OR IGNORE INTO app_state (id, active_test_suite_id, current_run_id) VALUES (1, NULL, NULL)",
+        [],
+    ).map_err(|err| err.to_string())?;
+
+    // Create indexes for common queries
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_test_cases_suite ON test_cases(test_suite_id)",
+        [],
+    ).map_err(|err| err.to_string())?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_results_run ON test_case_results(run_id)",
+        [],
+    ).map_err(|err| err.to_string())?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_runs_suite ON runs(test_suite_id)",
+        [],
+    ).map_err(|err| err.to_string())?;
+
+    Ok(())
+}
+
+fn create_code_arena_tables(conn: &Connection) -> Result<(), String> {
+    // Code Arena Runs table
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS code_arena_runs (
+            id TEXT PRIMARY KEY,
+            prompt TEXT NOT NULL,
+            system_prompt TEXT NOT NULL,
+            models TEXT NOT NULL,
+            parameters TEXT NOT NULL,
+            status TEXT NOT NULL,
+            started_at INTEGER NOT NULL,
+            completed_at INTEGER,
+            judge_model_id TEXT
+        )",
+        [],
+    ).map_err(|err| err.to_string())?;
+
+    // Code Arena Outputs table
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS code_arena_outputs (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            run_id TEXT NOT NULL,
+            model_id TEXT NOT NULL,
+            raw_response TEXT NOT NULL DEFAULT '',
+            extracted_code TEXT NOT NULL DEFAULT '',
+            status TEXT NOT NULL,
+            error TEXT,
+            latency_ms INTEGER,
+            prompt_tokens INTEGER,
+            completion_tokens INTEGER,
+            cost REAL,
+            streamed_content TEXT,
+            score TEXT,
+            FOREIGN KEY (run_id) REFERENCES code_arena_runs(id) ON DELETE CASCADE
+        )",
+        [],
+    ).map_err(|err| err.to_string())?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_code_arena_outputs_run ON code_arena_outputs(run_id)",
+        [],
+    ).map_err(|err| err.to_string())?;
+
+    add_column_if_missing(conn, "app_state", "current_code_arena_run_id", "TEXT")
+}
+
+fn add_usage_and_error_columns(conn: &Connection) -> Result<(), String> {
+    add_column_if_missing(conn, "test_case_results", "prompt_tokens", "INTEGER")?;
+    add_column_if_missing(conn, "test_case_results", "completion_tokens", "INTEGER")?;
+    add_column_if_missing(conn, "test_case_results", "cost", "REAL")?;
+    add_column_if_missing(conn, "runs", "error_count", "INTEGER")?;
+    add_column_if_missing(conn, "runs", "error_summary", "TEXT")
+}
+
+fn add_result_natural_key(conn: &Connection) -> Result<(), String> {
+    add_column_if_missing(conn, "test_case_results", "attempt", "INTEGER NOT NULL DEFAULT 0")?;
+
+    // Older saves could hold duplicate rows for the same cell; keep the latest
+    conn.execute(
+        "DELETE FROM test_case_results WHERE id NOT IN (
+            SELECT MAX(id) FROM test_case_results GROUP BY run_id, test_case_id, model_id, attempt
+        )",
+        [],
+    ).map_err(|err| err.to_string())?;
+
+    conn.execute(
+        "CREATE UNIQUE INDEX IF NOT EXISTS idx_results_natural_key
+         ON test_case_results(run_id, test_case_id, model_id, attempt)",
+        [],
+    ).map_err(|err| err.to_string())?;
+
+    Ok(())
+}
+
+fn create_search_indexes(conn: &Connection) -> Result<(), String> {
+    // Test cases are indexed straight from the table. Results go through a view
+    // so judge notes, which live inside the score JSON, are searchable too
+    // without storing a second copy of every response.
+    conn.execute_batch(
+        "CREATE VIRTUAL TABLE IF NOT EXISTS test_cases_fts USING fts5(
+            prompt, expected_output,
+            content='test_cases', content_rowid='rowid',
+            tokenize='unicode61 remove_diacritics 2'
+        );
+
+        CREATE TRIGGER IF NOT EXISTS test_cases_fts_insert AFTER INSERT ON test_cases BEGIN
+            INSERT INTO test_cases_fts(rowid, prompt, expected_output)
+            VALUES (new.rowid, new.prompt, new.expected_output);
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS test_cases_fts_delete AFTER DELETE ON test_cases BEGIN
+            INSERT INTO test_cases_fts(test_cases_fts, rowid, prompt, expected_output)
+            VALUES ('delete', old.rowid, old.prompt, old.expected_output);
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS test_cases_fts_update AFTER UPDATE OF prompt, expected_output ON test_cases BEGIN
+            INSERT INTO test_cases_fts(test_cases_fts, rowid, prompt, expected_output)
+            VALUES ('delete', old.rowid, old.prompt, old.expected_output);
+            INSERT INTO test_cases_fts(rowid, prompt, expected_output)
+            VALUES (new.rowid, new.prompt, new.expected_output);
+        END;
+
+        CREATE VIEW IF NOT EXISTS test_case_results_search AS
+            SELECT id, response,
+                   CASE WHEN json_valid(score) THEN json_extract(score, '$.notes') END AS notes
+            FROM test_case_results;
+
+        CREATE VIRTUAL TABLE IF NOT EXISTS results_fts USING fts5(
+            response, notes,
+            content='test_case_results_search', content_rowid='id',
+            tokenize='unicode61 remove_diacritics 2'
+        );
+
+        CREATE TRIGGER IF NOT EXISTS results_fts_insert AFTER INSERT ON test_case_results BEGIN
+            INSERT INTO results_fts(rowid, response, notes)
+            VALUES (new.id, new.response,
+                    CASE WHEN json_valid(new.score) THEN json_extract(new.score, '$.notes') END);
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS results_fts_delete AFTER DELETE ON test_case_results BEGIN
+            INSERT INTO results_fts(results_fts, rowid, response, notes)
+            VALUES ('delete', old.id, old.response,
+                    CASE WHEN json_valid(old.score) THEN json_extract(old.score, '$.notes') END);
+        END;
+
+        CREATE TRIGGER IF NOT EXISTS results_fts_update AFTER UPDATE OF response, score ON test_case_results BEGIN
+            INSERT INTO results_fts(results_fts, rowid, response, notes)
+            VALUES ('delete', old.id, old.response,
+                    CASE WHEN json_valid(old.score) THEN json_extract(old.score, '$.notes') END);
+            INSERT INTO results_fts(rowid, response, notes)
+            VALUES (new.id, new.response,
+                    CASE WHEN json_valid(new.score) THEN json_extract(new.score, '$.notes') END);
+        END;
+
+        INSERT INTO test_cases_fts(test_cases_fts) VALUES ('rebuild');
+        INSERT INTO results_fts(results_fts) VALUES ('rebuild');",
+    ).map_err(|err| err.to_string())
+}
+
+fn create_suite_revisions(conn: &Connection) -> Result<(), String> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS test_suite_revisions (
+            test_suite_id TEXT NOT NULL,
+            revision INTEGER NOT NULL,
+            content_hash TEXT NOT NULL,
+            name TEXT NOT NULL,
+            description TEXT,
+            system_prompt TEXT NOT NULL,
+            judge_system_prompt TEXT,
+            test_cases TEXT NOT NULL,
+            created_at INTEGER NOT NULL,
+            PRIMARY KEY (test_suite_id, revision)
+        )",
+        [],
+    ).map_err(|err| err.to_string())?;
+
+    add_column_if_missing(conn, "runs", "test_suite_revision", "INTEGER")?;
+
+    // Seed revision 1 from each suite as it stands today. Reads use the v7
+    // column set explicitly rather than the app's readers, which may change.
+    let suites: Vec<TestSuite> = {
+        let mut stmt = conn
+            .prepare("SELECT id, name, description, system_prompt, judge_system_prompt, created_at, updated_at FROM test_suites")
+            .map_err(|err| err.to_string())?;
+        let rows = stmt
+            .query_map([], |row| {
+                Ok(TestSuite {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    description: row.get(2)?,
+                    system_prompt: row.get(3)?,
+                    judge_system_prompt: row.get(4)?,
+                    test_cases: Vec::new(),
+                    created_at: row.get(5)?,
+                    updated_at: row.get(6)?,
+                })
+            })
+            .map_err(|err| err.to_string())?;
+        rows.collect::<Result<_, _>>().map_err(|err| err.to_string())?
+    };
+
+    let mut case_stmt = conn
+        .prepare("SELECT id, prompt, expected_output, scoring_method, weight, category, difficulty, tags FROM test_cases WHERE test_suite_id = ? ORDER BY sort_order")
+        .map_err(|err| err.to_string())?;
+
+    for mut suite in suites {
+        let rows = case_stmt
+            .query_map(params![suite.id], |row| {
+                let tags_json: String = row.get(7)?;
+                Ok(TestCase {
+                    id: row.get(0)?,
+                    prompt: row.get(1)?,
+                    expected_output: row.get(2)?,
+                    scoring_method: row.get(3)?,
+                    weight: row.get(4)?,
+                    metadata: TestCaseMetadata {
+                        category: row.get(5)?,
+                        difficulty: row.get(6)?,
+                        tags: serde_json::from_str(&tags_json).unwrap_or_default(),
+                    },
+                })
+            })
+            .map_err(|err| err.to_string())?;
+        suite.test_cases = rows.collect::<Result<_, _>>().map_err(|err| err.to_string())?;
+
+        record_suite_revision(conn, &suite)?;
+    }
+
+    // Runs started after the suite's last edit were scored against exactly
+    // that content; older runs stay unpinned because we can't know.
+    conn.execute(
+        "UPDATE runs SET test_suite_revision = 1
+         WHERE test_suite_revision IS NULL
+           AND EXISTS (
+               SELECT 1 FROM test_suites s
+               WHERE s.id = runs.test_suite_id AND s.updated_at <= runs.started_at
+           )",
+        [],
+    ).map_err(|err| err.to_string())?;
+
+    Ok(())
+}
+
+fn add_soft_delete(conn: &Connection) -> Result<(), String> {
+    for table in ["test_suites", "runs", "code_arena_runs"] {
+        add_column_if_missing(conn, table, "deleted_at", "INTEGER")?;
+    }
+
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS settings (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        )",
+        [],
+    ).map_err(|err| err.to_string())?;
+
+    Ok(())
+}
+
+fn create_quarantine(conn: &Connection) -> Result<(), String> {
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS quarantine (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            source_table TEXT NOT NULL,
+            source_rowid INTEGER,
+            column_name TEXT,
+            reason TEXT NOT NULL,
+            payload TEXT NOT NULL,
+            quarantined_at INTEGER NOT NULL
+        )",
+        [],
+    ).map_err(|err| err.to_string())?;
+
+    Ok(())
+}
+
+fn add_result_provider(conn: &Connection) -> Result<(), String> {
+    add_column_if_missing(conn, "test_case_results", "provider", "TEXT")?;
+
+    // Every model ran through OpenRouter before providers were pluggable
+    conn.execute("UPDATE test_case_results SET provider = 'openrouter' WHERE provider IS NULL", [])
+        .map_err(|err| err.to_string())?;
+
+    Ok(())
+}
+
+fn create_cassette(conn: &Connection) -> Result<(), String> {
+    // `key` hashes the provider and the request; see cassette::request_key
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS cassette_entries (
+            key TEXT PRIMARY KEY,
+            provider TEXT NOT NULL,
+            model TEXT NOT NULL,
+            request TEXT NOT NULL,
+            response TEXT NOT NULL,
+            recorded_at INTEGER NOT NULL
+        )",
+        [],
+    ).map_err(|err| err.to_string())?;
+
+    Ok(())
+}
+
+fn create_response_cache(conn: &Connection) -> Result<(), String> {
+    add_column_if_missing(conn, "test_case_results", "cached", "INTEGER NOT NULL DEFAULT 0")?;
+
+    // `key` hashes the provider, model, prompts and parameters; see
+    // response_cache::cache_key
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS response_cache (
+            key TEXT PRIMARY KEY,
+            provider TEXT NOT NULL,
+            model TEXT NOT NULL,
+            response TEXT NOT NULL,
+            latency_ms INTEGER,
+            size INTEGER NOT NULL,
+            created_at INTEGER NOT NULL,
+            last_used_at INTEGER NOT NULL
+        )",
+        [],
+    ).map_err(|err| err.to_string())?;
+
+    conn.execute("CREATE INDEX IF NOT EXISTS idx_response_cache_last_used ON response_cache(last_used_at)", [])
+        .map_err(|err| err.to_string())?;
+
+    Ok(())
+}
+
+// ============================================================================
+// Legacy Snapshot Import
+// ============================================================================
+
+fn migrate_from_snapshot(conn: &Connection) -> Result<(), String> {
+    // Read old snapshot
+    let payload: Option<String> = conn
+        .query_row(
+            "SELECT payload FROM benchmaker_snapshot WHERE id = 1",
+            [],
+            |row| row.get(0),
+        )
+        .optional()
+        .map_err(|err| err.to_string())?;
+
+    if let Some(json_payload) = payload {
+        let old_data: BenchmakerDb = serde_json::from_str(&json_payload)
+            .map_err(|err| format!("Failed to parse old snapshot: {}", err))?;
+
+        // Migrate test suites and test cases
+        for suite in &old_data.test_suites {
+            write_test_suite(conn, suite)?;
+        }
+
+        // Migrate runs and results
+        for run in &old_data.runs {
+            write_run_with_results(conn, run)?;
+        }
+
+        // Migrate code arena runs and outputs
+        for run in &old_data.code_arena_runs {
+            write_code_arena_run(conn, run)?;
+        }
+
+        // Migrate app state
+        conn.execute(
+            "UPDATE app_state SET active_test_suite_id = ?, current_run_id = ?, current_code_arena_run_id = ? WHERE id = 1",
+            params![old_data.active_test_suite_id, old_data.current_run_id, old_data.current_code_arena_run_id],
+        ).map_err(|err| err.to_string())?;
+
+    }
+
+    // Drop old snapshot table after successful migration
+    conn.execute("DROP TABLE IF EXISTS benchmaker_snapshot", [])
+        .map_err(|err| err.to_string())?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count(conn: &Connection, sql: &str) -> i64 {
+        conn.query_row(sql, [], |row| row.get(0)).unwrap()
+    }
+
+    fn insert_run(conn: &Connection, id: &str, started_at: i64) {
+        conn.execute(
+            "INSERT INTO runs (id, test_suite_id, test_suite_name, models, parameters, status, started_at)
+             VALUES (?, 's1', 'Suite', '[\"m\"]', '{}', 'completed', ?)",
+            params![id, started_at],
+        ).unwrap();
+    }
+
+    fn insert_result(conn: &Connection, run_id: &str, test_case_id: &str, response: &str) {
+        conn.execute(
+            "INSERT INTO test_case_results (run_id, test_case_id, model_id, response, status)
+             VALUES (?, ?, 'm', ?, 'completed')",
+            params![run_id, test_case_id, response],
+        ).unwrap();
+    }
+
+    #[test]
+    fn every_version_upgrades_to_the_current_schema() {
+        for migration in MIGRATIONS {
+            let conn = open_at_version(migration.version);
+            assert_eq!(schema_version(&conn).unwrap(), migration.version);
+
+            migrate_database(&conn).unwrap();
+            assert_eq!(schema_version(&conn).unwrap(), CURRENT_SCHEMA_VERSION);
+        }
+
+        let conn = open_at_version(CURRENT_SCHEMA_VERSION);
+        for table in ["code_arena_runs", "test_cases_fts", "test_suite_revisions", "settings", "quarantine", "cassette_entries", "response_cache"] {
+            assert!(table_exists(&conn, table).unwrap(), "{} is missing", table);
+        }
+        // Running again is a no-op
+        migrate_database(&conn).unwrap();
+    }
+
+    #[test]
+    fn newer_schemas_are_refused() {
+        let conn = open_at_version(CURRENT_SCHEMA_VERSION);
+        conn.execute("UPDATE schema_version SET version = ?", params![CURRENT_SCHEMA_VERSION + 1]).unwrap();
+
+        let err = migrate_database(&conn).unwrap_err();
+        assert!(err.contains("newer than this build"), "{}", err);
+    }
+
+    #[test]
+    fn duplicate_results_keep_the_latest_row() {
+        let conn = open_at_version(4);
+        insert_run(&conn, "r1", 10);
+        insert_result(&conn, "r1", "a", "first");
+        insert_result(&conn, "r1", "a", "second");
+        insert_result(&conn, "r1", "b", "only");
+
+        apply_migrations(&conn, 5).unwrap();
+
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM test_case_results"), 2);
+        let response: String = conn
+            .query_row("SELECT response FROM test_case_results WHERE test_case_id = 'a'", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(response, "second");
+    }
+
+    #[test]
+    fn search_indexes_cover_existing_rows() {
+        let conn = open_at_version(5);
+        conn.execute(
+            "INSERT INTO test_suites (id, name, system_prompt, created_at, updated_at) VALUES ('s1', 'Suite', '', 1, 1)",
+            [],
+        ).unwrap();
+        conn.execute(
+            "INSERT INTO test_cases (id, test_suite_id, prompt, scoring_method) VALUES ('a', 's1', 'Name the capital of France', 'exact-match')",
+            [],
+        ).unwrap();
+        insert_run(&conn, "r1", 10);
+        insert_result(&conn, "r1", "a", "Paris");
+
+        apply_migrations(&conn, 6).unwrap();
+
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM test_cases_fts WHERE test_cases_fts MATCH 'capital'"), 1);
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM results_fts WHERE results_fts MATCH 'paris'"), 1);
+    }
+
+    #[test]
+    fn revision_one_is_seeded_and_only_later_runs_are_pinned() {
+        let conn = open_at_version(6);
+        conn.execute(
+            "INSERT INTO test_suites (id, name, system_prompt, created_at, updated_at) VALUES ('s1', 'Suite', 'Be brief.', 1, 100)",
+            [],
+        ).unwrap();
+        for (sort_order, id) in ["b", "a"].into_iter().enumerate() {
+            conn.execute(
+                "INSERT INTO test_cases (id, test_suite_id, prompt, scoring_method, category, tags, sort_order)
+                 VALUES (?, 's1', 'Prompt', 'exact-match', 'math', '[\"easy\"]', ?)",
+                params![id, sort_order as i64],
+            ).unwrap();
+        }
+        insert_run(&conn, "before-edit", 50);
+        insert_run(&conn, "after-edit", 200);
+
+        apply_migrations(&conn, 7).unwrap();
+
+        let (revision, system_prompt, cases_json): (i64, String, String) = conn
+            .query_row(
+                "SELECT revision, system_prompt, test_cases FROM test_suite_revisions WHERE test_suite_id = 's1'",
+                [],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!(revision, 1);
+        assert_eq!(system_prompt, "Be brief.");
+        let cases: Vec<TestCase> = serde_json::from_str(&cases_json).unwrap();
+        assert_eq!(cases.iter().map(|case| case.id.as_str()).collect::<Vec<_>>(), ["b", "a"]);
+        assert_eq!(cases[0].metadata.category.as_deref(), Some("math"));
+        assert_eq!(cases[0].metadata.tags, ["easy"]);
+
+        let pinned = |id: &str| -> Option<i64> {
+            conn.query_row("SELECT test_suite_revision FROM runs WHERE id = ?", params![id], |row| row.get(0))
+                .unwrap()
+        };
+        assert_eq!(pinned("before-edit"), None);
+        assert_eq!(pinned("after-edit"), Some(1));
+    }
+
+    #[test]
+    fn existing_results_are_attributed_to_openrouter() {
+        let conn = open_at_version(9);
+        insert_run(&conn, "r1", 10);
+        insert_result(&conn, "r1", "a", "answer");
+
+        apply_migrations(&conn, 10).unwrap();
+
+        assert_eq!(count(&conn, "SELECT COUNT(*) FROM test_case_results WHERE provider = 'openrouter'"), 1);
+    }
+}
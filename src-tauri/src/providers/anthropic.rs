@@ -0,0 +1,175 @@
+//! Anthropic's Messages API. The system prompt is a top-level field rather
+//! than a message, `max_tokens` is required, and the frequency/presence
+//! penalties have no equivalent, so they are dropped.
+
+use serde_json::{json, Value};
+
+use super::{api_error, http_client, read_lines, send, ChatCompletionRequest, Completion, Provider, ProviderError, ProviderModel, Usage};
+
+pub const ANTHROPIC_API_URL: &str = "https://api.anthropic.com";
+
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+
+/// Used when the request leaves `max_tokens` open
+const DEFAULT_MAX_TOKENS: i64 = 1024;
+
+pub struct AnthropicProvider {
+    http: reqwest::blocking::Client,
+    base_url: String,
+    api_key: String,
+}
+
+impl AnthropicProvider {
+    pub fn new(api_key: &str, base_url: &str) -> Result<AnthropicProvider, String> {
+        Ok(AnthropicProvider {
+            http: http_client()?,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key: api_key.to_string(),
+        })
+    }
+
+    fn request(&self, builder: reqwest::blocking::RequestBuilder) -> reqwest::blocking::RequestBuilder {
+        builder.header("x-api-key", &self.api_key).header("anthropic-version", ANTHROPIC_VERSION)
+    }
+
+    fn post_messages(&self, request: &ChatCompletionRequest, stream: bool) -> Result<reqwest::blocking::Response, ProviderError> {
+        let system: Vec<&str> = request
+            .messages
+            .iter()
+            .filter(|message| message.role == "system")
+            .map(|message| message.content.as_str())
+            .filter(|content| !content.is_empty())
+            .collect();
+        let messages: Vec<Value> = request
+            .messages
+            .iter()
+            .filter(|message| message.role != "system")
+            .map(|message| json!({ "role": message.role, "content": message.content }))
+            .collect();
+
+        let mut body = json!({
+            "model": request.model,
+            "max_tokens": request.max_tokens.filter(|max_tokens| *max_tokens > 0).unwrap_or(DEFAULT_MAX_TOKENS),
+            "messages": messages,
+        });
+        if !system.is_empty() {
+            body["system"] = Value::from(system.join("\n\n"));
+        }
+        // Anthropic's temperature range is 0-1, OpenRouter's 0-2
+        if let Some(temperature) = request.temperature {
+            body["temperature"] = Value::from(temperature.clamp(0.0, 1.0));
+        }
+        // Newer models reject temperature and top_p together; 1.0 is the default
+        if let Some(top_p) = request.top_p.filter(|top_p| *top_p < 1.0) {
+            body["top_p"] = Value::from(top_p);
+        }
+        if stream {
+            body["stream"] = Value::Bool(true);
+        }
+
+        send(self.request(self.http.post(format!("{}/v1/messages", self.base_url))).json(&body))
+    }
+}
+
+fn usage(input_tokens: i64, output_tokens: i64) -> Usage {
+    Usage {
+        prompt_tokens: input_tokens,
+        completion_tokens: output_tokens,
+        total_tokens: input_tokens + output_tokens,
+        cost: None,
+    }
+}
+
+impl Provider for AnthropicProvider {
+    fn chat_completion(&self, request: &ChatCompletionRequest) -> Result<Completion, ProviderError> {
+        let json: Value = self.post_messages(request, false)?.json().unwrap_or(Value::Null);
+        if let Some(error) = api_error(&json) {
+            return Err(error);
+        }
+
+        let content = json["content"]
+            .as_array()
+            .map(|blocks| blocks.iter().filter_map(|block| block["text"].as_str()).collect::<String>())
+            .unwrap_or_default();
+        let usage = json["usage"]
+            .is_object()
+            .then(|| usage(json["usage"]["input_tokens"].as_i64().unwrap_or(0), json["usage"]["output_tokens"].as_i64().unwrap_or(0)));
+        Ok(Completion { content, usage })
+    }
+
+    /// Streams server-sent events; the event type is repeated in each
+    /// `data` payload, so the `event:` lines are skipped.
+    fn chat_completion_stream(&self, request: &ChatCompletionRequest, on_delta: &mut dyn FnMut(&str)) -> Result<Completion, ProviderError> {
+        let response = self.post_messages(request, true)?;
+
+        let mut content = String::new();
+        let mut input_tokens = None;
+        let mut output_tokens = None;
+
+        read_lines(response, |line| {
+            let Some(data) = line.strip_prefix("data:") else {
+                return Ok(true);
+            };
+            let Ok(json) = serde_json::from_str::<Value>(data.trim()) else {
+                return Ok(true);
+            };
+
+            match json["type"].as_str().unwrap_or_default() {
+                "error" => {
+                    let mut error = api_error(&json).unwrap_or_else(|| ProviderError::from("Stream error".to_string()));
+                    // Mid-stream errors carry a type instead of a status
+                    error.status = error.status.or(match json["error"]["type"].as_str() {
+                        Some("rate_limit_error") => Some(429),
+                        Some("overloaded_error") => Some(529),
+                        Some("api_error") => Some(500),
+                        _ => None,
+                    });
+                    return Err(error);
+                }
+                "message_start" => {
+                    input_tokens = json["message"]["usage"]["input_tokens"].as_i64();
+                }
+                "content_block_delta" => {
+                    if let Some(delta) = json["delta"]["text"].as_str() {
+                        if !delta.is_empty() {
+                            content.push_str(delta);
+                            on_delta(delta);
+                        }
+                    }
+                }
+                // Carries the running output count
+                "message_delta" => {
+                    output_tokens = json["usage"]["output_tokens"].as_i64().or(output_tokens);
+                }
+                "message_stop" => return Ok(false),
+                _ => {}
+            }
+            Ok(true)
+        })?;
+
+        let usage = (input_tokens.is_some() || output_tokens.is_some())
+            .then(|| usage(input_tokens.unwrap_or(0), output_tokens.unwrap_or(0)));
+        Ok(Completion { content, usage })
+    }
+
+    fn list_models(&self) -> Result<Vec<ProviderModel>, String> {
+        let json: Value = send(self.request(self.http.get(format!("{}/v1/models?limit=1000", self.base_url))))?
+            .json()
+            .unwrap_or(Value::Null);
+        let models = json["data"].as_array().ok_or_else(|| "Unexpected /v1/models response".to_string())?;
+
+        Ok(models
+            .iter()
+            .filter_map(|model| {
+                let id = model["id"].as_str()?;
+                Some(ProviderModel {
+                    id: id.to_string(),
+                    name: model["display_name"].as_str().unwrap_or(id).to_string(),
+                    description: None,
+                    context_length: None,
+                    pricing: None,
+                })
+            })
+            .collect())
+    }
+}
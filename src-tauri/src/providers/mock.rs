@@ -0,0 +1,365 @@
+//! Scripted provider for offline runs, demos and tests. Responses come from a
+//! fixture file keyed by model and test case id; anything the fixture
+//! doesn't cover gets a deterministic echo of the prompt, so a full run works
+//! without a fixture at all.
+//!
+//! ```yaml
+//! defaults:
+//!   latencyMs: 150
+//! responses:
+//!   - model: good
+//!     testCaseId: math-1
+//!     response: "4"
+//!     chunks: ["", "4"]
+//!   - model: flaky
+//!     error: rate-limit
+//!     failTimes: 2        # fails twice per case, then answers
+//!     retryAfterMs: 500
+//!   - model: judge        # no testCaseId: matches every case
+//!     response: '{"score": 80, "reasoning": "Scripted"}'
+//! ```
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use serde::Deserialize;
+
+use super::{timeout_message, ChatCompletionRequest, Completion, Provider, ProviderError, ProviderModel, Usage};
+
+/// Model listed when the fixture names none
+const ECHO_MODEL: &str = "echo";
+
+/// Characters per streamed chunk unless the fixture says otherwise
+const DEFAULT_CHUNK_SIZE: usize = 16;
+
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MockFixture {
+    /// Applied to every response, including the echo fallback
+    #[serde(default)]
+    pub defaults: MockBehavior,
+    #[serde(default)]
+    pub responses: Vec<MockResponse>,
+}
+
+#[derive(Debug, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct MockBehavior {
+    /// Total time to answer; spread over the chunks when streaming
+    pub latency_ms: Option<u64>,
+    pub chunk_size: Option<usize>,
+    /// Token counts default to an estimate of four characters per token
+    pub prompt_tokens: Option<i64>,
+    pub completion_tokens: Option<i64>,
+    pub cost: Option<f64>,
+}
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MockResponse {
+    /// Model id without the provider prefix; omitted matches every model
+    pub model: Option<String>,
+    /// Omitted matches every case, and judge requests
+    pub test_case_id: Option<String>,
+    #[serde(default)]
+    pub response: String,
+    /// Explicit stream chunks; by default `response` is cut every
+    /// `chunkSize` characters
+    pub chunks: Option<Vec<String>>,
+    #[serde(flatten)]
+    pub behavior: MockBehavior,
+    pub error: Option<MockError>,
+    /// With `error`, fail only the first calls for each case and answer
+    /// afterwards
+    pub fail_times: Option<u32>,
+    /// `Retry-After` reported with a rate limit
+    pub retry_after_ms: Option<u64>,
+}
+
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum MockError {
+    RateLimit,
+    Timeout,
+    Server,
+}
+
+impl MockFixture {
+    /// Reads a JSON or YAML fixture, whatever its file is called: JSON when
+    /// it starts with `{`, YAML otherwise.
+    pub fn load(path: &Path) -> Result<MockFixture, String> {
+        let text = std::fs::read_to_string(path).map_err(|err| format!("Failed to read {}: {}", path.display(), err))?;
+        let is_json = text.trim_start_matches('\u{feff}').trim_start().starts_with('{');
+
+        let parsed = if is_json {
+            serde_json::from_str(&text).map_err(|err| err.to_string())
+        } else {
+            serde_yaml::from_str(&text).map_err(|err| err.to_string())
+        };
+        parsed.map_err(|err| format!("Invalid mock fixture {}: {}", path.display(), err))
+    }
+
+    /// The most specific entry for a model and case: both matching beats the
+    /// model alone, which beats the case alone, which beats a catch-all.
+    fn find(&self, model: &str, test_case_id: Option<&str>) -> Option<&MockResponse> {
+        self.responses
+            .iter()
+            .filter(|entry| entry.model.as_deref().is_none_or(|entry_model| entry_model == model))
+            .filter(|entry| match (&entry.test_case_id, test_case_id) {
+                (None, _) => true,
+                (Some(entry_case), Some(case)) => entry_case == case,
+                (Some(_), None) => false,
+            })
+            .max_by_key(|entry| (entry.model.is_some(), entry.test_case_id.is_some()))
+    }
+}
+
+pub struct MockProvider {
+    fixture: MockFixture,
+    /// Calls so far per (model, test case), for `failTimes`
+    calls: Mutex<HashMap<(String, Option<String>), u32>>,
+}
+
+/// A resolved answer to one request
+struct Scripted {
+    chunks: Vec<String>,
+    latency: Duration,
+    usage: Usage,
+    error: Option<ProviderError>,
+}
+
+impl MockProvider {
+    pub fn new(fixture: MockFixture) -> MockProvider {
+        MockProvider { fixture, calls: Mutex::new(HashMap::new()) }
+    }
+
+    fn script(&self, request: &ChatCompletionRequest) -> Scripted {
+        let test_case_id = request.test_case_id.as_deref();
+        let entry = self.fixture.find(&request.model, test_case_id);
+        let defaults = &self.fixture.defaults;
+        let behavior = entry.map(|entry| &entry.behavior);
+        let pick = |get: fn(&MockBehavior) -> Option<i64>| behavior.and_then(get).or_else(|| get(defaults));
+
+        let response = match entry {
+            Some(entry) => entry.response.clone(),
+            None => {
+                let prompt = request.messages.last().map(|message| message.content.as_str()).unwrap_or_default();
+                format!("Mock response from {} to: {}", request.model, prompt)
+            }
+        };
+
+        let chunk_size = behavior.and_then(|behavior| behavior.chunk_size).or(defaults.chunk_size).unwrap_or(DEFAULT_CHUNK_SIZE).max(1);
+        let chunks = entry.and_then(|entry| entry.chunks.clone()).unwrap_or_else(|| {
+            let chars: Vec<char> = response.chars().collect();
+            chars.chunks(chunk_size).map(|chunk| chunk.iter().collect()).collect()
+        });
+
+        let error = entry.and_then(|entry| {
+            let error = mock_error(entry.error?, entry.retry_after_ms);
+            let Some(fail_times) = entry.fail_times else {
+                return Some(error);
+            };
+            let mut calls = self.calls.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            let count = calls.entry((request.model.clone(), request.test_case_id.clone())).or_insert(0);
+            *count += 1;
+            (*count <= fail_times).then_some(error)
+        });
+
+        let prompt_chars: usize = request.messages.iter().map(|message| message.content.chars().count()).sum();
+        let prompt_tokens = pick(|behavior| behavior.prompt_tokens).unwrap_or_else(|| estimate_tokens(prompt_chars));
+        let completion_tokens =
+            pick(|behavior| behavior.completion_tokens).unwrap_or_else(|| estimate_tokens(chunks.iter().map(|chunk| chunk.chars().count()).sum()));
+        let latency_ms = behavior.and_then(|behavior| behavior.latency_ms).or(defaults.latency_ms).unwrap_or(0);
+
+        Scripted {
+            chunks,
+            latency: Duration::from_millis(latency_ms),
+            usage: Usage {
+                prompt_tokens,
+                completion_tokens,
+                total_tokens: prompt_tokens + completion_tokens,
+                cost: behavior.and_then(|behavior| behavior.cost).or(defaults.cost),
+            },
+            error,
+        }
+    }
+}
+
+fn estimate_tokens(chars: usize) -> i64 {
+    chars.div_ceil(4).max(1) as i64
+}
+
+fn mock_error(error: MockError, retry_after_ms: Option<u64>) -> ProviderError {
+    match error {
+        MockError::RateLimit => ProviderError::http(429, "Rate limit exceeded (429)", retry_after_ms.map(Duration::from_millis)),
+        MockError::Timeout => ProviderError::network(timeout_message()),
+        MockError::Server => ProviderError::http(500, "Internal server error (500)", None),
+    }
+}
+
+impl Provider for MockProvider {
+    fn chat_completion(&self, request: &ChatCompletionRequest) -> Result<Completion, ProviderError> {
+        let scripted = self.script(request);
+        thread::sleep(scripted.latency);
+        if let Some(error) = scripted.error {
+            return Err(error);
+        }
+        Ok(Completion { content: scripted.chunks.concat(), usage: Some(scripted.usage) })
+    }
+
+    fn chat_completion_stream(&self, request: &ChatCompletionRequest, on_delta: &mut dyn FnMut(&str)) -> Result<Completion, ProviderError> {
+        let scripted = self.script(request);
+        if let Some(error) = scripted.error {
+            thread::sleep(scripted.latency);
+            return Err(error);
+        }
+
+        let pause = scripted.latency / scripted.chunks.len().max(1) as u32;
+        let mut content = String::new();
+        for chunk in &scripted.chunks {
+            thread::sleep(pause);
+            if !chunk.is_empty() {
+                content.push_str(chunk);
+                on_delta(chunk);
+            }
+        }
+        Ok(Completion { content, usage: Some(scripted.usage) })
+    }
+
+    fn list_models(&self) -> Result<Vec<ProviderModel>, String> {
+        let mut ids: Vec<&str> = self.fixture.responses.iter().filter_map(|entry| entry.model.as_deref()).collect();
+        ids.sort_unstable();
+        ids.dedup();
+        if ids.is_empty() {
+            ids.push(ECHO_MODEL);
+        }
+
+        Ok(ids
+            .into_iter()
+            .map(|id| ProviderModel {
+                id: id.to_string(),
+                name: format!("Mock {}", id),
+                description: Some("Scripted responses for offline runs".to_string()),
+                context_length: None,
+                pricing: None,
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::ChatMessage;
+    use crate::ModelParameters;
+
+    const FIXTURE: &str = r#"
+defaults:
+  chunkSize: 3
+responses:
+  - model: good
+    testCaseId: math-1
+    response: "42"
+    chunks: ["", "4", "", "2"]
+    completionTokens: 1
+  - model: good
+    response: Good answer
+  - testCaseId: math-1
+    response: Any model on math-1
+  - model: flaky
+    error: rate-limit
+    failTimes: 2
+    retryAfterMs: 500
+  - model: slow
+    error: timeout
+"#;
+
+    fn provider() -> MockProvider {
+        MockProvider::new(serde_yaml::from_str(FIXTURE).unwrap())
+    }
+
+    fn request(model: &str, test_case_id: Option<&str>) -> ChatCompletionRequest {
+        let mut request = ChatCompletionRequest::new(model, vec![ChatMessage::user("What is 6 x 7?")], &ModelParameters::default());
+        request.test_case_id = test_case_id.map(str::to_string);
+        request
+    }
+
+    fn answer(provider: &MockProvider, model: &str, test_case_id: Option<&str>) -> String {
+        provider.chat_completion(&request(model, test_case_id)).unwrap().content
+    }
+
+    #[test]
+    fn fixtures_are_read_by_content_not_extension() {
+        let dir = tempfile::tempdir().unwrap();
+        let json_named_yaml = dir.path().join("fixture.yaml");
+        std::fs::write(&json_named_yaml, r#"{"responses": [{"model": "good", "response": "4"}]}"#).unwrap();
+        let yaml_named_json = dir.path().join("fixture.json");
+        std::fs::write(&yaml_named_json, "responses:\n  - model: good\n    response: \"4\"\n").unwrap();
+
+        for path in [json_named_yaml, yaml_named_json] {
+            let fixture = MockFixture::load(&path).unwrap();
+            assert_eq!(fixture.responses[0].response, "4");
+        }
+    }
+
+    #[test]
+    fn the_most_specific_response_wins() {
+        let provider = provider();
+        assert_eq!(answer(&provider, "good", Some("math-1")), "42");
+        assert_eq!(answer(&provider, "good", Some("other")), "Good answer");
+        assert_eq!(answer(&provider, "good", None), "Good answer");
+        assert_eq!(answer(&provider, "other", Some("math-1")), "Any model on math-1");
+        assert_eq!(answer(&provider, "other", Some("other")), "Mock response from other to: What is 6 x 7?");
+
+        let usage = provider.chat_completion(&request("good", Some("math-1"))).unwrap().usage.unwrap();
+        assert_eq!(usage.completion_tokens, 1);
+        assert_eq!(usage.total_tokens, usage.prompt_tokens + 1);
+    }
+
+    #[test]
+    fn rate_limits_clear_after_fail_times_per_case() {
+        let provider = provider();
+        for _ in 0..2 {
+            let err = provider.chat_completion(&request("flaky", Some("a"))).unwrap_err();
+            assert!(err.is_rate_limit());
+            assert_eq!(err.retry_after, Some(Duration::from_millis(500)));
+        }
+        // Another case has its own count
+        assert!(provider.chat_completion(&request("flaky", Some("b"))).is_err());
+        assert!(provider.chat_completion(&request("flaky", Some("a"))).is_ok());
+    }
+
+    #[test]
+    fn timeouts_are_network_errors() {
+        let provider = provider();
+        let err = provider.chat_completion(&request("slow", Some("a"))).unwrap_err();
+        assert!(err.network);
+        assert_eq!(err.status, None);
+        assert_eq!(err.message, timeout_message());
+
+        let mut deltas = Vec::new();
+        let err = provider.chat_completion_stream(&request("slow", Some("a")), &mut |delta| deltas.push(delta.to_string()));
+        assert!(err.unwrap_err().network);
+        assert!(deltas.is_empty());
+    }
+
+    #[test]
+    fn streams_skip_empty_chunks() {
+        let provider = provider();
+        let stream = |model: &str, test_case_id: Option<&str>| {
+            let mut deltas = Vec::new();
+            let completion =
+                provider.chat_completion_stream(&request(model, test_case_id), &mut |delta| deltas.push(delta.to_string())).unwrap();
+            (completion.content, deltas)
+        };
+
+        assert_eq!(stream("good", Some("math-1")), ("42".to_string(), vec!["4".to_string(), "2".to_string()]));
+        // Without explicit chunks the response is cut every `chunkSize` characters
+        let (content, deltas) = stream("good", Some("other"));
+        assert_eq!(content, "Good answer");
+        assert_eq!(deltas, vec!["Goo", "d a", "nsw", "er"]);
+    }
+}
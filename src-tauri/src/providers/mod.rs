@@ -0,0 +1,664 @@
+//! Model backends. The rest of the backend builds a provider-neutral
+//! `ChatCompletionRequest`; the provider named by the model id translates it
+//! to its own API.
+//!
+//! Model ids are namespaced as `<provider>:<model>`, e.g. `ollama:llama3.1`
+//! or `anthropic:claude-sonnet-4-5`. Ids without a known namespace belong to
+//! OpenRouter, so existing ids such as `openai/gpt-4o` (or OpenRouter's own
+//! `:free` variants) keep working.
+
+mod anthropic;
+mod mock;
+mod ollama;
+mod openai;
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+use std::time::Duration;
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+#[cfg(feature = "app")]
+use tauri::State;
+
+use crate::cassette::Cassette;
+use crate::response_cache::ResponseCache;
+use crate::{get_setting, set_setting, Db, ModelParameters};
+
+pub use anthropic::{AnthropicProvider, ANTHROPIC_API_URL};
+pub use mock::{MockBehavior, MockError, MockFixture, MockProvider, MockResponse};
+pub use ollama::{OllamaProvider, OLLAMA_API_URL};
+pub use openai::{OpenAiProvider, OPENROUTER_API_URL};
+
+/// Provider of model ids without a namespace
+pub const OPENROUTER: &str = "openrouter";
+
+/// User-configured providers, stored as a JSON array of `ProviderConfig`
+const PROVIDERS_SETTING: &str = "providers";
+
+/// Per-request timeout, same as the frontend
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(120);
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ChatMessage {
+    pub role: String,
+    pub content: String,
+}
+
+impl ChatMessage {
+    pub fn system(content: &str) -> ChatMessage {
+        ChatMessage { role: "system".to_string(), content: content.to_string() }
+    }
+
+    pub fn user(content: &str) -> ChatMessage {
+        ChatMessage { role: "user".to_string(), content: content.to_string() }
+    }
+}
+
+/// OpenAI-style request; `model` is the provider's own id, without the
+/// namespace.
+#[derive(Debug, Serialize, Clone)]
+pub struct ChatCompletionRequest {
+    pub model: String,
+    pub messages: Vec<ChatMessage>,
+    /// Case being answered, if any. Never sent; the mock provider keys its
+    /// fixture on it.
+    #[serde(skip)]
+    pub test_case_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_tokens: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub frequency_penalty: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub presence_penalty: Option<f64>,
+}
+
+impl ChatCompletionRequest {
+    pub fn new(model: &str, messages: Vec<ChatMessage>, parameters: &ModelParameters) -> ChatCompletionRequest {
+        ChatCompletionRequest {
+            model: model.to_string(),
+            messages,
+            test_case_id: None,
+            temperature: Some(parameters.temperature),
+            top_p: Some(parameters.top_p),
+            max_tokens: Some(parameters.max_tokens),
+            frequency_penalty: Some(parameters.frequency_penalty),
+            presence_penalty: Some(parameters.presence_penalty),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct Usage {
+    #[serde(default)]
+    pub prompt_tokens: i64,
+    #[serde(default)]
+    pub completion_tokens: i64,
+    #[serde(default)]
+    pub total_tokens: i64,
+    /// Credits charged, for providers that report it (OpenRouter)
+    pub cost: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Completion {
+    pub content: String,
+    pub usage: Option<Usage>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderModel {
+    /// Namespaced id, ready to use in a run
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub context_length: Option<i64>,
+    pub pricing: Option<ModelPricing>,
+}
+
+/// USD per token, as decimal strings
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ModelPricing {
+    #[serde(default)]
+    pub prompt: String,
+    #[serde(default)]
+    pub completion: String,
+}
+
+/// A failed model call. The status and `Retry-After` let the scheduler
+/// tell rate limits and outages, which are worth retrying, from bad requests.
+#[derive(Debug, Clone)]
+pub struct ProviderError {
+    pub message: String,
+    /// HTTP status, or the upstream status an API reported in its body
+    pub status: Option<u16>,
+    pub retry_after: Option<Duration>,
+    /// Timeouts and failed connections
+    pub network: bool,
+}
+
+impl ProviderError {
+    pub fn http(status: u16, message: impl Into<String>, retry_after: Option<Duration>) -> ProviderError {
+        ProviderError { message: message.into(), status: Some(status), retry_after, network: false }
+    }
+
+    pub fn network(message: impl Into<String>) -> ProviderError {
+        ProviderError { message: message.into(), status: None, retry_after: None, network: true }
+    }
+
+    pub fn is_rate_limit(&self) -> bool {
+        self.status == Some(429)
+    }
+
+    /// Rate limits, server errors and network failures
+    pub fn is_retryable(&self) -> bool {
+        self.network || matches!(self.status, Some(408 | 429 | 500..=599))
+    }
+}
+
+impl From<String> for ProviderError {
+    fn from(message: String) -> ProviderError {
+        ProviderError { message, status: None, retry_after: None, network: false }
+    }
+}
+
+impl From<ProviderError> for String {
+    fn from(error: ProviderError) -> String {
+        error.message
+    }
+}
+
+impl fmt::Display for ProviderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+pub trait Provider: Send + Sync {
+    fn chat_completion(&self, request: &ChatCompletionRequest) -> Result<Completion, ProviderError>;
+
+    /// Streams a completion, handing each content fragment to `on_delta`.
+    fn chat_completion_stream(&self, request: &ChatCompletionRequest, on_delta: &mut dyn FnMut(&str)) -> Result<Completion, ProviderError>;
+
+    /// Models the provider serves; ids are the provider's own.
+    fn list_models(&self) -> Result<Vec<ProviderModel>, String>;
+}
+
+// ============================================================================
+// Configuration
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ProviderKind {
+    #[serde(rename = "openrouter")]
+    OpenRouter,
+    /// vLLM, llama.cpp server, Ollama's `/v1`, LM Studio, ...
+    #[serde(rename = "openai-compatible")]
+    OpenAiCompatible,
+    #[serde(rename = "ollama")]
+    Ollama,
+    #[serde(rename = "anthropic")]
+    Anthropic,
+    /// Scripted responses from a fixture file, for offline runs
+    #[serde(rename = "mock")]
+    Mock,
+}
+
+impl ProviderKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProviderKind::OpenRouter => "openrouter",
+            ProviderKind::OpenAiCompatible => "openai-compatible",
+            ProviderKind::Ollama => "ollama",
+            ProviderKind::Anthropic => "anthropic",
+            ProviderKind::Mock => "mock",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderConfig {
+    /// Namespace in model ids: lowercase letters, digits, `-` and `_`
+    pub id: String,
+    pub kind: ProviderKind,
+    /// Defaults to the vendor's endpoint; required for OpenAI-compatible
+    /// servers
+    pub base_url: Option<String>,
+    pub api_key: Option<String>,
+    /// Mock only: JSON or YAML fixture with the scripted responses
+    pub fixture: Option<String>,
+}
+
+/// A provider as the app sees it: whether a key is stored, never the key.
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderListing {
+    #[serde(flatten)]
+    pub config: ProviderConfig,
+    pub api_key_set: bool,
+}
+
+/// Always available; a stored config with the same id replaces them.
+fn builtin_configs() -> Vec<ProviderConfig> {
+    let builtin = |id: &str, kind| ProviderConfig { id: id.to_string(), kind, base_url: None, api_key: None, fixture: None };
+    vec![
+        builtin(OPENROUTER, ProviderKind::OpenRouter),
+        builtin("anthropic", ProviderKind::Anthropic),
+        builtin("ollama", ProviderKind::Ollama),
+        builtin("mock", ProviderKind::Mock),
+    ]
+}
+
+fn stored_configs(conn: &Connection) -> Result<Vec<ProviderConfig>, String> {
+    match get_setting(conn, PROVIDERS_SETTING)? {
+        Some(json) => serde_json::from_str(&json).map_err(|err| format!("Invalid provider settings: {}", err)),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Built-in providers merged with the stored ones.
+pub fn get_provider_configs(conn: &Connection) -> Result<Vec<ProviderConfig>, String> {
+    let stored = stored_configs(conn)?;
+    let mut configs: Vec<ProviderConfig> = builtin_configs()
+        .into_iter()
+        .filter(|builtin| !stored.iter().any(|config| config.id == builtin.id))
+        .collect();
+    configs.extend(stored);
+    Ok(configs)
+}
+
+/// The provider configs with their API keys taken out.
+pub fn list_provider_listings(conn: &Connection) -> Result<Vec<ProviderListing>, String> {
+    Ok(get_provider_configs(conn)?
+        .into_iter()
+        .map(|mut config| {
+            let api_key_set = non_empty(config.api_key.as_deref()).is_some();
+            config.api_key = None;
+            ProviderListing { config, api_key_set }
+        })
+        .collect())
+}
+
+fn validate_config(config: &ProviderConfig) -> Result<(), String> {
+    let valid_id = !config.id.is_empty()
+        && config.id.chars().all(|ch| ch.is_ascii_lowercase() || ch.is_ascii_digit() || ch == '-' || ch == '_');
+    if !valid_id {
+        return Err(format!(
+            "Provider id '{}' may only contain lowercase letters, digits, '-' and '_'.",
+            config.id
+        ));
+    }
+    if config.kind == ProviderKind::OpenAiCompatible && config.base_url.as_deref().is_none_or(|url| url.trim().is_empty()) {
+        return Err(format!("Provider '{}' needs a base URL.", config.id));
+    }
+    if let Some(fixture) = non_empty(config.fixture.as_deref()) {
+        MockFixture::load(Path::new(&fixture))?;
+    }
+    Ok(())
+}
+
+/// Saves a provider. Without an API key the stored one is kept, since the
+/// app only ever sees redacted configs; delete the provider to drop a key.
+pub fn save_provider_config(conn: &Connection, config: &ProviderConfig) -> Result<(), String> {
+    validate_config(config)?;
+
+    let mut config = config.clone();
+    config.api_key = non_empty(config.api_key.as_deref());
+    let mut stored = stored_configs(conn)?;
+    match stored.iter_mut().find(|existing| existing.id == config.id) {
+        Some(existing) => {
+            if config.api_key.is_none() {
+                config.api_key = existing.api_key.take();
+            }
+            *existing = config;
+        }
+        None => stored.push(config),
+    }
+    set_setting(conn, PROVIDERS_SETTING, &serde_json::to_string(&stored).map_err(|err| err.to_string())?)
+}
+
+/// Removes a stored provider; a built-in one falls back to its defaults.
+/// Returns false when nothing was stored under the id.
+pub fn delete_provider_config(conn: &Connection, id: &str) -> Result<bool, String> {
+    let mut stored = stored_configs(conn)?;
+    let before = stored.len();
+    stored.retain(|config| config.id != id);
+    if stored.len() == before {
+        return Ok(false);
+    }
+    set_setting(conn, PROVIDERS_SETTING, &serde_json::to_string(&stored).map_err(|err| err.to_string())?)?;
+    Ok(true)
+}
+
+fn non_empty(value: Option<&str>) -> Option<String> {
+    value.map(str::trim).filter(|value| !value.is_empty()).map(str::to_string)
+}
+
+fn build_provider(config: &ProviderConfig) -> Result<Box<dyn Provider>, String> {
+    let base_url = non_empty(config.base_url.as_deref());
+    let api_key = non_empty(config.api_key.as_deref());
+
+    Ok(match config.kind {
+        ProviderKind::OpenRouter => {
+            let api_key = api_key.ok_or_else(|| "An OpenRouter API key is required.".to_string())?;
+            Box::new(OpenAiProvider::openrouter(&api_key, base_url.as_deref())?)
+        }
+        ProviderKind::OpenAiCompatible => {
+            let base_url = base_url.ok_or_else(|| format!("Provider '{}' needs a base URL.", config.id))?;
+            Box::new(OpenAiProvider::compatible(&base_url, api_key.as_deref())?)
+        }
+        ProviderKind::Ollama => Box::new(OllamaProvider::new(base_url.as_deref().unwrap_or(OLLAMA_API_URL))?),
+        ProviderKind::Anthropic => {
+            let api_key = api_key
+                .or_else(|| non_empty(std::env::var("ANTHROPIC_API_KEY").ok().as_deref()))
+                .ok_or_else(|| "An Anthropic API key is required (provider settings or ANTHROPIC_API_KEY).".to_string())?;
+            Box::new(AnthropicProvider::new(&api_key, base_url.as_deref().unwrap_or(ANTHROPIC_API_URL))?)
+        }
+        ProviderKind::Mock => {
+            let fixture = match non_empty(config.fixture.as_deref())
+                .or_else(|| non_empty(std::env::var("BENCHMAKER_MOCK_FIXTURE").ok().as_deref()))
+            {
+                Some(path) => MockFixture::load(Path::new(&path))?,
+                None => MockFixture::default(),
+            };
+            Box::new(MockProvider::new(fixture))
+        }
+    })
+}
+
+// ============================================================================
+// Registry
+// ============================================================================
+
+/// The configured providers, by namespace. A provider that could not be set
+/// up (say, a missing API key) keeps the reason, reported when a model of
+/// that provider is used.
+pub struct Providers {
+    providers: HashMap<String, Result<Box<dyn Provider>, String>>,
+    cassette: Option<Cassette>,
+    response_cache: Option<ResponseCache>,
+}
+
+impl Providers {
+    pub fn from_configs(configs: &[ProviderConfig]) -> Providers {
+        Providers {
+            providers: configs.iter().map(|config| (config.id.clone(), build_provider(config))).collect(),
+            cassette: None,
+            response_cache: None,
+        }
+    }
+
+    /// Records model calls to, or replays them from, `cassette`.
+    pub fn with_cassette(mut self, cassette: Cassette) -> Providers {
+        self.cassette = Some(cassette);
+        self
+    }
+
+    pub fn cassette(&self) -> Option<&Cassette> {
+        self.cassette.as_ref()
+    }
+
+    /// Answers repeated model calls from `cache`.
+    pub fn with_response_cache(mut self, cache: ResponseCache) -> Providers {
+        self.response_cache = Some(cache);
+        self
+    }
+
+    pub fn response_cache(&self) -> Option<&ResponseCache> {
+        self.response_cache.as_ref()
+    }
+
+    /// Loads the configured providers. `openrouter_api_key` takes precedence
+    /// over a stored key, since the app keeps its key in the UI settings.
+    pub fn load(conn: &Connection, openrouter_api_key: Option<&str>) -> Result<Providers, String> {
+        let mut configs = get_provider_configs(conn)?;
+        if let Some(api_key) = non_empty(openrouter_api_key) {
+            for config in configs.iter_mut().filter(|config| config.id == OPENROUTER) {
+                config.api_key = Some(api_key.clone());
+            }
+        }
+        Ok(Providers::from_configs(&configs))
+    }
+
+    /// Splits a model id into its provider namespace and the provider's own
+    /// model id.
+    pub fn split_model_id<'a>(&self, model_id: &'a str) -> (&'a str, &'a str) {
+        match model_id.split_once(':') {
+            Some((namespace, model)) if self.providers.contains_key(namespace) => (namespace, model),
+            _ => (OPENROUTER, model_id),
+        }
+    }
+
+    /// The provider of a model id, its namespace and the provider's own
+    /// model id.
+    pub fn resolve<'a>(&self, model_id: &'a str) -> Result<(&dyn Provider, &'a str, &'a str), String> {
+        let (namespace, model) = self.split_model_id(model_id);
+        match self.providers.get(namespace) {
+            Some(Ok(provider)) => Ok((provider.as_ref(), namespace, model)),
+            Some(Err(reason)) => Err(reason.clone()),
+            None => Err(format!("Unknown provider '{}'.", namespace)),
+        }
+    }
+
+    /// Fails on the first model whose provider is not usable, so a run
+    /// doesn't start with models that can only fail.
+    pub fn check_models<'a>(&self, model_ids: impl IntoIterator<Item = &'a str>) -> Result<(), String> {
+        for model_id in model_ids {
+            self.resolve(model_id).map_err(|err| format!("{}: {}", model_id, err))?;
+        }
+        Ok(())
+    }
+
+    /// Models of one provider, with namespaced ids.
+    pub fn list_models(&self, provider_id: &str) -> Result<Vec<ProviderModel>, String> {
+        let provider = match self.providers.get(provider_id) {
+            Some(Ok(provider)) => provider,
+            Some(Err(reason)) => return Err(reason.clone()),
+            None => return Err(format!("Unknown provider '{}'.", provider_id)),
+        };
+
+        let mut models = provider.list_models()?;
+        if provider_id != OPENROUTER {
+            for model in &mut models {
+                model.id = format!("{}:{}", provider_id, model.id);
+            }
+        }
+        Ok(models)
+    }
+}
+
+// ============================================================================
+// HTTP helpers
+// ============================================================================
+
+fn http_client() -> Result<reqwest::blocking::Client, String> {
+    reqwest::blocking::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .build()
+        .map_err(|err| err.to_string())
+}
+
+fn timeout_message() -> String {
+    format!("Request timed out after {}s", REQUEST_TIMEOUT.as_secs())
+}
+
+/// reqwest's top-level message is generic ("error sending request"); the
+/// useful part is in the source chain.
+fn describe(err: &reqwest::Error) -> ProviderError {
+    if err.is_timeout() {
+        return ProviderError::network(timeout_message());
+    }
+    let mut message = err.to_string();
+    let mut source = std::error::Error::source(err);
+    while let Some(cause) = source {
+        message.push_str(&format!(": {}", cause));
+        source = cause.source();
+    }
+    if err.is_connect() || err.is_request() {
+        ProviderError::network(message)
+    } else {
+        ProviderError::from(message)
+    }
+}
+
+/// An error reported in a response body: `{"error": {"message": ...}}` or
+/// `{"error": "..."}`. OpenRouter puts the upstream status in `code`, also
+/// for errors sent mid-stream.
+fn api_error(json: &Value) -> Option<ProviderError> {
+    let message = json["error"]["message"].as_str().or_else(|| json["error"].as_str())?;
+    let status = json["error"]["code"]
+        .as_u64()
+        .and_then(|code| u16::try_from(code).ok())
+        .filter(|code| (400..600).contains(code));
+    Some(ProviderError { message: message.to_string(), status, retry_after: None, network: false })
+}
+
+/// `Retry-After` in seconds or as an HTTP date, or OpenAI's
+/// `retry-after-ms`.
+fn retry_after(headers: &reqwest::header::HeaderMap) -> Option<Duration> {
+    let header = |name: &str| headers.get(name).and_then(|value| value.to_str().ok()).map(str::trim);
+    if let Some(ms) = header("retry-after-ms").and_then(|value| value.parse::<f64>().ok()) {
+        return Some(Duration::from_secs_f64(ms.max(0.0) / 1000.0));
+    }
+    let value = header("retry-after")?;
+    if let Ok(seconds) = value.parse::<f64>() {
+        return Some(Duration::from_secs_f64(seconds.max(0.0)));
+    }
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    Some((date.with_timezone(&chrono::Utc) - chrono::Utc::now()).to_std().unwrap_or_default())
+}
+
+/// Sends a request and turns a non-success status into the API's message.
+fn send(request: reqwest::blocking::RequestBuilder) -> Result<reqwest::blocking::Response, ProviderError> {
+    let response = request.send().map_err(|err| describe(&err))?;
+
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let retry_after = retry_after(response.headers());
+    let json: Value = response.json().unwrap_or(Value::Null);
+    let message = api_error(&json)
+        .map(|error| error.message)
+        .or_else(|| json["message"].as_str().map(str::to_string))
+        .or_else(|| json["detail"].as_str().map(str::to_string));
+    Err(ProviderError::http(
+        status.as_u16(),
+        message.unwrap_or_else(|| format!("API request failed: {}", status.as_u16())),
+        retry_after,
+    ))
+}
+
+/// Hands each line of a streamed body to `on_line` until it returns false.
+fn read_lines(
+    response: reqwest::blocking::Response,
+    mut on_line: impl FnMut(&str) -> Result<bool, ProviderError>,
+) -> Result<(), ProviderError> {
+    for line in BufReader::new(response).lines() {
+        let line = line.map_err(|err| match err.kind() {
+            io::ErrorKind::TimedOut => ProviderError::network(timeout_message()),
+            _ => ProviderError::network(format!("Stream interrupted: {}", err)),
+        })?;
+        if !on_line(line.trim())? {
+            break;
+        }
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[cfg(feature = "app")]
+#[tauri::command]
+pub fn list_providers(db: State<'_, Db>) -> Result<Vec<ProviderListing>, String> {
+    let conn = db.conn()?;
+    list_provider_listings(&conn)
+}
+
+#[cfg(feature = "app")]
+#[tauri::command]
+pub fn save_provider(db: State<'_, Db>, config: ProviderConfig) -> Result<(), String> {
+    let conn = db.conn()?;
+    save_provider_config(&conn, &config)
+}
+
+/// Models of one provider, with namespaced ids, for the app's model picker.
+/// Most providers are asked over the network, so this runs off the main
+/// thread and without holding the database.
+#[cfg(feature = "app")]
+#[tauri::command(async)]
+pub fn list_provider_models(db: State<'_, Db>, provider_id: String) -> Result<Vec<ProviderModel>, String> {
+    let configs: Vec<ProviderConfig> = {
+        let conn = db.conn()?;
+        get_provider_configs(&conn)?.into_iter().filter(|config| config.id == provider_id).collect()
+    };
+    Providers::from_configs(&configs).list_models(&provider_id)
+}
+
+#[cfg(feature = "app")]
+#[tauri::command]
+pub fn delete_provider(db: State<'_, Db>, id: String) -> Result<bool, String> {
+    let conn = db.conn()?;
+    delete_provider_config(&conn, &id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::memory_db;
+
+    fn config(id: &str, kind: ProviderKind, api_key: Option<&str>) -> ProviderConfig {
+        ProviderConfig { id: id.to_string(), kind, base_url: None, api_key: api_key.map(str::to_string), fixture: None }
+    }
+
+    fn stored_key(conn: &Connection, id: &str) -> Option<String> {
+        get_provider_configs(conn).unwrap().into_iter().find(|config| config.id == id).and_then(|config| config.api_key)
+    }
+
+    #[test]
+    fn listings_never_contain_api_keys() {
+        let conn = memory_db();
+        save_provider_config(&conn, &config("anthropic", ProviderKind::Anthropic, Some("sk-secret"))).unwrap();
+
+        let listings = list_provider_listings(&conn).unwrap();
+        let anthropic = listings.iter().find(|listing| listing.config.id == "anthropic").unwrap();
+        assert!(anthropic.api_key_set);
+        assert!(listings.iter().all(|listing| listing.config.api_key.is_none()));
+        assert!(!serde_json::to_string(&listings).unwrap().contains("sk-secret"));
+    }
+
+    #[test]
+    fn provider_models_are_namespaced() {
+        let conn = memory_db();
+        let providers = Providers::load(&conn, None).unwrap();
+
+        let ids: Vec<String> = providers.list_models("mock").unwrap().into_iter().map(|model| model.id).collect();
+        assert_eq!(ids, vec!["mock:echo"]);
+        assert!(providers.list_models("nope").unwrap_err().contains("Unknown provider"));
+    }
+
+    #[test]
+    fn saving_without_a_key_keeps_the_stored_one() {
+        let conn = memory_db();
+        save_provider_config(&conn, &config("anthropic", ProviderKind::Anthropic, Some("sk-old"))).unwrap();
+
+        save_provider_config(&conn, &config("anthropic", ProviderKind::Anthropic, Some("  "))).unwrap();
+        assert_eq!(stored_key(&conn, "anthropic").as_deref(), Some("sk-old"));
+        save_provider_config(&conn, &config("anthropic", ProviderKind::Anthropic, None)).unwrap();
+        assert_eq!(stored_key(&conn, "anthropic").as_deref(), Some("sk-old"));
+
+        save_provider_config(&conn, &config("anthropic", ProviderKind::Anthropic, Some("sk-new"))).unwrap();
+        assert_eq!(stored_key(&conn, "anthropic").as_deref(), Some("sk-new"));
+    }
+}
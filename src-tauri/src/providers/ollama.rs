@@ -0,0 +1,112 @@
+//! Ollama's native `/api/chat`. Unlike its OpenAI-compatible `/v1`, the
+//! native API takes sampling options per request and reports token counts
+//! on every response.
+
+use serde_json::{json, Map, Value};
+
+use super::{api_error, http_client, read_lines, send, ChatCompletionRequest, Completion, Provider, ProviderError, ProviderModel, Usage};
+
+pub const OLLAMA_API_URL: &str = "http://localhost:11434";
+
+pub struct OllamaProvider {
+    http: reqwest::blocking::Client,
+    base_url: String,
+}
+
+impl OllamaProvider {
+    pub fn new(base_url: &str) -> Result<OllamaProvider, String> {
+        Ok(OllamaProvider { http: http_client()?, base_url: base_url.trim_end_matches('/').to_string() })
+    }
+
+    fn post_chat(&self, request: &ChatCompletionRequest, stream: bool) -> Result<reqwest::blocking::Response, ProviderError> {
+        let mut options = Map::new();
+        let mut option = |name: &str, value: Option<Value>| {
+            if let Some(value) = value {
+                options.insert(name.to_string(), value);
+            }
+        };
+        option("temperature", request.temperature.map(Value::from));
+        option("top_p", request.top_p.map(Value::from));
+        option("num_predict", request.max_tokens.map(Value::from));
+        option("frequency_penalty", request.frequency_penalty.map(Value::from));
+        option("presence_penalty", request.presence_penalty.map(Value::from));
+
+        let body = json!({
+            "model": request.model,
+            "messages": request.messages,
+            "stream": stream,
+            "options": options,
+        });
+
+        send(self.http.post(format!("{}/api/chat", self.base_url)).json(&body))
+    }
+}
+
+/// Token counts come with the final (`done`) message.
+fn usage_from_json(json: &Value) -> Option<Usage> {
+    if json["prompt_eval_count"].is_null() && json["eval_count"].is_null() {
+        return None;
+    }
+    let prompt_tokens = json["prompt_eval_count"].as_i64().unwrap_or(0);
+    let completion_tokens = json["eval_count"].as_i64().unwrap_or(0);
+    Some(Usage { prompt_tokens, completion_tokens, total_tokens: prompt_tokens + completion_tokens, cost: None })
+}
+
+impl Provider for OllamaProvider {
+    fn chat_completion(&self, request: &ChatCompletionRequest) -> Result<Completion, ProviderError> {
+        let json: Value = self.post_chat(request, false)?.json().unwrap_or(Value::Null);
+        if let Some(error) = api_error(&json) {
+            return Err(error);
+        }
+
+        let content = json["message"]["content"].as_str().unwrap_or_default().to_string();
+        Ok(Completion { content, usage: usage_from_json(&json) })
+    }
+
+    /// Streams newline-delimited JSON objects, one per fragment.
+    fn chat_completion_stream(&self, request: &ChatCompletionRequest, on_delta: &mut dyn FnMut(&str)) -> Result<Completion, ProviderError> {
+        let response = self.post_chat(request, true)?;
+
+        let mut content = String::new();
+        let mut usage = None;
+
+        read_lines(response, |line| {
+            let Ok(json) = serde_json::from_str::<Value>(line) else {
+                return Ok(true);
+            };
+            if let Some(error) = api_error(&json) {
+                return Err(error);
+            }
+            if let Some(delta) = json["message"]["content"].as_str() {
+                if !delta.is_empty() {
+                    content.push_str(delta);
+                    on_delta(delta);
+                }
+            }
+            if json["done"].as_bool() == Some(true) {
+                usage = usage_from_json(&json);
+                return Ok(false);
+            }
+            Ok(true)
+        })?;
+
+        Ok(Completion { content, usage })
+    }
+
+    fn list_models(&self) -> Result<Vec<ProviderModel>, String> {
+        let json: Value = send(self.http.get(format!("{}/api/tags", self.base_url)))?.json().unwrap_or(Value::Null);
+        let models = json["models"].as_array().ok_or_else(|| "Unexpected /api/tags response".to_string())?;
+
+        Ok(models
+            .iter()
+            .filter_map(|model| model["name"].as_str())
+            .map(|name| ProviderModel {
+                id: name.to_string(),
+                name: name.to_string(),
+                description: None,
+                context_length: None,
+                pricing: None,
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,178 @@
+//! OpenAI-style `/chat/completions` APIs: OpenRouter, and self-hosted
+//! servers that copy the API (vLLM, llama.cpp, Ollama's `/v1`, LM Studio).
+//! The OpenRouter flavour mirrors the request shape of
+//! `src/services/openrouter.ts`.
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use super::{api_error, http_client, read_lines, send, ChatCompletionRequest, Completion, ModelPricing, Provider, ProviderError, ProviderModel, Usage};
+
+pub const OPENROUTER_API_URL: &str = "https://openrouter.ai/api/v1";
+
+/// Reads `usage` from a completion or a stream chunk.
+fn extract_usage(json: &Value) -> Option<Usage> {
+    json.get("usage").filter(|usage| usage.is_object()).and_then(|usage| serde_json::from_value(usage.clone()).ok())
+}
+
+/// An entry of `/models`. OpenRouter fills in everything; other servers
+/// often only send the id.
+#[derive(Debug, Deserialize)]
+struct ModelEntry {
+    id: String,
+    name: Option<String>,
+    description: Option<String>,
+    context_length: Option<i64>,
+    pricing: Option<ModelPricing>,
+}
+
+pub struct OpenAiProvider {
+    http: reqwest::blocking::Client,
+    base_url: String,
+    api_key: Option<String>,
+    /// Enables OpenRouter's extensions (cost accounting, app attribution)
+    openrouter: bool,
+}
+
+impl OpenAiProvider {
+    pub fn openrouter(api_key: &str, base_url: Option<&str>) -> Result<OpenAiProvider, String> {
+        Ok(OpenAiProvider {
+            http: http_client()?,
+            base_url: base_url.unwrap_or(OPENROUTER_API_URL).trim_end_matches('/').to_string(),
+            api_key: Some(api_key.to_string()),
+            openrouter: true,
+        })
+    }
+
+    /// `base_url` is the API root, usually ending in `/v1`.
+    pub fn compatible(base_url: &str, api_key: Option<&str>) -> Result<OpenAiProvider, String> {
+        Ok(OpenAiProvider {
+            http: http_client()?,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_key: api_key.map(str::to_string),
+            openrouter: false,
+        })
+    }
+
+    fn request(&self, builder: reqwest::blocking::RequestBuilder) -> reqwest::blocking::RequestBuilder {
+        let builder = match &self.api_key {
+            Some(api_key) => builder.bearer_auth(api_key),
+            None => builder,
+        };
+        if self.openrouter {
+            builder.header("X-Title", "Benchmaker")
+        } else {
+            builder
+        }
+    }
+
+    fn post_chat(&self, request: &ChatCompletionRequest, stream: bool) -> Result<reqwest::blocking::Response, ProviderError> {
+        let mut body = serde_json::to_value(request).map_err(|err| err.to_string())?;
+        if self.openrouter {
+            // Ask OpenRouter to include the charged cost in `usage`
+            body["usage"] = serde_json::json!({ "include": true });
+        }
+        if stream {
+            body["stream"] = Value::Bool(true);
+            if !self.openrouter {
+                // Without this, most servers send no usage when streaming
+                body["stream_options"] = serde_json::json!({ "include_usage": true });
+            }
+        }
+
+        send(self.request(self.http.post(format!("{}/chat/completions", self.base_url))).json(&body))
+    }
+}
+
+impl Provider for OpenAiProvider {
+    fn chat_completion(&self, request: &ChatCompletionRequest) -> Result<Completion, ProviderError> {
+        let json: Value = self.post_chat(request, false)?.json().unwrap_or(Value::Null);
+        completion_from_json(&json)
+    }
+
+    /// Streams over SSE. A server that ignores `stream` and answers with a
+    /// plain JSON body is handled too.
+    fn chat_completion_stream(&self, request: &ChatCompletionRequest, on_delta: &mut dyn FnMut(&str)) -> Result<Completion, ProviderError> {
+        let response = self.post_chat(request, true)?;
+
+        let mut content = String::new();
+        let mut usage = None;
+        let mut raw_body = String::new();
+        let mut saw_data_line = false;
+
+        read_lines(response, |line| {
+            // Lines starting with ':' are keep-alive comments
+            let Some(data) = line.strip_prefix("data:") else {
+                if !saw_data_line && !line.starts_with(':') {
+                    raw_body.push_str(line);
+                    raw_body.push('\n');
+                }
+                return Ok(true);
+            };
+            saw_data_line = true;
+
+            let data = data.trim();
+            if data == "[DONE]" {
+                return Ok(false);
+            }
+            let Ok(json) = serde_json::from_str::<Value>(data) else {
+                return Ok(true);
+            };
+            if let Some(error) = api_error(&json) {
+                return Err(error);
+            }
+            let choice = &json["choices"][0];
+            if let Some(delta) = choice["delta"]["content"].as_str().or_else(|| choice["message"]["content"].as_str()) {
+                if !delta.is_empty() {
+                    content.push_str(delta);
+                    on_delta(delta);
+                }
+            }
+            // Usually only the final chunk carries usage
+            if let Some(chunk_usage) = extract_usage(&json) {
+                usage = Some(chunk_usage);
+            }
+            Ok(true)
+        })?;
+
+        if !saw_data_line {
+            let json: Value = serde_json::from_str(&raw_body).unwrap_or(Value::Null);
+            let completion = completion_from_json(&json)?;
+            if !completion.content.is_empty() {
+                on_delta(&completion.content);
+            }
+            return Ok(completion);
+        }
+
+        Ok(Completion { content, usage })
+    }
+
+    fn list_models(&self) -> Result<Vec<ProviderModel>, String> {
+        let json: Value = send(self.request(self.http.get(format!("{}/models", self.base_url))))?
+            .json()
+            .unwrap_or(Value::Null);
+        let entries: Vec<ModelEntry> =
+            serde_json::from_value(json["data"].clone()).map_err(|err| format!("Unexpected /models response: {}", err))?;
+
+        Ok(entries
+            .into_iter()
+            .map(|entry| ProviderModel {
+                name: entry.name.unwrap_or_else(|| entry.id.clone()),
+                id: entry.id,
+                description: entry.description,
+                context_length: entry.context_length,
+                pricing: entry.pricing,
+            })
+            .collect())
+    }
+}
+
+fn completion_from_json(json: &Value) -> Result<Completion, ProviderError> {
+    // OpenRouter reports some upstream failures inside a 200 response
+    if let Some(error) = api_error(json) {
+        return Err(error);
+    }
+
+    let content = json["choices"][0]["message"]["content"].as_str().unwrap_or_default().to_string();
+    Ok(Completion { content, usage: extract_usage(json) })
+}
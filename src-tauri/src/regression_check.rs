@@ -0,0 +1,559 @@
+//! Regression gate for CI. A run is compared with a baseline run of the same
+//! suite (each model's aggregate score and every case score) and/or with
+//! absolute score floors. The outcome is written as JUnit XML for test
+//! reporters and as a Markdown summary for pull request comments.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use rusqlite::Connection;
+use serde::Serialize;
+
+use crate::reports::{build_report, escape_cell, format_score, RunReport};
+use crate::{get_setting, set_setting, TestCase, TestCaseResult};
+
+/// Baseline run ids are stored per suite under `check_baseline:<suite id>`
+const BASELINE_SETTING_PREFIX: &str = "check_baseline:";
+
+/// Failing cases listed in the Markdown summary before it is truncated
+const MAX_SUMMARY_CASES: usize = 50;
+
+/// Scores are compared in 0-1 units; this absorbs float noise.
+const EPSILON: f64 = 1e-9;
+
+#[derive(Debug, Clone)]
+pub struct CheckOptions {
+    /// Allowed drop of a model's aggregate score versus the baseline
+    pub tolerance: f64,
+    /// Allowed drop of a single case score versus the baseline
+    pub case_tolerance: f64,
+    /// Minimum aggregate score every model must reach
+    pub min_score: Option<f64>,
+    /// Minimum score every case must reach
+    pub min_case_score: Option<f64>,
+}
+
+impl Default for CheckOptions {
+    fn default() -> Self {
+        CheckOptions {
+            tolerance: 0.02,
+            case_tolerance: 0.1,
+            min_score: None,
+            min_case_score: None,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum CheckStatus {
+    Passed,
+    /// Dropped by more than the tolerance
+    Regressed,
+    BelowThreshold,
+    /// The request failed or never ran
+    Errored,
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ScoreCheck {
+    pub model_id: String,
+    /// `None` for a model's aggregate score
+    pub test_case_id: Option<String>,
+    pub label: String,
+    pub score: Option<f64>,
+    pub baseline: Option<f64>,
+    pub status: CheckStatus,
+    pub message: Option<String>,
+}
+
+impl ScoreCheck {
+    pub fn change(&self) -> Option<f64> {
+        Some(self.score? - self.baseline?)
+    }
+}
+
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckReport {
+    pub run_id: String,
+    pub test_suite_name: String,
+    pub baseline_run_id: Option<String>,
+    pub tolerance: f64,
+    pub case_tolerance: f64,
+    /// One aggregate check per model, in leaderboard order
+    pub models: Vec<ScoreCheck>,
+    pub cases: Vec<ScoreCheck>,
+}
+
+impl CheckReport {
+    pub fn failures(&self) -> impl Iterator<Item = &ScoreCheck> {
+        self.models.iter().chain(&self.cases).filter(|check| check.status != CheckStatus::Passed)
+    }
+
+    pub fn passed(&self) -> bool {
+        self.failures().next().is_none()
+    }
+}
+
+// ============================================================================
+// Stored baselines
+// ============================================================================
+
+pub fn get_baseline(conn: &Connection, test_suite_id: &str) -> Result<Option<String>, String> {
+    get_setting(conn, &format!("{}{}", BASELINE_SETTING_PREFIX, test_suite_id))
+}
+
+pub fn set_baseline(conn: &Connection, test_suite_id: &str, run_id: &str) -> Result<(), String> {
+    set_setting(conn, &format!("{}{}", BASELINE_SETTING_PREFIX, test_suite_id), run_id)
+}
+
+// ============================================================================
+// Comparison
+// ============================================================================
+
+fn points(delta: f64) -> String {
+    format!("{:.1}", delta * 100.0)
+}
+
+/// Compares one score with its baseline and floor. A missing score counts
+/// as zero, so a model that stopped producing scored results regresses.
+fn evaluate(score: Option<f64>, baseline: Option<f64>, tolerance: f64, floor: Option<f64>) -> (CheckStatus, Option<String>) {
+    let value = score.unwrap_or(0.0);
+
+    if let Some(baseline) = baseline {
+        if value < baseline - tolerance - EPSILON {
+            return (
+                CheckStatus::Regressed,
+                Some(format!(
+                    "Score dropped from {} to {} ({} points, tolerance {})",
+                    format_score(Some(baseline)),
+                    format_score(score),
+                    points(value - baseline),
+                    points(tolerance)
+                )),
+            );
+        }
+    }
+    if let Some(floor) = floor {
+        if value < floor - EPSILON {
+            return (
+                CheckStatus::BelowThreshold,
+                Some(format!("Score {} is below the minimum of {}", format_score(score), format_score(Some(floor)))),
+            );
+        }
+    }
+
+    (CheckStatus::Passed, None)
+}
+
+fn case_label(test_case: &TestCase) -> String {
+    let prompt: String = test_case.prompt.split_whitespace().collect::<Vec<_>>().join(" ");
+    let excerpt: String = prompt.chars().take(60).collect();
+    let ellipsis = if prompt.chars().count() > 60 { "…" } else { "" };
+    format!("{}{} [{}]", excerpt, ellipsis, test_case.id)
+}
+
+fn result_score(result: &TestCaseResult) -> Option<f64> {
+    result.score.as_ref().map(|score| score.score)
+}
+
+fn check_case(
+    test_case: &TestCase,
+    model_id: &str,
+    result: Option<&TestCaseResult>,
+    baseline: Option<f64>,
+    options: &CheckOptions,
+) -> ScoreCheck {
+    let score = result.and_then(result_score);
+    let (status, message) = match result {
+        Some(result) if result.status == "failed" => {
+            (CheckStatus::Errored, Some(result.error.clone().unwrap_or_else(|| "Request failed".to_string())))
+        }
+        Some(result) if result.status == "completed" => evaluate(score, baseline, options.case_tolerance, options.min_case_score),
+        _ => (CheckStatus::Errored, Some("Not run".to_string())),
+    };
+
+    ScoreCheck {
+        model_id: model_id.to_string(),
+        test_case_id: Some(test_case.id.clone()),
+        label: case_label(test_case),
+        score,
+        baseline,
+        status,
+        message,
+    }
+}
+
+/// Checks `current` against an optional baseline. Models and cases missing
+/// from the baseline are only held to the thresholds.
+pub fn compare_runs(current: &RunReport, baseline: Option<&RunReport>, options: &CheckOptions) -> CheckReport {
+    let baseline_scores: HashMap<&str, Option<f64>> = baseline
+        .map(|baseline| baseline.leaderboard.iter().map(|standing| (standing.model_id.as_str(), standing.score)).collect())
+        .unwrap_or_default();
+    let baseline_cases: HashMap<(&str, &str), f64> = baseline
+        .map(|baseline| {
+            baseline
+                .run
+                .results
+                .iter()
+                .filter_map(|result| Some(((result.model_id.as_str(), result.test_case_id.as_str()), result_score(result)?)))
+                .collect()
+        })
+        .unwrap_or_default();
+    let results: HashMap<(&str, &str), &TestCaseResult> = current
+        .run
+        .results
+        .iter()
+        .map(|result| ((result.model_id.as_str(), result.test_case_id.as_str()), result))
+        .collect();
+
+    let mut models = Vec::new();
+    let mut cases = Vec::new();
+    for standing in &current.leaderboard {
+        let model_id = standing.model_id.as_str();
+        let baseline_score = baseline_scores.get(model_id).copied().flatten();
+        let (status, message) = evaluate(standing.score, baseline_score, options.tolerance, options.min_score);
+        models.push(ScoreCheck {
+            model_id: model_id.to_string(),
+            test_case_id: None,
+            label: "Aggregate score".to_string(),
+            score: standing.score,
+            baseline: baseline_score,
+            status,
+            message,
+        });
+
+        for test_case in &current.test_cases {
+            let key = (model_id, test_case.id.as_str());
+            cases.push(check_case(test_case, model_id, results.get(&key).copied(), baseline_cases.get(&key).copied(), options));
+        }
+    }
+
+    CheckReport {
+        run_id: current.run.id.clone(),
+        test_suite_name: current.run.test_suite_name.clone(),
+        baseline_run_id: baseline.map(|baseline| baseline.run.id.clone()),
+        tolerance: options.tolerance,
+        case_tolerance: options.case_tolerance,
+        models,
+        cases,
+    }
+}
+
+pub fn check_run_internal(
+    conn: &Connection,
+    run_id: &str,
+    baseline_run_id: Option<&str>,
+    options: &CheckOptions,
+) -> Result<CheckReport, String> {
+    let current = build_report(conn, run_id)?;
+    let baseline = match baseline_run_id {
+        Some(baseline_run_id) if baseline_run_id == run_id => {
+            return Err("A run cannot be checked against itself.".to_string());
+        }
+        Some(baseline_run_id) => Some(build_report(conn, baseline_run_id)?),
+        None => None,
+    };
+
+    if let Some(baseline) = &baseline {
+        if baseline.run.test_suite_id != current.run.test_suite_id {
+            return Err(format!(
+                "Baseline run {} is for suite '{}', not '{}'.",
+                baseline.run.id, baseline.run.test_suite_name, current.run.test_suite_name
+            ));
+        }
+    }
+    if baseline.is_none() && options.min_score.is_none() && options.min_case_score.is_none() {
+        return Err("Nothing to check against: set a baseline run or a minimum score.".to_string());
+    }
+
+    Ok(compare_runs(&current, baseline.as_ref(), options))
+}
+
+// ============================================================================
+// JUnit XML
+// ============================================================================
+
+/// Escapes text for XML attributes and content, dropping control characters
+/// XML 1.0 does not allow.
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\t' | '\n' | '\r' => escaped.push(ch),
+            _ if ch.is_control() => {}
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+fn junit_testcase(xml: &mut String, check: &ScoreCheck) {
+    xml.push_str(&format!(
+        "    <testcase classname=\"{}\" name=\"{}\"",
+        escape_xml(&check.model_id),
+        escape_xml(&check.label)
+    ));
+
+    let message = escape_xml(check.message.as_deref().unwrap_or_default());
+    match check.status {
+        CheckStatus::Passed => xml.push_str(" />\n"),
+        CheckStatus::Errored => xml.push_str(&format!(">\n      <error message=\"{}\" />\n    </testcase>\n", message)),
+        CheckStatus::Regressed | CheckStatus::BelowThreshold => {
+            let kind = if check.status == CheckStatus::Regressed { "regression" } else { "threshold" };
+            xml.push_str(&format!(
+                ">\n      <failure type=\"{}\" message=\"{}\">score: {}\nbaseline: {}</failure>\n    </testcase>\n",
+                kind,
+                message,
+                format_score(check.score),
+                format_score(check.baseline)
+            ));
+        }
+    }
+}
+
+/// One `<testsuite>` per model holding its aggregate check and its cases.
+pub fn render_junit(report: &CheckReport) -> String {
+    let count = |checks: &[&ScoreCheck], status: CheckStatus| checks.iter().filter(|check| check.status == status).count();
+    let all: Vec<&ScoreCheck> = report.models.iter().chain(&report.cases).collect();
+    let failures = all.len() - count(&all, CheckStatus::Passed) - count(&all, CheckStatus::Errored);
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    xml.push_str(&format!(
+        "<testsuites name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\">\n",
+        escape_xml(&format!("Benchmaker: {}", report.test_suite_name)),
+        all.len(),
+        failures,
+        count(&all, CheckStatus::Errored)
+    ));
+
+    for model in &report.models {
+        let checks: Vec<&ScoreCheck> = std::iter::once(model)
+            .chain(report.cases.iter().filter(|check| check.model_id == model.model_id))
+            .collect();
+        let errors = count(&checks, CheckStatus::Errored);
+        xml.push_str(&format!(
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\">\n",
+            escape_xml(&model.model_id),
+            checks.len(),
+            checks.len() - count(&checks, CheckStatus::Passed) - errors,
+            errors
+        ));
+        for check in checks {
+            junit_testcase(&mut xml, check);
+        }
+        xml.push_str("  </testsuite>\n");
+    }
+
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+// ============================================================================
+// Markdown
+// ============================================================================
+
+fn format_change(check: &ScoreCheck) -> String {
+    match check.change() {
+        Some(change) if change.abs() < 0.0005 => "±0.0".to_string(),
+        Some(change) if change > 0.0 => format!("+{}", points(change)),
+        Some(change) => points(change),
+        None => "—".to_string(),
+    }
+}
+
+fn status_label(status: CheckStatus) -> &'static str {
+    match status {
+        CheckStatus::Passed => "✅ Passed",
+        CheckStatus::Regressed => "❌ Regressed",
+        CheckStatus::BelowThreshold => "❌ Below threshold",
+        CheckStatus::Errored => "⚠️ Error",
+    }
+}
+
+pub fn render_check_markdown(report: &CheckReport) -> String {
+    let mut md = String::new();
+    let failures: Vec<&ScoreCheck> = report.failures().collect();
+
+    let outcome = if failures.is_empty() { "passed" } else { "failed" };
+    md.push_str(&format!("## Benchmark check {}: {}\n\n", outcome, report.test_suite_name));
+    match &report.baseline_run_id {
+        Some(baseline) => md.push_str(&format!(
+            "Run `{}` compared with baseline `{}` · tolerance {} points (aggregate), {} points (per case)\n\n",
+            report.run_id,
+            baseline,
+            points(report.tolerance),
+            points(report.case_tolerance)
+        )),
+        None => md.push_str(&format!("Run `{}` checked against score thresholds\n\n", report.run_id)),
+    }
+
+    md.push_str("| Model | Score | Baseline | Change | Failing cases | Result |\n");
+    md.push_str("|---|---|---|---|---|---|\n");
+    for model in &report.models {
+        let failing_cases = failures.iter().filter(|check| check.test_case_id.is_some() && check.model_id == model.model_id).count();
+        md.push_str(&format!(
+            "| `{}` | **{}** | {} | {} | {} | {} |\n",
+            escape_cell(&model.model_id),
+            format_score(model.score),
+            format_score(model.baseline),
+            format_change(model),
+            failing_cases,
+            status_label(model.status)
+        ));
+    }
+
+    let failing_cases: Vec<&&ScoreCheck> = failures.iter().filter(|check| check.test_case_id.is_some()).collect();
+    if !failing_cases.is_empty() {
+        md.push_str(&format!("\n### Failing cases ({})\n\n", failing_cases.len()));
+        md.push_str("| Model | Case | Score | Baseline | Change | Reason |\n");
+        md.push_str("|---|---|---|---|---|---|\n");
+        for check in failing_cases.iter().take(MAX_SUMMARY_CASES) {
+            md.push_str(&format!(
+                "| `{}` | {} | {} | {} | {} | {} |\n",
+                escape_cell(&check.model_id),
+                escape_cell(&check.label),
+                format_score(check.score),
+                format_score(check.baseline),
+                format_change(check),
+                escape_cell(check.message.as_deref().unwrap_or_default())
+            ));
+        }
+        if failing_cases.len() > MAX_SUMMARY_CASES {
+            md.push_str(&format!("\n…and {} more; see the JUnit report.\n", failing_cases.len() - MAX_SUMMARY_CASES));
+        }
+    }
+
+    md
+}
+
+pub fn write_check_outputs(report: &CheckReport, junit_path: Option<&Path>, markdown_path: Option<&Path>) -> Result<(), String> {
+    if let Some(path) = junit_path {
+        fs::write(path, render_junit(report)).map_err(|err| err.to_string())?;
+    }
+    if let Some(path) = markdown_path {
+        fs::write(path, render_check_markdown(report)).map_err(|err| err.to_string())?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{memory_db, result, run, suite, test_case};
+    use crate::{write_run_with_results, write_test_suite, ScoringResult};
+
+    fn check(model_id: &str, label: &str, case: bool, status: CheckStatus) -> ScoreCheck {
+        ScoreCheck {
+            model_id: model_id.to_string(),
+            test_case_id: case.then(|| "a".to_string()),
+            label: label.to_string(),
+            score: Some(0.5),
+            baseline: Some(0.9),
+            status,
+            message: Some(format!("{} failed", label)),
+        }
+    }
+
+    #[test]
+    fn drops_within_the_tolerance_pass() {
+        // 0.8 - 0.1 is 0.7000000000000001 in floats
+        assert_eq!(evaluate(Some(0.7), Some(0.8), 0.1, None).0, CheckStatus::Passed);
+        assert_eq!(evaluate(Some(0.69), Some(0.8), 0.1, None).0, CheckStatus::Regressed);
+        assert_eq!(evaluate(Some(0.3), None, 0.0, Some(0.1 + 0.2)).0, CheckStatus::Passed);
+        assert_eq!(evaluate(Some(0.29), None, 0.0, Some(0.3)).0, CheckStatus::BelowThreshold);
+    }
+
+    #[test]
+    fn a_missing_score_counts_as_zero() {
+        let (status, message) = evaluate(None, Some(0.05), 0.02, None);
+        assert_eq!(status, CheckStatus::Regressed);
+        assert_eq!(message.unwrap(), "Score dropped from 5.0% to — (-5.0 points, tolerance 2.0)");
+        assert_eq!(evaluate(None, None, 0.02, Some(0.01)).0, CheckStatus::BelowThreshold);
+        assert_eq!(evaluate(None, Some(0.01), 0.02, None).0, CheckStatus::Passed);
+    }
+
+    #[test]
+    fn failed_and_missing_cases_are_errors() {
+        let options = CheckOptions::default();
+        let test_case = test_case("a");
+
+        let mut failed = result("a", "model-a", "failed");
+        failed.error = Some("Rate limit exceeded (429)".to_string());
+        let checked = check_case(&test_case, "model-a", Some(&failed), Some(1.0), &options);
+        assert_eq!(checked.status, CheckStatus::Errored);
+        assert_eq!(checked.message.as_deref(), Some("Rate limit exceeded (429)"));
+
+        for pending in [None, Some(&result("a", "model-a", "pending"))] {
+            let checked = check_case(&test_case, "model-a", pending, None, &options);
+            assert_eq!(checked.status, CheckStatus::Errored);
+            assert_eq!(checked.message.as_deref(), Some("Not run"));
+        }
+    }
+
+    #[test]
+    fn junit_counts_each_model_separately() {
+        let report = CheckReport {
+            run_id: "r1".to_string(),
+            test_suite_name: "Suite & co".to_string(),
+            baseline_run_id: None,
+            tolerance: 0.02,
+            case_tolerance: 0.1,
+            models: vec![
+                check("vendor/<model>", "Aggregate score", false, CheckStatus::Regressed),
+                check("model-b", "Aggregate score", false, CheckStatus::Passed),
+            ],
+            cases: vec![
+                check("vendor/<model>", "Say \"hi\" <script> [a]", true, CheckStatus::BelowThreshold),
+                check("vendor/<model>", "Second [b]", true, CheckStatus::Errored),
+                check("model-b", "Second [b]", true, CheckStatus::Errored),
+            ],
+        };
+
+        let xml = render_junit(&report);
+        assert!(xml.contains("<testsuites name=\"Benchmaker: Suite &amp; co\" tests=\"5\" failures=\"2\" errors=\"2\">"));
+        assert!(xml.contains("<testsuite name=\"vendor/&lt;model&gt;\" tests=\"3\" failures=\"2\" errors=\"1\">"));
+        assert!(xml.contains("<testsuite name=\"model-b\" tests=\"2\" failures=\"0\" errors=\"1\">"));
+        assert!(xml.contains("name=\"Say &quot;hi&quot; &lt;script&gt; [a]\""));
+        assert!(!xml.contains("<script>"));
+    }
+
+    #[test]
+    fn baselines_must_be_another_run_of_the_suite() {
+        let conn = memory_db();
+        let first = suite("s1", &["a"]);
+        let other = suite("s2", &["a"]);
+        write_test_suite(&conn, &first).unwrap();
+        write_test_suite(&conn, &other).unwrap();
+        for (id, suite) in [("r1", &first), ("r2", &first), ("r3", &other)] {
+            let mut run = run(id, suite, "completed");
+            run.results[0].score = Some(ScoringResult {
+                score: 1.0,
+                confidence: None,
+                notes: None,
+                raw_score: None,
+                max_score: None,
+            });
+            write_run_with_results(&conn, &run).unwrap();
+        }
+        let options = CheckOptions::default();
+
+        let err = check_run_internal(&conn, "r2", Some("r2"), &options).unwrap_err();
+        assert_eq!(err, "A run cannot be checked against itself.");
+        let err = check_run_internal(&conn, "r2", Some("r3"), &options).unwrap_err();
+        assert_eq!(err, "Baseline run r3 is for suite 'Suite s2', not 'Suite s1'.");
+        assert!(check_run_internal(&conn, "r2", None, &options).is_err());
+
+        let report = check_run_internal(&conn, "r2", Some("r1"), &options).unwrap();
+        assert_eq!(report.baseline_run_id.as_deref(), Some("r1"));
+        assert!(report.passed());
+    }
+}
@@ -0,0 +1,602 @@
+//! Shareable run reports. A report is built from a run and the suite
+//! revision it was scored against, then rendered either as a single
+//! self-contained HTML page (no scripts or external assets) or as a compact
+//! Markdown summary that fits in a pull request comment.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::Path;
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "app")]
+use tauri::State;
+
+use crate::suite_revisions::get_suite_revision_internal;
+use crate::{chrono_now, get_run_internal, get_test_suite_internal, Db, RunResult, TestCase, TestCaseResult};
+
+const UNCATEGORIZED: &str = "Uncategorized";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ReportFormat {
+    Html,
+    Markdown,
+}
+
+impl ReportFormat {
+    /// Markdown for `.md`/`.markdown` files, HTML for everything else.
+    pub fn from_path(path: &Path) -> ReportFormat {
+        match path.extension().and_then(|ext| ext.to_str()).map(str::to_ascii_lowercase).as_deref() {
+            Some("md") | Some("markdown") => ReportFormat::Markdown,
+            _ => ReportFormat::Html,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelStanding {
+    pub rank: usize,
+    pub model_id: String,
+    /// Weighted mean of the normalized scores, as in the results view
+    pub score: Option<f64>,
+    pub scored: usize,
+    pub completed: usize,
+    pub failed: usize,
+    pub avg_latency_ms: Option<f64>,
+    pub total_tokens: i64,
+    pub total_cost: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CategoryScores {
+    pub category: String,
+    pub test_case_count: usize,
+    /// Aligned with the leaderboard order
+    pub scores: Vec<Option<f64>>,
+}
+
+pub struct RunReport {
+    pub run: RunResult,
+    pub system_prompt: Option<String>,
+    /// Cases from the run's suite revision, or the current suite when the
+    /// revision is unknown.
+    pub test_cases: Vec<TestCase>,
+    pub leaderboard: Vec<ModelStanding>,
+    pub categories: Vec<CategoryScores>,
+}
+
+fn weighted_mean<'a>(results: impl Iterator<Item = &'a TestCaseResult>, weights: &HashMap<&str, f64>) -> Option<f64> {
+    let (total, weight_sum) = results
+        .filter_map(|result| {
+            let score = result.score.as_ref()?.score;
+            let weight = weights.get(result.test_case_id.as_str()).copied().unwrap_or(1.0);
+            Some((score * weight, weight))
+        })
+        .fold((0.0, 0.0), |(total, sum), (score, weight)| (total + score, sum + weight));
+    (weight_sum > 0.0).then(|| total / weight_sum)
+}
+
+/// Ranks the run's models by weighted score. Models without any scored
+/// result are listed last.
+pub fn leaderboard(run: &RunResult, test_cases: &[TestCase]) -> Vec<ModelStanding> {
+    let weights: HashMap<&str, f64> = test_cases.iter().map(|tc| (tc.id.as_str(), tc.weight)).collect();
+
+    let mut model_ids: Vec<&str> = run.models.iter().map(String::as_str).collect();
+    for result in &run.results {
+        if !model_ids.contains(&result.model_id.as_str()) {
+            model_ids.push(&result.model_id);
+        }
+    }
+
+    let mut standings: Vec<ModelStanding> = model_ids
+        .into_iter()
+        .map(|model_id| {
+            let results: Vec<&TestCaseResult> = run.results.iter().filter(|result| result.model_id == model_id).collect();
+            let latencies: Vec<i64> = results.iter().filter_map(|result| result.latency_ms).collect();
+            let costs: Vec<f64> = results.iter().filter_map(|result| result.cost).collect();
+
+            ModelStanding {
+                rank: 0,
+                model_id: model_id.to_string(),
+                score: weighted_mean(results.iter().copied(), &weights),
+                scored: results.iter().filter(|result| result.score.is_some()).count(),
+                completed: results.iter().filter(|result| result.status == "completed").count(),
+                failed: results.iter().filter(|result| result.status == "failed").count(),
+                avg_latency_ms: (!latencies.is_empty()).then(|| latencies.iter().sum::<i64>() as f64 / latencies.len() as f64),
+                total_tokens: results.iter().filter_map(|result| result.token_count).sum(),
+                total_cost: (!costs.is_empty()).then(|| costs.iter().sum()),
+            }
+        })
+        .collect();
+
+    standings.sort_by(|a, b| {
+        b.score
+            .unwrap_or(f64::NEG_INFINITY)
+            .total_cmp(&a.score.unwrap_or(f64::NEG_INFINITY))
+            .then_with(|| a.model_id.cmp(&b.model_id))
+    });
+    for (index, standing) in standings.iter_mut().enumerate() {
+        standing.rank = index + 1;
+    }
+
+    standings
+}
+
+fn category_scores(run: &RunResult, test_cases: &[TestCase], leaderboard: &[ModelStanding]) -> Vec<CategoryScores> {
+    let weights: HashMap<&str, f64> = test_cases.iter().map(|tc| (tc.id.as_str(), tc.weight)).collect();
+
+    let mut by_category: BTreeMap<&str, Vec<&str>> = BTreeMap::new();
+    for test_case in test_cases {
+        let category = test_case.metadata.category.as_deref().filter(|category| !category.trim().is_empty());
+        by_category.entry(category.unwrap_or(UNCATEGORIZED)).or_default().push(&test_case.id);
+    }
+
+    let mut categories: Vec<CategoryScores> = by_category
+        .into_iter()
+        .map(|(category, case_ids)| CategoryScores {
+            category: category.to_string(),
+            test_case_count: case_ids.len(),
+            scores: leaderboard
+                .iter()
+                .map(|standing| {
+                    let results = run.results.iter().filter(|result| {
+                        result.model_id == standing.model_id && case_ids.contains(&result.test_case_id.as_str())
+                    });
+                    weighted_mean(results, &weights)
+                })
+                .collect(),
+        })
+        .collect();
+
+    // Keep the catch-all bucket at the end
+    categories.sort_by_key(|category| category.category == UNCATEGORIZED);
+    categories
+}
+
+pub fn build_report(conn: &Connection, run_id: &str) -> Result<RunReport, String> {
+    let run = get_run_internal(conn, run_id)?.ok_or_else(|| format!("Run {} not found.", run_id))?;
+
+    let revision = match run.test_suite_revision {
+        Some(revision) => get_suite_revision_internal(conn, &run.test_suite_id, revision)?,
+        None => None,
+    };
+    let (system_prompt, test_cases) = match revision {
+        Some(revision) => (Some(revision.system_prompt), revision.test_cases),
+        None => match get_test_suite_internal(conn, &run.test_suite_id)? {
+            Some(suite) => (Some(suite.system_prompt), suite.test_cases),
+            None => (None, Vec::new()),
+        },
+    };
+
+    let leaderboard = leaderboard(&run, &test_cases);
+    let categories = category_scores(&run, &test_cases, &leaderboard);
+
+    Ok(RunReport {
+        run,
+        system_prompt,
+        test_cases,
+        leaderboard,
+        categories,
+    })
+}
+
+// ============================================================================
+// Formatting
+// ============================================================================
+
+pub fn format_score(score: Option<f64>) -> String {
+    score.map(|score| format!("{:.1}%", score * 100.0)).unwrap_or_else(|| "—".to_string())
+}
+
+fn format_cost(cost: Option<f64>) -> String {
+    match cost {
+        None => "—".to_string(),
+        Some(0.0) => "$0.00".to_string(),
+        Some(cost) if cost < 0.0001 => "<$0.0001".to_string(),
+        Some(cost) if cost < 0.01 => format!("${:.4}", cost),
+        Some(cost) => format!("${:.2}", cost),
+    }
+}
+
+fn format_latency(latency_ms: Option<f64>) -> String {
+    latency_ms.map(|ms| format!("{:.0} ms", ms)).unwrap_or_else(|| "—".to_string())
+}
+
+fn format_time(timestamp_ms: i64) -> String {
+    chrono::DateTime::from_timestamp_millis(timestamp_ms)
+        .map(|time| time.format("%Y-%m-%d %H:%M UTC").to_string())
+        .unwrap_or_default()
+}
+
+fn parameter_rows(run: &RunResult) -> Vec<(&'static str, String)> {
+    let parameters = &run.parameters;
+    vec![
+        ("Judge model", run.judge_model.clone().unwrap_or_else(|| "—".to_string())),
+        ("Temperature", parameters.temperature.to_string()),
+        ("Top P", parameters.top_p.to_string()),
+        ("Max tokens", parameters.max_tokens.to_string()),
+        ("Frequency penalty", parameters.frequency_penalty.to_string()),
+        ("Presence penalty", parameters.presence_penalty.to_string()),
+    ]
+}
+
+fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+/// Makes text safe for a single Markdown table cell.
+pub fn escape_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+// ============================================================================
+// Markdown
+// ============================================================================
+
+/// A summary for pull request comments: leaderboard, categories and
+/// settings. Responses are left out to keep it short.
+pub fn render_markdown(report: &RunReport) -> String {
+    let run = &report.run;
+    let mut md = String::new();
+
+    md.push_str(&format!("## Benchmark: {}\n\n", run.test_suite_name));
+    let revision = run.test_suite_revision.map(|revision| format!(" (revision {})", revision)).unwrap_or_default();
+    md.push_str(&format!(
+        "Run `{}`{} · {} · started {}\n\n",
+        run.id,
+        revision,
+        run.status,
+        format_time(run.started_at)
+    ));
+
+    md.push_str("| # | Model | Score | Scored | Failed | Avg latency | Tokens | Cost |\n");
+    md.push_str("|---|---|---|---|---|---|---|---|\n");
+    for standing in &report.leaderboard {
+        md.push_str(&format!(
+            "| {} | `{}` | **{}** | {} | {} | {} | {} | {} |\n",
+            standing.rank,
+            escape_cell(&standing.model_id),
+            format_score(standing.score),
+            standing.scored,
+            standing.failed,
+            format_latency(standing.avg_latency_ms),
+            standing.total_tokens,
+            format_cost(standing.total_cost)
+        ));
+    }
+
+    if report.categories.len() > 1 {
+        md.push_str("\n### By category\n\n| Category | Cases |");
+        for standing in &report.leaderboard {
+            md.push_str(&format!(" `{}` |", escape_cell(&standing.model_id)));
+        }
+        md.push_str("\n|---|---|");
+        md.push_str(&"---|".repeat(report.leaderboard.len()));
+        md.push('\n');
+        for category in &report.categories {
+            md.push_str(&format!("| {} | {} |", escape_cell(&category.category), category.test_case_count));
+            for score in &category.scores {
+                md.push_str(&format!(" {} |", format_score(*score)));
+            }
+            md.push('\n');
+        }
+    }
+
+    md.push_str("\n<details><summary>Parameters</summary>\n\n| Setting | Value |\n|---|---|\n");
+    for (label, value) in parameter_rows(run) {
+        md.push_str(&format!("| {} | {} |\n", label, escape_cell(&value)));
+    }
+    md.push_str("\n</details>\n");
+
+    md
+}
+
+// ============================================================================
+// HTML
+// ============================================================================
+
+const HTML_STYLE: &str = "
+body { font-family: -apple-system, BlinkMacSystemFont, 'Segoe UI', Roboto, sans-serif; margin: 0 auto; max-width: 1100px; padding: 32px 24px; color: #1f2328; background: #fff; line-height: 1.5; }
+h1 { margin-bottom: 4px; } h2 { margin-top: 40px; border-bottom: 1px solid #d0d7de; padding-bottom: 6px; }
+.meta { color: #57606a; margin-top: 0; }
+table { border-collapse: collapse; width: 100%; margin: 12px 0; font-size: 14px; }
+th, td { border: 1px solid #d0d7de; padding: 6px 10px; text-align: left; vertical-align: top; }
+th { background: #f6f8fa; } td.num { text-align: right; white-space: nowrap; }
+code, pre { font-family: ui-monospace, SFMono-Regular, Menlo, monospace; font-size: 13px; }
+pre { background: #f6f8fa; padding: 10px; border-radius: 6px; white-space: pre-wrap; word-break: break-word; margin: 6px 0; }
+.case { border: 1px solid #d0d7de; border-radius: 8px; padding: 12px 16px; margin: 16px 0; }
+.case h3 { margin: 0 0 6px; font-size: 16px; } .tag { display: inline-block; background: #eaeef2; border-radius: 10px; padding: 0 8px; margin-right: 4px; font-size: 12px; }
+.notes { color: #57606a; font-size: 13px; } .error { color: #cf222e; }
+";
+
+fn html_score_cell(score: Option<f64>) -> String {
+    // Green at 100%, red at 0%
+    match score {
+        Some(value) => {
+            let hue = (value.clamp(0.0, 1.0) * 120.0).round();
+            format!(
+                "<td class=\"num\" style=\"background: hsl({}, 70%, 92%)\">{}</td>",
+                hue,
+                format_score(score)
+            )
+        }
+        None => "<td class=\"num\">—</td>".to_string(),
+    }
+}
+
+fn render_case(html: &mut String, test_case: &TestCase, results: &[&TestCaseResult]) {
+    html.push_str("<div class=\"case\">\n");
+    html.push_str(&format!("<h3><code>{}</code></h3>\n<p>", escape_html(&test_case.id)));
+    html.push_str(&format!("<span class=\"tag\">{}</span>", escape_html(&test_case.scoring_method)));
+    if let Some(category) = &test_case.metadata.category {
+        html.push_str(&format!("<span class=\"tag\">{}</span>", escape_html(category)));
+    }
+    if let Some(difficulty) = &test_case.metadata.difficulty {
+        html.push_str(&format!("<span class=\"tag\">{}</span>", escape_html(difficulty)));
+    }
+    html.push_str(&format!("<span class=\"tag\">weight {}</span></p>\n", test_case.weight));
+
+    html.push_str(&format!("<strong>Prompt</strong><pre>{}</pre>\n", escape_html(&test_case.prompt)));
+    if let Some(expected) = &test_case.expected_output {
+        html.push_str(&format!("<strong>Expected</strong><pre>{}</pre>\n", escape_html(expected)));
+    }
+
+    html.push_str("<table><tr><th>Model</th><th>Score</th><th>Response</th><th>Latency</th><th>Tokens</th><th>Cost</th></tr>\n");
+    for result in results {
+        html.push_str(&format!("<tr><td><code>{}</code></td>", escape_html(&result.model_id)));
+        html.push_str(&html_score_cell(result.score.as_ref().map(|score| score.score)));
+
+        html.push_str("<td>");
+        if let Some(error) = &result.error {
+            html.push_str(&format!("<div class=\"error\">{}</div>", escape_html(error)));
+        }
+        if !result.response.is_empty() {
+            html.push_str(&format!(
+                "<details><summary>Response</summary><pre>{}</pre></details>",
+                escape_html(&result.response)
+            ));
+        }
+        if let Some(score) = &result.score {
+            if let Some(notes) = &score.notes {
+                html.push_str(&format!("<div class=\"notes\">{}</div>", escape_html(notes)));
+            }
+            if let Some(confidence) = score.confidence {
+                html.push_str(&format!("<div class=\"notes\">Confidence {:.0}%</div>", confidence * 100.0));
+            }
+        }
+        html.push_str("</td>");
+
+        html.push_str(&format!(
+            "<td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>\n",
+            format_latency(result.latency_ms.map(|ms| ms as f64)),
+            result.token_count.map(|tokens| tokens.to_string()).unwrap_or_else(|| "—".to_string()),
+            format_cost(result.cost)
+        ));
+    }
+    html.push_str("</table>\n</div>\n");
+}
+
+/// A standalone page with the leaderboard, per-category scores, settings and
+/// every response with its score and judge notes.
+pub fn render_html(report: &RunReport) -> String {
+    let run = &report.run;
+    let mut html = String::new();
+
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n");
+    html.push_str(&format!("<title>{} · Benchmaker report</title>\n", escape_html(&run.test_suite_name)));
+    html.push_str(&format!("<style>{}</style>\n</head>\n<body>\n", HTML_STYLE));
+
+    html.push_str(&format!("<h1>{}</h1>\n", escape_html(&run.test_suite_name)));
+    let revision = run.test_suite_revision.map(|revision| format!(" · revision {}", revision)).unwrap_or_default();
+    let completed = run.completed_at.map(|at| format!(" · completed {}", format_time(at))).unwrap_or_default();
+    html.push_str(&format!(
+        "<p class=\"meta\">Run <code>{}</code>{} · {} · started {}{}</p>\n",
+        escape_html(&run.id),
+        revision,
+        escape_html(&run.status),
+        format_time(run.started_at),
+        completed
+    ));
+
+    html.push_str("<h2>Leaderboard</h2>\n<table><tr><th>#</th><th>Model</th><th>Score</th><th>Scored</th><th>Completed</th><th>Failed</th><th>Avg latency</th><th>Tokens</th><th>Cost</th></tr>\n");
+    for standing in &report.leaderboard {
+        html.push_str(&format!("<tr><td class=\"num\">{}</td><td><code>{}</code></td>", standing.rank, escape_html(&standing.model_id)));
+        html.push_str(&html_score_cell(standing.score));
+        html.push_str(&format!(
+            "<td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td><td class=\"num\">{}</td></tr>\n",
+            standing.scored,
+            standing.completed,
+            standing.failed,
+            format_latency(standing.avg_latency_ms),
+            standing.total_tokens,
+            format_cost(standing.total_cost)
+        ));
+    }
+    html.push_str("</table>\n");
+
+    if !report.categories.is_empty() {
+        html.push_str("<h2>By category</h2>\n<table><tr><th>Category</th><th>Cases</th>");
+        for standing in &report.leaderboard {
+            html.push_str(&format!("<th><code>{}</code></th>", escape_html(&standing.model_id)));
+        }
+        html.push_str("</tr>\n");
+        for category in &report.categories {
+            html.push_str(&format!(
+                "<tr><td>{}</td><td class=\"num\">{}</td>",
+                escape_html(&category.category),
+                category.test_case_count
+            ));
+            for score in &category.scores {
+                html.push_str(&html_score_cell(*score));
+            }
+            html.push_str("</tr>\n");
+        }
+        html.push_str("</table>\n");
+    }
+
+    html.push_str("<h2>Parameters</h2>\n<table>\n");
+    for (label, value) in parameter_rows(run) {
+        html.push_str(&format!("<tr><th>{}</th><td>{}</td></tr>\n", label, escape_html(&value)));
+    }
+    html.push_str("</table>\n");
+    if let Some(system_prompt) = report.system_prompt.as_deref().filter(|prompt| !prompt.is_empty()) {
+        html.push_str(&format!("<strong>System prompt</strong><pre>{}</pre>\n", escape_html(system_prompt)));
+    }
+
+    html.push_str("<h2>Test cases</h2>\n");
+    for test_case in &report.test_cases {
+        let results: Vec<&TestCaseResult> = report
+            .leaderboard
+            .iter()
+            .flat_map(|standing| {
+                run.results
+                    .iter()
+                    .filter(move |result| result.test_case_id == test_case.id && result.model_id == standing.model_id)
+            })
+            .collect();
+        if !results.is_empty() {
+            render_case(&mut html, test_case, &results);
+        }
+    }
+
+    html.push_str(&format!(
+        "<p class=\"meta\">Generated by Benchmaker {} on {}</p>\n</body>\n</html>\n",
+        env!("CARGO_PKG_VERSION"),
+        format_time(chrono_now())
+    ));
+    html
+}
+
+pub fn generate_report_internal(conn: &Connection, run_id: &str, format: ReportFormat, path: &Path) -> Result<(), String> {
+    let report = build_report(conn, run_id)?;
+    let contents = match format {
+        ReportFormat::Html => render_html(&report),
+        ReportFormat::Markdown => render_markdown(&report),
+    };
+    fs::write(path, contents).map_err(|err| format!("Failed to write {}: {}", path.display(), err))
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Writes a report for `run_id` to `path`. The format defaults to the file
+/// extension.
+#[cfg(feature = "app")]
+#[tauri::command]
+pub fn generate_report(db: State<'_, Db>, run_id: String, format: Option<ReportFormat>, path: String) -> Result<(), String> {
+    let path = Path::new(&path);
+    let conn = db.conn()?;
+    generate_report_internal(&conn, &run_id, format.unwrap_or_else(|| ReportFormat::from_path(path)), path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{result, run, suite};
+    use crate::ScoringResult;
+
+    fn scored(test_case_id: &str, model_id: &str, score: Option<f64>) -> TestCaseResult {
+        let mut scored = result(test_case_id, model_id, if score.is_some() { "completed" } else { "failed" });
+        scored.score = score.map(|score| ScoringResult { score, confidence: None, notes: None, raw_score: None, max_score: None });
+        scored
+    }
+
+    fn report(run: RunResult, test_cases: Vec<TestCase>) -> RunReport {
+        let leaderboard = leaderboard(&run, &test_cases);
+        let categories = category_scores(&run, &test_cases, &leaderboard);
+        RunReport { run, system_prompt: Some("Answer briefly.".to_string()), test_cases, leaderboard, categories }
+    }
+
+    #[test]
+    fn leaderboard_ranks_by_weighted_score() {
+        let mut suite = suite("s1", &["a", "b"]);
+        suite.test_cases[0].weight = 3.0;
+        let mut run = run("r1", &suite, "completed");
+        run.models = ["m-z", "m-none", "m-b", "m-a"].map(str::to_string).to_vec();
+        run.results = vec![
+            scored("a", "m-z", Some(1.0)),
+            scored("b", "m-z", Some(0.0)),
+            scored("a", "m-none", None),
+            scored("a", "m-b", Some(0.5)),
+            scored("b", "m-b", Some(1.0)),
+            scored("a", "m-a", Some(1.0)),
+            scored("b", "m-a", Some(0.0)),
+        ];
+
+        let standings = leaderboard(&run, &suite.test_cases);
+        let ranked: Vec<(usize, &str, Option<f64>)> =
+            standings.iter().map(|standing| (standing.rank, standing.model_id.as_str(), standing.score)).collect();
+        // m-a and m-z tie and are ordered by id; m-none has nothing scored
+        assert_eq!(
+            ranked,
+            vec![(1, "m-a", Some(0.75)), (2, "m-z", Some(0.75)), (3, "m-b", Some(0.625)), (4, "m-none", None)]
+        );
+        assert_eq!((standings[3].scored, standings[3].failed), (0, 1));
+    }
+
+    #[test]
+    fn uncategorized_cases_come_last() {
+        let mut suite = suite("s1", &["a", "b", "c", "d"]);
+        let categories = [Some("math"), None, Some("  "), Some("code")];
+        for (test_case, category) in suite.test_cases.iter_mut().zip(categories) {
+            test_case.metadata.category = category.map(str::to_string);
+        }
+        let mut run = run("r1", &suite, "completed");
+        run.results = vec![scored("a", "model-a", Some(1.0)), scored("b", "model-a", Some(0.5)), scored("c", "model-a", Some(0.0))];
+
+        let report = report(run, suite.test_cases);
+        let categories: Vec<(&str, usize, Option<f64>)> = report
+            .categories
+            .iter()
+            .map(|category| (category.category.as_str(), category.test_case_count, category.scores[0]))
+            .collect();
+        assert_eq!(categories, vec![("code", 1, None), ("math", 1, Some(1.0)), ("Uncategorized", 2, Some(0.25))]);
+    }
+
+    #[test]
+    fn markdown_keeps_each_row_on_one_line() {
+        let suite = suite("s1", &["a"]);
+        let mut run = run("r1", &suite, "completed");
+        run.models = vec!["vendor/a|b\nc".to_string(), "<script>alert(1)</script>".to_string()];
+        run.results = vec![scored("a", "vendor/a|b\nc", Some(1.0)), scored("a", "<script>alert(1)</script>", Some(0.0))];
+        run.results[0].response = "x | y\n<script>alert(1)</script>".to_string();
+
+        let md = render_markdown(&report(run, suite.test_cases));
+        assert!(md.contains("| 1 | `vendor/a\\|b c` | **100.0%** |"), "{}", md);
+        assert!(md.contains("| 2 | `<script>alert(1)</script>` | **0.0%** |"), "{}", md);
+        // Responses are left out of the summary
+        assert!(!md.contains("x | y"));
+    }
+
+    #[test]
+    fn html_escapes_responses_and_model_ids() {
+        let suite = suite("s1", &["a"]);
+        let mut run = run("r1", &suite, "completed");
+        run.models = vec!["<b>model</b>".to_string()];
+        run.results = vec![scored("a", "<b>model</b>", Some(1.0))];
+        run.results[0].response = "<script>alert('x')</script>".to_string();
+        run.results[0].error = Some("Bad \"quote\" & <tag>".to_string());
+
+        let html = render_html(&report(run, suite.test_cases));
+        assert!(html.contains("<pre>&lt;script&gt;alert(&#39;x&#39;)&lt;/script&gt;</pre>"));
+        assert!(html.contains("<code>&lt;b&gt;model&lt;/b&gt;</code>"));
+        assert!(html.contains("<div class=\"error\">Bad &quot;quote&quot; &amp; &lt;tag&gt;</div>"));
+        assert!(!html.contains("<script") && !html.contains("<b>"));
+    }
+}
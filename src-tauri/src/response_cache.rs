@@ -0,0 +1,330 @@
+//! Opt-in cache of model responses. When enabled, a case whose provider,
+//! model, prompts and parameters match an earlier call is answered from the
+//! cache instead of the provider, so re-running a suite after editing one
+//! case only pays for that case. Hits are flagged `cached` on the result and
+//! keep the tokens and latency of the original call, at a cost of zero.
+//!
+//! Entries expire after a TTL, and the least recently used ones are evicted
+//! once the stored responses outgrow the size limit. Unlike a cassette, a
+//! miss simply goes to the provider.
+
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+#[cfg(feature = "app")]
+use tauri::State;
+
+use crate::providers::Completion;
+use crate::{chrono_now, get_setting, open_connection, set_setting, Db, ModelParameters};
+
+const ENABLED_SETTING: &str = "response_cache_enabled";
+const TTL_SETTING: &str = "response_cache_ttl_hours";
+const MAX_SIZE_SETTING: &str = "response_cache_max_size_mb";
+const DEFAULT_TTL_HOURS: i64 = 7 * 24;
+const DEFAULT_MAX_SIZE_MB: i64 = 100;
+const HOUR_MS: i64 = 60 * 60 * 1000;
+const MB: i64 = 1024 * 1024;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheSettings {
+    pub enabled: bool,
+    /// Hours an entry stays valid; 0 keeps entries until evicted
+    pub ttl_hours: i64,
+    /// Total size of the stored responses; 0 is unlimited
+    pub max_size_mb: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CacheStatus {
+    pub settings: CacheSettings,
+    pub entries: i64,
+    pub size_bytes: i64,
+}
+
+/// A stored answer with the latency of the call that produced it
+#[derive(Debug, Clone)]
+pub struct CachedResponse {
+    pub completion: Completion,
+    pub latency_ms: Option<i64>,
+}
+
+fn setting_or(conn: &Connection, key: &str, default: i64) -> i64 {
+    get_setting(conn, key)
+        .ok()
+        .flatten()
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(default)
+}
+
+pub fn cache_settings(conn: &Connection) -> CacheSettings {
+    CacheSettings {
+        enabled: setting_or(conn, ENABLED_SETTING, 0) != 0,
+        ttl_hours: setting_or(conn, TTL_SETTING, DEFAULT_TTL_HOURS),
+        max_size_mb: setting_or(conn, MAX_SIZE_SETTING, DEFAULT_MAX_SIZE_MB),
+    }
+}
+
+/// Saves the settings and applies the new limits to what is stored.
+pub fn save_cache_settings(conn: &Connection, settings: &CacheSettings) -> Result<(), String> {
+    if settings.ttl_hours < 0 || settings.max_size_mb < 0 {
+        return Err("Cache TTL and size limit must be zero or positive.".to_string());
+    }
+
+    set_setting(conn, ENABLED_SETTING, if settings.enabled { "1" } else { "0" })?;
+    set_setting(conn, TTL_SETTING, &settings.ttl_hours.to_string())?;
+    set_setting(conn, MAX_SIZE_SETTING, &settings.max_size_mb.to_string())?;
+    prune_cache(conn, settings)
+}
+
+pub fn cache_status(conn: &Connection) -> Result<CacheStatus, String> {
+    let (entries, size_bytes) = conn
+        .query_row("SELECT COUNT(*), COALESCE(SUM(size), 0) FROM response_cache", [], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|err| err.to_string())?;
+    Ok(CacheStatus { settings: cache_settings(conn), entries, size_bytes })
+}
+
+/// SHA-256 over everything that shapes the answer to a case. The test case
+/// id is left out, so identical prompts share an entry across suites.
+pub fn cache_key(provider: &str, model: &str, system_prompt: &str, user_prompt: &str, parameters: &ModelParameters) -> String {
+    let json = serde_json::to_vec(&serde_json::json!({
+        "provider": provider,
+        "model": model,
+        "systemPrompt": system_prompt,
+        "userPrompt": user_prompt,
+        "parameters": parameters,
+    }))
+    .unwrap_or_default();
+    hex::encode(Sha256::digest(&json))
+}
+
+pub struct ResponseCache {
+    conn: Mutex<Connection>,
+    settings: CacheSettings,
+    /// Skip lookups but still store fresh responses
+    bypass: bool,
+}
+
+impl ResponseCache {
+    /// Opens the cache in the database at `path` with its saved settings, or
+    /// returns `None` when the cache is disabled there.
+    pub fn open(path: &Path, bypass: bool) -> Result<Option<ResponseCache>, String> {
+        let conn = open_connection(path)?;
+        let settings = cache_settings(&conn);
+        Ok(settings.enabled.then(|| ResponseCache { conn: Mutex::new(conn), settings, bypass }))
+    }
+
+    fn conn(&self) -> Result<MutexGuard<'_, Connection>, String> {
+        self.conn.lock().map_err(|_| "Response cache is unavailable after a previous failure.".to_string())
+    }
+
+    pub fn lookup(&self, key: &str) -> Result<Option<CachedResponse>, String> {
+        if self.bypass {
+            return Ok(None);
+        }
+        find_entry(&*self.conn()?, key, self.settings.ttl_hours)
+    }
+
+    pub fn store(&self, key: &str, provider: &str, model: &str, completion: &Completion, latency_ms: Option<i64>) -> Result<(), String> {
+        let conn = self.conn()?;
+        store_entry(&conn, key, provider, model, completion, latency_ms)?;
+        prune_cache(&conn, &self.settings)
+    }
+}
+
+/// A live entry for `key`, marked as just used.
+pub fn find_entry(conn: &Connection, key: &str, ttl_hours: i64) -> Result<Option<CachedResponse>, String> {
+    let now = chrono_now();
+    let oldest = if ttl_hours > 0 { now - ttl_hours * HOUR_MS } else { i64::MIN };
+    let entry: Option<(String, Option<i64>)> = conn
+        .query_row(
+            "SELECT response, latency_ms FROM response_cache WHERE key = ? AND created_at >= ?",
+            params![key, oldest],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )
+        .optional()
+        .map_err(|err| err.to_string())?;
+    let Some((response, latency_ms)) = entry else {
+        return Ok(None);
+    };
+
+    conn.execute("UPDATE response_cache SET last_used_at = ? WHERE key = ?", params![now, key])
+        .map_err(|err| err.to_string())?;
+    let completion = serde_json::from_str(&response).map_err(|err| format!("Corrupt cache entry {}: {}", key, err))?;
+    Ok(Some(CachedResponse { completion, latency_ms }))
+}
+
+/// Stores a response, replacing an earlier one for the same key.
+pub fn store_entry(
+    conn: &Connection,
+    key: &str,
+    provider: &str,
+    model: &str,
+    completion: &Completion,
+    latency_ms: Option<i64>,
+) -> Result<(), String> {
+    let response = serde_json::to_string(completion).map_err(|err| err.to_string())?;
+    let now = chrono_now();
+
+    conn.execute(
+        "INSERT INTO response_cache (key, provider, model, response, latency_ms, size, created_at, last_used_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+         ON CONFLICT(key) DO UPDATE SET
+           response = excluded.response,
+           latency_ms = excluded.latency_ms,
+           size = excluded.size,
+           created_at = excluded.created_at,
+           last_used_at = excluded.last_used_at",
+        params![key, provider, model, response, latency_ms, response.len() as i64, now, now],
+    )
+    .map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+/// Drops expired entries, then the least recently used ones until the cache
+/// fits its size limit.
+pub fn prune_cache(conn: &Connection, settings: &CacheSettings) -> Result<(), String> {
+    if settings.ttl_hours > 0 {
+        conn.execute("DELETE FROM response_cache WHERE created_at < ?", params![chrono_now() - settings.ttl_hours * HOUR_MS])
+            .map_err(|err| err.to_string())?;
+    }
+    if settings.max_size_mb > 0 {
+        conn.execute(
+            "DELETE FROM response_cache WHERE key IN (
+               SELECT key FROM (
+                 SELECT key, SUM(size) OVER (ORDER BY last_used_at DESC, key) AS running_size FROM response_cache
+               ) WHERE running_size > ?
+             )",
+            params![settings.max_size_mb * MB],
+        )
+        .map_err(|err| err.to_string())?;
+    }
+    Ok(())
+}
+
+/// Returns the number of entries removed.
+pub fn clear_response_cache(conn: &Connection) -> Result<usize, String> {
+    conn.execute("DELETE FROM response_cache", []).map_err(|err| err.to_string())
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[cfg(feature = "app")]
+#[tauri::command]
+pub fn get_cache_status(db: State<'_, Db>) -> Result<CacheStatus, String> {
+    let conn = db.conn()?;
+    cache_status(&conn)
+}
+
+#[cfg(feature = "app")]
+#[tauri::command]
+pub fn set_cache_settings(db: State<'_, Db>, settings: CacheSettings) -> Result<CacheStatus, String> {
+    let conn = db.conn()?;
+    save_cache_settings(&conn, &settings)?;
+    cache_status(&conn)
+}
+
+/// Returns the number of entries removed.
+#[cfg(feature = "app")]
+#[tauri::command]
+pub fn clear_cache(db: State<'_, Db>) -> Result<usize, String> {
+    let conn = db.conn()?;
+    clear_response_cache(&conn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::memory_db;
+
+    fn completion(content: &str) -> Completion {
+        Completion { content: content.to_string(), usage: None }
+    }
+
+    /// Stores an entry, then backdates it and sets its size to `size_kb`.
+    fn insert(conn: &Connection, key: &str, size_kb: i64, used_at: i64) {
+        store_entry(conn, key, "mock", "m", &completion(key), Some(5)).unwrap();
+        conn.execute(
+            "UPDATE response_cache SET size = ?, created_at = ?, last_used_at = ? WHERE key = ?",
+            params![size_kb * 1024, used_at, used_at, key],
+        )
+        .unwrap();
+    }
+
+    fn keys(conn: &Connection) -> Vec<String> {
+        let mut stmt = conn.prepare("SELECT key FROM response_cache ORDER BY key").unwrap();
+        let rows = stmt.query_map([], |row| row.get(0)).unwrap();
+        rows.collect::<Result<_, _>>().unwrap()
+    }
+
+    #[test]
+    fn keys_cover_everything_that_shapes_the_answer() {
+        let parameters = ModelParameters::default();
+        let base = cache_key("openai", "gpt", "system", "user", &parameters);
+        assert_eq!(base, cache_key("openai", "gpt", "system", "user", &parameters));
+
+        let warmer = ModelParameters { temperature: parameters.temperature + 0.1, ..parameters.clone() };
+        for other in [
+            cache_key("anthropic", "gpt", "system", "user", &parameters),
+            cache_key("openai", "gpt-mini", "system", "user", &parameters),
+            cache_key("openai", "gpt", "other system", "user", &parameters),
+            cache_key("openai", "gpt", "system", "other user", &parameters),
+            cache_key("openai", "gpt", "system", "user", &warmer),
+        ] {
+            assert_ne!(base, other);
+        }
+    }
+
+    #[test]
+    fn least_recently_used_entries_go_first() {
+        let conn = memory_db();
+        let now = chrono_now();
+        insert(&conn, "a", 400, now - 3000);
+        insert(&conn, "b", 400, now - 2000);
+        insert(&conn, "c", 400, now - 1000);
+
+        // A hit makes the oldest entry the most recently used
+        assert!(find_entry(&conn, "a", 0).unwrap().is_some());
+
+        let settings = CacheSettings { enabled: true, ttl_hours: 0, max_size_mb: 1 };
+        prune_cache(&conn, &settings).unwrap();
+        assert_eq!(keys(&conn), ["a", "c"]);
+
+        prune_cache(&conn, &CacheSettings { max_size_mb: 0, ..settings }).unwrap();
+        assert_eq!(keys(&conn), ["a", "c"]);
+    }
+
+    #[test]
+    fn expired_entries_miss_and_are_pruned() {
+        let conn = memory_db();
+        let now = chrono_now();
+        insert(&conn, "old", 1, now - 3 * HOUR_MS);
+        insert(&conn, "new", 1, now);
+
+        assert!(find_entry(&conn, "old", 2).unwrap().is_none());
+        assert!(find_entry(&conn, "old", 0).unwrap().is_some());
+        let hit = find_entry(&conn, "new", 2).unwrap().unwrap();
+        assert_eq!(hit.completion.content, "new");
+        assert_eq!(hit.latency_ms, Some(5));
+
+        save_cache_settings(&conn, &CacheSettings { enabled: true, ttl_hours: 2, max_size_mb: 0 }).unwrap();
+        assert_eq!(keys(&conn), ["new"]);
+
+        let status = cache_status(&conn).unwrap();
+        assert!(status.settings.enabled);
+        assert_eq!(status.entries, 1);
+    }
+
+    #[test]
+    fn negative_limits_are_rejected() {
+        let conn = memory_db();
+        assert!(save_cache_settings(&conn, &CacheSettings { enabled: true, ttl_hours: -1, max_size_mb: 0 }).is_err());
+        assert!(!cache_settings(&conn).enabled);
+    }
+}
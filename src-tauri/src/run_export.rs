@@ -0,0 +1,346 @@
+//! Flat exports of run results for analysis outside the app. Each result
+//! becomes one row joined with its run and test case, written as CSV, JSONL
+//! or Parquet. Rows are streamed straight from the query into the writer so
+//! exporting a large history never holds it all in memory.
+
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::Arc;
+#[cfg(feature = "app")]
+use std::time::Duration;
+
+use arrow_array::{ArrayRef, Float64Array, Int64Array, RecordBatch, StringArray};
+use arrow_schema::{DataType, Field, Schema, SchemaRef};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use rusqlite::types::Value;
+use rusqlite::{params_from_iter, Connection};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "app")]
+use rusqlite::OpenFlags;
+#[cfg(feature = "app")]
+use tauri::AppHandle;
+
+#[cfg(feature = "app")]
+use crate::db_path;
+use crate::run_queries::{filter_clause, RunListQuery};
+
+/// Rows buffered per Parquet record batch
+const PARQUET_BATCH_SIZE: usize = 4096;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ExportFormat {
+    Csv,
+    Jsonl,
+    Parquet,
+}
+
+impl ExportFormat {
+    /// JSONL for `.jsonl`/`.ndjson`, Parquet for `.parquet`, CSV for everything else.
+    fn from_path(path: &Path) -> ExportFormat {
+        match path.extension().and_then(|ext| ext.to_str()).map(str::to_ascii_lowercase).as_deref() {
+            Some("jsonl") | Some("ndjson") => ExportFormat::Jsonl,
+            Some("parquet") => ExportFormat::Parquet,
+            _ => ExportFormat::Csv,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RunExportRequest {
+    pub path: String,
+    /// Defaults to the file extension
+    pub format: Option<ExportFormat>,
+    /// Runs to export. Combined with `filter` when both are given.
+    pub run_ids: Option<Vec<String>>,
+    /// Same filters as the run list; paging and sorting are ignored.
+    pub filter: Option<RunListQuery>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RunExportReport {
+    pub path: String,
+    pub format: ExportFormat,
+    pub runs: usize,
+    pub rows: usize,
+}
+
+/// One exported result. Field names double as the column names in every
+/// format, so they stay snake_case for spreadsheet and dataframe users.
+#[derive(Debug, Serialize, Clone)]
+pub struct ExportRow {
+    pub run_id: String,
+    pub suite: String,
+    pub model: String,
+    pub provider: Option<String>,
+    pub test_case_id: String,
+    /// From the suite revision the run was scored against, or the current
+    /// suite for runs that predate revisions.
+    pub category: Option<String>,
+    pub difficulty: Option<String>,
+    pub score: Option<f64>,
+    pub raw_score: Option<f64>,
+    pub latency_ms: Option<i64>,
+    pub tokens: Option<i64>,
+    pub cost: Option<f64>,
+    pub status: String,
+    pub error: Option<String>,
+}
+
+// ============================================================================
+// Writers
+// ============================================================================
+
+fn parquet_schema() -> SchemaRef {
+    let text = |name: &str, nullable: bool| Field::new(name, DataType::Utf8, nullable);
+    Arc::new(Schema::new(vec![
+        text("run_id", false),
+        text("suite", false),
+        text("model", false),
+        text("provider", true),
+        text("test_case_id", false),
+        text("category", true),
+        text("difficulty", true),
+        Field::new("score", DataType::Float64, true),
+        Field::new("raw_score", DataType::Float64, true),
+        Field::new("latency_ms", DataType::Int64, true),
+        Field::new("tokens", DataType::Int64, true),
+        Field::new("cost", DataType::Float64, true),
+        text("status", false),
+        text("error", true),
+    ]))
+}
+
+struct ParquetSink {
+    writer: ArrowWriter<File>,
+    schema: SchemaRef,
+    pending: Vec<ExportRow>,
+}
+
+impl ParquetSink {
+    fn flush(&mut self) -> Result<(), String> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+
+        let rows = std::mem::take(&mut self.pending);
+        let text = |get: fn(&ExportRow) -> Option<&str>| -> ArrayRef { Arc::new(rows.iter().map(get).collect::<StringArray>()) };
+        let float = |get: fn(&ExportRow) -> Option<f64>| -> ArrayRef { Arc::new(rows.iter().map(get).collect::<Float64Array>()) };
+        let int = |get: fn(&ExportRow) -> Option<i64>| -> ArrayRef { Arc::new(rows.iter().map(get).collect::<Int64Array>()) };
+
+        let columns = vec![
+            text(|row| Some(&row.run_id)),
+            text(|row| Some(&row.suite)),
+            text(|row| Some(&row.model)),
+            text(|row| row.provider.as_deref()),
+            text(|row| Some(&row.test_case_id)),
+            text(|row| row.category.as_deref()),
+            text(|row| row.difficulty.as_deref()),
+            float(|row| row.score),
+            float(|row| row.raw_score),
+            int(|row| row.latency_ms),
+            int(|row| row.tokens),
+            float(|row| row.cost),
+            text(|row| Some(&row.status)),
+            text(|row| row.error.as_deref()),
+        ];
+
+        let batch = RecordBatch::try_new(self.schema.clone(), columns).map_err(|err| err.to_string())?;
+        self.writer.write(&batch).map_err(|err| err.to_string())
+    }
+}
+
+enum ExportWriter {
+    Csv(Box<csv::Writer<File>>),
+    Jsonl(BufWriter<File>),
+    Parquet(Box<ParquetSink>),
+}
+
+impl ExportWriter {
+    fn create(path: &Path, format: ExportFormat) -> Result<ExportWriter, String> {
+        let file = File::create(path).map_err(|err| format!("Failed to create {}: {}", path.display(), err))?;
+
+        Ok(match format {
+            ExportFormat::Csv => ExportWriter::Csv(Box::new(csv::Writer::from_writer(file))),
+            ExportFormat::Jsonl => ExportWriter::Jsonl(BufWriter::new(file)),
+            ExportFormat::Parquet => {
+                let schema = parquet_schema();
+                let props = WriterProperties::builder().set_compression(Compression::SNAPPY).build();
+                let writer = ArrowWriter::try_new(file, schema.clone(), Some(props)).map_err(|err| err.to_string())?;
+                ExportWriter::Parquet(Box::new(ParquetSink {
+                    writer,
+                    schema,
+                    pending: Vec::with_capacity(PARQUET_BATCH_SIZE),
+                }))
+            }
+        })
+    }
+
+    fn write_row(&mut self, row: ExportRow) -> Result<(), String> {
+        match self {
+            ExportWriter::Csv(writer) => writer.serialize(&row).map_err(|err| err.to_string()),
+            ExportWriter::Jsonl(writer) => {
+                serde_json::to_writer(&mut *writer, &row).map_err(|err| err.to_string())?;
+                writer.write_all(b"\n").map_err(|err| err.to_string())
+            }
+            ExportWriter::Parquet(sink) => {
+                sink.pending.push(row);
+                if sink.pending.len() >= PARQUET_BATCH_SIZE {
+                    sink.flush()?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    fn finish(self) -> Result<(), String> {
+        match self {
+            ExportWriter::Csv(mut writer) => writer.flush().map_err(|err| err.to_string()),
+            ExportWriter::Jsonl(mut writer) => writer.flush().map_err(|err| err.to_string()),
+            ExportWriter::Parquet(mut sink) => {
+                sink.flush()?;
+                sink.writer.close().map(|_| ()).map_err(|err| err.to_string())
+            }
+        }
+    }
+}
+
+// ============================================================================
+// Export
+// ============================================================================
+
+fn export_rows(conn: &Connection, request: &RunExportRequest, writer: &mut ExportWriter) -> Result<(usize, usize), String> {
+    let (mut where_clause, mut values) = filter_clause(&request.filter.clone().unwrap_or_default());
+    if let Some(run_ids) = &request.run_ids {
+        where_clause.push_str(" AND r.id IN (SELECT value FROM json_each(?))");
+        values.push(Value::Text(serde_json::to_string(run_ids).map_err(|err| err.to_string())?));
+    }
+
+    let sql = format!(
+        "SELECT r.id, r.test_suite_name, res.model_id, res.provider, res.test_case_id,
+                COALESCE(json_extract(pc.value, '$.metadata.category'), tc.category),
+                COALESCE(json_extract(pc.value, '$.metadata.difficulty'), tc.difficulty),
+                CASE WHEN json_valid(res.score) THEN json_extract(res.score, '$.score') END,
+                CASE WHEN json_valid(res.score) THEN json_extract(res.score, '$.rawScore') END,
+                res.latency_ms, res.token_count, res.cost, res.status, res.error
+         FROM test_case_results res
+         JOIN runs r ON r.id = res.run_id
+         LEFT JOIN test_suite_revisions rev
+           ON rev.test_suite_id = r.test_suite_id AND rev.revision = r.test_suite_revision
+         LEFT JOIN json_each(rev.test_cases) pc ON json_extract(pc.value, '$.id') = res.test_case_id
+         LEFT JOIN test_cases tc ON rev.test_suite_id IS NULL AND tc.id = res.test_case_id
+         {}
+         ORDER BY r.started_at, r.id, res.id",
+        where_clause
+    );
+
+    let mut stmt = conn.prepare(&sql).map_err(|err| err.to_string())?;
+    let mut rows = stmt.query(params_from_iter(values.iter())).map_err(|err| err.to_string())?;
+
+    let mut row_count = 0;
+    let mut run_count = 0;
+    let mut last_run: Option<String> = None;
+    while let Some(row) = rows.next().map_err(|err| err.to_string())? {
+        let export_row = ExportRow {
+            run_id: row.get(0).map_err(|err| err.to_string())?,
+            suite: row.get(1).map_err(|err| err.to_string())?,
+            model: row.get(2).map_err(|err| err.to_string())?,
+            provider: row.get(3).map_err(|err| err.to_string())?,
+            test_case_id: row.get(4).map_err(|err| err.to_string())?,
+            category: row.get(5).map_err(|err| err.to_string())?,
+            difficulty: row.get(6).map_err(|err| err.to_string())?,
+            score: row.get(7).map_err(|err| err.to_string())?,
+            raw_score: row.get(8).map_err(|err| err.to_string())?,
+            latency_ms: row.get(9).map_err(|err| err.to_string())?,
+            tokens: row.get(10).map_err(|err| err.to_string())?,
+            cost: row.get(11).map_err(|err| err.to_string())?,
+            status: row.get(12).map_err(|err| err.to_string())?,
+            error: row.get(13).map_err(|err| err.to_string())?,
+        };
+
+        if last_run.as_deref() != Some(export_row.run_id.as_str()) {
+            run_count += 1;
+            last_run = Some(export_row.run_id.clone());
+        }
+        writer.write_row(export_row)?;
+        row_count += 1;
+    }
+
+    Ok((run_count, row_count))
+}
+
+/// Writes the selected results to `request.path`. A failed export removes
+/// the partial file.
+pub fn export_runs_internal(conn: &Connection, request: &RunExportRequest) -> Result<RunExportReport, String> {
+    let path = Path::new(&request.path);
+    let format = request.format.unwrap_or_else(|| ExportFormat::from_path(path));
+
+    let mut writer = ExportWriter::create(path, format)?;
+    let result = export_rows(conn, request, &mut writer).and_then(|counts| writer.finish().map(|_| counts));
+
+    match result {
+        Ok((runs, rows)) => Ok(RunExportReport {
+            path: request.path.clone(),
+            format,
+            runs,
+            rows,
+        }),
+        Err(err) => {
+            let _ = fs::remove_file(path);
+            Err(format!("Export failed: {}", err))
+        }
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Reads through its own read-only connection so a long export doesn't hold
+/// up the commands sharing `Db`.
+#[cfg(feature = "app")]
+#[tauri::command]
+pub fn export_runs(app: AppHandle, request: RunExportRequest) -> Result<RunExportReport, String> {
+    let conn = Connection::open_with_flags(db_path(&app)?, OpenFlags::SQLITE_OPEN_READ_ONLY).map_err(|err| err.to_string())?;
+    conn.busy_timeout(Duration::from_secs(5)).map_err(|err| err.to_string())?;
+    export_runs_internal(&conn, &request)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{memory_db, run, suite};
+    use crate::{write_run_with_results, write_test_suite};
+
+    #[test]
+    fn categories_come_from_the_revision_the_run_used() {
+        let conn = memory_db();
+        let mut edited = suite("s1", &["c1"]);
+        write_test_suite(&conn, &edited).unwrap();
+        write_run_with_results(&conn, &run("r1", &edited, "completed")).unwrap();
+
+        edited.test_cases[0].metadata.category = Some("renamed".to_string());
+        edited.test_cases[0].prompt = "A new prompt".to_string();
+        write_test_suite(&conn, &edited).unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("export.jsonl");
+        let request = RunExportRequest {
+            path: path.to_string_lossy().into_owned(),
+            format: None,
+            run_ids: None,
+            filter: None,
+        };
+        let report = export_runs_internal(&conn, &request).unwrap();
+        assert_eq!(report.rows, 1);
+
+        let line = fs::read_to_string(&path).unwrap();
+        let row: serde_json::Value = serde_json::from_str(line.trim()).unwrap();
+        assert_eq!(row["category"], "general");
+    }
+}
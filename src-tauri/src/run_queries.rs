@@ -0,0 +1,268 @@
+//! Lightweight run listing for history views. Returns one summary row per run
+//! with aggregates computed in SQLite, so callers never have to load results
+//! or streamed content just to render a list.
+
+use rusqlite::types::Value;
+use rusqlite::{params_from_iter, Connection};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "app")]
+use tauri::State;
+
+use crate::{Db, ModelParameters};
+
+const DEFAULT_PAGE_SIZE: i64 = 50;
+const MAX_PAGE_SIZE: i64 = 500;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum RunSortField {
+    #[default]
+    StartedAt,
+    CompletedAt,
+    SuiteName,
+    Status,
+    MeanScore,
+    TotalCost,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SortDirection {
+    Asc,
+    #[default]
+    Desc,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct RunListQuery {
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+    pub sort_by: RunSortField,
+    pub sort_dir: SortDirection,
+    pub test_suite_id: Option<String>,
+    pub model_id: Option<String>,
+    pub status: Option<String>,
+    pub started_after: Option<i64>,
+    pub started_before: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RunSummary {
+    pub id: String,
+    pub test_suite_id: String,
+    pub test_suite_name: String,
+    pub test_suite_revision: Option<i64>,
+    pub models: Vec<String>,
+    pub parameters: ModelParameters,
+    pub status: String,
+    pub started_at: i64,
+    pub completed_at: Option<i64>,
+    pub judge_model: Option<String>,
+    pub error_count: Option<i64>,
+    pub error_summary: Option<String>,
+    pub result_count: i64,
+    pub completed_count: i64,
+    pub failed_count: i64,
+    /// Unweighted mean of the normalized scores of scored results.
+    pub mean_score: Option<f64>,
+    pub total_cost: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RunListPage {
+    pub runs: Vec<RunSummary>,
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+}
+
+pub fn filter_clause(query: &RunListQuery) -> (String, Vec<Value>) {
+    let mut conditions = vec!["r.deleted_at IS NULL"];
+    let mut values = Vec::new();
+
+    if let Some(suite_id) = &query.test_suite_id {
+        conditions.push("r.test_suite_id = ?");
+        values.push(Value::Text(suite_id.clone()));
+    }
+    if let Some(model_id) = &query.model_id {
+        conditions.push("EXISTS (SELECT 1 FROM json_each(CASE WHEN json_valid(r.models) THEN r.models ELSE '[]' END) WHERE value = ?)");
+        values.push(Value::Text(model_id.clone()));
+    }
+    if let Some(status) = &query.status {
+        conditions.push("r.status = ?");
+        values.push(Value::Text(status.clone()));
+    }
+    if let Some(after) = query.started_after {
+        conditions.push("r.started_at >= ?");
+        values.push(Value::Integer(after));
+    }
+    if let Some(before) = query.started_before {
+        conditions.push("r.started_at < ?");
+        values.push(Value::Integer(before));
+    }
+
+    (format!("WHERE {}", conditions.join(" AND ")), values)
+}
+
+pub fn list_runs_internal(conn: &Connection, query: &RunListQuery) -> Result<RunListPage, String> {
+    let limit = query.limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE);
+    let offset = query.offset.unwrap_or(0).max(0);
+    let (where_clause, values) = filter_clause(query);
+
+    let total: i64 = conn
+        .query_row(
+            &format!("SELECT COUNT(*) FROM runs r {}", where_clause),
+            params_from_iter(values.iter()),
+            |row| row.get(0),
+        )
+        .map_err(|err| err.to_string())?;
+
+    let sort_column = match query.sort_by {
+        RunSortField::StartedAt => "r.started_at",
+        RunSortField::CompletedAt => "r.completed_at",
+        RunSortField::SuiteName => "r.test_suite_name COLLATE NOCASE",
+        RunSortField::Status => "r.status",
+        RunSortField::MeanScore => "agg.mean_score",
+        RunSortField::TotalCost => "agg.total_cost",
+    };
+    let sort_dir = match query.sort_dir {
+        SortDirection::Asc => "ASC",
+        SortDirection::Desc => "DESC",
+    };
+
+    let sql = format!(
+        "SELECT r.id, r.test_suite_id, r.test_suite_name, r.models, r.status, r.started_at, r.completed_at, r.judge_model,
+                COALESCE(agg.result_count, 0), COALESCE(agg.completed_count, 0), COALESCE(agg.failed_count, 0),
+                agg.mean_score, agg.total_cost, r.test_suite_revision, r.parameters, r.error_count, r.error_summary
+         FROM runs r
+         LEFT JOIN (
+             SELECT run_id,
+                    COUNT(*) AS result_count,
+                    SUM(status = 'completed') AS completed_count,
+                    SUM(status = 'failed') AS failed_count,
+                    AVG(CASE WHEN json_valid(score) THEN json_extract(score, '$.score') END) AS mean_score,
+                    SUM(cost) AS total_cost
+             FROM test_case_results
+             GROUP BY run_id
+         ) agg ON agg.run_id = r.id
+         {}
+         ORDER BY {} {} NULLS LAST, r.id
+         LIMIT ? OFFSET ?",
+        where_clause, sort_column, sort_dir
+    );
+
+    let mut page_values = values;
+    page_values.push(Value::Integer(limit));
+    page_values.push(Value::Integer(offset));
+
+    let mut stmt = conn.prepare(&sql).map_err(|err| err.to_string())?;
+    let rows = stmt
+        .query_map(params_from_iter(page_values.iter()), |row| {
+            let models_json: String = row.get(3)?;
+            let params_json: String = row.get(14)?;
+            Ok(RunSummary {
+                id: row.get(0)?,
+                test_suite_id: row.get(1)?,
+                test_suite_name: row.get(2)?,
+                test_suite_revision: row.get(13)?,
+                models: serde_json::from_str(&models_json).unwrap_or_default(),
+                parameters: serde_json::from_str(&params_json).unwrap_or_default(),
+                status: row.get(4)?,
+                started_at: row.get(5)?,
+                completed_at: row.get(6)?,
+                judge_model: row.get(7)?,
+                error_count: row.get(15)?,
+                error_summary: row.get(16)?,
+                result_count: row.get(8)?,
+                completed_count: row.get(9)?,
+                failed_count: row.get(10)?,
+                mean_score: row.get(11)?,
+                total_cost: row.get(12)?,
+            })
+        })
+        .map_err(|err| err.to_string())?;
+
+    let mut runs = Vec::new();
+    for row in rows {
+        runs.push(row.map_err(|err| err.to_string())?);
+    }
+
+    Ok(RunListPage {
+        runs,
+        total,
+        limit,
+        offset,
+    })
+}
+
+#[cfg(feature = "app")]
+#[tauri::command]
+pub fn list_runs(db: State<'_, Db>, query: Option<RunListQuery>) -> Result<RunListPage, String> {
+    let conn = db.conn()?;
+    list_runs_internal(&conn, &query.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{memory_db, run, suite};
+    use crate::trash::{move_to_trash, TrashKind};
+    use crate::{write_run, write_test_suite};
+
+    fn ids(page: &RunListPage) -> Vec<&str> {
+        page.runs.iter().map(|run| run.id.as_str()).collect()
+    }
+
+    #[test]
+    fn pages_filter_by_model_and_date() {
+        let conn = memory_db();
+        let suite = suite("s1", &["a"]);
+        write_test_suite(&conn, &suite).unwrap();
+        let models = [&["model-b"][..], &["model-a"], &["model-a", "model-b"], &["model-b-mini"], &["model-b"], &["model-b"]];
+        for (index, models) in models.iter().enumerate() {
+            let mut run = run(&format!("r{}", index + 1), &suite, "completed");
+            run.models = models.iter().map(|model| model.to_string()).collect();
+            run.started_at = (index as i64 + 1) * 10;
+            write_run(&conn, &run).unwrap();
+        }
+        move_to_trash(&conn, TrashKind::Run, "r5").unwrap();
+
+        // r1 is before the window, r6 at its exclusive end, r5 trashed
+        let mut query = RunListQuery {
+            model_id: Some("model-b".to_string()),
+            started_after: Some(20),
+            started_before: Some(60),
+            sort_dir: SortDirection::Asc,
+            limit: Some(1),
+            ..RunListQuery::default()
+        };
+        let page = list_runs_internal(&conn, &query).unwrap();
+        assert_eq!((ids(&page), page.total), (vec!["r3"], 1));
+
+        query.started_before = None;
+        query.offset = Some(1);
+        let page = list_runs_internal(&conn, &query).unwrap();
+        assert_eq!((ids(&page), page.total, page.offset), (vec!["r6"], 2, 1));
+    }
+
+    #[test]
+    fn page_bounds_are_clamped() {
+        let conn = memory_db();
+        let suite = suite("s1", &["a"]);
+        write_test_suite(&conn, &suite).unwrap();
+        for id in ["r1", "r2"] {
+            write_run(&conn, &run(id, &suite, "completed")).unwrap();
+        }
+
+        let query = RunListQuery { limit: Some(0), offset: Some(-5), ..RunListQuery::default() };
+        let page = list_runs_internal(&conn, &query).unwrap();
+        assert_eq!((page.runs.len(), page.total, page.limit, page.offset), (1, 2, 1, 0));
+
+        let query = RunListQuery { limit: Some(10_000), ..RunListQuery::default() };
+        assert_eq!(list_runs_internal(&conn, &query).unwrap().limit, MAX_PAGE_SIZE);
+        assert_eq!(list_runs_internal(&conn, &RunListQuery::default()).unwrap().limit, DEFAULT_PAGE_SIZE);
+    }
+}
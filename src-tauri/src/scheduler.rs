@@ -0,0 +1,747 @@
+//! Schedules the model calls of a run. Providers and models can each have a
+//! token-bucket limit on requests and tokens per minute, and calls wait for
+//! their buckets instead of running into 429s. Rate limits, server errors and
+//! network failures are retried after the provider's `Retry-After`, or with
+//! exponential backoff and jitter, and each provider's concurrency halves on
+//! such errors and grows back as calls succeed.
+//!
+//! Workers take the next case whose model can send right away, so a
+//! throttled model doesn't hold up the others. Limits are stored in the
+//! settings; the adaptive state lasts for one run.
+
+use std::collections::hash_map::RandomState;
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Condvar, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "app")]
+use tauri::State;
+
+use crate::providers::{ChatCompletionRequest, Completion, ProviderError};
+use crate::{chrono_now, get_setting, set_setting, Db};
+
+const RATE_LIMITS_SETTING: &str = "rate_limits";
+/// Retries of one call after rate limits, server errors or network failures
+const MAX_RETRIES: u32 = 5;
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+/// Longest a waiting worker sleeps before checking for cancellation
+const MAX_WAIT: Duration = Duration::from_millis(250);
+/// Errors this soon after a concurrency cut come from requests sent before
+/// it, so they don't cut again
+const DECREASE_COOLDOWN: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RateLimit {
+    pub requests_per_minute: Option<u32>,
+    pub tokens_per_minute: Option<u32>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RateLimits {
+    /// By provider id; shared by all of the provider's models
+    #[serde(default)]
+    pub providers: BTreeMap<String, RateLimit>,
+    /// By namespaced model id, on top of the provider's limit
+    #[serde(default)]
+    pub models: BTreeMap<String, RateLimit>,
+}
+
+pub fn rate_limits(conn: &Connection) -> Result<RateLimits, String> {
+    match get_setting(conn, RATE_LIMITS_SETTING)? {
+        Some(json) => serde_json::from_str(&json).map_err(|err| format!("Invalid rate limits: {}", err)),
+        None => Ok(RateLimits::default()),
+    }
+}
+
+/// Saves the limits, dropping entries that limit nothing.
+pub fn save_rate_limits(conn: &Connection, limits: &RateLimits) -> Result<RateLimits, String> {
+    let mut limits = limits.clone();
+    for (target, limit) in limits.providers.iter().chain(&limits.models) {
+        if target.trim().is_empty() {
+            return Err("Rate limits need a provider or model id.".to_string());
+        }
+        if limit.requests_per_minute == Some(0) || limit.tokens_per_minute == Some(0) {
+            return Err(format!("Rate limits for '{}' must be positive; leave a limit out to lift it.", target));
+        }
+    }
+    limits.providers.retain(|_, limit| *limit != RateLimit::default());
+    limits.models.retain(|_, limit| *limit != RateLimit::default());
+
+    let json = serde_json::to_string(&limits).map_err(|err| err.to_string())?;
+    set_setting(conn, RATE_LIMITS_SETTING, &json)?;
+    Ok(limits)
+}
+
+/// Refills continuously up to one minute's worth.
+struct TokenBucket {
+    per_minute: f64,
+    level: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    fn new(per_minute: u32, now: Instant) -> TokenBucket {
+        TokenBucket { per_minute: per_minute as f64, level: per_minute as f64, updated: now }
+    }
+
+    fn refill(&mut self, now: Instant) {
+        let elapsed = now.saturating_duration_since(self.updated).as_secs_f64();
+        self.level = (self.level + elapsed * self.per_minute / 60.0).min(self.per_minute);
+        self.updated = now;
+    }
+
+    /// Time until `amount` is available; more than a minute's worth waits for
+    /// a full bucket.
+    fn wait(&mut self, amount: f64, now: Instant) -> Duration {
+        self.refill(now);
+        let missing = amount.min(self.per_minute) - self.level;
+        if missing <= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(missing * 60.0 / self.per_minute)
+        }
+    }
+
+    /// A negative amount gives back what was reserved but not used.
+    fn take(&mut self, amount: f64) {
+        self.level = (self.level - amount).min(self.per_minute);
+    }
+}
+
+struct Limiter {
+    requests: Option<TokenBucket>,
+    tokens: Option<TokenBucket>,
+    /// Set by a rate limit or `Retry-After`
+    paused_until: Option<Instant>,
+}
+
+impl Limiter {
+    fn new(limit: Option<&RateLimit>, now: Instant) -> Limiter {
+        Limiter {
+            requests: limit.and_then(|limit| limit.requests_per_minute).map(|per_minute| TokenBucket::new(per_minute, now)),
+            tokens: limit.and_then(|limit| limit.tokens_per_minute).map(|per_minute| TokenBucket::new(per_minute, now)),
+            paused_until: None,
+        }
+    }
+
+    /// Time until a request using `tokens` may be sent.
+    fn wait(&mut self, tokens: f64, now: Instant) -> Duration {
+        let paused = self.paused_until.map(|until| until.saturating_duration_since(now)).unwrap_or_default();
+        let requests = self.requests.as_mut().map(|bucket| bucket.wait(1.0, now)).unwrap_or_default();
+        let tokens = self.tokens.as_mut().map(|bucket| bucket.wait(tokens, now)).unwrap_or_default();
+        paused.max(requests).max(tokens)
+    }
+
+    fn take(&mut self, requests: f64, tokens: f64) {
+        if let Some(bucket) = &mut self.requests {
+            bucket.take(requests);
+        }
+        if let Some(bucket) = &mut self.tokens {
+            bucket.take(tokens);
+        }
+    }
+
+    fn pause(&mut self, until: Instant) {
+        self.paused_until = Some(self.paused_until.map_or(until, |paused| paused.max(until)));
+    }
+
+    /// Epoch milliseconds when a pause ends, if one is in effect
+    fn paused_until_ms(&self, now: Instant) -> Option<i64> {
+        let remaining = self.paused_until?.checked_duration_since(now).filter(|remaining| !remaining.is_zero())?;
+        Some(chrono_now() + remaining.as_millis() as i64)
+    }
+}
+
+struct ProviderState {
+    limiter: Limiter,
+    /// Adaptive limit on concurrent calls, between 1 and the run's concurrency
+    concurrency: f64,
+    /// Calls in progress
+    in_flight: usize,
+    /// Cases in progress
+    running: usize,
+    last_cut: Option<Instant>,
+}
+
+impl ProviderState {
+    fn slots(&self) -> usize {
+        self.concurrency.floor().max(1.0) as usize
+    }
+}
+
+struct ModelState {
+    provider: String,
+    limiter: Limiter,
+    queued: usize,
+    running: usize,
+    /// Calls waiting for a limit
+    waiting: usize,
+    retries: u32,
+}
+
+struct Job {
+    id: usize,
+    provider: String,
+    model_id: String,
+}
+
+#[derive(Default)]
+struct QueueData {
+    providers: HashMap<String, ProviderState>,
+    models: HashMap<String, ModelState>,
+    jobs: VecDeque<Job>,
+    /// Bumped on every change, so observers can skip unchanged snapshots
+    version: u64,
+}
+
+impl QueueData {
+    /// Time until a case of the model may start: its limits allow a request
+    /// and the provider has a slot for another case.
+    fn job_wait(&mut self, provider: &str, model_id: &str, now: Instant) -> Duration {
+        self.wait(provider, model_id, 0.0, |provider| provider.running, now)
+    }
+
+    fn call_wait(&mut self, provider: &str, model_id: &str, tokens: f64, now: Instant) -> Duration {
+        self.wait(provider, model_id, tokens, |provider| provider.in_flight, now)
+    }
+
+    /// A full provider waits for a wake-up rather than a known time.
+    fn wait(&mut self, provider: &str, model_id: &str, tokens: f64, busy: fn(&ProviderState) -> usize, now: Instant) -> Duration {
+        let Some(provider) = self.providers.get_mut(provider) else {
+            return Duration::ZERO;
+        };
+        if busy(provider) >= provider.slots() {
+            return MAX_WAIT;
+        }
+        let provider_wait = provider.limiter.wait(tokens, now);
+        let model_wait = self.models.get_mut(model_id).map(|model| model.limiter.wait(tokens, now)).unwrap_or_default();
+        provider_wait.max(model_wait)
+    }
+}
+
+/// Live state of a run's queue, sent with `run-queue` events
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct QueueState {
+    pub providers: Vec<ProviderQueueState>,
+    pub models: Vec<ModelQueueState>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderQueueState {
+    pub provider: String,
+    /// Current adaptive limit on concurrent calls
+    pub concurrency: usize,
+    pub in_flight: usize,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelQueueState {
+    pub model_id: String,
+    /// Cases not started yet
+    pub queued: usize,
+    pub running: usize,
+    /// Calls held back by a rate limit or a full provider
+    pub waiting: usize,
+    pub retries: u32,
+    /// Epoch milliseconds when a rate-limit pause of the model or its
+    /// provider ends
+    pub paused_until: Option<i64>,
+}
+
+pub struct Scheduler<'a> {
+    limits: RateLimits,
+    max_concurrency: usize,
+    cancel: &'a AtomicBool,
+    queue: Mutex<QueueData>,
+    wake: Condvar,
+}
+
+impl<'a> Scheduler<'a> {
+    /// `max_concurrency` caps each provider's concurrent calls; once `cancel`
+    /// is set no job starts and no call waits any longer.
+    pub fn new(limits: RateLimits, max_concurrency: usize, cancel: &'a AtomicBool) -> Scheduler<'a> {
+        Scheduler {
+            limits,
+            max_concurrency: max_concurrency.max(1),
+            cancel,
+            queue: Mutex::new(QueueData::default()),
+            wake: Condvar::new(),
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, QueueData> {
+        self.queue.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn sleep<'g>(&self, queue: MutexGuard<'g, QueueData>, wait: Duration) -> MutexGuard<'g, QueueData> {
+        match self.wake.wait_timeout(queue, wait.min(MAX_WAIT)) {
+            Ok((queue, _)) => queue,
+            Err(poisoned) => poisoned.into_inner().0,
+        }
+    }
+
+    fn cancelled(&self) -> bool {
+        self.cancel.load(Ordering::Relaxed)
+    }
+
+    fn register(&self, queue: &mut QueueData, provider: &str, model_id: &str) {
+        let now = Instant::now();
+        queue.providers.entry(provider.to_string()).or_insert_with(|| ProviderState {
+            limiter: Limiter::new(self.limits.providers.get(provider), now),
+            concurrency: self.max_concurrency as f64,
+            in_flight: 0,
+            running: 0,
+            last_cut: None,
+        });
+        queue.models.entry(model_id.to_string()).or_insert_with(|| ModelState {
+            provider: provider.to_string(),
+            limiter: Limiter::new(self.limits.models.get(model_id), now),
+            queued: 0,
+            running: 0,
+            waiting: 0,
+            retries: 0,
+        });
+    }
+
+    /// Queues a case; `id` is handed back by `next_job`.
+    pub fn push_job(&self, id: usize, provider: &str, model_id: &str) {
+        let mut queue = self.lock();
+        self.register(&mut queue, provider, model_id);
+        if let Some(model) = queue.models.get_mut(model_id) {
+            model.queued += 1;
+        }
+        queue.jobs.push_back(Job { id, provider: provider.to_string(), model_id: model_id.to_string() });
+        queue.version += 1;
+    }
+
+    /// The first queued case whose model can start now, waiting while none
+    /// can. `None` once the queue is empty or the run is cancelled.
+    pub fn next_job(&self) -> Option<usize> {
+        let mut queue = self.lock();
+        loop {
+            if self.cancelled() || queue.jobs.is_empty() {
+                return None;
+            }
+
+            let now = Instant::now();
+            let mut shortest = MAX_WAIT;
+            let mut checked: Vec<&str> = Vec::new();
+            let mut candidates = Vec::new();
+            for (position, job) in queue.jobs.iter().enumerate() {
+                if !checked.contains(&job.model_id.as_str()) {
+                    checked.push(&job.model_id);
+                    candidates.push((position, job.provider.clone(), job.model_id.clone()));
+                }
+            }
+
+            for (position, provider, model_id) in candidates {
+                let wait = queue.job_wait(&provider, &model_id, now);
+                if !wait.is_zero() {
+                    shortest = shortest.min(wait);
+                    continue;
+                }
+                let job = queue.jobs.remove(position)?;
+                if let Some(model) = queue.models.get_mut(&model_id) {
+                    model.queued -= 1;
+                    model.running += 1;
+                }
+                if let Some(provider) = queue.providers.get_mut(&provider) {
+                    provider.running += 1;
+                }
+                queue.version += 1;
+                return Some(job.id);
+            }
+
+            queue = self.sleep(queue, shortest);
+        }
+    }
+
+    pub fn finish_job(&self, provider: &str, model_id: &str) {
+        let mut queue = self.lock();
+        if let Some(model) = queue.models.get_mut(model_id) {
+            model.running = model.running.saturating_sub(1);
+        }
+        if let Some(provider) = queue.providers.get_mut(provider) {
+            provider.running = provider.running.saturating_sub(1);
+        }
+        queue.version += 1;
+        self.wake.notify_all();
+    }
+
+    /// Makes a model call once the limits allow it, retrying rate limits,
+    /// server errors and network failures.
+    pub fn call(
+        &self,
+        provider: &str,
+        model_id: &str,
+        request: &ChatCompletionRequest,
+        mut send: impl FnMut() -> Result<Completion, ProviderError>,
+    ) -> Result<Completion, ProviderError> {
+        let reserved = reserved_tokens(request);
+        let mut attempt = 0;
+        loop {
+            self.acquire(provider, model_id, reserved)?;
+            let outcome = send();
+            let Some(delay) = self.release(provider, model_id, reserved, &outcome, attempt) else {
+                return outcome;
+            };
+
+            attempt += 1;
+            let resume = Instant::now() + delay;
+            while let Some(remaining) = resume.checked_duration_since(Instant::now()).filter(|remaining| !remaining.is_zero()) {
+                if self.cancelled() {
+                    return outcome;
+                }
+                std::thread::sleep(remaining.min(MAX_WAIT));
+            }
+        }
+    }
+
+    fn acquire(&self, provider: &str, model_id: &str, tokens: f64) -> Result<(), ProviderError> {
+        let mut queue = self.lock();
+        self.register(&mut queue, provider, model_id);
+
+        let mut waiting = false;
+        loop {
+            let wait = queue.call_wait(provider, model_id, tokens, Instant::now());
+            if wait.is_zero() {
+                break;
+            }
+            if self.cancelled() {
+                if let Some(model) = queue.models.get_mut(model_id).filter(|_| waiting) {
+                    model.waiting -= 1;
+                    queue.version += 1;
+                }
+                return Err(ProviderError::from("Run cancelled while waiting for a rate limit".to_string()));
+            }
+            if !waiting {
+                waiting = true;
+                if let Some(model) = queue.models.get_mut(model_id) {
+                    model.waiting += 1;
+                }
+                queue.version += 1;
+            }
+            queue = self.sleep(queue, wait);
+        }
+
+        if let Some(provider) = queue.providers.get_mut(provider) {
+            provider.limiter.take(1.0, tokens);
+            provider.in_flight += 1;
+        }
+        if let Some(model) = queue.models.get_mut(model_id) {
+            model.limiter.take(1.0, tokens);
+            if waiting {
+                model.waiting -= 1;
+            }
+        }
+        queue.version += 1;
+        Ok(())
+    }
+
+    /// Settles a finished call and adapts the provider's concurrency. Returns
+    /// how long to wait before retrying, or `None` when the outcome stands.
+    fn release(
+        &self,
+        provider_id: &str,
+        model_id: &str,
+        reserved: f64,
+        outcome: &Result<Completion, ProviderError>,
+        attempt: u32,
+    ) -> Option<Duration> {
+        let mut queue = self.lock();
+        let now = Instant::now();
+        let QueueData { providers, models, .. } = &mut *queue;
+        let (Some(provider), Some(model)) = (providers.get_mut(provider_id), models.get_mut(model_id)) else {
+            return None;
+        };
+        provider.in_flight = provider.in_flight.saturating_sub(1);
+
+        let retry = match outcome {
+            Ok(completion) => {
+                // Charge what the call actually used instead of the reservation
+                if let Some(usage) = &completion.usage {
+                    let difference = usage.total_tokens as f64 - reserved;
+                    provider.limiter.take(0.0, difference);
+                    model.limiter.take(0.0, difference);
+                }
+                provider.concurrency = (provider.concurrency + 1.0 / provider.concurrency).min(self.max_concurrency as f64);
+                None
+            }
+            Err(error) => {
+                // A rejected request used no tokens
+                provider.limiter.take(0.0, -reserved);
+                model.limiter.take(0.0, -reserved);
+
+                let retryable = error.is_retryable() && attempt < MAX_RETRIES;
+                if error.is_retryable() && provider.last_cut.is_none_or(|cut| now.duration_since(cut) >= DECREASE_COOLDOWN) {
+                    provider.concurrency = (provider.concurrency / 2.0).max(1.0);
+                    provider.last_cut = Some(now);
+                }
+                retryable.then(|| {
+                    let delay = error.retry_after.unwrap_or_else(|| backoff(attempt));
+                    // Providers rate-limit per account as well as per model,
+                    // so the pause holds back the provider's other models too
+                    if error.is_rate_limit() || error.retry_after.is_some() {
+                        model.limiter.pause(now + delay);
+                        provider.limiter.pause(now + delay);
+                    }
+                    model.retries += 1;
+                    delay
+                })
+            }
+        };
+
+        queue.version += 1;
+        self.wake.notify_all();
+        retry
+    }
+
+    /// Changes whenever the queue state does
+    pub fn version(&self) -> u64 {
+        self.lock().version
+    }
+
+    pub fn queue_state(&self) -> QueueState {
+        let queue = self.lock();
+        let now = Instant::now();
+
+        let mut providers: Vec<ProviderQueueState> = queue
+            .providers
+            .iter()
+            .map(|(provider, state)| ProviderQueueState {
+                provider: provider.clone(),
+                concurrency: state.slots(),
+                in_flight: state.in_flight,
+            })
+            .collect();
+        providers.sort_by(|a, b| a.provider.cmp(&b.provider));
+
+        let mut models: Vec<ModelQueueState> = queue
+            .models
+            .iter()
+            .map(|(model_id, state)| ModelQueueState {
+                model_id: model_id.clone(),
+                queued: state.queued,
+                running: state.running,
+                waiting: state.waiting,
+                retries: state.retries,
+                paused_until: state
+                    .limiter
+                    .paused_until_ms(now)
+                    .max(queue.providers.get(&state.provider).and_then(|provider| provider.limiter.paused_until_ms(now))),
+            })
+            .collect();
+        models.sort_by(|a, b| a.model_id.cmp(&b.model_id));
+
+        QueueState { providers, models }
+    }
+}
+
+/// Tokens held for a call until its usage is known: the prompt at about four
+/// characters per token plus the completion limit, as providers count it.
+fn reserved_tokens(request: &ChatCompletionRequest) -> f64 {
+    let prompt_chars: usize = request.messages.iter().map(|message| message.content.chars().count()).sum();
+    (prompt_chars / 4) as f64 + request.max_tokens.unwrap_or(0).max(0) as f64
+}
+
+/// Exponential backoff with equal jitter: half the delay is fixed, half
+/// random, so parallel calls that failed together don't retry together.
+fn backoff(attempt: u32) -> Duration {
+    let ceiling = INITIAL_BACKOFF.saturating_mul(1 << attempt.min(16)).min(MAX_BACKOFF);
+    ceiling / 2 + ceiling.mul_f64(random_fraction() / 2.0)
+}
+
+/// A number in [0, 1) without an RNG crate; std seeds every `RandomState`
+/// differently.
+fn random_fraction() -> f64 {
+    let hash = RandomState::new().build_hasher().finish();
+    (hash >> 11) as f64 / (1u64 << 53) as f64
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[cfg(feature = "app")]
+#[tauri::command]
+pub fn get_rate_limits(db: State<'_, Db>) -> Result<RateLimits, String> {
+    let conn = db.conn()?;
+    rate_limits(&conn)
+}
+
+/// Returns the limits as saved.
+#[cfg(feature = "app")]
+#[tauri::command]
+pub fn set_rate_limits(db: State<'_, Db>, limits: RateLimits) -> Result<RateLimits, String> {
+    let conn = db.conn()?;
+    save_rate_limits(&conn, &limits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::Usage;
+
+    fn rate_limited(retry_after: Option<Duration>) -> Result<Completion, ProviderError> {
+        Err(ProviderError::http(429, "Rate limit exceeded (429)", retry_after))
+    }
+
+    fn completion(total_tokens: i64) -> Result<Completion, ProviderError> {
+        Ok(Completion {
+            content: "ok".to_string(),
+            usage: Some(Usage { prompt_tokens: 0, completion_tokens: total_tokens, total_tokens, cost: None }),
+        })
+    }
+
+    fn concurrency(scheduler: &Scheduler, provider: &str) -> f64 {
+        scheduler.lock().providers[provider].concurrency
+    }
+
+    #[test]
+    fn token_buckets_refill_over_a_minute() {
+        let start = Instant::now();
+        let mut bucket = TokenBucket::new(60, start);
+        assert!(bucket.wait(60.0, start).is_zero());
+
+        bucket.take(60.0);
+        assert_eq!(bucket.wait(1.0, start), Duration::from_secs(1));
+        assert_eq!(bucket.wait(30.0, start + Duration::from_secs(10)), Duration::from_secs(20));
+        // Never fills past a minute's worth, and larger amounts wait for a full bucket
+        assert!(bucket.wait(1000.0, start + Duration::from_secs(600)).is_zero());
+        assert_eq!(bucket.level, 60.0);
+
+        // Unused reservations are given back without overfilling
+        bucket.take(50.0);
+        bucket.take(-80.0);
+        assert_eq!(bucket.level, 60.0);
+    }
+
+    #[test]
+    fn backoff_doubles_with_jitter_up_to_the_cap() {
+        for attempt in 0..8 {
+            let ceiling = INITIAL_BACKOFF.saturating_mul(1 << attempt).min(MAX_BACKOFF);
+            let delay = backoff(attempt);
+            assert!(delay >= ceiling / 2 && delay <= ceiling, "attempt {}: {:?}", attempt, delay);
+        }
+        assert!(backoff(40) <= MAX_BACKOFF);
+    }
+
+    #[test]
+    fn errors_halve_concurrency_and_successes_grow_it_back() {
+        let cancel = AtomicBool::new(false);
+        let scheduler = Scheduler::new(RateLimits::default(), 8, &cancel);
+        let server_error = Err(ProviderError::http(503, "Unavailable", None));
+
+        scheduler.acquire("p", "p:m", 0.0).unwrap();
+        assert!(scheduler.release("p", "p:m", 0.0, &server_error, 0).is_some());
+        assert_eq!(concurrency(&scheduler, "p"), 4.0);
+
+        // Errors right after a cut come from calls sent before it
+        scheduler.acquire("p", "p:m", 0.0).unwrap();
+        scheduler.release("p", "p:m", 0.0, &server_error, 1);
+        assert_eq!(concurrency(&scheduler, "p"), 4.0);
+
+        scheduler.lock().providers.get_mut("p").unwrap().last_cut = Some(Instant::now() - DECREASE_COOLDOWN);
+        scheduler.acquire("p", "p:m", 0.0).unwrap();
+        scheduler.release("p", "p:m", 0.0, &server_error, 2);
+        assert_eq!(concurrency(&scheduler, "p"), 2.0);
+
+        for _ in 0..100 {
+            scheduler.acquire("p", "p:m", 0.0).unwrap();
+            assert!(scheduler.release("p", "p:m", 0.0, &completion(10), 0).is_none());
+        }
+        assert_eq!(concurrency(&scheduler, "p"), 8.0);
+
+        // Bad requests and exhausted retries stand
+        let bad_request = Err(ProviderError::http(400, "Bad request", None));
+        scheduler.acquire("p", "p:m", 0.0).unwrap();
+        assert!(scheduler.release("p", "p:m", 0.0, &bad_request, 0).is_none());
+        scheduler.acquire("p", "p:m", 0.0).unwrap();
+        assert!(scheduler.release("p", "p:m", 0.0, &server_error, MAX_RETRIES).is_none());
+        assert_eq!(scheduler.lock().providers["p"].in_flight, 0);
+    }
+
+    #[test]
+    fn calls_are_charged_what_they_used() {
+        let cancel = AtomicBool::new(false);
+        let limit = RateLimit { requests_per_minute: None, tokens_per_minute: Some(1000) };
+        let limits = RateLimits { providers: BTreeMap::from([("p".to_string(), limit)]), models: BTreeMap::new() };
+        let scheduler = Scheduler::new(limits, 4, &cancel);
+
+        scheduler.acquire("p", "p:m", 600.0).unwrap();
+        scheduler.release("p", "p:m", 600.0, &completion(100), 0);
+        scheduler.acquire("p", "p:m", 600.0).unwrap();
+        scheduler.release("p", "p:m", 600.0, &Err(ProviderError::network("timed out")), MAX_RETRIES);
+
+        let level = scheduler.lock().providers["p"].limiter.tokens.as_ref().unwrap().level;
+        assert!((899.0..=901.0).contains(&level), "{}", level);
+    }
+
+    #[test]
+    fn throttled_models_do_not_hold_up_the_queue() {
+        let cancel = AtomicBool::new(false);
+        let limit = RateLimit { requests_per_minute: Some(1), tokens_per_minute: None };
+        let limits = RateLimits { providers: BTreeMap::new(), models: BTreeMap::from([("p:slow".to_string(), limit)]) };
+        let scheduler = Scheduler::new(limits, 4, &cancel);
+        scheduler.push_job(0, "p", "p:slow");
+        scheduler.push_job(1, "p", "p:slow");
+        scheduler.push_job(2, "p", "p:fast");
+
+        assert_eq!(scheduler.next_job(), Some(0));
+        scheduler.acquire("p", "p:slow", 0.0).unwrap();
+        assert_eq!(scheduler.next_job(), Some(2));
+
+        cancel.store(true, Ordering::Relaxed);
+        assert_eq!(scheduler.next_job(), None);
+        assert!(scheduler.acquire("p", "p:slow", 0.0).is_err());
+    }
+
+    #[test]
+    fn empty_and_zero_limits_are_not_saved() {
+        let conn = crate::test_support::memory_db();
+        let zero = RateLimit { requests_per_minute: Some(0), tokens_per_minute: None };
+        let limits = RateLimits { providers: BTreeMap::from([("p".to_string(), zero)]), models: BTreeMap::new() };
+        assert!(save_rate_limits(&conn, &limits).is_err());
+
+        let limits = RateLimits {
+            providers: BTreeMap::from([("p".to_string(), RateLimit::default())]),
+            models: BTreeMap::from([("p:m".to_string(), RateLimit { requests_per_minute: Some(10), tokens_per_minute: None })]),
+        };
+        let saved = save_rate_limits(&conn, &limits).unwrap();
+        assert!(saved.providers.is_empty());
+        assert_eq!(rate_limits(&conn).unwrap(), saved);
+    }
+
+    #[test]
+    fn rate_limits_pause_the_whole_provider() {
+        let cancel = AtomicBool::new(false);
+        let scheduler = Scheduler::new(RateLimits::default(), 4, &cancel);
+        scheduler.push_job(0, "openai", "openai:a");
+        scheduler.push_job(1, "openai", "openai:b");
+        scheduler.push_job(2, "anthropic", "anthropic:c");
+
+        scheduler.acquire("openai", "openai:a", 0.0).unwrap();
+        let delay = scheduler.release("openai", "openai:a", 0.0, &rate_limited(Some(Duration::from_secs(30))), 0);
+        assert_eq!(delay, Some(Duration::from_secs(30)));
+
+        let now = Instant::now();
+        let mut queue = scheduler.lock();
+        assert!(queue.call_wait("openai", "openai:b", 0.0, now) > Duration::from_secs(25));
+        assert!(queue.call_wait("anthropic", "anthropic:c", 0.0, now).is_zero());
+        drop(queue);
+
+        let state = scheduler.queue_state();
+        let paused: Vec<bool> = state.models.iter().map(|model| model.paused_until.is_some()).collect();
+        assert_eq!(paused, [false, true, true]);
+    }
+}
@@ -0,0 +1,638 @@
+//! Backend port of the scorers in `src/scoring`. Scores must match what the
+//! app produces for the same response, so keep the two in sync.
+
+use regex::{Regex, RegexBuilder};
+use serde_json::{Map, Value};
+
+use crate::cassette::CassetteMode;
+use crate::providers::{ChatCompletionRequest, ChatMessage, Completion, Providers};
+use crate::scheduler::Scheduler;
+use crate::{ScoringResult, TestCase};
+
+const BASE_JUDGE_SYSTEM_PROMPT: &str = r#"You are an expert evaluator assessing the quality of AI model responses. Your task is to score responses objectively based on accuracy, completeness, and adherence to the task requirements.
+
+SCORING GUIDELINES:
+- Score from 0 to 100, where:
+  - 100: Perfect response, fully correct and complete
+  - 80-99: Excellent response with minor issues
+  - 60-79: Good response but missing some elements or has small errors
+  - 40-59: Partially correct but significant issues
+  - 20-39: Mostly incorrect but shows some understanding
+  - 0-19: Completely wrong or irrelevant
+
+RESPONSE FORMAT:
+You MUST respond with a JSON object containing:
+- "score": A number from 0 to 100
+- "reasoning": A brief explanation of your scoring decision
+
+Example response:
+{"score": 85, "reasoning": "The response correctly identifies the main concept but lacks one minor detail."}
+
+If additional benchmark instructions specify a different output format, follow that format exactly."#;
+
+/// Tolerance used by numeric-tolerance scoring
+const NUMERIC_TOLERANCE: f64 = 0.01;
+
+/// Model used to grade llm-judge cases; `model` is a namespaced id
+pub struct Judge<'a> {
+    pub providers: &'a Providers,
+    /// Judge calls share the run's rate limits
+    pub scheduler: &'a Scheduler<'a>,
+    pub model: &'a str,
+}
+
+fn result(score: f64, confidence: Option<f64>, notes: impl Into<String>) -> ScoringResult {
+    ScoringResult {
+        score,
+        confidence,
+        notes: Some(notes.into()),
+        raw_score: None,
+        max_score: None,
+    }
+}
+
+/// Adds the 0-100 raw score the deterministic scorers report.
+fn with_percent(mut scored: ScoringResult) -> ScoringResult {
+    scored.raw_score = Some((scored.score * 100.0).round());
+    scored.max_score = Some(100.0);
+    scored
+}
+
+fn pattern(source: &str) -> Regex {
+    Regex::new(source).expect("built-in pattern is valid")
+}
+
+pub fn score_response(test_case: &TestCase, response: &str, judge: Option<&Judge>, judge_system_prompt: Option<&str>) -> ScoringResult {
+    let expected = test_case.expected_output.as_deref().unwrap_or_default();
+
+    match test_case.scoring_method.as_str() {
+        "exact-match" => score_exact_match(response, expected),
+        "regex-match" => score_regex_match(response, expected),
+        "numeric-tolerance" => score_numeric_tolerance(response, expected),
+        "boolean" => score_boolean_match(response, expected),
+        "llm-judge" => match judge {
+            Some(judge) => score_llm_judge(judge, &test_case.prompt, response, test_case.expected_output.as_deref(), judge_system_prompt),
+            // Fallback to boolean if no judge configured
+            None => score_boolean_match(response, expected),
+        },
+        _ => ScoringResult {
+            score: 0.0,
+            confidence: None,
+            notes: Some("Unknown scoring method".to_string()),
+            raw_score: None,
+            max_score: None,
+        },
+    }
+}
+
+// ============================================================================
+// Deterministic scorers
+// ============================================================================
+
+pub fn score_boolean_match(response: &str, expected: &str) -> ScoringResult {
+    if expected.is_empty() {
+        return result(1.0, None, "No expected output - auto pass");
+    }
+
+    let contains = response.trim().to_lowercase().contains(&expected.trim().to_lowercase());
+    if contains {
+        result(1.0, None, "Expected output found in response")
+    } else {
+        result(0.0, None, "Expected output not found in response")
+    }
+}
+
+pub fn score_exact_match(response: &str, expected: &str) -> ScoringResult {
+    with_percent(exact_match(response, expected))
+}
+
+fn exact_match(response: &str, expected: &str) -> ScoringResult {
+    if expected.is_empty() {
+        return result(1.0, None, "No expected output specified");
+    }
+
+    let response = response.trim();
+    let expected = expected.trim();
+
+    if response == expected {
+        return result(1.0, Some(1.0), "Exact match");
+    }
+    if response.to_lowercase() == expected.to_lowercase() {
+        return result(0.95, Some(1.0), "Case-insensitive match");
+    }
+
+    let similarity = calculate_similarity(response, expected);
+    let extra_content_ratio = 1.0 - (expected.chars().count() as f64 / response.chars().count().max(1) as f64);
+
+    if response.contains(expected) {
+        let score = (0.95 - extra_content_ratio * 0.35).max(0.6);
+        return result(
+            score,
+            Some(0.9),
+            format!("Expected output found within response ({:.0}% extra content)", extra_content_ratio * 100.0),
+        );
+    }
+    if response.to_lowercase().contains(&expected.to_lowercase()) {
+        let score = (0.90 - extra_content_ratio * 0.35).max(0.55);
+        return result(
+            score,
+            Some(0.85),
+            format!("Expected output found (case-insensitive, {:.0}% extra content)", extra_content_ratio * 100.0),
+        );
+    }
+
+    if similarity > 0.5 {
+        return result(
+            similarity * 0.7,
+            Some((similarity * 0.8).max(0.4)),
+            format!("Partial similarity: {:.1}%", similarity * 100.0),
+        );
+    }
+    if similarity > 0.2 {
+        return result(similarity * 0.4, Some(0.3), format!("Low similarity: {:.1}%", similarity * 100.0));
+    }
+
+    result(0.0, Some(1.0), "No match")
+}
+
+fn calculate_similarity(a: &str, b: &str) -> f64 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    let (longer, shorter) = if a.len() > b.len() { (&a, &b) } else { (&b, &a) };
+
+    if longer.is_empty() {
+        return 1.0;
+    }
+
+    (longer.len() - levenshtein_distance(longer, shorter)) as f64 / longer.len() as f64
+}
+
+fn levenshtein_distance(a: &[char], b: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for i in 1..=a.len() {
+        current[0] = i;
+        for j in 1..=b.len() {
+            current[j] = if a[i - 1] == b[j - 1] {
+                previous[j - 1]
+            } else {
+                1 + previous[j].min(current[j - 1]).min(previous[j - 1])
+            };
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+/// Accepts a bare pattern or the `/pattern/flags` form. JavaScript-only
+/// syntax such as lookarounds is reported as an invalid pattern.
+pub fn score_regex_match(response: &str, expected: &str) -> ScoringResult {
+    with_percent(regex_match(response, expected))
+}
+
+fn regex_match(response: &str, expected: &str) -> ScoringResult {
+    if expected.is_empty() {
+        return result(1.0, None, "No pattern specified");
+    }
+
+    let (source, flags) = match expected.strip_prefix('/').and_then(|rest| rest.rfind('/').map(|end| (&rest[..end], &rest[end + 1..]))) {
+        Some((source, flags)) => (source, flags),
+        None => (expected, ""),
+    };
+
+    // RegExp rejects unknown and repeated flags, and `u` together with `v`
+    let invalid_flag = flags.char_indices().any(|(index, flag)| !"dgimsuvy".contains(flag) || flags[..index].contains(flag));
+    if invalid_flag || (flags.contains('u') && flags.contains('v')) {
+        return result(0.0, Some(0.0), format!("Invalid regex pattern: Invalid flags supplied to RegExp constructor '{}'", flags));
+    }
+
+    let regex = RegexBuilder::new(source)
+        .case_insensitive(flags.contains('i'))
+        .multi_line(flags.contains('m'))
+        .dot_matches_new_line(flags.contains('s'))
+        .build();
+
+    match regex {
+        Ok(regex) => match regex.find(response) {
+            Some(found) => result(1.0, Some(1.0), format!("Pattern matched: \"{}\"", found.as_str())),
+            None => result(0.0, Some(1.0), "Pattern did not match"),
+        },
+        Err(err) => result(0.0, Some(0.0), format!("Invalid regex pattern: {}", err)),
+    }
+}
+
+/// `parseFloat`: the longest numeric prefix, ignoring leading whitespace.
+fn parse_float_prefix(text: &str) -> Option<f64> {
+    pattern(r"^[+-]?(?:\d+\.?\d*|\.\d+)(?:[eE][+-]?\d+)?")
+        .find(text.trim_start())
+        .and_then(|found| found.as_str().parse().ok())
+}
+
+pub fn score_numeric_tolerance(response: &str, expected: &str) -> ScoringResult {
+    with_percent(numeric_tolerance(response, expected, NUMERIC_TOLERANCE))
+}
+
+fn numeric_tolerance(response: &str, expected: &str, tolerance: f64) -> ScoringResult {
+    if expected.is_empty() {
+        return result(1.0, None, "No expected value specified");
+    }
+
+    let Some(expected_number) = parse_float_prefix(expected) else {
+        return result(0.0, Some(0.0), format!("Invalid expected number: \"{}\"", expected));
+    };
+
+    let numbers: Vec<f64> = pattern(r"-?\d+\.?\d*(?:[eE][+-]?\d+)?")
+        .find_iter(response)
+        .filter_map(|found| parse_float_prefix(found.as_str()))
+        .collect();
+    if numbers.is_empty() {
+        return result(0.0, Some(1.0), "No numbers found in response");
+    }
+
+    for &number in &numbers {
+        let diff = (number - expected_number).abs();
+        if diff <= tolerance || (diff / expected_number).abs() <= tolerance {
+            return result(1.0, Some(1.0), format!("Exact match: {} ≈ {}", number, expected_number));
+        }
+    }
+
+    let closest = numbers
+        .iter()
+        .copied()
+        .reduce(|prev, curr| if (curr - expected_number).abs() < (prev - expected_number).abs() { curr } else { prev })
+        .unwrap_or_default();
+    let diff = (closest - expected_number).abs();
+    let relative_diff = if expected_number != 0.0 { diff / expected_number.abs() } else { diff };
+
+    if relative_diff >= 0.25 {
+        return result(
+            0.0,
+            Some(1.0),
+            format!("No match: closest was {} (expected {}, diff: {:.1}%)", closest, expected_number, relative_diff * 100.0),
+        );
+    }
+
+    // 1.0 at 0% error, decaying to 0 at 25% error
+    let score = (1.0 - (relative_diff / 0.25).sqrt()).max(0.0);
+    let confidence = (1.0 - relative_diff * 2.0).max(0.5);
+    let label = if score >= 0.99 { "Exact" } else if score >= 0.8 { "Close" } else { "Partial" };
+
+    result(
+        score,
+        Some(confidence),
+        format!("{} match: {} (expected {}, diff: {:.1}%)", label, closest, expected_number, relative_diff * 100.0),
+    )
+}
+
+// ============================================================================
+// LLM judge
+// ============================================================================
+
+pub fn score_llm_judge(
+    judge: &Judge,
+    prompt: &str,
+    response: &str,
+    expected_output: Option<&str>,
+    judge_system_prompt: Option<&str>,
+) -> ScoringResult {
+    if response.trim().is_empty() {
+        return ScoringResult {
+            score: 0.0,
+            confidence: Some(1.0),
+            notes: Some("Empty response".to_string()),
+            raw_score: Some(0.0),
+            max_score: Some(100.0),
+        };
+    }
+
+    let (namespace, model) = judge.providers.split_model_id(judge.model);
+    let request = judge_request(model, prompt, response, expected_output, judge_system_prompt);
+
+    match judge_completion(judge, namespace, &request) {
+        Ok(completion) => parse_judge_response(&completion.content),
+        Err(err) => result(0.0, Some(0.0), format!("Judge evaluation failed: {}", err)),
+    }
+}
+
+/// The request that asks the judge to grade `response`; `model` is the
+/// provider's own id.
+pub fn judge_request(
+    model: &str,
+    prompt: &str,
+    response: &str,
+    expected_output: Option<&str>,
+    judge_system_prompt: Option<&str>,
+) -> ChatCompletionRequest {
+    ChatCompletionRequest {
+        model: model.to_string(),
+        messages: vec![
+            ChatMessage::system(&build_judge_system_prompt(judge_system_prompt)),
+            ChatMessage::user(&build_judge_prompt(prompt, response, expected_output)),
+        ],
+        test_case_id: None,
+        // Low temperature for consistent scoring
+        temperature: Some(0.1),
+        top_p: None,
+        max_tokens: Some(500),
+        frequency_penalty: None,
+        presence_penalty: None,
+    }
+}
+
+/// Serves a replayed judge answer, failing on a miss unless the cassette
+/// allows live judging; otherwise asks the judge (and records the answer
+/// when recording).
+fn judge_completion(judge: &Judge, namespace: &str, request: &ChatCompletionRequest) -> Result<Completion, String> {
+    let cassette = judge.providers.cassette();
+    if let Some(cassette) = cassette.filter(|cassette| cassette.mode() == CassetteMode::Replay) {
+        if let Some(recorded) = cassette.lookup(namespace, request)? {
+            return Ok(recorded);
+        }
+        if !cassette.allows_live_judge() {
+            return Err(format!("Not in the cassette: judge {}. Allow live judging to grade with it.", judge.model));
+        }
+    }
+
+    let (provider, _, _) = judge.providers.resolve(judge.model)?;
+    let completion = judge.scheduler.call(namespace, judge.model, request, || provider.chat_completion(request))?;
+    if let Some(cassette) = cassette.filter(|cassette| cassette.mode() == CassetteMode::Record) {
+        cassette.record(namespace, request, &completion)?;
+    }
+    Ok(completion)
+}
+
+fn build_judge_system_prompt(custom_prompt: Option<&str>) -> String {
+    match custom_prompt.map(str::trim).filter(|prompt| !prompt.is_empty()) {
+        Some(custom) => format!("{}\n\n## Additional Benchmark Instructions\n{}", BASE_JUDGE_SYSTEM_PROMPT, custom),
+        None => BASE_JUDGE_SYSTEM_PROMPT.to_string(),
+    }
+}
+
+fn build_judge_prompt(original_prompt: &str, response: &str, expected_output: Option<&str>) -> String {
+    let mut prompt = format!("## Original Task/Question\n{}\n\n## Model Response\n{}\n", original_prompt, response);
+    if let Some(expected) = expected_output.filter(|expected| !expected.is_empty()) {
+        prompt.push_str(&format!("\n## Expected/Reference Answer\n{}\n", expected));
+    }
+    prompt.push_str("\n## Your Task\nEvaluate the model's response and provide a score from 0-100 with reasoning.\nRespond ONLY with a valid JSON object.");
+    prompt
+}
+
+fn clamp_score(score: f64) -> f64 {
+    if score.is_nan() {
+        0.0
+    } else {
+        score.clamp(0.0, 100.0)
+    }
+}
+
+fn percent_result(raw_score: f64, confidence: f64, notes: String) -> ScoringResult {
+    ScoringResult {
+        score: (raw_score / 100.0).clamp(0.0, 1.0),
+        confidence: Some(confidence),
+        notes: Some(notes),
+        raw_score: Some(raw_score),
+        max_score: Some(100.0),
+    }
+}
+
+pub fn parse_judge_response(response: &str) -> ScoringResult {
+    if response.trim().is_empty() {
+        return result(0.0, Some(0.0), "Empty judge response");
+    }
+
+    if let Some(structured) = parse_structured_judge_response(response) {
+        return structured;
+    }
+
+    if let Some(parsed) = extract_json_candidate(response).and_then(|candidate| safe_parse_json(&candidate)) {
+        if let Some(normalized) = normalize_parsed_judge_score(&parsed) {
+            return normalized;
+        }
+    }
+
+    if let Some((score, reasoning)) = extract_score_from_text(response) {
+        let clamped = clamp_score(score);
+        let notes = reasoning.unwrap_or_else(|| format!("Extracted score from text: {}/100", clamped));
+        return percent_result(clamped, 0.7, notes);
+    }
+
+    result(0.0, Some(0.0), "Could not parse judge response")
+}
+
+/// Scores given on a 10-point scale (explicitly or implicitly) are scaled to 100.
+fn scaled_capture(captures: &regex::Captures) -> Option<f64> {
+    let mut score: f64 = captures.get(1)?.as_str().parse().ok()?;
+    if captures.get(2).map(|scale| scale.as_str()) == Some("10") || score <= 10.0 {
+        score *= 10.0;
+    }
+    Some(score)
+}
+
+fn parse_structured_judge_response(response: &str) -> Option<ScoringResult> {
+    let constraint = pattern(r"(?i)constraint\s+satisfaction\s*:\s*\[?\s*(yes|no)\s*\]?").captures(response)?;
+    let semantic = pattern(r"(?i)semantic\s+score\s*:\s*\[?\s*(\d+(?:\.\d+)?)\s*(?:/\s*(10|100))?\s*\]?").captures(response)?;
+    let persona = pattern(r"(?i)persona\s+score\s*:\s*\[?\s*(\d+(?:\.\d+)?)\s*(?:/\s*(10|100))?\s*\]?").captures(response)?;
+    let reasoning = pattern(r"(?i)final\s+rational(?:e)?\s*:\s*([^\n\r]+)")
+        .captures(response)
+        .and_then(|captures| captures.get(1))
+        .map(|reason| reason.as_str().trim().to_string());
+
+    let constraint_satisfied = constraint[1].eq_ignore_ascii_case("yes");
+    let semantic_score = clamp_score(scaled_capture(&semantic)?);
+    let persona_score = clamp_score(scaled_capture(&persona)?);
+    let final_score = if constraint_satisfied { (semantic_score + persona_score) / 2.0 } else { 0.0 };
+
+    let mut parts = vec![
+        format!("Constraint: {}", if constraint_satisfied { "Yes" } else { "No" }),
+        format!("Semantic: {}/100", semantic_score),
+        format!("Persona: {}/100", persona_score),
+    ];
+    if let Some(reasoning) = reasoning.filter(|reason| !reason.is_empty()) {
+        parts.push(format!("Reason: {}", reasoning));
+    }
+
+    Some(percent_result(final_score, 0.85, parts.join(" | ")))
+}
+
+/// A fenced code block, or the first balanced `{...}` object.
+fn extract_json_candidate(response: &str) -> Option<String> {
+    if let Some(fenced) = pattern(r"(?is)```(?:json)?\s*(.*?)```").captures(response) {
+        return Some(fenced[1].trim().to_string());
+    }
+
+    let start = response.find('{')?;
+    let mut depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for (offset, ch) in response[start..].char_indices() {
+        if escaped {
+            escaped = false;
+            continue;
+        }
+        match ch {
+            '\\' if in_string => escaped = true,
+            '"' => in_string = !in_string,
+            '{' if !in_string => depth += 1,
+            '}' if !in_string => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(response[start..start + offset + 1].to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+fn safe_parse_json(raw: &str) -> Option<Map<String, Value>> {
+    let trimmed = raw.trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+    if let Ok(Value::Object(object)) = serde_json::from_str(trimmed) {
+        return Some(object);
+    }
+
+    // Tolerate a byte order mark and trailing commas
+    let cleaned = pattern(r",\s*([}\]])").replace_all(trimmed.trim_start_matches('\u{feff}'), "$1");
+    match serde_json::from_str(&cleaned) {
+        Ok(Value::Object(object)) => Some(object),
+        _ => None,
+    }
+}
+
+fn normalize_parsed_judge_score(parsed: &Map<String, Value>) -> Option<ScoringResult> {
+    let score_key = parsed.keys().find(|key| key.eq_ignore_ascii_case("score") || key.eq_ignore_ascii_case("rating"))?;
+    let score_value = match &parsed[score_key] {
+        Value::Number(number) => number.as_f64(),
+        Value::String(text) if !text.trim().is_empty() => parse_float_prefix(text),
+        _ => None,
+    }?;
+
+    let mut raw_score = clamp_score(score_value);
+    if raw_score <= 10.0 {
+        raw_score *= 10.0;
+    }
+
+    let reasoning = parsed
+        .iter()
+        .find(|(key, _)| ["reasoning", "rationale", "explanation", "notes", "reason"].iter().any(|name| key.eq_ignore_ascii_case(name)))
+        .and_then(|(_, value)| value.as_str())
+        .map(str::trim)
+        .filter(|reason| !reason.is_empty())
+        .map(str::to_string);
+
+    Some(percent_result(
+        raw_score,
+        0.9,
+        reasoning.unwrap_or_else(|| "Judge evaluation complete".to_string()),
+    ))
+}
+
+fn extract_score_from_text(response: &str) -> Option<(f64, Option<String>)> {
+    let captures = pattern(r#"(?i)(?:score|rating)[\s*"':=\-]*(\d+(?:\.\d+)?)(?:\s*/\s*(10|100))?"#)
+        .captures(response)
+        .or_else(|| pattern(r"(?i)^\s*(\d+(?:\.\d+)?)(?:\s*/\s*(10|100))?\s*$").captures(response))?;
+    let score = scaled_capture(&captures)?;
+
+    let reasoning = pattern(r#"(?i)(?:reasoning|rationale|explanation)[\s*"':=\-]*([^\n\r]+)"#)
+        .captures(response)
+        .map(|captures| captures[1].trim().to_string());
+
+    Some((score, reasoning))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::test_case;
+
+    /// (response, expected, score, confidence, notes) as `src/scoring` scores them
+    type Pair<'a> = (&'a str, &'a str, f64, Option<f64>, &'a str);
+
+    fn assert_pairs(scorer: fn(&str, &str) -> ScoringResult, pairs: &[Pair]) {
+        for &(response, expected, score, confidence, notes) in pairs {
+            let scored = scorer(response, expected);
+            let case = format!("{:?} against {:?}: {:?}", response, expected, scored);
+            assert!((scored.score - score).abs() < 1e-12, "{}", case);
+            assert_eq!(scored.confidence.map(|value| (value * 1e9).round()), confidence.map(|value| (value * 1e9).round()), "{}", case);
+            assert_eq!(scored.notes.as_deref(), Some(notes), "{}", case);
+        }
+    }
+
+    #[test]
+    fn exact_match_gives_partial_credit() {
+        assert_pairs(score_exact_match, &[
+            ("Paris", "Paris", 1.0, Some(1.0), "Exact match"),
+            ("paris", "Paris", 0.95, Some(1.0), "Case-insensitive match"),
+            ("The answer is Paris.", "Paris", 0.6875, Some(0.9), "Expected output found within response (75% extra content)"),
+            ("the answer is paris", "Paris", 0.6421052631578947, Some(0.85), "Expected output found (case-insensitive, 74% extra content)"),
+            ("Pariss", "Paris", 0.8916666666666666, Some(0.9), "Expected output found within response (17% extra content)"),
+            ("kitten", "sitting", 0.39999999999999997, Some(0.45714285714285713), "Partial similarity: 57.1%"),
+            ("abc", "xyz", 0.0, Some(1.0), "No match"),
+            ("anything", "", 1.0, None, "No expected output specified"),
+        ]);
+        assert_eq!(score_exact_match("The answer is Paris.", "Paris").raw_score, Some(69.0));
+    }
+
+    #[test]
+    fn regex_match_reads_slash_delimited_flags() {
+        assert_pairs(score_regex_match, &[
+            ("Hello World", "/hello/i", 1.0, Some(1.0), "Pattern matched: \"Hello\""),
+            ("Hello World", "/hello/", 0.0, Some(1.0), "Pattern did not match"),
+            ("line one\nline two", "/^line two$/m", 1.0, Some(1.0), "Pattern matched: \"line two\""),
+            ("a\nb", "/a.b/s", 1.0, Some(1.0), "Pattern matched: \"a\nb\""),
+            ("Total: 42", r"\d+", 1.0, Some(1.0), "Pattern matched: \"42\""),
+            ("path/to/file", "/to/", 1.0, Some(1.0), "Pattern matched: \"to\""),
+            ("/a/b", "/a/b", 0.0, Some(0.0), "Invalid regex pattern: Invalid flags supplied to RegExp constructor 'b'"),
+            ("Hello", "/hello/ii", 0.0, Some(0.0), "Invalid regex pattern: Invalid flags supplied to RegExp constructor 'ii'"),
+        ]);
+
+        // The engines word their syntax errors differently
+        let invalid = score_regex_match("no", "/(/");
+        assert_eq!((invalid.score, invalid.confidence), (0.0, Some(0.0)));
+        assert!(invalid.notes.unwrap().starts_with("Invalid regex pattern: "));
+    }
+
+    #[test]
+    fn numeric_tolerance_parses_like_parse_float() {
+        assert_pairs(score_numeric_tolerance, &[
+            ("The answer is 42", "42", 1.0, Some(1.0), "Exact match: 42 ≈ 42"),
+            ("42.3", "42", 1.0, Some(1.0), "Exact match: 42.3 ≈ 42"),
+            ("about 40", "42", 0.5635642195280153, Some(0.9047619047619048), "Partial match: 40 (expected 42, diff: 4.8%)"),
+            ("about 35", "42", 0.18350341907227397, Some(0.6666666666666667), "Partial match: 35 (expected 42, diff: 16.7%)"),
+            ("12 and 41", "42", 0.6913933000758161, Some(0.9523809523809523), "Partial match: 41 (expected 42, diff: 2.4%)"),
+            ("3.14159", "3.14 radians", 1.0, Some(1.0), "Exact match: 3.14159 ≈ 3.14"),
+            ("1e3 units", "1000", 1.0, Some(1.0), "Exact match: 1000 ≈ 1000"),
+            ("-5 degrees", "-5", 1.0, Some(1.0), "Exact match: -5 ≈ -5"),
+            ("0.005", "0", 1.0, Some(1.0), "Exact match: 0.005 ≈ 0"),
+            ("no numbers", "42", 0.0, Some(1.0), "No numbers found in response"),
+            ("42", "abc", 0.0, Some(0.0), "Invalid expected number: \"abc\""),
+        ]);
+    }
+
+    #[test]
+    fn boolean_match_looks_for_the_expected_text() {
+        assert_pairs(score_boolean_match, &[
+            ("  YES, it is ", "yes", 1.0, None, "Expected output found in response"),
+            ("no", "yes", 0.0, None, "Expected output not found in response"),
+            ("whatever", "", 1.0, None, "No expected output - auto pass"),
+        ]);
+    }
+
+    #[test]
+    fn llm_judge_without_a_judge_falls_back_to_boolean() {
+        let mut judged = test_case("a");
+        judged.scoring_method = "llm-judge".to_string();
+
+        let scored = score_response(&judged, "I think the Answer for a is right", None, None);
+        assert_eq!(scored.score, 1.0);
+        assert_eq!(scored.notes.as_deref(), Some("Expected output found in response"));
+        assert_eq!(score_response(&judged, "No idea", None, None).score, 0.0);
+    }
+}
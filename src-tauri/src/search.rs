@@ -0,0 +1,213 @@
+//! Full-text search over test case prompts/expected outputs and result
+//! responses/judge notes, backed by the FTS5 indexes created in migration 6.
+//! bm25 scores depend on the statistics of the table they come from, so each
+//! index returns its own ranked group instead of one merged list.
+
+use rusqlite::{params, Connection};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "app")]
+use tauri::State;
+
+use crate::Db;
+
+const DEFAULT_LIMIT: i64 = 50;
+const MAX_LIMIT: i64 = 200;
+
+/// Markers wrapped around matched terms in `SearchHit::snippet`.
+const SNIPPET_START: &str = "«";
+const SNIPPET_END: &str = "»";
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SearchHitKind {
+    TestCase,
+    Result,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase", default)]
+pub struct SearchQuery {
+    pub text: String,
+    pub limit: Option<i64>,
+    /// Restrict to these hit kinds; all kinds when empty.
+    pub kinds: Vec<SearchHitKind>,
+    pub test_suite_id: Option<String>,
+    pub model_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHit {
+    pub kind: SearchHitKind,
+    /// bm25 rank within its group; lower is a better match.
+    pub rank: f64,
+    pub snippet: String,
+    pub test_suite_id: Option<String>,
+    pub test_suite_name: Option<String>,
+    pub test_case_id: String,
+    pub run_id: Option<String>,
+    pub run_started_at: Option<i64>,
+    pub model_id: Option<String>,
+}
+
+/// Turns free text into an FTS5 query: every term is quoted so punctuation
+/// can't produce syntax errors, and the last term matches as a prefix.
+fn to_match_expression(text: &str) -> Option<String> {
+    let terms: Vec<String> = text
+        .split_whitespace()
+        .map(|term| format!("\"{}\"", term.replace('"', "\"\"")))
+        .collect();
+
+    if terms.is_empty() {
+        return None;
+    }
+
+    Some(format!("{}*", terms.join(" ")))
+}
+
+fn wants(query: &SearchQuery, kind: SearchHitKind) -> bool {
+    query.kinds.is_empty() || query.kinds.contains(&kind)
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResults {
+    /// Best matches first, up to the query limit
+    pub test_cases: Vec<SearchHit>,
+    /// Best matches first, up to the query limit
+    pub results: Vec<SearchHit>,
+}
+
+pub fn search_internal(conn: &Connection, query: &SearchQuery) -> Result<SearchResults, String> {
+    let Some(expression) = to_match_expression(&query.text) else {
+        return Ok(SearchResults::default());
+    };
+    let limit = query.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT);
+
+    let mut found = SearchResults::default();
+
+    if wants(query, SearchHitKind::TestCase) {
+        let mut stmt = conn
+            .prepare(
+                "SELECT bm25(test_cases_fts), snippet(test_cases_fts, -1, ?, ?, '…', 16),
+                        s.id, s.name, tc.id
+                 FROM test_cases_fts
+                 JOIN test_cases tc ON tc.rowid = test_cases_fts.rowid
+                 JOIN test_suites s ON s.id = tc.test_suite_id
+                 WHERE test_cases_fts MATCH ?
+                   AND s.deleted_at IS NULL
+                   AND (? IS NULL OR tc.test_suite_id = ?)
+                 ORDER BY bm25(test_cases_fts)
+                 LIMIT ?",
+            )
+            .map_err(|err| err.to_string())?;
+
+        let rows = stmt
+            .query_map(
+                params![SNIPPET_START, SNIPPET_END, expression, query.test_suite_id, query.test_suite_id, limit],
+                |row| {
+                    Ok(SearchHit {
+                        kind: SearchHitKind::TestCase,
+                        rank: row.get(0)?,
+                        snippet: row.get(1)?,
+                        test_suite_id: row.get(2)?,
+                        test_suite_name: row.get(3)?,
+                        test_case_id: row.get(4)?,
+                        run_id: None,
+                        run_started_at: None,
+                        model_id: None,
+                    })
+                },
+            )
+            .map_err(|err| err.to_string())?;
+
+        for row in rows {
+            found.test_cases.push(row.map_err(|err| err.to_string())?);
+        }
+    }
+
+    if wants(query, SearchHitKind::Result) {
+        let mut stmt = conn
+            .prepare(
+                "SELECT bm25(results_fts), snippet(results_fts, -1, ?, ?, '…', 16),
+                        runs.test_suite_id, runs.test_suite_name, r.test_case_id,
+                        r.run_id, runs.started_at, r.model_id
+                 FROM results_fts
+                 JOIN test_case_results r ON r.id = results_fts.rowid
+                 JOIN runs ON runs.id = r.run_id
+                 WHERE results_fts MATCH ?
+                   AND runs.deleted_at IS NULL
+                   AND (? IS NULL OR runs.test_suite_id = ?)
+                   AND (? IS NULL OR r.model_id = ?)
+                 ORDER BY bm25(results_fts)
+                 LIMIT ?",
+            )
+            .map_err(|err| err.to_string())?;
+
+        let rows = stmt
+            .query_map(
+                params![
+                    SNIPPET_START,
+                    SNIPPET_END,
+                    expression,
+                    query.test_suite_id,
+                    query.test_suite_id,
+                    query.model_id,
+                    query.model_id,
+                    limit,
+                ],
+                |row| {
+                    Ok(SearchHit {
+                        kind: SearchHitKind::Result,
+                        rank: row.get(0)?,
+                        snippet: row.get(1)?,
+                        test_suite_id: row.get(2)?,
+                        test_suite_name: row.get(3)?,
+                        test_case_id: row.get(4)?,
+                        run_id: row.get(5)?,
+                        run_started_at: row.get(6)?,
+                        model_id: row.get(7)?,
+                    })
+                },
+            )
+            .map_err(|err| err.to_string())?;
+
+        for row in rows {
+            found.results.push(row.map_err(|err| err.to_string())?);
+        }
+    }
+
+    Ok(found)
+}
+
+#[cfg(feature = "app")]
+#[tauri::command]
+pub fn search(db: State<'_, Db>, query: SearchQuery) -> Result<SearchResults, String> {
+    let conn = db.conn()?;
+    search_internal(&conn, &query)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{memory_db, run, suite};
+    use crate::{write_run_with_results, write_test_suite};
+
+    #[test]
+    fn each_index_returns_its_own_ranked_group() {
+        let conn = memory_db();
+        let suite = suite("s1", &["a", "b"]);
+        write_test_suite(&conn, &suite).unwrap();
+        write_run_with_results(&conn, &run("r1", &suite, "completed")).unwrap();
+
+        let query = SearchQuery { text: "answer".to_string(), limit: Some(1), ..SearchQuery::default() };
+        let found = search_internal(&conn, &query).unwrap();
+        assert_eq!(found.test_cases.len(), 1);
+        assert_eq!(found.results.len(), 1);
+        assert!(found.test_cases.iter().all(|hit| hit.kind == SearchHitKind::TestCase));
+        assert!(found.results.iter().all(|hit| hit.kind == SearchHitKind::Result));
+
+        let query = SearchQuery { kinds: vec![SearchHitKind::Result], ..query };
+        assert!(search_internal(&conn, &query).unwrap().test_cases.is_empty());
+    }
+}
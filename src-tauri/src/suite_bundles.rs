@@ -0,0 +1,354 @@
+//! Portable test suite bundles. A bundle is a single JSON or YAML file that
+//! carries one suite with its cases and prompts, plus enough provenance
+//! (format and schema versions, content hash) to validate it on import.
+
+use std::collections::HashSet;
+use std::fs;
+use std::path::Path;
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "app")]
+use tauri::State;
+
+use crate::migrations::CURRENT_SCHEMA_VERSION;
+use crate::suite_revisions::{content_hash, diff_revisions, get_suite_revision_internal, latest_revision, SuiteRevisionDiff};
+use crate::trash::{restore_item, TrashKind};
+use crate::{chrono_now, generate_id, get_test_suite_internal, write_test_suite, Db, TestSuite};
+
+const BUNDLE_FORMAT: &str = "benchmaker.test-suite";
+const BUNDLE_FORMAT_VERSION: i64 = 1;
+
+pub const SCORING_METHODS: [&str; 5] = ["exact-match", "regex-match", "numeric-tolerance", "boolean", "llm-judge"];
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum BundleFormat {
+    Json,
+    Yaml,
+}
+
+impl BundleFormat {
+    /// Format to export to: YAML for `.yaml`/`.yml` files, JSON for
+    /// everything else.
+    pub fn from_path(path: &Path) -> BundleFormat {
+        match path.extension().and_then(|ext| ext.to_str()).map(str::to_ascii_lowercase).as_deref() {
+            Some("yaml") | Some("yml") => BundleFormat::Yaml,
+            _ => BundleFormat::Json,
+        }
+    }
+
+    /// Format of a bundle as read, whatever its file is called: JSON when it
+    /// starts with `{`, YAML otherwise.
+    pub fn sniff(contents: &str) -> BundleFormat {
+        if contents.trim_start_matches('\u{feff}').trim_start().starts_with('{') {
+            BundleFormat::Json
+        } else {
+            BundleFormat::Yaml
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SuiteBundle {
+    pub format: String,
+    pub format_version: i64,
+    /// Database schema of the app that wrote the bundle
+    pub schema_version: i64,
+    pub app_version: String,
+    pub exported_at: i64,
+    /// Suite revision the bundle was taken from, if known
+    pub revision: Option<i64>,
+    /// Same hash as `test_suite_revisions.content_hash`
+    pub content_hash: String,
+    pub suite: TestSuite,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum CollisionStrategy {
+    /// Leave the existing suite untouched and skip the import
+    Keep,
+    /// Import as a new suite with fresh ids
+    Rename,
+    /// Replace the existing suite's prompts and cases
+    Overwrite,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ImportOutcome {
+    Created,
+    Kept,
+    Renamed,
+    Overwritten,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct IdChange {
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SuiteImportReport {
+    pub outcome: ImportOutcome,
+    pub test_suite_id: String,
+    pub test_suite_name: String,
+    /// Suite id as written in the bundle
+    pub source_id: String,
+    /// Revision the suite is at after the import
+    pub revision: Option<i64>,
+    /// False when the bundle was edited after export
+    pub hash_verified: bool,
+    /// What changed relative to the suite that was already there
+    pub changes: Option<SuiteRevisionDiff>,
+    pub renamed_test_cases: Vec<IdChange>,
+    pub warnings: Vec<String>,
+}
+
+pub fn build_bundle(conn: &Connection, id: &str) -> Result<SuiteBundle, String> {
+    let suite = get_test_suite_internal(conn, id)?
+        .ok_or_else(|| format!("Test suite {} not found.", id))?;
+
+    Ok(SuiteBundle {
+        format: BUNDLE_FORMAT.to_string(),
+        format_version: BUNDLE_FORMAT_VERSION,
+        schema_version: CURRENT_SCHEMA_VERSION,
+        app_version: env!("CARGO_PKG_VERSION").to_string(),
+        exported_at: chrono_now(),
+        revision: latest_revision(conn, id)?,
+        content_hash: content_hash(&suite.system_prompt, suite.judge_system_prompt.as_deref(), &suite.test_cases),
+        suite,
+    })
+}
+
+pub fn write_bundle(bundle: &SuiteBundle, path: &Path, format: BundleFormat) -> Result<(), String> {
+    let contents = match format {
+        BundleFormat::Json => serde_json::to_string_pretty(bundle).map_err(|err| err.to_string())?,
+        BundleFormat::Yaml => serde_yaml::to_string(bundle).map_err(|err| err.to_string())?,
+    };
+    fs::write(path, contents).map_err(|err| format!("Failed to write {}: {}", path.display(), err))
+}
+
+pub fn read_bundle(path: &Path) -> Result<SuiteBundle, String> {
+    let contents = fs::read_to_string(path).map_err(|err| format!("Failed to read {}: {}", path.display(), err))?;
+    let bundle: SuiteBundle = match BundleFormat::sniff(&contents) {
+        BundleFormat::Json => serde_json::from_str(&contents).map_err(|err| format!("Invalid suite bundle: {}", err))?,
+        BundleFormat::Yaml => serde_yaml::from_str(&contents).map_err(|err| format!("Invalid suite bundle: {}", err))?,
+    };
+
+    if bundle.format != BUNDLE_FORMAT {
+        return Err(format!("Not a Benchmaker suite bundle (format '{}').", bundle.format));
+    }
+    if bundle.format_version > BUNDLE_FORMAT_VERSION {
+        return Err(format!(
+            "Bundle format version {} is newer than this build supports ({}). Please update Benchmaker.",
+            bundle.format_version, BUNDLE_FORMAT_VERSION
+        ));
+    }
+
+    Ok(bundle)
+}
+
+/// Structural checks the database constraints wouldn't catch. Returns every
+/// problem at once so a hand-edited bundle can be fixed in one pass.
+fn validate_suite(suite: &TestSuite) -> Result<(), String> {
+    let mut problems = Vec::new();
+
+    if suite.id.trim().is_empty() {
+        problems.push("suite id is empty".to_string());
+    }
+    if suite.name.trim().is_empty() {
+        problems.push("suite name is empty".to_string());
+    }
+
+    let mut seen = HashSet::new();
+    for (index, test_case) in suite.test_cases.iter().enumerate() {
+        let label = format!("test case {} ({})", index + 1, test_case.id);
+        if test_case.id.trim().is_empty() {
+            problems.push(format!("test case {} has no id", index + 1));
+        } else if !seen.insert(test_case.id.as_str()) {
+            problems.push(format!("{} is duplicated", label));
+        }
+        if test_case.prompt.trim().is_empty() {
+            problems.push(format!("{} has an empty prompt", label));
+        }
+        if !SCORING_METHODS.contains(&test_case.scoring_method.as_str()) {
+            problems.push(format!("{} has unknown scoring method '{}'", label, test_case.scoring_method));
+        }
+        if !test_case.weight.is_finite() || test_case.weight < 0.0 {
+            problems.push(format!("{} has invalid weight {}", label, test_case.weight));
+        }
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("Invalid suite bundle: {}.", problems.join("; ")))
+    }
+}
+
+pub fn import_bundle(conn: &Connection, bundle: SuiteBundle, on_conflict: CollisionStrategy) -> Result<SuiteImportReport, String> {
+    validate_suite(&bundle.suite)?;
+
+    let mut suite = bundle.suite;
+    let source_id = suite.id.clone();
+    let mut warnings = Vec::new();
+
+    let hash_verified = content_hash(&suite.system_prompt, suite.judge_system_prompt.as_deref(), &suite.test_cases) == bundle.content_hash;
+    if !hash_verified {
+        warnings.push("Content hash does not match; the bundle was modified after export.".to_string());
+    }
+
+    let exists: bool = conn
+        .query_row("SELECT 1 FROM test_suites WHERE id = ?", params![suite.id], |_| Ok(()))
+        .optional()
+        .map_err(|err| err.to_string())?
+        .is_some();
+
+    let outcome = match (exists, on_conflict) {
+        (false, _) => ImportOutcome::Created,
+        (true, CollisionStrategy::Keep) => {
+            return Ok(SuiteImportReport {
+                outcome: ImportOutcome::Kept,
+                test_suite_id: suite.id.clone(),
+                test_suite_name: suite.name,
+                source_id,
+                revision: latest_revision(conn, &suite.id)?,
+                hash_verified,
+                changes: None,
+                renamed_test_cases: Vec::new(),
+                warnings,
+            });
+        }
+        (true, CollisionStrategy::Rename) => ImportOutcome::Renamed,
+        (true, CollisionStrategy::Overwrite) => ImportOutcome::Overwritten,
+    };
+
+    // Case ids are unique across all suites, so a renamed suite gets fresh
+    // ones and any other case id already owned by a different suite is remapped.
+    let mut renamed_test_cases = Vec::new();
+    if outcome == ImportOutcome::Renamed {
+        suite.id = generate_id();
+    }
+    for test_case in &mut suite.test_cases {
+        let owner: Option<String> = conn
+            .query_row("SELECT test_suite_id FROM test_cases WHERE id = ?", params![test_case.id], |row| row.get(0))
+            .optional()
+            .map_err(|err| err.to_string())?;
+
+        let collides = match owner {
+            Some(owner) => owner != suite.id,
+            None => false,
+        };
+        if outcome == ImportOutcome::Renamed || collides {
+            let new_id = generate_id();
+            renamed_test_cases.push(IdChange {
+                from: std::mem::replace(&mut test_case.id, new_id.clone()),
+                to: new_id,
+            });
+        }
+    }
+    if outcome != ImportOutcome::Renamed && !renamed_test_cases.is_empty() {
+        warnings.push(format!(
+            "{} test case id(s) were already used by another suite and were reassigned.",
+            renamed_test_cases.len()
+        ));
+    }
+
+    let previous_revision = match outcome {
+        ImportOutcome::Overwritten => latest_revision(conn, &suite.id)?,
+        _ => None,
+    };
+
+    // Overwriting is an explicit choice, so it also takes the suite out of
+    // the trash; saves alone never do
+    if outcome == ImportOutcome::Overwritten {
+        restore_item(conn, TrashKind::TestSuite, &suite.id)?;
+    }
+
+    suite.updated_at = chrono_now();
+    write_test_suite(conn, &suite)?;
+    let revision = latest_revision(conn, &suite.id)?;
+
+    let changes = match (previous_revision, revision) {
+        (Some(from), Some(to)) => {
+            let before = get_suite_revision_internal(conn, &suite.id, from)?;
+            let after = get_suite_revision_internal(conn, &suite.id, to)?;
+            before.zip(after).map(|(before, after)| diff_revisions(&before, &after))
+        }
+        _ => None,
+    };
+
+    Ok(SuiteImportReport {
+        outcome,
+        test_suite_id: suite.id,
+        test_suite_name: suite.name,
+        source_id,
+        revision,
+        hash_verified,
+        changes,
+        renamed_test_cases,
+        warnings,
+    })
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Writes suite `id` to `path`. The format defaults to the file extension.
+#[cfg(feature = "app")]
+#[tauri::command]
+pub fn export_test_suite(db: State<'_, Db>, id: String, path: String, format: Option<BundleFormat>) -> Result<(), String> {
+    let path = Path::new(&path);
+    let bundle = {
+        let conn = db.conn()?;
+        build_bundle(&conn, &id)?
+    };
+    write_bundle(&bundle, path, format.unwrap_or_else(|| BundleFormat::from_path(path)))
+}
+
+#[cfg(feature = "app")]
+#[tauri::command]
+pub fn import_test_suite(db: State<'_, Db>, path: String, on_conflict: Option<CollisionStrategy>) -> Result<SuiteImportReport, String> {
+    let bundle = read_bundle(Path::new(&path))?;
+
+    let mut conn = db.conn()?;
+    let tx = conn.transaction().map_err(|err| err.to_string())?;
+    let report = import_bundle(&tx, bundle, on_conflict.unwrap_or(CollisionStrategy::Rename))?;
+    tx.commit().map_err(|err| err.to_string())?;
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{memory_db, suite};
+
+    #[test]
+    fn bundles_are_read_by_content_not_extension() {
+        let conn = memory_db();
+        write_test_suite(&conn, &suite("s1", &["a"])).unwrap();
+        let bundle = build_bundle(&conn, "s1").unwrap();
+        let dir = tempfile::tempdir().unwrap();
+
+        let json_named_yaml = dir.path().join("suite.yaml");
+        write_bundle(&bundle, &json_named_yaml, BundleFormat::Json).unwrap();
+        let yaml_named_json = dir.path().join("suite.json");
+        write_bundle(&bundle, &yaml_named_json, BundleFormat::Yaml).unwrap();
+        let yaml_named_txt = dir.path().join("suite.txt");
+        write_bundle(&bundle, &yaml_named_txt, BundleFormat::Yaml).unwrap();
+
+        for path in [json_named_yaml, yaml_named_json, yaml_named_txt] {
+            let read = read_bundle(&path).unwrap();
+            assert_eq!(read.content_hash, bundle.content_hash);
+            assert_eq!(read.suite.test_cases.len(), 1);
+        }
+    }
+}
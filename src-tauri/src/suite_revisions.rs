@@ -0,0 +1,433 @@
+//! Immutable test suite revisions. Every save that changes a suite's prompts
+//! or cases appends a new revision, and runs record the revision they were
+//! scored against so old results stay interpretable after the suite is edited.
+
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+#[cfg(feature = "app")]
+use tauri::State;
+
+use crate::{chrono_now, Db, TestCase, TestSuite};
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SuiteRevisionSummary {
+    pub test_suite_id: String,
+    pub revision: i64,
+    pub content_hash: String,
+    pub name: String,
+    pub test_case_count: i64,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SuiteRevision {
+    pub test_suite_id: String,
+    pub revision: i64,
+    pub content_hash: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub system_prompt: String,
+    pub judge_system_prompt: Option<String>,
+    pub test_cases: Vec<TestCase>,
+    pub created_at: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldChange {
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TestCaseChange {
+    pub id: String,
+    pub changed_fields: Vec<String>,
+    pub before: TestCase,
+    pub after: TestCase,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SuiteRevisionDiff {
+    pub test_suite_id: String,
+    pub from_revision: i64,
+    pub to_revision: i64,
+    pub system_prompt: Option<FieldChange>,
+    pub judge_system_prompt: Option<FieldChange>,
+    pub added: Vec<TestCase>,
+    pub removed: Vec<TestCase>,
+    pub changed: Vec<TestCaseChange>,
+    /// True when the shared cases appear in a different order.
+    pub reordered: bool,
+}
+
+/// The parts of a suite that affect how a run is scored. Name and description
+/// are deliberately left out so renaming a suite doesn't create a revision.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RevisionContent<'a> {
+    system_prompt: &'a str,
+    judge_system_prompt: Option<&'a str>,
+    test_cases: &'a [TestCase],
+}
+
+pub fn content_hash(system_prompt: &str, judge_system_prompt: Option<&str>, test_cases: &[TestCase]) -> String {
+    let content = RevisionContent {
+        system_prompt,
+        judge_system_prompt,
+        test_cases,
+    };
+    let json = serde_json::to_vec(&content).unwrap_or_default();
+    hex::encode(Sha256::digest(&json))
+}
+
+pub fn latest_revision(conn: &Connection, test_suite_id: &str) -> Result<Option<i64>, String> {
+    conn.query_row(
+        "SELECT MAX(revision) FROM test_suite_revisions WHERE test_suite_id = ?",
+        params![test_suite_id],
+        |row| row.get(0),
+    )
+    .map_err(|err| err.to_string())
+}
+
+/// Appends a revision for `suite` unless its content matches the latest one.
+/// Returns the revision number that now reflects the suite.
+pub fn record_suite_revision(conn: &Connection, suite: &TestSuite) -> Result<i64, String> {
+    let hash = content_hash(&suite.system_prompt, suite.judge_system_prompt.as_deref(), &suite.test_cases);
+
+    let latest: Option<(i64, String)> = conn
+        .prepare_cached(
+            "SELECT revision, content_hash FROM test_suite_revisions
+             WHERE test_suite_id = ? ORDER BY revision DESC LIMIT 1",
+        )
+        .and_then(|mut stmt| {
+            stmt.query_row(params![suite.id], |row| Ok((row.get(0)?, row.get(1)?)))
+                .optional()
+        })
+        .map_err(|err| err.to_string())?;
+
+    if let Some((revision, latest_hash)) = &latest {
+        if *latest_hash == hash {
+            return Ok(*revision);
+        }
+    }
+
+    let revision = latest.map(|(revision, _)| revision + 1).unwrap_or(1);
+    let cases_json = serde_json::to_string(&suite.test_cases)
+        .unwrap_or_else(|_| "[]".to_string());
+
+    conn.prepare_cached(
+        "INSERT INTO test_suite_revisions (test_suite_id, revision, content_hash, name, description, system_prompt, judge_system_prompt, test_cases, created_at)
+         VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+    )
+    .and_then(|mut stmt| stmt.execute(params![
+        suite.id,
+        revision,
+        hash,
+        suite.name,
+        suite.description,
+        suite.system_prompt,
+        suite.judge_system_prompt,
+        cases_json,
+        chrono_now(),
+    ]))
+    .map_err(|err| err.to_string())?;
+
+    Ok(revision)
+}
+
+pub fn get_suite_revision_internal(conn: &Connection, test_suite_id: &str, revision: i64) -> Result<Option<SuiteRevision>, String> {
+    let row = conn
+        .query_row(
+            "SELECT content_hash, name, description, system_prompt, judge_system_prompt, test_cases, created_at
+             FROM test_suite_revisions WHERE test_suite_id = ? AND revision = ?",
+            params![test_suite_id, revision],
+            |row| {
+                Ok((
+                    row.get::<_, String>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, Option<String>>(2)?,
+                    row.get::<_, String>(3)?,
+                    row.get::<_, Option<String>>(4)?,
+                    row.get::<_, String>(5)?,
+                    row.get::<_, i64>(6)?,
+                ))
+            },
+        )
+        .optional()
+        .map_err(|err| err.to_string())?;
+
+    let Some((content_hash, name, description, system_prompt, judge_system_prompt, cases_json, created_at)) = row else {
+        return Ok(None);
+    };
+
+    let test_cases: Vec<TestCase> = serde_json::from_str(&cases_json)
+        .map_err(|err| format!("Revision {} of suite {} has unreadable test cases: {}", revision, test_suite_id, err))?;
+
+    Ok(Some(SuiteRevision {
+        test_suite_id: test_suite_id.to_string(),
+        revision,
+        content_hash,
+        name,
+        description,
+        system_prompt,
+        judge_system_prompt,
+        test_cases,
+        created_at,
+    }))
+}
+
+fn field_change(before: Option<&str>, after: Option<&str>) -> Option<FieldChange> {
+    if before == after {
+        return None;
+    }
+    Some(FieldChange {
+        before: before.map(str::to_string),
+        after: after.map(str::to_string),
+    })
+}
+
+fn changed_fields(before: &TestCase, after: &TestCase) -> Vec<String> {
+    let mut fields = Vec::new();
+    if before.prompt != after.prompt {
+        fields.push("prompt".to_string());
+    }
+    if before.expected_output != after.expected_output {
+        fields.push("expectedOutput".to_string());
+    }
+    if before.scoring_method != after.scoring_method {
+        fields.push("scoringMethod".to_string());
+    }
+    if before.weight != after.weight {
+        fields.push("weight".to_string());
+    }
+    if before.metadata.category != after.metadata.category {
+        fields.push("category".to_string());
+    }
+    if before.metadata.difficulty != after.metadata.difficulty {
+        fields.push("difficulty".to_string());
+    }
+    if before.metadata.tags != after.metadata.tags {
+        fields.push("tags".to_string());
+    }
+    fields
+}
+
+pub fn diff_revisions(from: &SuiteRevision, to: &SuiteRevision) -> SuiteRevisionDiff {
+    let before_by_id: HashMap<&str, &TestCase> = from.test_cases.iter().map(|tc| (tc.id.as_str(), tc)).collect();
+    let after_by_id: HashMap<&str, &TestCase> = to.test_cases.iter().map(|tc| (tc.id.as_str(), tc)).collect();
+
+    let added = to.test_cases
+        .iter()
+        .filter(|tc| !before_by_id.contains_key(tc.id.as_str()))
+        .cloned()
+        .collect();
+    let removed = from.test_cases
+        .iter()
+        .filter(|tc| !after_by_id.contains_key(tc.id.as_str()))
+        .cloned()
+        .collect();
+
+    let mut changed = Vec::new();
+    for after in &to.test_cases {
+        if let Some(before) = before_by_id.get(after.id.as_str()) {
+            let fields = changed_fields(before, after);
+            if !fields.is_empty() {
+                changed.push(TestCaseChange {
+                    id: after.id.clone(),
+                    changed_fields: fields,
+                    before: (*before).clone(),
+                    after: after.clone(),
+                });
+            }
+        }
+    }
+
+    let shared_before: Vec<&str> = from.test_cases
+        .iter()
+        .map(|tc| tc.id.as_str())
+        .filter(|id| after_by_id.contains_key(id))
+        .collect();
+    let shared_after: Vec<&str> = to.test_cases
+        .iter()
+        .map(|tc| tc.id.as_str())
+        .filter(|id| before_by_id.contains_key(id))
+        .collect();
+
+    SuiteRevisionDiff {
+        test_suite_id: to.test_suite_id.clone(),
+        from_revision: from.revision,
+        to_revision: to.revision,
+        system_prompt: field_change(Some(&from.system_prompt), Some(&to.system_prompt)),
+        judge_system_prompt: field_change(from.judge_system_prompt.as_deref(), to.judge_system_prompt.as_deref()),
+        added,
+        removed,
+        changed,
+        reordered: shared_before != shared_after,
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[cfg(feature = "app")]
+#[tauri::command]
+pub fn list_suite_revisions(db: State<'_, Db>, test_suite_id: String) -> Result<Vec<SuiteRevisionSummary>, String> {
+    let conn = db.conn()?;
+
+    let mut stmt = conn
+        .prepare(
+            "SELECT revision, content_hash, name, json_array_length(test_cases), created_at
+             FROM test_suite_revisions WHERE test_suite_id = ? ORDER BY revision DESC",
+        )
+        .map_err(|err| err.to_string())?;
+
+    let rows = stmt
+        .query_map(params![test_suite_id], |row| {
+            Ok(SuiteRevisionSummary {
+                test_suite_id: test_suite_id.clone(),
+                revision: row.get(0)?,
+                content_hash: row.get(1)?,
+                name: row.get(2)?,
+                test_case_count: row.get(3)?,
+                created_at: row.get(4)?,
+            })
+        })
+        .map_err(|err| err.to_string())?;
+
+    let mut revisions = Vec::new();
+    for row in rows {
+        revisions.push(row.map_err(|err| err.to_string())?);
+    }
+
+    Ok(revisions)
+}
+
+#[cfg(feature = "app")]
+#[tauri::command]
+pub fn get_suite_revision(db: State<'_, Db>, test_suite_id: String, revision: i64) -> Result<Option<SuiteRevision>, String> {
+    let conn = db.conn()?;
+    get_suite_revision_internal(&conn, &test_suite_id, revision)
+}
+
+#[cfg(feature = "app")]
+#[tauri::command]
+pub fn diff_suite_revisions(
+    db: State<'_, Db>,
+    test_suite_id: String,
+    from_revision: i64,
+    to_revision: i64,
+) -> Result<SuiteRevisionDiff, String> {
+    let conn = db.conn()?;
+
+    let from = get_suite_revision_internal(&conn, &test_suite_id, from_revision)?
+        .ok_or_else(|| format!("Revision {} of suite {} not found.", from_revision, test_suite_id))?;
+    let to = get_suite_revision_internal(&conn, &test_suite_id, to_revision)?
+        .ok_or_else(|| format!("Revision {} of suite {} not found.", to_revision, test_suite_id))?;
+
+    Ok(diff_revisions(&from, &to))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{memory_db, run, suite, test_case};
+    use crate::{get_run_internal, write_run, write_test_suite};
+
+    fn revision(conn: &Connection, suite: &TestSuite) -> SuiteRevision {
+        let revision = record_suite_revision(conn, suite).unwrap();
+        get_suite_revision_internal(conn, &suite.id, revision).unwrap().unwrap()
+    }
+
+    fn pinned(conn: &Connection, run_id: &str) -> Option<i64> {
+        get_run_internal(conn, run_id).unwrap().unwrap().test_suite_revision
+    }
+
+    #[test]
+    fn unchanged_content_keeps_the_revision() {
+        let conn = memory_db();
+        let mut suite = suite("s1", &["a"]);
+        write_test_suite(&conn, &suite).unwrap();
+        assert_eq!(record_suite_revision(&conn, &suite).unwrap(), 1);
+
+        // Name and description don't affect scoring
+        suite.name = "Renamed".to_string();
+        suite.description = Some("New description".to_string());
+        write_test_suite(&conn, &suite).unwrap();
+        assert_eq!(latest_revision(&conn, "s1").unwrap(), Some(1));
+
+        suite.test_cases[0].prompt = "Edited".to_string();
+        write_test_suite(&conn, &suite).unwrap();
+        assert_eq!(record_suite_revision(&conn, &suite).unwrap(), 2);
+        assert_eq!(latest_revision(&conn, "s1").unwrap(), Some(2));
+        assert_eq!(get_suite_revision_internal(&conn, "s1", 1).unwrap().unwrap().test_cases[0].prompt, "Prompt for a");
+    }
+
+    #[test]
+    fn runs_keep_the_revision_they_started_on() {
+        let conn = memory_db();
+        let mut suite = suite("s1", &["a"]);
+        write_test_suite(&conn, &suite).unwrap();
+        let mut first = run("r1", &suite, "running");
+        write_run(&conn, &first).unwrap();
+        assert_eq!(pinned(&conn, "r1"), Some(1));
+
+        suite.system_prompt = "Answer in detail.".to_string();
+        write_test_suite(&conn, &suite).unwrap();
+        first.status = "completed".to_string();
+        write_run(&conn, &first).unwrap();
+        assert_eq!(pinned(&conn, "r1"), Some(1));
+
+        write_run(&conn, &run("r2", &suite, "running")).unwrap();
+        assert_eq!(pinned(&conn, "r2"), Some(2));
+
+        let mut imported = run("r3", &suite, "completed");
+        imported.test_suite_revision = Some(1);
+        write_run(&conn, &imported).unwrap();
+        assert_eq!(pinned(&conn, "r3"), Some(1));
+    }
+
+    #[test]
+    fn diff_lists_case_and_prompt_changes() {
+        let conn = memory_db();
+        let mut suite = suite("s1", &["a", "b", "c"]);
+        let from = revision(&conn, &suite);
+
+        suite.system_prompt = "Answer in detail.".to_string();
+        suite.judge_system_prompt = Some("Be strict.".to_string());
+        suite.test_cases.remove(0);
+        suite.test_cases[0].prompt = "Edited".to_string();
+        suite.test_cases[0].weight = 2.0;
+        suite.test_cases.swap(0, 1);
+        suite.test_cases.push(test_case("d"));
+        let to = revision(&conn, &suite);
+
+        let diff = diff_revisions(&from, &to);
+        assert_eq!((diff.from_revision, diff.to_revision), (1, 2));
+        let system_prompt = diff.system_prompt.unwrap();
+        assert_eq!(system_prompt.before.as_deref(), Some("Answer briefly."));
+        assert_eq!(system_prompt.after.as_deref(), Some("Answer in detail."));
+        let judge_system_prompt = diff.judge_system_prompt.unwrap();
+        assert_eq!((judge_system_prompt.before, judge_system_prompt.after.as_deref()), (None, Some("Be strict.")));
+
+        let ids = |cases: &[TestCase]| cases.iter().map(|tc| tc.id.clone()).collect::<Vec<_>>();
+        assert_eq!(ids(&diff.added), vec!["d"]);
+        assert_eq!(ids(&diff.removed), vec!["a"]);
+        assert_eq!(diff.changed.len(), 1);
+        assert_eq!(diff.changed[0].id, "b");
+        assert_eq!(diff.changed[0].changed_fields, vec!["prompt", "weight"]);
+        assert!(diff.reordered);
+
+        let unchanged = diff_revisions(&to, &to);
+        assert!(unchanged.system_prompt.is_none() && unchanged.judge_system_prompt.is_none());
+        assert!(unchanged.added.is_empty() && unchanged.removed.is_empty() && unchanged.changed.is_empty());
+        assert!(!unchanged.reordered);
+    }
+}
@@ -0,0 +1,84 @@
+//! Fixtures shared by the unit tests.
+
+use std::path::Path;
+
+use rusqlite::Connection;
+
+use crate::{
+    open_connection, ModelParameters, RunResult, TestCase, TestCaseMetadata, TestCaseResult, TestSuite,
+};
+
+/// In-memory database at the current schema.
+pub fn memory_db() -> Connection {
+    open_connection(Path::new(":memory:")).unwrap()
+}
+
+pub fn test_case(id: &str) -> TestCase {
+    TestCase {
+        id: id.to_string(),
+        prompt: format!("Prompt for {}", id),
+        expected_output: Some(format!("Answer for {}", id)),
+        scoring_method: "exact-match".to_string(),
+        weight: 1.0,
+        metadata: TestCaseMetadata {
+            category: Some("general".to_string()),
+            difficulty: None,
+            tags: Vec::new(),
+        },
+    }
+}
+
+pub fn suite(id: &str, case_ids: &[&str]) -> TestSuite {
+    TestSuite {
+        id: id.to_string(),
+        name: format!("Suite {}", id),
+        description: None,
+        system_prompt: "Answer briefly.".to_string(),
+        judge_system_prompt: None,
+        test_cases: case_ids.iter().map(|case_id| test_case(case_id)).collect(),
+        created_at: 1,
+        updated_at: 1,
+    }
+}
+
+pub fn result(test_case_id: &str, model_id: &str, status: &str) -> TestCaseResult {
+    TestCaseResult {
+        test_case_id: test_case_id.to_string(),
+        model_id: model_id.to_string(),
+        provider: None,
+        attempt: 0,
+        response: format!("Answer for {}", test_case_id),
+        token_count: None,
+        prompt_tokens: None,
+        completion_tokens: None,
+        cost: None,
+        latency_ms: None,
+        status: status.to_string(),
+        error: None,
+        score: None,
+        streamed_content: None,
+        cached: false,
+    }
+}
+
+pub fn run(id: &str, suite: &TestSuite, status: &str) -> RunResult {
+    RunResult {
+        id: id.to_string(),
+        test_suite_id: suite.id.clone(),
+        test_suite_name: suite.name.clone(),
+        test_suite_revision: None,
+        models: vec!["model-a".to_string()],
+        parameters: ModelParameters::default(),
+        results: suite
+            .test_cases
+            .iter()
+            .map(|test_case| result(&test_case.id, "model-a", status))
+            .collect(),
+        status: status.to_string(),
+        started_at: 10,
+        completed_at: None,
+        judge_model: None,
+        error_count: None,
+        error_summary: None,
+    }
+}
@@ -0,0 +1,410 @@
+//! Soft delete for suites and runs. Deleting sets `deleted_at`; rows stay in
+//! the trash until restored, purged by hand, or aged out by the retention
+//! setting on startup. Saves of a trashed item are ignored; only `restore`
+//! brings it back.
+
+use rusqlite::types::Value;
+use rusqlite::{params, params_from_iter, Connection};
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "app")]
+use tauri::State;
+
+use crate::{chrono_now, get_setting, set_setting, Db};
+
+const RETENTION_SETTING: &str = "trash_retention_days";
+const DEFAULT_RETENTION_DAYS: i64 = 30;
+const DAY_MS: i64 = 24 * 60 * 60 * 1000;
+
+/// More than this many items of one kind in a single write is always a mass
+/// delete.
+const MASS_DELETE_MAX_COUNT: i64 = 25;
+/// From this many items on, deleting more than half of the live ones is a
+/// mass delete too.
+const MASS_DELETE_RATIO_MIN_COUNT: i64 = 3;
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum TrashKind {
+    TestSuite,
+    Run,
+    CodeArenaRun,
+}
+
+impl TrashKind {
+    const ALL: [TrashKind; 3] = [TrashKind::TestSuite, TrashKind::Run, TrashKind::CodeArenaRun];
+
+    pub fn table(self) -> &'static str {
+        match self {
+            TrashKind::TestSuite => "test_suites",
+            TrashKind::Run => "runs",
+            TrashKind::CodeArenaRun => "code_arena_runs",
+        }
+    }
+
+    fn label_column(self) -> &'static str {
+        match self {
+            TrashKind::TestSuite => "name",
+            TrashKind::Run => "test_suite_name",
+            TrashKind::CodeArenaRun => "substr(prompt, 1, 120)",
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashItem {
+    pub kind: TrashKind,
+    pub id: String,
+    pub label: String,
+    pub deleted_at: i64,
+    /// When automatic purge will remove the item; `None` if retention is off.
+    pub purge_at: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashListing {
+    pub items: Vec<TrashItem>,
+    pub retention_days: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashRef {
+    pub kind: TrashKind,
+    pub id: String,
+}
+
+pub fn retention_days(conn: &Connection) -> Result<i64, String> {
+    Ok(get_setting(conn, RETENTION_SETTING)?
+        .and_then(|value| value.parse().ok())
+        .unwrap_or(DEFAULT_RETENTION_DAYS))
+}
+
+pub fn move_to_trash(conn: &Connection, kind: TrashKind, id: &str) -> Result<(), String> {
+    conn.execute(
+        &format!("UPDATE {} SET deleted_at = ? WHERE id = ? AND deleted_at IS NULL", kind.table()),
+        params![chrono_now(), id],
+    ).map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+/// Whether deleting `count` of `live` items at once needs `allowMassDelete`.
+pub fn is_mass_delete(count: i64, live: i64) -> bool {
+    count > MASS_DELETE_MAX_COUNT || (count >= MASS_DELETE_RATIO_MIN_COUNT && count * 2 > live)
+}
+
+/// Trashes the items a snapshot write lists as deleted. Only ids the frontend
+/// names explicitly are touched, so runs it never loaded stay live. Refuses a
+/// mass delete of any one kind unless `allow_mass_delete` is set.
+pub fn trash_deleted(conn: &Connection, items: &[TrashRef], allow_mass_delete: bool) -> Result<(), String> {
+    for kind in TrashKind::ALL {
+        let ids: Vec<Value> = items
+            .iter()
+            .filter(|item| item.kind == kind)
+            .map(|item| Value::Text(item.id.clone()))
+            .collect();
+        if ids.is_empty() {
+            continue;
+        }
+
+        let table = kind.table();
+        let placeholders = ids.iter().map(|_| "?").collect::<Vec<_>>().join(",");
+        let live: i64 = conn
+            .query_row(&format!("SELECT COUNT(*) FROM {} WHERE deleted_at IS NULL", table), [], |row| row.get(0))
+            .map_err(|err| err.to_string())?;
+        let count: i64 = conn
+            .query_row(
+                &format!("SELECT COUNT(*) FROM {} WHERE deleted_at IS NULL AND id IN ({})", table, placeholders),
+                params_from_iter(ids.iter()),
+                |row| row.get(0),
+            )
+            .map_err(|err| err.to_string())?;
+
+        if count == 0 {
+            continue;
+        }
+
+        if !allow_mass_delete && is_mass_delete(count, live) {
+            return Err(format!(
+                "Refusing to delete {} of {} {} from a snapshot write without allowMassDelete.",
+                count, live, table
+            ));
+        }
+
+        let mut values = vec![Value::Integer(chrono_now())];
+        values.extend(ids);
+        conn.execute(
+            &format!("UPDATE {} SET deleted_at = ? WHERE deleted_at IS NULL AND id IN ({})", table, placeholders),
+            params_from_iter(values.iter()),
+        ).map_err(|err| err.to_string())?;
+    }
+
+    Ok(())
+}
+
+pub fn list_trash_internal(conn: &Connection) -> Result<TrashListing, String> {
+    let retention_days = retention_days(conn)?;
+    let mut items = Vec::new();
+
+    for kind in TrashKind::ALL {
+        let mut stmt = conn
+            .prepare(&format!(
+                "SELECT id, {}, deleted_at FROM {} WHERE deleted_at IS NOT NULL",
+                kind.label_column(),
+                kind.table()
+            ))
+            .map_err(|err| err.to_string())?;
+
+        let rows = stmt
+            .query_map([], |row| {
+                let deleted_at: i64 = row.get(2)?;
+                Ok(TrashItem {
+                    kind,
+                    id: row.get(0)?,
+                    label: row.get(1)?,
+                    deleted_at,
+                    purge_at: (retention_days > 0).then(|| deleted_at + retention_days * DAY_MS),
+                })
+            })
+            .map_err(|err| err.to_string())?;
+
+        for row in rows {
+            items.push(row.map_err(|err| err.to_string())?);
+        }
+    }
+
+    items.sort_by_key(|item| std::cmp::Reverse(item.deleted_at));
+
+    Ok(TrashListing {
+        items,
+        retention_days,
+    })
+}
+
+/// Drops the revisions of suites that no longer exist, except those a
+/// remaining run is pinned to, which reports and exports still read.
+fn purge_orphaned_revisions(conn: &Connection) -> Result<(), String> {
+    conn.execute(
+        "DELETE FROM test_suite_revisions
+         WHERE NOT EXISTS (SELECT 1 FROM test_suites s WHERE s.id = test_suite_revisions.test_suite_id)
+           AND NOT EXISTS (
+               SELECT 1 FROM runs r
+               WHERE r.test_suite_id = test_suite_revisions.test_suite_id
+                 AND r.test_suite_revision = test_suite_revisions.revision
+           )",
+        [],
+    ).map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+/// Permanently removes trashed rows (cascading to their cases, results and
+/// outputs, and dropping revisions nothing refers to any more). Only rows
+/// already in the trash are touched.
+fn purge(conn: &Connection, kind: TrashKind, id: Option<&str>) -> Result<usize, String> {
+    let purged = match id {
+        Some(id) => conn.execute(
+            &format!("DELETE FROM {} WHERE id = ? AND deleted_at IS NOT NULL", kind.table()),
+            params![id],
+        ),
+        None => conn.execute(
+            &format!("DELETE FROM {} WHERE deleted_at IS NOT NULL", kind.table()),
+            [],
+        ),
+    }
+    .map_err(|err| err.to_string())?;
+
+    purge_orphaned_revisions(conn)?;
+    Ok(purged)
+}
+
+/// Purges items that have been in the trash longer than the retention
+/// setting. A retention of 0 days keeps trashed items until purged by hand.
+pub fn purge_expired(conn: &Connection) -> Result<usize, String> {
+    let retention_days = retention_days(conn)?;
+    if retention_days <= 0 {
+        return Ok(0);
+    }
+
+    let cutoff = chrono_now() - retention_days * DAY_MS;
+    let mut purged = 0;
+    for kind in TrashKind::ALL {
+        purged += conn
+            .execute(
+                &format!("DELETE FROM {} WHERE deleted_at IS NOT NULL AND deleted_at < ?", kind.table()),
+                params![cutoff],
+            )
+            .map_err(|err| err.to_string())?;
+    }
+    purge_orphaned_revisions(conn)?;
+
+    Ok(purged)
+}
+
+/// Takes an item out of the trash. Returns 0 if it wasn't trashed.
+pub fn restore_item(conn: &Connection, kind: TrashKind, id: &str) -> Result<usize, String> {
+    conn.execute(
+        &format!("UPDATE {} SET deleted_at = NULL WHERE id = ? AND deleted_at IS NOT NULL", kind.table()),
+        params![id],
+    ).map_err(|err| err.to_string())
+}
+
+pub fn restore_internal(conn: &mut Connection, items: &[TrashRef]) -> Result<usize, String> {
+    let tx = conn.transaction().map_err(|err| err.to_string())?;
+
+    let mut restored = 0;
+    for item in items {
+        restored += restore_item(&tx, item.kind, &item.id)?;
+    }
+
+    tx.commit().map_err(|err| err.to_string())?;
+    Ok(restored)
+}
+
+pub fn purge_trash_internal(conn: &mut Connection, items: Option<&[TrashRef]>) -> Result<usize, String> {
+    let tx = conn.transaction().map_err(|err| err.to_string())?;
+
+    let mut purged = 0;
+    match items {
+        Some(items) => {
+            for item in items {
+                purged += purge(&tx, item.kind, Some(&item.id))?;
+            }
+        }
+        None => {
+            for kind in TrashKind::ALL {
+                purged += purge(&tx, kind, None)?;
+            }
+        }
+    }
+
+    tx.commit().map_err(|err| err.to_string())?;
+    Ok(purged)
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[cfg(feature = "app")]
+#[tauri::command]
+pub fn list_trash(db: State<'_, Db>) -> Result<TrashListing, String> {
+    let conn = db.conn()?;
+    list_trash_internal(&conn)
+}
+
+#[cfg(feature = "app")]
+#[tauri::command]
+pub fn restore(db: State<'_, Db>, items: Vec<TrashRef>) -> Result<usize, String> {
+    let mut conn = db.conn()?;
+    restore_internal(&mut conn, &items)
+}
+
+/// Purges the given trashed items, or the whole trash when `items` is omitted.
+#[cfg(feature = "app")]
+#[tauri::command]
+pub fn purge_trash(db: State<'_, Db>, items: Option<Vec<TrashRef>>) -> Result<usize, String> {
+    let mut conn = db.conn()?;
+    purge_trash_internal(&mut conn, items.as_deref())
+}
+
+#[cfg(feature = "app")]
+#[tauri::command]
+pub fn set_trash_retention_days(db: State<'_, Db>, days: i64) -> Result<(), String> {
+    if days < 0 {
+        return Err("Retention must be zero (keep forever) or a positive number of days.".to_string());
+    }
+    let conn = db.conn()?;
+    set_setting(&conn, RETENTION_SETTING, &days.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{memory_db, run, suite};
+    use crate::{get_test_suite_internal, write_run_with_results, write_test_suite};
+
+    fn revisions(conn: &Connection, suite_id: &str) -> Vec<i64> {
+        let mut stmt = conn
+            .prepare("SELECT revision FROM test_suite_revisions WHERE test_suite_id = ? ORDER BY revision")
+            .unwrap();
+        let rows = stmt.query_map(params![suite_id], |row| row.get(0)).unwrap();
+        rows.collect::<Result<_, _>>().unwrap()
+    }
+
+    #[test]
+    fn mass_delete_threshold() {
+        assert!(!is_mass_delete(1, 1));
+        assert!(!is_mass_delete(2, 3));
+        assert!(!is_mass_delete(3, 6));
+        assert!(is_mass_delete(3, 5));
+        assert!(is_mass_delete(26, 1000));
+    }
+
+    #[test]
+    fn only_listed_items_are_trashed() {
+        let conn = memory_db();
+        for id in ["s1", "s2", "s3"] {
+            write_test_suite(&conn, &suite(id, &[&format!("{}-a", id)])).unwrap();
+        }
+
+        let deleted = [TrashRef { kind: TrashKind::TestSuite, id: "s2".to_string() }];
+        trash_deleted(&conn, &deleted, false).unwrap();
+        assert!(get_test_suite_internal(&conn, "s1").unwrap().is_some());
+        assert!(get_test_suite_internal(&conn, "s2").unwrap().is_none());
+        assert!(get_test_suite_internal(&conn, "s3").unwrap().is_some());
+    }
+
+    #[test]
+    fn mass_delete_needs_the_flag() {
+        let conn = memory_db();
+        let ids = ["s1", "s2", "s3", "s4"];
+        for id in ids {
+            write_test_suite(&conn, &suite(id, &[&format!("{}-a", id)])).unwrap();
+        }
+
+        let deleted: Vec<TrashRef> = ids[..3]
+            .iter()
+            .map(|id| TrashRef { kind: TrashKind::TestSuite, id: id.to_string() })
+            .collect();
+        assert!(trash_deleted(&conn, &deleted, false).is_err());
+        trash_deleted(&conn, &deleted, true).unwrap();
+        assert!(get_test_suite_internal(&conn, "s1").unwrap().is_none());
+        assert!(get_test_suite_internal(&conn, "s4").unwrap().is_some());
+    }
+
+    #[test]
+    fn saving_a_trashed_item_does_not_restore_it() {
+        let mut conn = memory_db();
+        let mut suite = suite("s1", &["a"]);
+        write_test_suite(&conn, &suite).unwrap();
+        move_to_trash(&conn, TrashKind::TestSuite, "s1").unwrap();
+
+        suite.name = "Edited".to_string();
+        write_test_suite(&conn, &suite).unwrap();
+        assert!(get_test_suite_internal(&conn, "s1").unwrap().is_none());
+
+        let items = [TrashRef { kind: TrashKind::TestSuite, id: "s1".to_string() }];
+        assert_eq!(restore_internal(&mut conn, &items).unwrap(), 1);
+        assert_eq!(get_test_suite_internal(&conn, "s1").unwrap().unwrap().name, "Suite s1");
+    }
+
+    #[test]
+    fn purge_drops_revisions_no_run_is_pinned_to() {
+        let mut conn = memory_db();
+        let mut s1 = suite("s1", &["a"]);
+        write_test_suite(&conn, &s1).unwrap();
+        write_run_with_results(&conn, &run("r1", &s1, "completed")).unwrap();
+        s1.test_cases.push(crate::test_support::test_case("b"));
+        write_test_suite(&conn, &s1).unwrap();
+        assert_eq!(revisions(&conn, "s1"), vec![1, 2]);
+
+        move_to_trash(&conn, TrashKind::TestSuite, "s1").unwrap();
+        purge_trash_internal(&mut conn, None).unwrap();
+        assert_eq!(revisions(&conn, "s1"), vec![1]);
+
+        move_to_trash(&conn, TrashKind::Run, "r1").unwrap();
+        purge_trash_internal(&mut conn, None).unwrap();
+        assert!(revisions(&conn, "s1").is_empty());
+    }
+}
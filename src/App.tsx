@@ -6,10 +6,12 @@ import { AutoUpdater } from '@/components/layout/AutoUpdater'
 import { Header } from '@/components/layout/Header'
 import { MainTabs } from '@/components/layout/MainTabs'
 import { initLocalDb } from '@/services/localDb'
+import { listenToBackendRuns } from '@/services/backendExecution'
 
 function App() {
   useEffect(() => {
     void initLocalDb()
+    void listenToBackendRuns()
   }, [])
 
   return (
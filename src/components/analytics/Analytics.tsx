@@ -1,4 +1,4 @@
-import { useMemo, useState } from 'react'
+import { useEffect, useMemo, useState } from 'react'
 import {
   Trophy,
   TrendingUp,
@@ -28,6 +28,7 @@ import {
 } from '@/components/ui/select'
 import { useRunStore } from '@/stores/runStore'
 import { useTestSuiteStore } from '@/stores/testSuiteStore'
+import { loadRunResults } from '@/services/localDb'
 import { computeAnalytics, type AnalyticsData, type InterestingFact } from '@/services/analytics'
 
 function FactIcon({ type }: { type: InterestingFact['type'] }) {
@@ -495,6 +496,14 @@ function TimelineChart({ analytics }: { analytics: AnalyticsData }) {
 export function Analytics() {
   const { runs } = useRunStore()
   const { testSuites } = useTestSuiteStore()
+  const unloadedRunKey = runs.filter((r) => r.summary).map((r) => r.id).join(',')
+
+  // Analytics aggregate over every result, so fetch runs only listed as history
+  useEffect(() => {
+    if (unloadedRunKey) {
+      void loadRunResults(unloadedRunKey.split(','))
+    }
+  }, [unloadedRunKey])
 
   const analytics = useMemo(() => {
     return computeAnalytics(runs, testSuites)
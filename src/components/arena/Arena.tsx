@@ -5,6 +5,7 @@ import { useSettingsStore } from '@/stores/settingsStore'
 import { useTestSuiteStore } from '@/stores/testSuiteStore'
 import { useModelStore } from '@/stores/modelStore'
 import { getOpenRouterClient } from '@/services/openrouter'
+import { isBackendExecutionAvailable, listBackendModels } from '@/services/backendExecution'
 import { ModelSelector } from './ModelSelector'
 import { ParameterPanel } from './ParameterPanel'
 import { ExecutionControls } from './ExecutionControls'
@@ -23,10 +24,12 @@ export function Arena() {
   } = useModelStore()
 
   const activeTestSuite = testSuites.find((s) => s.id === activeTestSuiteId)
+  // The desktop app can run mock and other backend provider models without an API key
+  const canRunOffline = isBackendExecutionAvailable()
 
   // Fetch models when API key is available
   useEffect(() => {
-    if (!apiKey) return
+    if (!apiKey && !canRunOffline) return
 
     // Only fetch if we haven't fetched in the last 5 minutes
     const fiveMinutesAgo = Date.now() - 5 * 60 * 1000
@@ -39,9 +42,9 @@ export function Arena() {
       setModelsError(null)
 
       try {
-        const client = getOpenRouterClient(apiKey)
-        const models = await client.fetchModels()
-        setAvailableModels(models)
+        const models = apiKey ? await getOpenRouterClient(apiKey).fetchModels() : []
+        const backendModels = await listBackendModels().catch(() => [])
+        setAvailableModels([...models, ...backendModels])
         setLastFetchedAt(Date.now())
       } catch (error) {
         setModelsError(
@@ -55,7 +58,7 @@ export function Arena() {
     fetchModels()
   }, [apiKey])
 
-  if (!apiKey) {
+  if (!apiKey && !canRunOffline) {
     return (
       <EmptyState
         icon={Key}
@@ -1,6 +1,8 @@
-import { useState } from 'react'
+import { useRef, useState } from 'react'
 import { Play, Square, Repeat, ChevronDown } from 'lucide-react'
 import { Button } from '@/components/ui/button'
+import { Label } from '@/components/ui/label'
+import { Switch } from '@/components/ui/switch'
 import {
   DropdownMenu,
   DropdownMenuContent,
@@ -12,6 +14,11 @@ import { useSettingsStore } from '@/stores/settingsStore'
 import { useModelStore } from '@/stores/modelStore'
 import { useRunStore } from '@/stores/runStore'
 import { executeRun } from '@/services/execution'
+import {
+  cancelBackendRun,
+  executeRunInBackend,
+  isBackendExecutionAvailable,
+} from '@/services/backendExecution'
 import type { TestSuite } from '@/types'
 
 interface ExecutionControlsProps {
@@ -20,18 +27,47 @@ interface ExecutionControlsProps {
 
 export function ExecutionControls({ testSuite }: ExecutionControlsProps) {
   const { apiKey } = useSettingsStore()
-  const { selectedModelIds, parameters, judgeModelId } = useModelStore()
+  const { selectedModelIds, parameters, judgeModelId, getEffectiveParameters } = useModelStore()
   const { createRun } = useRunStore()
+  // Scheduler state of the backend run in progress, if any
+  const queue = useRunStore((state) => (state.currentRunId ? state.queues[state.currentRunId] : undefined))
   const { toast } = useToast()
 
   const [isRunning, setIsRunning] = useState(false)
   const [abortController, setAbortController] = useState<AbortController | null>(null)
   const [currentRunIndex, setCurrentRunIndex] = useState(0)
   const [totalRuns, setTotalRuns] = useState(1)
+  // Ask every model afresh instead of reusing cached responses
+  const [bypassCache, setBypassCache] = useState(false)
+  const backendRunId = useRef<string | null>(null)
 
   const canRun = selectedModelIds.length > 0 && testSuite.testCases.length > 0
 
   const executeSingleRun = async (controller: AbortController): Promise<string> => {
+    // In the desktop app the backend executes the run, so it survives a reload
+    if (isBackendExecutionAvailable()) {
+      const run = await executeRunInBackend(
+        {
+          testSuiteId: testSuite.id,
+          models: selectedModelIds,
+          judgeModel: judgeModelId || undefined,
+          parameters: getEffectiveParameters(),
+          bypassCache,
+        },
+        apiKey || null,
+        (started) => {
+          backendRunId.current = started.id
+          // Stop was pressed before the backend handed back the run id
+          if (controller.signal.aborted) void cancelBackendRun(started.id)
+        }
+      )
+      backendRunId.current = null
+      if (run.status === 'cancelled') {
+        throw new DOMException('Aborted', 'AbortError')
+      }
+      return run.id
+    }
+
     const run = createRun({
       testSuiteId: testSuite.id,
       testSuiteName: testSuite.name,
@@ -48,7 +84,8 @@ export function ExecutionControls({ testSuite }: ExecutionControlsProps) {
   }
 
   const handleRun = async (numRuns: number = 1) => {
-    if (!canRun || !apiKey) {
+    // Without a key only models of the backend's own providers can run
+    if (!canRun || (!apiKey && !isBackendExecutionAvailable())) {
       toast({
         title: 'Cannot start run',
         description: 'Please select at least one model and ensure test cases exist',
@@ -105,6 +142,7 @@ export function ExecutionControls({ testSuite }: ExecutionControlsProps) {
         })
       }
     } finally {
+      backendRunId.current = null
       setIsRunning(false)
       setAbortController(null)
       setCurrentRunIndex(0)
@@ -116,10 +154,16 @@ export function ExecutionControls({ testSuite }: ExecutionControlsProps) {
     if (abortController) {
       abortController.abort()
     }
+    if (backendRunId.current) {
+      void cancelBackendRun(backendRunId.current)
+    }
   }
 
   const runOptions = [3, 5, 10]
 
+  const waiting = queue?.models.reduce((sum, model) => sum + model.waiting, 0) ?? 0
+  const retries = queue?.models.reduce((sum, model) => sum + model.retries, 0) ?? 0
+
   return (
     <div className="flex items-center gap-2">
       {isRunning ? (
@@ -160,6 +204,21 @@ export function ExecutionControls({ testSuite }: ExecutionControlsProps) {
         </div>
       )}
 
+      {!isRunning && isBackendExecutionAvailable() && (
+        <div className="flex items-center gap-2">
+          <Switch id="bypass-cache" checked={bypassCache} onCheckedChange={setBypassCache} />
+          <Label htmlFor="bypass-cache" className="text-sm cursor-pointer">Bypass cache</Label>
+        </div>
+      )}
+
+      {isRunning && (waiting > 0 || retries > 0) && (
+        <span className="text-sm text-muted-foreground">
+          {waiting > 0 && `${waiting} ${waiting === 1 ? 'call' : 'calls'} held by rate limits`}
+          {waiting > 0 && retries > 0 && ' · '}
+          {retries > 0 && `${retries} ${retries === 1 ? 'retry' : 'retries'}`}
+        </span>
+      )}
+
       {selectedModelIds.length === 0 && (
         <span className="text-sm text-muted-foreground">
           Select models to run
@@ -25,7 +25,8 @@ export function JudgeSelector() {
       id.includes('gemini') ||
       id.includes('llama-3') ||
       id.includes('mistral-large') ||
-      id.includes('command-r')
+      id.includes('command-r') ||
+      id.startsWith('mock:')
     )
   })
 
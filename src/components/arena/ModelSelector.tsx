@@ -72,7 +72,8 @@ export function ModelSelector({ useCodeArenaStore: useCodeArena = false }: Model
         model.name.toLowerCase().includes(searchTerm)
       if (!matchesSearch) return
 
-      const provider = model.id.split('/')[0]
+      // `openai/gpt-4o`, or a namespaced backend id like `mock:echo`
+      const provider = model.id.split(/[/:]/)[0]
       if (provider) providerSet.add(provider)
     })
     return Array.from(providerSet).sort()
@@ -87,7 +88,9 @@ export function ModelSelector({ useCodeArenaStore: useCodeArena = false }: Model
         model.name.toLowerCase().includes(searchTerm)
 
       const matchesProvider =
-        !providerFilter || model.id.startsWith(`${providerFilter}/`)
+        !providerFilter ||
+        model.id.startsWith(`${providerFilter}/`) ||
+        model.id.startsWith(`${providerFilter}:`)
 
       // Price filter
       const promptPrice = parseFloat(model.pricing.prompt) || 0
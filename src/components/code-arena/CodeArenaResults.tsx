@@ -169,7 +169,7 @@ export function CodeArenaResults() {
         open={deleteDialogOpen}
         onOpenChange={setDeleteDialogOpen}
         title="Delete Code Arena Run"
-        description="Are you sure you want to delete this run? This action cannot be undone."
+        description="Are you sure you want to delete this run? It moves to the trash in the Data view, where it can be restored."
         confirmLabel="Delete"
         variant="destructive"
         onConfirm={() => {
@@ -0,0 +1,198 @@
+import { useCallback, useEffect, useState } from 'react'
+import { Archive, Plus, RotateCcw, Trash2, Loader2 } from 'lucide-react'
+import { Button } from '@/components/ui/button'
+import { Card, CardContent, CardDescription, CardHeader, CardTitle } from '@/components/ui/card'
+import { Input } from '@/components/ui/input'
+import { Label } from '@/components/ui/label'
+import { Badge } from '@/components/ui/badge'
+import { ScrollArea } from '@/components/ui/scroll-area'
+import { ConfirmDialog } from '@/components/ui/confirm-dialog'
+import { useToast } from '@/components/ui/use-toast'
+import { createBackup, deleteBackup, listBackups, restoreBackup, setBackupSettings } from '@/services/localDb'
+import type { BackupInfo, BackupKind, BackupListing } from '@/types'
+
+const kindLabels: Record<BackupKind, string> = {
+  manual: 'Manual',
+  startup: 'Startup',
+  scheduled: 'Scheduled',
+  preMigration: 'Before migration',
+  preRestore: 'Before restore',
+  preRepair: 'Before repair',
+}
+
+function formatSize(bytes: number): string {
+  if (bytes < 1024 * 1024) return `${(bytes / 1024).toFixed(0)} KB`
+  return `${(bytes / (1024 * 1024)).toFixed(1)} MB`
+}
+
+export function BackupPanel() {
+  const [listing, setListing] = useState<BackupListing | null>(null)
+  const [retentionCount, setRetentionCount] = useState('')
+  const [intervalHours, setIntervalHours] = useState('')
+  const [busy, setBusy] = useState(false)
+  const [restoreTarget, setRestoreTarget] = useState<BackupInfo | null>(null)
+  const { toast } = useToast()
+
+  const refresh = useCallback(async () => {
+    const found = await listBackups()
+    setListing(found)
+    if (found) {
+      setRetentionCount(String(found.settings.retentionCount))
+      setIntervalHours(String(found.settings.intervalHours))
+    }
+  }, [])
+
+  useEffect(() => {
+    void refresh()
+  }, [refresh])
+
+  const handleCreate = async () => {
+    setBusy(true)
+    const backup = await createBackup()
+    setBusy(false)
+    if (backup) {
+      toast({ title: 'Backup created', description: backup.fileName })
+    } else {
+      toast({ title: 'Backup failed', description: 'See the log for details.', variant: 'destructive' })
+    }
+    await refresh()
+  }
+
+  const handleRestore = async (backup: BackupInfo) => {
+    setBusy(true)
+    try {
+      const safetyCopy = await restoreBackup(backup.fileName)
+      toast({
+        title: 'Backup restored',
+        description: safetyCopy ? `The previous database was saved as ${safetyCopy.fileName}.` : backup.fileName,
+      })
+    } catch (error) {
+      toast({
+        title: 'Restore refused',
+        description: error instanceof Error ? error.message : String(error),
+        variant: 'destructive',
+      })
+    } finally {
+      setBusy(false)
+      await refresh()
+    }
+  }
+
+  const handleDelete = async (backup: BackupInfo) => {
+    await deleteBackup(backup.fileName)
+    await refresh()
+  }
+
+  const handleSaveSettings = async () => {
+    const retention = Number.parseInt(retentionCount, 10)
+    const interval = Number.parseInt(intervalHours, 10)
+    if (!Number.isFinite(retention) || retention < 0 || !Number.isFinite(interval) || interval < 0) {
+      toast({ title: 'Invalid settings', description: 'Enter 0 or more for both values.', variant: 'destructive' })
+      return
+    }
+    await setBackupSettings({ retentionCount: retention, intervalHours: interval })
+    await refresh()
+  }
+
+  const settingsChanged = listing !== null && (
+    retentionCount !== String(listing.settings.retentionCount) || intervalHours !== String(listing.settings.intervalHours)
+  )
+  const backups = listing?.backups ?? []
+
+  return (
+    <Card className="h-full min-h-0 flex flex-col">
+      <CardHeader className="pb-3 shrink-0">
+        <div className="flex items-center justify-between">
+          <div>
+            <CardTitle className="text-lg flex items-center gap-2">
+              <Archive className="h-5 w-5" />
+              Backups
+            </CardTitle>
+            <CardDescription>
+              Copies of the database taken on startup, on a schedule and before risky changes
+            </CardDescription>
+          </div>
+          <Button variant="outline" size="sm" onClick={handleCreate} disabled={busy}>
+            {busy ? <Loader2 className="h-4 w-4 mr-1 animate-spin" /> : <Plus className="h-4 w-4 mr-1" />}
+            Back Up Now
+          </Button>
+        </div>
+      </CardHeader>
+
+      <CardContent className="flex-1 min-h-0 flex flex-col gap-4">
+        <div className="flex items-end gap-2 shrink-0">
+          <div className="space-y-1">
+            <Label htmlFor="backup-retention">Automatic backups to keep (0 keeps all)</Label>
+            <Input
+              id="backup-retention"
+              type="number"
+              min={0}
+              value={retentionCount}
+              onChange={(e) => setRetentionCount(e.target.value)}
+              className="w-40"
+            />
+          </div>
+          <div className="space-y-1">
+            <Label htmlFor="backup-interval">Hours between backups (0 disables)</Label>
+            <Input
+              id="backup-interval"
+              type="number"
+              min={0}
+              value={intervalHours}
+              onChange={(e) => setIntervalHours(e.target.value)}
+              className="w-40"
+            />
+          </div>
+          <Button variant="outline" onClick={handleSaveSettings} disabled={!settingsChanged}>
+            Save
+          </Button>
+        </div>
+
+        <ScrollArea className="flex-1 pr-2">
+          <div className="space-y-2">
+            {backups.length === 0 ? (
+              <div className="text-center py-8 text-muted-foreground">No backups yet.</div>
+            ) : (
+              backups.map((backup) => (
+                <div key={backup.fileName} className="flex items-center gap-3 p-3 rounded-lg border border-border/50">
+                  <div className="flex-1 min-w-0">
+                    <div className="font-medium truncate">{new Date(backup.createdAt).toLocaleString()}</div>
+                    <div className="text-xs text-muted-foreground truncate">
+                      {backup.fileName} · {formatSize(backup.sizeBytes)}
+                    </div>
+                  </div>
+                  <Badge variant="outline" className="shrink-0">{kindLabels[backup.kind]}</Badge>
+                  <Button variant="outline" size="sm" disabled={busy} onClick={() => setRestoreTarget(backup)}>
+                    <RotateCcw className="h-4 w-4 mr-1" />
+                    Restore
+                  </Button>
+                  <Button
+                    variant="ghost"
+                    size="sm"
+                    className="text-destructive hover:text-destructive"
+                    onClick={() => handleDelete(backup)}
+                  >
+                    <Trash2 className="h-4 w-4" />
+                  </Button>
+                </div>
+              ))
+            )}
+          </div>
+        </ScrollArea>
+      </CardContent>
+
+      <ConfirmDialog
+        open={restoreTarget !== null}
+        onOpenChange={(open) => !open && setRestoreTarget(null)}
+        title="Restore this backup?"
+        description="The current database is replaced by the backup. A copy of it is saved first so the restore can be undone."
+        confirmLabel="Restore"
+        variant="destructive"
+        onConfirm={() => {
+          if (restoreTarget) void handleRestore(restoreTarget)
+          setRestoreTarget(null)
+        }}
+      />
+    </Card>
+  )
+}
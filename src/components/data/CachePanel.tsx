@@ -0,0 +1,150 @@
+import { useCallback, useEffect, useState } from 'react'
+import { Database, Trash2 } from 'lucide-react'
+import { Button } from '@/components/ui/button'
+import { Card, CardContent, CardDescription, CardHeader, CardTitle } from '@/components/ui/card'
+import { Input } from '@/components/ui/input'
+import { Label } from '@/components/ui/label'
+import { Switch } from '@/components/ui/switch'
+import { ConfirmDialog } from '@/components/ui/confirm-dialog'
+import { useToast } from '@/components/ui/use-toast'
+import { clearCache, getCacheStatus, setCacheSettings } from '@/services/localDb'
+import type { CacheStatus } from '@/types'
+
+function formatSize(bytes: number): string {
+  if (bytes < 1024 * 1024) return `${(bytes / 1024).toFixed(0)} KB`
+  return `${(bytes / (1024 * 1024)).toFixed(1)} MB`
+}
+
+export function CachePanel() {
+  const [status, setStatus] = useState<CacheStatus | null>(null)
+  const [ttlHours, setTtlHours] = useState('')
+  const [maxSizeMb, setMaxSizeMb] = useState('')
+  const [confirmClear, setConfirmClear] = useState(false)
+  const { toast } = useToast()
+
+  const apply = useCallback((found: CacheStatus | null) => {
+    setStatus(found)
+    if (found) {
+      setTtlHours(String(found.settings.ttlHours))
+      setMaxSizeMb(String(found.settings.maxSizeMb))
+    }
+  }, [])
+
+  useEffect(() => {
+    void getCacheStatus().then(apply)
+  }, [apply])
+
+  const save = async (enabled: boolean) => {
+    const ttl = Number.parseInt(ttlHours, 10)
+    const maxSize = Number.parseInt(maxSizeMb, 10)
+    if (!Number.isFinite(ttl) || ttl < 0 || !Number.isFinite(maxSize) || maxSize < 0) {
+      toast({ title: 'Invalid settings', description: 'Enter 0 or more for both values.', variant: 'destructive' })
+      return
+    }
+    try {
+      apply(await setCacheSettings({ enabled, ttlHours: ttl, maxSizeMb: maxSize }))
+    } catch (error) {
+      toast({ title: 'Settings not saved', description: String(error), variant: 'destructive' })
+    }
+  }
+
+  const handleClear = async () => {
+    try {
+      const removed = await clearCache()
+      toast({ title: 'Cache cleared', description: `${removed} cached response${removed !== 1 ? 's' : ''} removed.` })
+    } catch (error) {
+      toast({ title: 'Clear failed', description: String(error), variant: 'destructive' })
+    }
+    apply(await getCacheStatus())
+  }
+
+  const settingsChanged = status !== null && (
+    ttlHours !== String(status.settings.ttlHours) || maxSizeMb !== String(status.settings.maxSizeMb)
+  )
+
+  return (
+    <Card className="h-full min-h-0 flex flex-col">
+      <CardHeader className="pb-3 shrink-0">
+        <div className="flex items-center justify-between">
+          <div>
+            <CardTitle className="text-lg flex items-center gap-2">
+              <Database className="h-5 w-5" />
+              Response Cache
+            </CardTitle>
+            <CardDescription>
+              Cases whose model, prompts and parameters match an earlier call reuse its response instead of calling the provider
+            </CardDescription>
+          </div>
+          <Button
+            variant="outline"
+            size="sm"
+            onClick={() => setConfirmClear(true)}
+            disabled={!status || status.entries === 0}
+          >
+            <Trash2 className="h-4 w-4 mr-1" />
+            Clear
+          </Button>
+        </div>
+      </CardHeader>
+
+      <CardContent className="space-y-4">
+        <div className="flex items-center justify-between">
+          <div>
+            <Label htmlFor="cache-enabled" className="cursor-pointer">Use cached responses</Label>
+            <p className="text-xs text-muted-foreground">
+              {status ? `${status.entries} responses stored, ${formatSize(status.sizeBytes)}` : 'Unavailable'}
+            </p>
+          </div>
+          <Switch
+            id="cache-enabled"
+            checked={status?.settings.enabled ?? false}
+            disabled={!status}
+            onCheckedChange={(enabled) => void save(enabled)}
+          />
+        </div>
+
+        <div className="flex items-end gap-2">
+          <div className="space-y-1">
+            <Label htmlFor="cache-ttl">Hours a response stays valid (0 never expires)</Label>
+            <Input
+              id="cache-ttl"
+              type="number"
+              min={0}
+              value={ttlHours}
+              onChange={(e) => setTtlHours(e.target.value)}
+              className="w-40"
+            />
+          </div>
+          <div className="space-y-1">
+            <Label htmlFor="cache-size">Size limit in MB (0 is unlimited)</Label>
+            <Input
+              id="cache-size"
+              type="number"
+              min={0}
+              value={maxSizeMb}
+              onChange={(e) => setMaxSizeMb(e.target.value)}
+              className="w-40"
+            />
+          </div>
+          <Button
+            variant="outline"
+            onClick={() => status && void save(status.settings.enabled)}
+            disabled={!settingsChanged}
+          >
+            Save
+          </Button>
+        </div>
+      </CardContent>
+
+      <ConfirmDialog
+        open={confirmClear}
+        onOpenChange={setConfirmClear}
+        title="Clear the response cache?"
+        description="Every stored response is removed. Later runs call the models again."
+        confirmLabel="Clear"
+        variant="destructive"
+        onConfirm={() => void handleClear()}
+      />
+    </Card>
+  )
+}
@@ -1,4 +1,12 @@
+import { Tabs, TabsContent, TabsList, TabsTrigger } from '@/components/ui/tabs'
 import { LocalDbPanel } from './LocalDbPanel'
+import { SearchPanel } from './SearchPanel'
+import { TrashPanel } from './TrashPanel'
+import { BackupPanel } from './BackupPanel'
+import { HealthPanel } from './HealthPanel'
+import { CachePanel } from './CachePanel'
+import { RateLimitsPanel } from './RateLimitsPanel'
+import { ProvidersPanel } from './ProvidersPanel'
 
 export function DataManager() {
   return (
@@ -9,9 +17,42 @@ export function DataManager() {
           Inspect the live JSON store and patch it directly for reproducible runs.
         </p>
       </div>
-      <div className="flex-1 min-h-0">
-        <LocalDbPanel />
-      </div>
+      <Tabs defaultValue="explorer" className="flex-1 min-h-0 flex flex-col">
+        <TabsList className="shrink-0 self-start">
+          <TabsTrigger value="explorer">Explorer</TabsTrigger>
+          <TabsTrigger value="search">Search</TabsTrigger>
+          <TabsTrigger value="trash">Trash</TabsTrigger>
+          <TabsTrigger value="backups">Backups</TabsTrigger>
+          <TabsTrigger value="health">Health</TabsTrigger>
+          <TabsTrigger value="cache">Cache</TabsTrigger>
+          <TabsTrigger value="limits">Rate Limits</TabsTrigger>
+          <TabsTrigger value="providers">Providers</TabsTrigger>
+        </TabsList>
+        <TabsContent value="explorer" className="flex-1 min-h-0 mt-3">
+          <LocalDbPanel />
+        </TabsContent>
+        <TabsContent value="search" className="flex-1 min-h-0 mt-3">
+          <SearchPanel />
+        </TabsContent>
+        <TabsContent value="trash" className="flex-1 min-h-0 mt-3">
+          <TrashPanel />
+        </TabsContent>
+        <TabsContent value="backups" className="flex-1 min-h-0 mt-3">
+          <BackupPanel />
+        </TabsContent>
+        <TabsContent value="health" className="flex-1 min-h-0 mt-3">
+          <HealthPanel />
+        </TabsContent>
+        <TabsContent value="cache" className="flex-1 min-h-0 mt-3">
+          <CachePanel />
+        </TabsContent>
+        <TabsContent value="limits" className="flex-1 min-h-0 mt-3">
+          <RateLimitsPanel />
+        </TabsContent>
+        <TabsContent value="providers" className="flex-1 min-h-0 mt-3">
+          <ProvidersPanel />
+        </TabsContent>
+      </Tabs>
     </div>
   )
 }
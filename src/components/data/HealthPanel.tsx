@@ -0,0 +1,163 @@
+import { useCallback, useEffect, useState } from 'react'
+import { ShieldCheck, Wrench, RotateCcw, Loader2, CheckCircle2, AlertTriangle } from 'lucide-react'
+import { Button } from '@/components/ui/button'
+import { Card, CardContent, CardDescription, CardHeader, CardTitle } from '@/components/ui/card'
+import { Badge } from '@/components/ui/badge'
+import { ScrollArea } from '@/components/ui/scroll-area'
+import { ConfirmDialog } from '@/components/ui/confirm-dialog'
+import { useToast } from '@/components/ui/use-toast'
+import { checkDatabase, listQuarantine, repairDatabase, restoreQuarantined } from '@/services/localDb'
+import type { DatabaseCheckReport, DatabaseIssueKind, QuarantinedRow } from '@/types'
+
+const issueLabels: Record<DatabaseIssueKind, string> = {
+  integrity: 'Integrity',
+  missingParent: 'Missing parent',
+  orphanedResult: 'Orphaned result',
+  invalidJson: 'Invalid JSON',
+  danglingReference: 'Dangling reference',
+}
+
+export function HealthPanel() {
+  const [report, setReport] = useState<DatabaseCheckReport | null>(null)
+  const [quarantine, setQuarantine] = useState<QuarantinedRow[]>([])
+  const [busy, setBusy] = useState(false)
+  const [confirmRepair, setConfirmRepair] = useState(false)
+  const { toast } = useToast()
+
+  const refreshQuarantine = useCallback(async () => {
+    setQuarantine(await listQuarantine())
+  }, [])
+
+  useEffect(() => {
+    void refreshQuarantine()
+  }, [refreshQuarantine])
+
+  const handleCheck = async () => {
+    setBusy(true)
+    setReport(await checkDatabase())
+    setBusy(false)
+  }
+
+  const handleRepair = async () => {
+    setBusy(true)
+    const repair = await repairDatabase()
+    setBusy(false)
+    if (!repair) {
+      toast({ title: 'Repair failed', description: 'See the log for details.', variant: 'destructive' })
+      return
+    }
+    setReport(repair.after)
+    toast({
+      title: 'Repair finished',
+      description: `${repair.actions.length} issue${repair.actions.length !== 1 ? 's' : ''} handled, ${repair.after.issues.length} left.`,
+    })
+    await refreshQuarantine()
+  }
+
+  const handleRestore = async (row: QuarantinedRow) => {
+    const restored = await restoreQuarantined([row.id])
+    if (restored > 0) {
+      toast({ title: 'Restored', description: `Row from ${row.sourceTable} put back.` })
+    }
+    await refreshQuarantine()
+  }
+
+  return (
+    <Card className="h-full min-h-0 flex flex-col">
+      <CardHeader className="pb-3 shrink-0">
+        <div className="flex items-center justify-between">
+          <div>
+            <CardTitle className="text-lg flex items-center gap-2">
+              <ShieldCheck className="h-5 w-5" />
+              Health
+            </CardTitle>
+            <CardDescription>
+              Check the database for corruption and broken references, and repair what can be repaired
+            </CardDescription>
+          </div>
+          <div className="flex gap-2">
+            <Button variant="outline" size="sm" onClick={handleCheck} disabled={busy}>
+              {busy ? <Loader2 className="h-4 w-4 mr-1 animate-spin" /> : <ShieldCheck className="h-4 w-4 mr-1" />}
+              Check
+            </Button>
+            <Button
+              variant="outline"
+              size="sm"
+              onClick={() => setConfirmRepair(true)}
+              disabled={busy || !report || report.issues.length === 0}
+            >
+              <Wrench className="h-4 w-4 mr-1" />
+              Repair
+            </Button>
+          </div>
+        </div>
+      </CardHeader>
+
+      <CardContent className="flex-1 min-h-0 flex flex-col gap-4">
+        {report && (
+          <div className="shrink-0 space-y-2">
+            {report.issues.length === 0 ? (
+              <div className="flex items-center gap-2 text-sm text-green-600">
+                <CheckCircle2 className="h-4 w-4" />
+                No issues found ({new Date(report.checkedAt).toLocaleString()})
+              </div>
+            ) : (
+              <ScrollArea className="max-h-[200px]">
+                <div className="space-y-1 pr-2">
+                  {report.issues.map((issue, idx) => (
+                    <div key={idx} className="flex items-center gap-2 text-sm p-2 rounded-md bg-amber-500/10">
+                      <AlertTriangle className="h-4 w-4 text-amber-500 shrink-0" />
+                      <Badge variant="outline" className="shrink-0">{issueLabels[issue.kind]}</Badge>
+                      <span className="truncate">{issue.detail}</span>
+                    </div>
+                  ))}
+                </div>
+              </ScrollArea>
+            )}
+          </div>
+        )}
+
+        <div className="flex-1 min-h-0 flex flex-col gap-2">
+          <div className="flex items-center justify-between shrink-0">
+            <h4 className="font-medium">Quarantine</h4>
+            <Badge variant="outline">{quarantine.length} rows</Badge>
+          </div>
+          <ScrollArea className="flex-1 pr-2">
+            <div className="space-y-2">
+              {quarantine.length === 0 ? (
+                <div className="text-center py-8 text-muted-foreground">Nothing has been set aside by a repair.</div>
+              ) : (
+                quarantine.map((row) => (
+                  <div key={row.id} className="flex items-center gap-3 p-3 rounded-lg border border-border/50">
+                    <div className="flex-1 min-w-0">
+                      <div className="font-medium truncate">
+                        {row.sourceTable}
+                        {row.columnName && `.${row.columnName}`}
+                      </div>
+                      <div className="text-xs text-muted-foreground truncate">
+                        {row.reason} · {new Date(row.quarantinedAt).toLocaleString()}
+                      </div>
+                    </div>
+                    <Button variant="outline" size="sm" onClick={() => handleRestore(row)}>
+                      <RotateCcw className="h-4 w-4 mr-1" />
+                      Restore
+                    </Button>
+                  </div>
+                ))
+              )}
+            </div>
+          </ScrollArea>
+        </div>
+      </CardContent>
+
+      <ConfirmDialog
+        open={confirmRepair}
+        onOpenChange={setConfirmRepair}
+        title="Repair the database?"
+        description="A backup is taken first. Broken rows are moved to the quarantine, where they can be restored."
+        confirmLabel="Repair"
+        onConfirm={() => void handleRepair()}
+      />
+    </Card>
+  )
+}
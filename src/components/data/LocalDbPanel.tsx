@@ -1,4 +1,4 @@
-import { useState } from 'react'
+import { useEffect, useState } from 'react'
 import { Database, FileText, Play, FlaskConical, ChevronRight, Trash2, Download, Upload } from 'lucide-react'
 import { Button } from '@/components/ui/button'
 import { Card, CardContent, CardDescription, CardHeader, CardTitle } from '@/components/ui/card'
@@ -8,7 +8,8 @@ import { ScrollArea } from '@/components/ui/scroll-area'
 import { Textarea } from '@/components/ui/textarea'
 import { useTestSuiteStore } from '@/stores/testSuiteStore'
 import { useRunStore } from '@/stores/runStore'
-import { buildSnapshot, hydrateFromDb, writeLocalDb } from '@/services/localDb'
+import { SuiteHistory } from './SuiteHistory'
+import { allowNextMassDelete, buildSnapshot, importSnapshot, loadRunResults, readLocalDb } from '@/services/localDb'
 import type { BenchmakerDb, TestSuite, RunResult } from '@/types'
 
 function StatCard({ icon: Icon, label, value, sublabel }: {
@@ -63,8 +64,8 @@ function RunRow({ run }: { run: RunResult }) {
     idle: 'bg-gray-500/10 text-gray-600',
   }
 
-  const completedResults = run.results.filter(r => r.status === 'completed').length
-  const totalResults = run.results.length
+  const completedResults = run.summary?.completedCount ?? run.results.filter(r => r.status === 'completed').length
+  const totalResults = run.summary?.resultCount ?? run.results.length
 
   return (
     <div className="flex items-center gap-3 p-3 rounded-lg border border-border/50 hover:bg-muted/50 transition-colors">
@@ -75,6 +76,7 @@ function RunRow({ run }: { run: RunResult }) {
         <div className="font-medium truncate">{run.testSuiteName}</div>
         <div className="text-xs text-muted-foreground">
           {run.models.length} model{run.models.length !== 1 ? 's' : ''} · {completedResults}/{totalResults} results
+          {run.testSuiteRevision !== undefined && run.testSuiteRevision !== null && ` · revision ${run.testSuiteRevision}`}
         </div>
       </div>
       <Badge className={statusColors[run.status] || statusColors.idle}>
@@ -160,18 +162,26 @@ export function LocalDbPanel() {
   const clearAllRuns = useRunStore((state) => state.clearAllRuns)
 
   const [selectedSuite, setSelectedSuite] = useState<TestSuite | null>(null)
-  const [selectedRun, setSelectedRun] = useState<RunResult | null>(null)
+  const [selectedRunId, setSelectedRunId] = useState<string | null>(null)
   const [jsonTab, setJsonTab] = useState<'view' | 'edit'>('view')
   const [jsonText, setJsonText] = useState('')
   const [error, setError] = useState('')
 
   // Calculate stats
   const totalTestCases = testSuites.reduce((sum, s) => sum + s.testCases.length, 0)
-  const totalResults = runs.reduce((sum, r) => sum + r.results.length, 0)
+  const totalResults = runs.reduce((sum, r) => sum + (r.summary?.resultCount ?? r.results.length), 0)
   const completedRuns = runs.filter(r => r.status === 'completed').length
+  const selectedRun = runs.find((r) => r.id === selectedRunId) ?? null
 
-  const handleExport = () => {
-    const snapshot = buildSnapshot()
+  useEffect(() => {
+    if (selectedRunId) {
+      void loadRunResults([selectedRunId])
+    }
+  }, [selectedRunId])
+
+  const handleExport = async () => {
+    // Export from the database so runs whose results aren't loaded are complete
+    const snapshot = (await readLocalDb()) ?? buildSnapshot()
     const blob = new Blob([JSON.stringify(snapshot, null, 2)], { type: 'application/json' })
     const url = URL.createObjectURL(blob)
     const a = document.createElement('a')
@@ -209,8 +219,7 @@ export function LocalDbPanel() {
           currentCodeArenaRunId: parsed.currentCodeArenaRunId ?? null,
         }
 
-        hydrateFromDb(normalized)
-        await writeLocalDb(normalized)
+        await importSnapshot(normalized)
         setError('')
       } catch {
         setError('Failed to parse JSON file.')
@@ -239,8 +248,7 @@ export function LocalDbPanel() {
         currentCodeArenaRunId: parsed.currentCodeArenaRunId ?? null,
       }
 
-      hydrateFromDb(normalized)
-      await writeLocalDb(normalized)
+      await importSnapshot(normalized)
       setJsonTab('view')
     } catch {
       setError('Invalid JSON syntax.')
@@ -311,8 +319,9 @@ export function LocalDbPanel() {
               </ScrollArea>
 
               {selectedSuite && (
-                <div className="flex-1 border-l pl-4">
+                <div className="flex-1 border-l pl-4 space-y-4">
                   <TestCasesTable suite={selectedSuite} />
+                  <SuiteHistory suite={selectedSuite} />
                 </div>
               )}
             </div>
@@ -326,7 +335,10 @@ export function LocalDbPanel() {
                     variant="outline"
                     size="sm"
                     className="text-destructive hover:text-destructive"
-                    onClick={() => clearAllRuns()}
+                    onClick={() => {
+                      allowNextMassDelete()
+                      clearAllRuns()
+                    }}
                   >
                     <Trash2 className="h-4 w-4 mr-1" />
                     Clear All Runs
@@ -344,7 +356,7 @@ export function LocalDbPanel() {
                       runs.map((run) => (
                         <div
                           key={run.id}
-                          onClick={() => setSelectedRun(selectedRun?.id === run.id ? null : run)}
+                          onClick={() => setSelectedRunId(selectedRunId === run.id ? null : run.id)}
                           className="cursor-pointer"
                         >
                           <RunRow run={run} />
@@ -0,0 +1,288 @@
+import { useCallback, useEffect, useState } from 'react'
+import { Server, Plus, Trash2, RotateCcw, FolderOpen, Loader2, PlugZap } from 'lucide-react'
+import { Button } from '@/components/ui/button'
+import { Card, CardContent, CardDescription, CardHeader, CardTitle } from '@/components/ui/card'
+import { Input } from '@/components/ui/input'
+import { Label } from '@/components/ui/label'
+import { Badge } from '@/components/ui/badge'
+import { ScrollArea } from '@/components/ui/scroll-area'
+import { ConfirmDialog } from '@/components/ui/confirm-dialog'
+import {
+  Select,
+  SelectContent,
+  SelectItem,
+  SelectTrigger,
+  SelectValue,
+} from '@/components/ui/select'
+import { useToast } from '@/components/ui/use-toast'
+import { cn } from '@/lib/utils'
+import { useModelStore } from '@/stores/modelStore'
+import { deleteProvider, listProviders, saveProvider } from '@/services/localDb'
+import { listProviderModels } from '@/services/backendExecution'
+import { pickFile } from '@/services/fileDialogs'
+import type { ProviderConfig, ProviderKind, ProviderListing } from '@/types'
+
+// Built-in providers go back to their defaults when deleted
+const BUILTIN_IDS = ['openrouter', 'anthropic', 'ollama', 'mock']
+
+const FIXTURE_FILTERS = [{ name: 'Fixture', extensions: ['json', 'yaml', 'yml'] }]
+
+const kindLabels: Record<ProviderKind, string> = {
+  openrouter: 'OpenRouter',
+  'openai-compatible': 'OpenAI-compatible',
+  ollama: 'Ollama',
+  anthropic: 'Anthropic',
+  mock: 'Mock',
+}
+
+const emptyDraft: ProviderConfig = { id: '', kind: 'openai-compatible', baseUrl: '', apiKey: '', fixture: '' }
+
+export function ProvidersPanel() {
+  const [providers, setProviders] = useState<ProviderListing[]>([])
+  const [selectedId, setSelectedId] = useState<string | null>(null)
+  const [draft, setDraft] = useState<ProviderConfig>(emptyDraft)
+  const [checking, setChecking] = useState(false)
+  const [deleteTarget, setDeleteTarget] = useState<ProviderListing | null>(null)
+  const setLastFetchedAt = useModelStore((state) => state.setLastFetchedAt)
+  const { toast } = useToast()
+
+  const refresh = useCallback(async () => {
+    setProviders(await listProviders())
+  }, [])
+
+  useEffect(() => {
+    void refresh()
+  }, [refresh])
+
+  const selected = providers.find((provider) => provider.id === selectedId)
+
+  const edit = (provider: ProviderListing | null) => {
+    setSelectedId(provider?.id ?? null)
+    setDraft(
+      provider
+        ? { id: provider.id, kind: provider.kind, baseUrl: provider.baseUrl ?? '', apiKey: '', fixture: provider.fixture ?? '' }
+        : emptyDraft
+    )
+  }
+
+  // The model picker lists provider models on its next load
+  const modelsChanged = () => setLastFetchedAt(0)
+
+  const handleSave = async () => {
+    try {
+      await saveProvider({
+        id: draft.id.trim(),
+        kind: draft.kind,
+        baseUrl: draft.baseUrl?.trim() || null,
+        // Empty keeps the stored key
+        apiKey: draft.apiKey?.trim() || null,
+        fixture: draft.fixture?.trim() || null,
+      })
+      toast({ title: 'Provider saved', description: `Use its models as ${draft.id.trim()}:<model>.` })
+      modelsChanged()
+      await refresh()
+      setSelectedId(draft.id.trim())
+      setDraft({ ...draft, apiKey: '' })
+    } catch (error) {
+      toast({ title: 'Provider not saved', description: String(error), variant: 'destructive' })
+    }
+  }
+
+  const handleCheck = async () => {
+    if (!selectedId) return
+    setChecking(true)
+    try {
+      const models = await listProviderModels(selectedId)
+      toast({ title: 'Provider reachable', description: `${models.length} model${models.length !== 1 ? 's' : ''} available.` })
+    } catch (error) {
+      toast({ title: 'Provider unavailable', description: String(error), variant: 'destructive' })
+    } finally {
+      setChecking(false)
+    }
+  }
+
+  const handleDelete = async (provider: ProviderListing) => {
+    try {
+      await deleteProvider(provider.id)
+      modelsChanged()
+      if (provider.id === selectedId) edit(null)
+      await refresh()
+    } catch (error) {
+      toast({ title: 'Delete failed', description: String(error), variant: 'destructive' })
+    }
+  }
+
+  const builtin = (id: string) => BUILTIN_IDS.includes(id)
+  const needsKey = draft.kind === 'openrouter' || draft.kind === 'anthropic' || draft.kind === 'openai-compatible'
+
+  return (
+    <Card className="h-full min-h-0 flex flex-col">
+      <CardHeader className="pb-3 shrink-0">
+        <div className="flex items-center justify-between">
+          <div>
+            <CardTitle className="text-lg flex items-center gap-2">
+              <Server className="h-5 w-5" />
+              Providers
+            </CardTitle>
+            <CardDescription>
+              Backends for desktop runs; their models appear in the Arena as provider:model
+            </CardDescription>
+          </div>
+          <Button variant="outline" size="sm" onClick={() => edit(null)}>
+            <Plus className="h-4 w-4 mr-1" />
+            Add Provider
+          </Button>
+        </div>
+      </CardHeader>
+
+      <CardContent className="flex-1 min-h-0 flex gap-4">
+        <ScrollArea className="w-72 shrink-0 pr-2">
+          <div className="space-y-2">
+            {providers.map((provider) => (
+              <div
+                key={provider.id}
+                onClick={() => edit(provider)}
+                className={cn(
+                  'flex items-center gap-2 p-3 rounded-lg border border-border/50 cursor-pointer hover:bg-muted/50',
+                  provider.id === selectedId && 'bg-muted'
+                )}
+              >
+                <div className="flex-1 min-w-0">
+                  <div className="font-medium truncate">{provider.id}</div>
+                  <div className="text-xs text-muted-foreground truncate">
+                    {kindLabels[provider.kind]}
+                    {provider.baseUrl && ` · ${provider.baseUrl}`}
+                  </div>
+                </div>
+                {provider.apiKeySet && <Badge variant="outline" className="shrink-0">Key set</Badge>}
+              </div>
+            ))}
+          </div>
+        </ScrollArea>
+
+        <div className="flex-1 min-w-0 space-y-4">
+          <div className="grid grid-cols-2 gap-3">
+            <div className="space-y-1">
+              <Label htmlFor="provider-id">Id</Label>
+              <Input
+                id="provider-id"
+                value={draft.id}
+                disabled={selected !== undefined}
+                placeholder="vllm"
+                onChange={(e) => setDraft({ ...draft, id: e.target.value })}
+              />
+            </div>
+            <div className="space-y-1">
+              <Label>Kind</Label>
+              <Select
+                value={draft.kind}
+                disabled={selected !== undefined && builtin(selected.id)}
+                onValueChange={(kind) => setDraft({ ...draft, kind: kind as ProviderKind })}
+              >
+                <SelectTrigger>
+                  <SelectValue />
+                </SelectTrigger>
+                <SelectContent>
+                  {(Object.keys(kindLabels) as ProviderKind[]).map((kind) => (
+                    <SelectItem key={kind} value={kind}>
+                      {kindLabels[kind]}
+                    </SelectItem>
+                  ))}
+                </SelectContent>
+              </Select>
+            </div>
+          </div>
+
+          {draft.kind !== 'mock' && (
+            <div className="space-y-1">
+              <Label htmlFor="provider-url">
+                Base URL {draft.kind !== 'openai-compatible' && '(blank uses the vendor default)'}
+              </Label>
+              <Input
+                id="provider-url"
+                value={draft.baseUrl ?? ''}
+                placeholder={draft.kind === 'ollama' ? 'http://localhost:11434' : 'http://localhost:8000/v1'}
+                onChange={(e) => setDraft({ ...draft, baseUrl: e.target.value })}
+              />
+            </div>
+          )}
+
+          {needsKey && (
+            <div className="space-y-1">
+              <Label htmlFor="provider-key">API key</Label>
+              <Input
+                id="provider-key"
+                type="password"
+                value={draft.apiKey ?? ''}
+                placeholder={selected?.apiKeySet ? 'Stored; leave empty to keep it' : 'Not set'}
+                onChange={(e) => setDraft({ ...draft, apiKey: e.target.value })}
+              />
+            </div>
+          )}
+
+          {draft.kind === 'mock' && (
+            <div className="space-y-1">
+              <Label>Fixture (blank echoes every prompt)</Label>
+              <div className="flex gap-2">
+                <Input
+                  value={draft.fixture ?? ''}
+                  placeholder="No file selected"
+                  onChange={(e) => setDraft({ ...draft, fixture: e.target.value })}
+                  className="flex-1"
+                />
+                <Button
+                  variant="outline"
+                  onClick={async () => {
+                    const path = await pickFile(FIXTURE_FILTERS)
+                    if (path) setDraft({ ...draft, fixture: path })
+                  }}
+                >
+                  <FolderOpen className="h-4 w-4" />
+                </Button>
+              </div>
+            </div>
+          )}
+
+          <div className="flex gap-2">
+            <Button onClick={handleSave} disabled={draft.id.trim() === ''}>
+              Save
+            </Button>
+            {selected && (
+              <>
+                <Button variant="outline" onClick={handleCheck} disabled={checking}>
+                  {checking ? <Loader2 className="h-4 w-4 mr-1 animate-spin" /> : <PlugZap className="h-4 w-4 mr-1" />}
+                  Check
+                </Button>
+                <Button
+                  variant="ghost"
+                  className="text-destructive hover:text-destructive"
+                  onClick={() => setDeleteTarget(selected)}
+                >
+                  {builtin(selected.id) ? <RotateCcw className="h-4 w-4 mr-1" /> : <Trash2 className="h-4 w-4 mr-1" />}
+                  {builtin(selected.id) ? 'Reset' : 'Delete'}
+                </Button>
+              </>
+            )}
+          </div>
+        </div>
+      </CardContent>
+
+      <ConfirmDialog
+        open={deleteTarget !== null}
+        onOpenChange={(open) => !open && setDeleteTarget(null)}
+        title={deleteTarget && builtin(deleteTarget.id) ? 'Reset this provider?' : 'Delete this provider?'}
+        description={
+          deleteTarget && builtin(deleteTarget.id)
+            ? 'Its settings and stored API key are removed and the defaults apply again.'
+            : 'Its settings and stored API key are removed. Runs that used its models keep their results.'
+        }
+        confirmLabel={deleteTarget && builtin(deleteTarget.id) ? 'Reset' : 'Delete'}
+        variant="destructive"
+        onConfirm={() => {
+          if (deleteTarget) void handleDelete(deleteTarget)
+          setDeleteTarget(null)
+        }}
+      />
+    </Card>
+  )
+}
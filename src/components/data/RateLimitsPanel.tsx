@@ -0,0 +1,172 @@
+import { useCallback, useEffect, useState } from 'react'
+import { Gauge, Plus, Trash2 } from 'lucide-react'
+import { Button } from '@/components/ui/button'
+import { Card, CardContent, CardDescription, CardHeader, CardTitle } from '@/components/ui/card'
+import { Input } from '@/components/ui/input'
+import { Badge } from '@/components/ui/badge'
+import { ScrollArea } from '@/components/ui/scroll-area'
+import { useToast } from '@/components/ui/use-toast'
+import { getRateLimits, setRateLimits } from '@/services/localDb'
+import type { RateLimit, RateLimits } from '@/types'
+
+type Scope = keyof RateLimits
+
+interface LimitRow {
+  scope: Scope
+  id: string
+  requestsPerMinute: string
+  tokensPerMinute: string
+}
+
+const scopeLabels: Record<Scope, string> = {
+  providers: 'Provider',
+  models: 'Model',
+}
+
+function toRows(limits: RateLimits): LimitRow[] {
+  return (Object.keys(scopeLabels) as Scope[]).flatMap((scope) =>
+    Object.entries(limits[scope]).map(([id, limit]) => ({
+      scope,
+      id,
+      requestsPerMinute: limit.requestsPerMinute?.toString() ?? '',
+      tokensPerMinute: limit.tokensPerMinute?.toString() ?? '',
+    }))
+  )
+}
+
+// Blank fields mean no limit; anything else must be a positive whole number
+function parseLimit(value: string): number | null | undefined {
+  if (value.trim() === '') return null
+  const parsed = Number(value)
+  return Number.isInteger(parsed) && parsed > 0 ? parsed : undefined
+}
+
+export function RateLimitsPanel() {
+  const [rows, setRows] = useState<LimitRow[]>([])
+  const [saved, setSaved] = useState<LimitRow[]>([])
+  const { toast } = useToast()
+
+  const apply = useCallback((limits: RateLimits | null) => {
+    const found = limits ? toRows(limits) : []
+    setRows(found)
+    setSaved(found)
+  }, [])
+
+  useEffect(() => {
+    void getRateLimits().then(apply)
+  }, [apply])
+
+  const updateRow = (index: number, patch: Partial<LimitRow>) => {
+    setRows(rows.map((row, idx) => (idx === index ? { ...row, ...patch } : row)))
+  }
+
+  const addRow = (scope: Scope) => {
+    setRows([...rows, { scope, id: '', requestsPerMinute: '', tokensPerMinute: '' }])
+  }
+
+  const handleSave = async () => {
+    const limits: RateLimits = { providers: {}, models: {} }
+    for (const row of rows) {
+      const id = row.id.trim()
+      const requestsPerMinute = parseLimit(row.requestsPerMinute)
+      const tokensPerMinute = parseLimit(row.tokensPerMinute)
+      if (!id || requestsPerMinute === undefined || tokensPerMinute === undefined) {
+        toast({
+          title: 'Invalid limit',
+          description: 'Every row needs an id, and limits must be positive whole numbers or blank.',
+          variant: 'destructive',
+        })
+        return
+      }
+      const limit: RateLimit = { requestsPerMinute, tokensPerMinute }
+      limits[row.scope][id] = limit
+    }
+    try {
+      apply(await setRateLimits(limits))
+      toast({ title: 'Rate limits saved', description: 'They apply to runs started from now on.' })
+    } catch (error) {
+      toast({ title: 'Rate limits not saved', description: String(error), variant: 'destructive' })
+    }
+  }
+
+  const changed = JSON.stringify(rows) !== JSON.stringify(saved)
+
+  return (
+    <Card className="h-full min-h-0 flex flex-col">
+      <CardHeader className="pb-3 shrink-0">
+        <div className="flex items-center justify-between">
+          <div>
+            <CardTitle className="text-lg flex items-center gap-2">
+              <Gauge className="h-5 w-5" />
+              Rate Limits
+            </CardTitle>
+            <CardDescription>
+              Requests and tokens per minute; a provider limit is shared by all of its models
+            </CardDescription>
+          </div>
+          <div className="flex gap-2">
+            <Button variant="outline" size="sm" onClick={() => addRow('providers')}>
+              <Plus className="h-4 w-4 mr-1" />
+              Provider
+            </Button>
+            <Button variant="outline" size="sm" onClick={() => addRow('models')}>
+              <Plus className="h-4 w-4 mr-1" />
+              Model
+            </Button>
+            <Button size="sm" onClick={handleSave} disabled={!changed}>
+              Save
+            </Button>
+          </div>
+        </div>
+      </CardHeader>
+
+      <CardContent className="flex-1 min-h-0">
+        <ScrollArea className="h-full pr-2">
+          <div className="space-y-2">
+            {rows.length === 0 ? (
+              <div className="text-center py-8 text-muted-foreground">
+                No limits set. Calls still back off when a provider answers 429.
+              </div>
+            ) : (
+              rows.map((row, index) => (
+                <div key={index} className="flex items-center gap-2 p-3 rounded-lg border border-border/50">
+                  <Badge variant="outline" className="shrink-0 w-20 justify-center">{scopeLabels[row.scope]}</Badge>
+                  <Input
+                    value={row.id}
+                    placeholder={row.scope === 'providers' ? 'openrouter' : 'Model id as used in runs'}
+                    onChange={(e) => updateRow(index, { id: e.target.value })}
+                    className="flex-1"
+                  />
+                  <Input
+                    type="number"
+                    min={1}
+                    value={row.requestsPerMinute}
+                    placeholder="Requests/min"
+                    onChange={(e) => updateRow(index, { requestsPerMinute: e.target.value })}
+                    className="w-32"
+                  />
+                  <Input
+                    type="number"
+                    min={1}
+                    value={row.tokensPerMinute}
+                    placeholder="Tokens/min"
+                    onChange={(e) => updateRow(index, { tokensPerMinute: e.target.value })}
+                    className="w-32"
+                  />
+                  <Button
+                    variant="ghost"
+                    size="sm"
+                    className="text-destructive hover:text-destructive"
+                    onClick={() => setRows(rows.filter((_, idx) => idx !== index))}
+                  >
+                    <Trash2 className="h-4 w-4" />
+                  </Button>
+                </div>
+              ))
+            )}
+          </div>
+        </ScrollArea>
+      </CardContent>
+    </Card>
+  )
+}
@@ -0,0 +1,156 @@
+import { useEffect, useState } from 'react'
+import { Search, FileText, MessageSquare, Loader2 } from 'lucide-react'
+import { Card, CardContent, CardDescription, CardHeader, CardTitle } from '@/components/ui/card'
+import { Input } from '@/components/ui/input'
+import { Badge } from '@/components/ui/badge'
+import { ScrollArea } from '@/components/ui/scroll-area'
+import { useTestSuiteStore } from '@/stores/testSuiteStore'
+import { useRunStore } from '@/stores/runStore'
+import { searchDb } from '@/services/localDb'
+import type { SearchHit, SearchResults } from '@/types'
+
+const SEARCH_DEBOUNCE_MS = 300
+
+// Snippets wrap matched terms in « »
+function Snippet({ text }: { text: string }) {
+  const parts = text.split(/«|»/)
+  return (
+    <span>
+      {parts.map((part, idx) =>
+        idx % 2 === 1 ? (
+          <mark key={idx} className="bg-primary/20 text-foreground rounded px-0.5">{part}</mark>
+        ) : (
+          <span key={idx}>{part}</span>
+        )
+      )}
+    </span>
+  )
+}
+
+function HitRow({ hit, onSelect }: { hit: SearchHit; onSelect: () => void }) {
+  return (
+    <div
+      className="p-3 rounded-lg border border-border/50 hover:bg-muted/50 cursor-pointer transition-colors space-y-1"
+      onClick={onSelect}
+    >
+      <div className="flex items-center gap-2 text-xs text-muted-foreground">
+        <span className="truncate">{hit.testSuiteName ?? 'Deleted suite'}</span>
+        {hit.modelId && (
+          <Badge variant="secondary" className="text-xs font-mono">{hit.modelId.split('/').pop()}</Badge>
+        )}
+        {hit.runStartedAt && (
+          <span className="ml-auto shrink-0">{new Date(hit.runStartedAt).toLocaleString()}</span>
+        )}
+      </div>
+      <div className="text-sm line-clamp-3">
+        <Snippet text={hit.snippet} />
+      </div>
+    </div>
+  )
+}
+
+function HitGroup({ title, icon: Icon, hits, onSelect }: {
+  title: string
+  icon: React.ElementType
+  hits: SearchHit[]
+  onSelect: (hit: SearchHit) => void
+}) {
+  return (
+    <div className="flex-1 min-h-0 flex flex-col gap-2">
+      <div className="flex items-center gap-2 shrink-0">
+        <Icon className="h-4 w-4 text-muted-foreground" />
+        <h4 className="font-medium">{title}</h4>
+        <Badge variant="outline">{hits.length}</Badge>
+      </div>
+      <ScrollArea className="flex-1 pr-2">
+        <div className="space-y-2">
+          {hits.length === 0 ? (
+            <div className="text-center py-8 text-sm text-muted-foreground">No matches</div>
+          ) : (
+            hits.map((hit, idx) => <HitRow key={idx} hit={hit} onSelect={() => onSelect(hit)} />)
+          )}
+        </div>
+      </ScrollArea>
+    </div>
+  )
+}
+
+export function SearchPanel() {
+  const setActiveTestSuite = useTestSuiteStore((state) => state.setActiveTestSuite)
+  const setCurrentRun = useRunStore((state) => state.setCurrentRun)
+
+  const [text, setText] = useState('')
+  const [results, setResults] = useState<SearchResults | null>(null)
+  const [searching, setSearching] = useState(false)
+
+  useEffect(() => {
+    if (!text.trim()) {
+      setResults(null)
+      return
+    }
+
+    let stale = false
+    const timer = window.setTimeout(async () => {
+      setSearching(true)
+      const found = await searchDb({ text })
+      if (!stale) {
+        setResults(found)
+        setSearching(false)
+      }
+    }, SEARCH_DEBOUNCE_MS)
+
+    return () => {
+      stale = true
+      window.clearTimeout(timer)
+    }
+  }, [text])
+
+  // Test case hits open their suite; result hits open their run
+  const handleSelect = (hit: SearchHit) => {
+    if (hit.testSuiteId) {
+      setActiveTestSuite(hit.testSuiteId)
+    }
+    if (hit.runId) {
+      setCurrentRun(hit.runId)
+    }
+  }
+
+  return (
+    <Card className="h-full min-h-0 flex flex-col">
+      <CardHeader className="pb-3 shrink-0">
+        <CardTitle className="text-lg flex items-center gap-2">
+          <Search className="h-5 w-5" />
+          Search
+        </CardTitle>
+        <CardDescription>
+          Find text in prompts, expected outputs, responses and judge notes
+        </CardDescription>
+      </CardHeader>
+
+      <CardContent className="flex-1 min-h-0 flex flex-col gap-4">
+        <div className="relative shrink-0">
+          <Input
+            value={text}
+            onChange={(e) => setText(e.target.value)}
+            placeholder="Search for words; the last one matches as a prefix"
+            className="pr-10"
+          />
+          {searching && (
+            <Loader2 className="absolute right-3 top-3 h-4 w-4 animate-spin text-muted-foreground" />
+          )}
+        </div>
+
+        {results ? (
+          <div className="flex-1 min-h-0 grid md:grid-cols-2 gap-4">
+            <HitGroup title="Test Cases" icon={FileText} hits={results.testCases} onSelect={handleSelect} />
+            <HitGroup title="Results" icon={MessageSquare} hits={results.results} onSelect={handleSelect} />
+          </div>
+        ) : (
+          <div className="text-center py-8 text-muted-foreground">
+            Type to search the database.
+          </div>
+        )}
+      </CardContent>
+    </Card>
+  )
+}
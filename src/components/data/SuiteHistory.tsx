@@ -0,0 +1,133 @@
+import { useEffect, useState } from 'react'
+import { History } from 'lucide-react'
+import { Badge } from '@/components/ui/badge'
+import { ScrollArea } from '@/components/ui/scroll-area'
+import { cn } from '@/lib/utils'
+import { diffSuiteRevisions, getSuiteRevision, listSuiteRevisions } from '@/services/localDb'
+import type { FieldChange, SuiteRevision, SuiteRevisionDiff, SuiteRevisionSummary, TestSuite } from '@/types'
+
+function PromptChange({ label, change }: { label: string; change: FieldChange }) {
+  return (
+    <div className="space-y-1">
+      <div className="text-xs font-medium">{label}</div>
+      <div className="text-xs rounded bg-red-500/10 p-2 whitespace-pre-wrap line-through decoration-red-500/50">
+        {change.before || '(empty)'}
+      </div>
+      <div className="text-xs rounded bg-green-500/10 p-2 whitespace-pre-wrap">{change.after || '(empty)'}</div>
+    </div>
+  )
+}
+
+function DiffView({ diff }: { diff: SuiteRevisionDiff }) {
+  const unchanged = !diff.systemPrompt && !diff.judgeSystemPrompt && diff.added.length === 0
+    && diff.removed.length === 0 && diff.changed.length === 0 && !diff.reordered
+
+  return (
+    <div className="space-y-3">
+      <div className="text-xs text-muted-foreground">
+        Changes from revision {diff.fromRevision} to {diff.toRevision}
+      </div>
+      {unchanged && <div className="text-sm text-muted-foreground">No changes.</div>}
+      {diff.systemPrompt && <PromptChange label="System prompt" change={diff.systemPrompt} />}
+      {diff.judgeSystemPrompt && <PromptChange label="Judge prompt" change={diff.judgeSystemPrompt} />}
+      {diff.added.map((tc) => (
+        <div key={tc.id} className="text-xs rounded bg-green-500/10 p-2 truncate">+ {tc.prompt}</div>
+      ))}
+      {diff.removed.map((tc) => (
+        <div key={tc.id} className="text-xs rounded bg-red-500/10 p-2 truncate">− {tc.prompt}</div>
+      ))}
+      {diff.changed.map((change) => (
+        <div key={change.id} className="text-xs rounded bg-amber-500/10 p-2 space-y-1">
+          <div className="truncate">~ {change.after.prompt}</div>
+          <div className="flex flex-wrap gap-1">
+            {change.changedFields.map((field) => (
+              <Badge key={field} variant="outline" className="text-[10px]">{field}</Badge>
+            ))}
+          </div>
+        </div>
+      ))}
+      {diff.reordered && <div className="text-xs text-muted-foreground">Test cases were reordered.</div>}
+    </div>
+  )
+}
+
+// The first revision has nothing to diff against, so it is shown whole
+function RevisionView({ revision }: { revision: SuiteRevision }) {
+  return (
+    <div className="space-y-3">
+      <div className="text-xs text-muted-foreground">The first recorded revision.</div>
+      <div className="space-y-1">
+        <div className="text-xs font-medium">System prompt</div>
+        <div className="text-xs rounded bg-muted/50 p-2 whitespace-pre-wrap">{revision.systemPrompt || '(empty)'}</div>
+      </div>
+      {revision.testCases.map((tc) => (
+        <div key={tc.id} className="text-xs rounded bg-muted/50 p-2 truncate">{tc.prompt}</div>
+      ))}
+    </div>
+  )
+}
+
+// Revisions are recorded on save, newest first; selecting one shows what it changed
+export function SuiteHistory({ suite }: { suite: TestSuite }) {
+  const [revisions, setRevisions] = useState<SuiteRevisionSummary[]>([])
+  const [selected, setSelected] = useState<number | null>(null)
+  const [diff, setDiff] = useState<SuiteRevisionDiff | null>(null)
+  const [first, setFirst] = useState<SuiteRevision | null>(null)
+
+  useEffect(() => {
+    setSelected(null)
+    void listSuiteRevisions(suite.id).then((found) =>
+      setRevisions([...found].sort((a, b) => b.revision - a.revision))
+    )
+  }, [suite.id, suite.updatedAt])
+
+  useEffect(() => {
+    setDiff(null)
+    setFirst(null)
+    if (selected === null) return
+    if (selected <= 1) {
+      void getSuiteRevision(suite.id, selected).then(setFirst)
+      return
+    }
+    void diffSuiteRevisions(suite.id, selected - 1, selected).then(setDiff)
+  }, [suite.id, selected])
+
+  if (revisions.length === 0) return null
+
+  return (
+    <div className="space-y-2">
+      <div className="flex items-center justify-between">
+        <h4 className="font-medium flex items-center gap-2">
+          <History className="h-4 w-4" />
+          History
+        </h4>
+        <Badge variant="outline">{revisions.length} revisions</Badge>
+      </div>
+      <div className="flex gap-3">
+        <ScrollArea className="h-[160px] w-48 shrink-0">
+          <div className="space-y-1 pr-2">
+            {revisions.map((rev) => (
+              <div
+                key={rev.revision}
+                onClick={() => setSelected(selected === rev.revision ? null : rev.revision)}
+                className={cn(
+                  'p-2 rounded-md border border-border/50 text-xs cursor-pointer hover:bg-muted/50',
+                  selected === rev.revision && 'bg-muted'
+                )}
+              >
+                <div className="font-medium">Revision {rev.revision}</div>
+                <div className="text-muted-foreground">
+                  {rev.testCaseCount} cases · {new Date(rev.createdAt).toLocaleDateString()}
+                </div>
+              </div>
+            ))}
+          </div>
+        </ScrollArea>
+        <ScrollArea className="h-[160px] flex-1">
+          {first && <RevisionView revision={first} />}
+          {diff && <DiffView diff={diff} />}
+        </ScrollArea>
+      </div>
+    </div>
+  )
+}
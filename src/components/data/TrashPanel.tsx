@@ -0,0 +1,171 @@
+import { useCallback, useEffect, useState } from 'react'
+import { Trash2, RotateCcw, FileText, Play, Code2 } from 'lucide-react'
+import { Button } from '@/components/ui/button'
+import { Card, CardContent, CardDescription, CardHeader, CardTitle } from '@/components/ui/card'
+import { Input } from '@/components/ui/input'
+import { Label } from '@/components/ui/label'
+import { ScrollArea } from '@/components/ui/scroll-area'
+import { ConfirmDialog } from '@/components/ui/confirm-dialog'
+import { useToast } from '@/components/ui/use-toast'
+import { listTrash, purgeTrash, restoreFromTrash, setTrashRetentionDays } from '@/services/localDb'
+import type { TrashItem, TrashKind, TrashListing } from '@/types'
+
+const kindIcons: Record<TrashKind, React.ElementType> = {
+  testSuite: FileText,
+  run: Play,
+  codeArenaRun: Code2,
+}
+
+const kindLabels: Record<TrashKind, string> = {
+  testSuite: 'Test suite',
+  run: 'Run',
+  codeArenaRun: 'Code Arena run',
+}
+
+function TrashRow({ item, onRestore, onPurge }: {
+  item: TrashItem
+  onRestore: () => void
+  onPurge: () => void
+}) {
+  const Icon = kindIcons[item.kind]
+  return (
+    <div className="flex items-center gap-3 p-3 rounded-lg border border-border/50">
+      <div className="p-2 rounded-md bg-muted">
+        <Icon className="h-4 w-4 text-muted-foreground" />
+      </div>
+      <div className="flex-1 min-w-0">
+        <div className="font-medium truncate">{item.label}</div>
+        <div className="text-xs text-muted-foreground">
+          {kindLabels[item.kind]} · deleted {new Date(item.deletedAt).toLocaleString()}
+          {item.purgeAt && ` · purged ${new Date(item.purgeAt).toLocaleDateString()}`}
+        </div>
+      </div>
+      <Button variant="outline" size="sm" onClick={onRestore}>
+        <RotateCcw className="h-4 w-4 mr-1" />
+        Restore
+      </Button>
+      <Button variant="ghost" size="sm" className="text-destructive hover:text-destructive" onClick={onPurge}>
+        <Trash2 className="h-4 w-4" />
+      </Button>
+    </div>
+  )
+}
+
+export function TrashPanel() {
+  const [listing, setListing] = useState<TrashListing | null>(null)
+  const [retention, setRetention] = useState('')
+  const [confirmEmpty, setConfirmEmpty] = useState(false)
+  const { toast } = useToast()
+
+  const refresh = useCallback(async () => {
+    const found = await listTrash()
+    setListing(found)
+    if (found) setRetention(String(found.retentionDays))
+  }, [])
+
+  useEffect(() => {
+    void refresh()
+  }, [refresh])
+
+  const handleRestore = async (item: TrashItem) => {
+    const restored = await restoreFromTrash([{ kind: item.kind, id: item.id }])
+    if (restored > 0) {
+      toast({ title: 'Restored', description: item.label })
+    }
+    await refresh()
+  }
+
+  const handlePurge = async (items?: TrashItem[]) => {
+    await purgeTrash(items?.map(({ kind, id }) => ({ kind, id })))
+    await refresh()
+  }
+
+  const handleRetention = async () => {
+    const days = Number.parseInt(retention, 10)
+    if (!Number.isFinite(days) || days < 0) {
+      toast({ title: 'Invalid retention', description: 'Enter 0 or more days.', variant: 'destructive' })
+      return
+    }
+    await setTrashRetentionDays(days)
+    await refresh()
+  }
+
+  const items = listing?.items ?? []
+
+  return (
+    <Card className="h-full min-h-0 flex flex-col">
+      <CardHeader className="pb-3 shrink-0">
+        <div className="flex items-center justify-between">
+          <div>
+            <CardTitle className="text-lg flex items-center gap-2">
+              <Trash2 className="h-5 w-5" />
+              Trash
+            </CardTitle>
+            <CardDescription>
+              Deleted suites and runs stay here until they are purged
+            </CardDescription>
+          </div>
+          <Button
+            variant="outline"
+            size="sm"
+            className="text-destructive hover:text-destructive"
+            disabled={items.length === 0}
+            onClick={() => setConfirmEmpty(true)}
+          >
+            Empty Trash
+          </Button>
+        </div>
+      </CardHeader>
+
+      <CardContent className="flex-1 min-h-0 flex flex-col gap-4">
+        <div className="flex items-end gap-2 shrink-0">
+          <div className="space-y-1">
+            <Label htmlFor="trash-retention">Purge after (days, 0 keeps forever)</Label>
+            <Input
+              id="trash-retention"
+              type="number"
+              min={0}
+              value={retention}
+              onChange={(e) => setRetention(e.target.value)}
+              className="w-40"
+            />
+          </div>
+          <Button
+            variant="outline"
+            onClick={handleRetention}
+            disabled={!listing || retention === String(listing.retentionDays)}
+          >
+            Save
+          </Button>
+        </div>
+
+        <ScrollArea className="flex-1 pr-2">
+          <div className="space-y-2">
+            {items.length === 0 ? (
+              <div className="text-center py-8 text-muted-foreground">The trash is empty.</div>
+            ) : (
+              items.map((item) => (
+                <TrashRow
+                  key={`${item.kind}:${item.id}`}
+                  item={item}
+                  onRestore={() => handleRestore(item)}
+                  onPurge={() => handlePurge([item])}
+                />
+              ))
+            )}
+          </div>
+        </ScrollArea>
+      </CardContent>
+
+      <ConfirmDialog
+        open={confirmEmpty}
+        onOpenChange={setConfirmEmpty}
+        title="Empty trash?"
+        description={`${items.length} item${items.length !== 1 ? 's' : ''} will be deleted permanently.`}
+        confirmLabel="Empty Trash"
+        variant="destructive"
+        onConfirm={() => handlePurge()}
+      />
+    </Card>
+  )
+}
@@ -0,0 +1,242 @@
+import { useState } from 'react'
+import { FileInput, FolderOpen, Loader2 } from 'lucide-react'
+import { Button } from '@/components/ui/button'
+import { Input } from '@/components/ui/input'
+import { Label } from '@/components/ui/label'
+import { ScrollArea } from '@/components/ui/scroll-area'
+import { Tabs, TabsContent, TabsList, TabsTrigger } from '@/components/ui/tabs'
+import {
+  Dialog,
+  DialogContent,
+  DialogDescription,
+  DialogFooter,
+  DialogHeader,
+  DialogTitle,
+  DialogTrigger,
+} from '@/components/ui/dialog'
+import { useToast } from '@/components/ui/use-toast'
+import { useTestSuiteStore } from '@/stores/testSuiteStore'
+import { importLmEvalTask, importOpenAiEvals, importTestCases } from '@/services/localDb'
+import { pickFile } from '@/services/fileDialogs'
+import type { ColumnMapping, ImportTarget, TestCaseImportReport, TestSuite } from '@/types'
+
+type ImportSource = 'dataset' | 'openai-evals' | 'lm-eval'
+
+const DATASET_FILTERS = [{ name: 'Dataset', extensions: ['csv', 'jsonl', 'ndjson', 'json'] }]
+const SAMPLES_FILTERS = [{ name: 'Eval samples', extensions: ['jsonl'] }]
+const TASK_FILTERS = [{ name: 'Task', extensions: ['yaml', 'yml'] }]
+const SPLIT_FILTERS = [{ name: 'Split', extensions: ['jsonl', 'csv'] }]
+
+const mappingFields: { key: keyof ColumnMapping; label: string; placeholder: string }[] = [
+  { key: 'prompt', label: 'Prompt', placeholder: 'question' },
+  { key: 'expectedOutput', label: 'Expected output', placeholder: 'answer' },
+  { key: 'scoringMethod', label: 'Scoring method', placeholder: 'optional' },
+  { key: 'category', label: 'Category', placeholder: 'optional' },
+  { key: 'difficulty', label: 'Difficulty', placeholder: 'optional' },
+  { key: 'tags', label: 'Tags', placeholder: 'optional' },
+]
+
+function FileField({ label, path, onPick }: { label: string; path: string; onPick: () => void }) {
+  return (
+    <div className="space-y-2">
+      <Label>{label}</Label>
+      <div className="flex gap-2">
+        <Input value={path} readOnly placeholder="No file selected" className="flex-1" />
+        <Button variant="outline" onClick={onPick}>
+          <FolderOpen className="h-4 w-4" />
+        </Button>
+      </div>
+    </div>
+  )
+}
+
+export function ImportCasesDialog({ suite }: { suite?: TestSuite }) {
+  const setActiveTestSuite = useTestSuiteStore((state) => state.setActiveTestSuite)
+  const { toast } = useToast()
+
+  const [open, setOpen] = useState(false)
+  const [source, setSource] = useState<ImportSource>('dataset')
+  const [path, setPath] = useState('')
+  const [taskPath, setTaskPath] = useState('')
+  const [limit, setLimit] = useState('')
+  const [mapping, setMapping] = useState<ColumnMapping>({ prompt: 'prompt', expectedOutput: 'expected_output' })
+  const [newSuiteName, setNewSuiteName] = useState('')
+  const [appendToActive, setAppendToActive] = useState(true)
+  const [importing, setImporting] = useState(false)
+  const [report, setReport] = useState<TestCaseImportReport | null>(null)
+
+  const target: ImportTarget = appendToActive && suite
+    ? { mode: 'append', testSuiteId: suite.id }
+    : { mode: 'create', name: newSuiteName.trim() }
+
+  const pick = async (setter: (path: string) => void, filters = DATASET_FILTERS) => {
+    const picked = await pickFile(filters)
+    if (picked) {
+      setter(picked)
+      setReport(null)
+    }
+  }
+
+  const handleImport = async () => {
+    setImporting(true)
+    try {
+      const parsedLimit = Number.parseInt(limit, 10)
+      const found = source === 'dataset'
+        ? await importTestCases({ path, mapping, target })
+        : source === 'openai-evals'
+          ? await importOpenAiEvals({ path, target })
+          : await importLmEvalTask({
+              taskPath,
+              dataPath: path,
+              target,
+              limit: Number.isFinite(parsedLimit) && parsedLimit > 0 ? parsedLimit : undefined,
+            })
+      setReport(found)
+      if (found?.testSuiteId) {
+        setActiveTestSuite(found.testSuiteId)
+      }
+      if (found && found.errors.length === 0) {
+        toast({ title: 'Import finished', description: `${found.imported} test cases imported.` })
+        setOpen(false)
+      }
+    } catch (error) {
+      toast({ title: 'Import failed', description: String(error), variant: 'destructive' })
+    } finally {
+      setImporting(false)
+    }
+  }
+
+  // Benchmark imports name a new suite after the eval or task when left blank
+  const sourceReady = source === 'dataset'
+    ? mapping.prompt.trim() !== '' && (target.mode === 'append' || target.name !== '')
+    : source === 'lm-eval'
+      ? taskPath !== ''
+      : true
+  const canImport = path !== '' && sourceReady
+
+  return (
+    <Dialog open={open} onOpenChange={setOpen}>
+      <DialogTrigger asChild>
+        <Button variant="outline" size="sm" className="gap-2 bg-background/50">
+          <FileInput className="h-4 w-4" />
+          Import Cases
+        </Button>
+      </DialogTrigger>
+      <DialogContent className="max-w-2xl">
+        <DialogHeader>
+          <DialogTitle>Import Test Cases</DialogTitle>
+          <DialogDescription>
+            Read test cases from a dataset, OpenAI Evals samples or an lm-evaluation-harness task. Invalid rows
+            are skipped and listed.
+          </DialogDescription>
+        </DialogHeader>
+
+        <div className="space-y-4">
+          <Tabs
+            value={source}
+            onValueChange={(value) => {
+              setSource(value as ImportSource)
+              setPath('')
+              setReport(null)
+            }}
+          >
+            <TabsList>
+              <TabsTrigger value="dataset">Dataset</TabsTrigger>
+              <TabsTrigger value="openai-evals">OpenAI Evals</TabsTrigger>
+              <TabsTrigger value="lm-eval">lm-eval</TabsTrigger>
+            </TabsList>
+            <TabsContent value="dataset" className="space-y-4">
+              <FileField label="Dataset file" path={path} onPick={() => pick(setPath)} />
+              <div className="space-y-2">
+                <Label>Columns</Label>
+                <div className="grid grid-cols-2 gap-2">
+                  {mappingFields.map(({ key, label, placeholder }) => (
+                    <div key={key} className="flex items-center gap-2">
+                      <span className="text-xs text-muted-foreground w-28 shrink-0">{label}</span>
+                      <Input
+                        value={mapping[key] ?? ''}
+                        placeholder={placeholder}
+                        onChange={(e) => setMapping({ ...mapping, [key]: e.target.value || undefined })}
+                        className="h-8"
+                      />
+                    </div>
+                  ))}
+                </div>
+                <p className="text-xs text-muted-foreground">JSON columns may use dotted paths such as input.question.</p>
+              </div>
+            </TabsContent>
+            <TabsContent value="openai-evals" className="space-y-2">
+              <FileField label="Samples file" path={path} onPick={() => pick(setPath, SAMPLES_FILTERS)} />
+              <p className="text-xs text-muted-foreground">
+                Samples with one ideal answer use exact match; several ideals become a regex match.
+              </p>
+            </TabsContent>
+            <TabsContent value="lm-eval" className="space-y-4">
+              <FileField label="Task YAML" path={taskPath} onPick={() => pick(setTaskPath, TASK_FILTERS)} />
+              <FileField label="Split file" path={path} onPick={() => pick(setPath, SPLIT_FILTERS)} />
+              <div className="space-y-2">
+                <Label htmlFor="lm-eval-limit">Rows to import (blank imports all)</Label>
+                <Input
+                  id="lm-eval-limit"
+                  type="number"
+                  min={1}
+                  value={limit}
+                  onChange={(e) => setLimit(e.target.value)}
+                  className="w-40"
+                />
+              </div>
+            </TabsContent>
+          </Tabs>
+
+          <div className="space-y-2">
+            <Label>Add to</Label>
+            <div className="flex gap-2">
+              {suite && (
+                <Button variant={appendToActive ? 'default' : 'outline'} size="sm" onClick={() => setAppendToActive(true)}>
+                  {suite.name}
+                </Button>
+              )}
+              <Button variant={!appendToActive || !suite ? 'default' : 'outline'} size="sm" onClick={() => setAppendToActive(false)}>
+                New suite
+              </Button>
+            </div>
+            {(!appendToActive || !suite) && (
+              <Input
+                value={newSuiteName}
+                onChange={(e) => setNewSuiteName(e.target.value)}
+                placeholder={source === 'dataset' ? 'Suite name' : 'Suite name (defaults to the source name)'}
+              />
+            )}
+          </div>
+
+          {report && report.errors.length > 0 && (
+            <div className="space-y-1">
+              <div className="text-sm">
+                {report.imported} imported, {report.skipped} skipped:
+              </div>
+              <ScrollArea className="h-[120px] border rounded-lg">
+                <div className="p-2 space-y-1">
+                  {report.errors.map((error, idx) => (
+                    <div key={idx} className="text-xs text-destructive">
+                      Row {error.row}: {error.message}
+                    </div>
+                  ))}
+                </div>
+              </ScrollArea>
+            </div>
+          )}
+        </div>
+
+        <DialogFooter>
+          <Button variant="ghost" onClick={() => setOpen(false)}>
+            Close
+          </Button>
+          <Button onClick={handleImport} disabled={!canImport || importing}>
+            {importing && <Loader2 className="h-4 w-4 mr-2 animate-spin" />}
+            Import
+          </Button>
+        </DialogFooter>
+      </DialogContent>
+    </Dialog>
+  )
+}
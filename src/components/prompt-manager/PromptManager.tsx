@@ -22,6 +22,8 @@ import { TestCaseList } from './TestCaseList'
 import { TestSuiteSelector } from './TestSuiteSelector'
 import { TestCaseGeneratorDialog } from './TestCaseGeneratorDialog'
 import { BenchmarkGeneratorDialog } from './BenchmarkGeneratorDialog'
+import { SuiteBundleActions } from './SuiteBundleActions'
+import { ImportCasesDialog } from './ImportCasesDialog'
 
 export function PromptManager() {
   const { testSuites, activeTestSuiteId, createTestSuite } = useTestSuiteStore()
@@ -141,6 +143,8 @@ export function PromptManager() {
         <div className="flex flex-col gap-2 sm:gap-3 sm:flex-row sm:items-center shrink-0">
           {activeTestSuite && <TestCaseGeneratorDialog testSuite={activeTestSuite} />}
           <BenchmarkGeneratorDialog />
+          <ImportCasesDialog suite={activeTestSuite} />
+          <SuiteBundleActions suite={activeTestSuite} />
           <Dialog open={dialogOpen} onOpenChange={setDialogOpen}>
             <DialogTrigger asChild>
               <Button variant="outline" size="sm" className="gap-2 bg-background/50">
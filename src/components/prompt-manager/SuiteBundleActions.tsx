@@ -0,0 +1,94 @@
+import { Download, Upload, ChevronDown } from 'lucide-react'
+import { Button } from '@/components/ui/button'
+import {
+  DropdownMenu,
+  DropdownMenuContent,
+  DropdownMenuItem,
+  DropdownMenuTrigger,
+} from '@/components/ui/dropdown-menu'
+import { useToast } from '@/components/ui/use-toast'
+import { useTestSuiteStore } from '@/stores/testSuiteStore'
+import { exportTestSuite, importTestSuite } from '@/services/localDb'
+import { pickFile, pickSavePath } from '@/services/fileDialogs'
+import type { CollisionStrategy, SuiteImportReport, TestSuite } from '@/types'
+
+const BUNDLE_FILTERS = [{ name: 'Suite bundle', extensions: ['json', 'yaml', 'yml'] }]
+
+const importOptions: { strategy: CollisionStrategy; label: string }[] = [
+  { strategy: 'rename', label: 'Keep both if it exists' },
+  { strategy: 'overwrite', label: 'Replace it if it exists' },
+  { strategy: 'keep', label: 'Skip it if it exists' },
+]
+
+function describeImport(report: SuiteImportReport): string {
+  const outcome = {
+    created: 'Imported',
+    renamed: 'Imported as a copy',
+    overwritten: 'Replaced',
+    kept: 'Already present, left unchanged:',
+  }[report.outcome]
+  const notes = [
+    !report.hashVerified && 'the bundle was edited after export',
+    ...report.warnings,
+  ].filter(Boolean)
+  return `${outcome} "${report.testSuiteName}"${notes.length > 0 ? ` (${notes.join('; ')})` : ''}`
+}
+
+export function SuiteBundleActions({ suite }: { suite?: TestSuite }) {
+  const setActiveTestSuite = useTestSuiteStore((state) => state.setActiveTestSuite)
+  const { toast } = useToast()
+
+  const handleExport = async () => {
+    if (!suite) return
+    const path = await pickSavePath(`${suite.name}.json`, BUNDLE_FILTERS)
+    if (!path) return
+
+    try {
+      await exportTestSuite(suite.id, path)
+      toast({ title: 'Suite exported', description: path })
+    } catch (error) {
+      toast({ title: 'Export failed', description: String(error), variant: 'destructive' })
+    }
+  }
+
+  const handleImport = async (strategy: CollisionStrategy) => {
+    const path = await pickFile(BUNDLE_FILTERS)
+    if (!path) return
+
+    try {
+      const report = await importTestSuite(path, strategy)
+      if (!report) return
+      setActiveTestSuite(report.testSuiteId)
+      toast({ title: 'Suite imported', description: describeImport(report) })
+    } catch (error) {
+      toast({ title: 'Import failed', description: String(error), variant: 'destructive' })
+    }
+  }
+
+  return (
+    <div className="flex gap-2">
+      {suite && (
+        <Button variant="outline" size="sm" className="gap-2 bg-background/50" onClick={handleExport}>
+          <Download className="h-4 w-4" />
+          Export
+        </Button>
+      )}
+      <DropdownMenu>
+        <DropdownMenuTrigger asChild>
+          <Button variant="outline" size="sm" className="gap-2 bg-background/50">
+            <Upload className="h-4 w-4" />
+            Import
+            <ChevronDown className="h-3 w-3" />
+          </Button>
+        </DropdownMenuTrigger>
+        <DropdownMenuContent align="end">
+          {importOptions.map(({ strategy, label }) => (
+            <DropdownMenuItem key={strategy} onClick={() => handleImport(strategy)}>
+              {label}
+            </DropdownMenuItem>
+          ))}
+        </DropdownMenuContent>
+      </DropdownMenu>
+    </div>
+  )
+}
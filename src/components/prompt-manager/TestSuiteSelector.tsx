@@ -72,8 +72,8 @@ export function TestSuiteSelector() {
             <DialogHeader>
               <DialogTitle>Delete Test Suite</DialogTitle>
               <DialogDescription>
-                Are you sure you want to delete "{activeTestSuite.name}"? This action
-                cannot be undone.
+                Are you sure you want to delete "{activeTestSuite.name}"? It moves to the
+                trash in the Data view, where it can be restored.
               </DialogDescription>
             </DialogHeader>
             <DialogFooter>
@@ -1,4 +1,4 @@
-import { useState, useMemo } from 'react'
+import { useEffect, useState, useMemo } from 'react'
 import { BarChart3, TrendingUp, AlertCircle } from 'lucide-react'
 import { Card, CardContent, CardHeader, CardTitle } from '@/components/ui/card'
 import { Button } from '@/components/ui/button'
@@ -10,6 +10,7 @@ import {
 } from '@/components/ui/collapsible'
 import { useRunStore, type ModelComparison } from '@/stores/runStore'
 import { useTestSuiteStore } from '@/stores/testSuiteStore'
+import { loadRunResults } from '@/services/localDb'
 import type { RunResult } from '@/types'
 
 interface MultiRunAnalysisProps {
@@ -23,6 +24,13 @@ export function MultiRunAnalysis({ currentRun }: MultiRunAnalysisProps) {
 
   const testSuite = testSuites.find((s) => s.id === currentRun.testSuiteId)
   const relatedRuns = getRunsForTestSuite(currentRun.testSuiteId)
+  const relatedRunKey = relatedRuns.map((r) => r.id).join(',')
+
+  useEffect(() => {
+    if (relatedRunKey) {
+      void loadRunResults(relatedRunKey.split(','))
+    }
+  }, [relatedRunKey])
 
   // Only show if there are multiple completed runs
   if (relatedRuns.length < 2) {
@@ -62,6 +62,11 @@ export function ResponseCell({ result }: ResponseCellProps) {
             {(result.latencyMs / 1000).toFixed(1)}s
           </Badge>
         )}
+        {result.cached && (
+          <Badge variant="outline" className="text-xs" title="Served from the response cache">
+            Cached
+          </Badge>
+        )}
         {result.score && (
           <Badge
             variant={result.score.score >= 0.7 ? 'default' : 'secondary'}
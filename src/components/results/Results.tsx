@@ -1,4 +1,4 @@
-import { useState } from 'react'
+import { useEffect, useState } from 'react'
 import { History, Trash2, Play } from 'lucide-react'
 import { Button } from '@/components/ui/button'
 import { EmptyState } from '@/components/ui/empty-state'
@@ -14,9 +14,11 @@ import {
 import { useRunStore } from '@/stores/runStore'
 import { useTestSuiteStore } from '@/stores/testSuiteStore'
 import { useSettingsStore } from '@/stores/settingsStore'
+import { loadRunResults } from '@/services/localDb'
 import { ComparisonGrid } from './ComparisonGrid'
 import { ReportSummary } from './ReportSummary'
 import { MultiRunAnalysis } from './MultiRunAnalysis'
+import { RunExportActions } from './RunExportActions'
 
 export function Results() {
   const { runs, currentRunId, setCurrentRun, deleteRun } = useRunStore()
@@ -26,6 +28,12 @@ export function Results() {
 
   const currentRun = runs.find((r) => r.id === currentRunId)
 
+  useEffect(() => {
+    if (currentRunId) {
+      void loadRunResults([currentRunId])
+    }
+  }, [currentRunId])
+
   const hasApiKey = Boolean(apiKey)
   const hasTestSuites = testSuites.length > 0
   const hasTestCases = testSuites.some((s) => s.testCases.length > 0)
@@ -97,6 +105,8 @@ export function Results() {
             </SelectContent>
           </Select>
 
+          {currentRun && <RunExportActions run={currentRun} />}
+
           {currentRunId && (
             <Button
               variant="ghost"
@@ -141,7 +151,7 @@ export function Results() {
         open={deleteDialogOpen}
         onOpenChange={setDeleteDialogOpen}
         title="Delete Benchmark Run"
-        description="Are you sure you want to delete this benchmark run? This action cannot be undone."
+        description="Are you sure you want to delete this benchmark run? It moves to the trash in the Data view, where it can be restored."
         confirmLabel="Delete"
         variant="destructive"
         onConfirm={() => {
@@ -0,0 +1,77 @@
+import { Download, ChevronDown, FileText } from 'lucide-react'
+import { Button } from '@/components/ui/button'
+import {
+  DropdownMenu,
+  DropdownMenuContent,
+  DropdownMenuItem,
+  DropdownMenuSeparator,
+  DropdownMenuTrigger,
+} from '@/components/ui/dropdown-menu'
+import { useToast } from '@/components/ui/use-toast'
+import { exportRuns, generateReport } from '@/services/localDb'
+import { pickSavePath } from '@/services/fileDialogs'
+import type { RunResult } from '@/types'
+
+const EXPORT_FILTERS = [{ name: 'Results', extensions: ['csv', 'jsonl', 'parquet'] }]
+const REPORT_FILTERS = [
+  { name: 'HTML report', extensions: ['html'] },
+  { name: 'Markdown report', extensions: ['md'] },
+]
+
+interface RunExportActionsProps {
+  run: RunResult
+}
+
+export function RunExportActions({ run }: RunExportActionsProps) {
+  const { toast } = useToast()
+
+  const handleExport = async (runIds?: string[]) => {
+    const defaultName = runIds ? `${run.testSuiteName} results.csv` : 'benchmark results.csv'
+    const path = await pickSavePath(defaultName, EXPORT_FILTERS)
+    if (!path) return
+
+    try {
+      const report = await exportRuns({ path, runIds })
+      if (!report) return
+      toast({
+        title: 'Results exported',
+        description: `${report.rows} rows from ${report.runs} run${report.runs !== 1 ? 's' : ''} written to ${report.path}`,
+      })
+    } catch (error) {
+      toast({ title: 'Export failed', description: String(error), variant: 'destructive' })
+    }
+  }
+
+  const handleReport = async () => {
+    const path = await pickSavePath(`${run.testSuiteName} report.html`, REPORT_FILTERS)
+    if (!path) return
+
+    try {
+      await generateReport(run.id, path)
+      toast({ title: 'Report saved', description: path })
+    } catch (error) {
+      toast({ title: 'Report failed', description: String(error), variant: 'destructive' })
+    }
+  }
+
+  return (
+    <DropdownMenu>
+      <DropdownMenuTrigger asChild>
+        <Button variant="outline" size="sm" className="gap-2">
+          <Download className="h-4 w-4" />
+          Export
+          <ChevronDown className="h-3 w-3" />
+        </Button>
+      </DropdownMenuTrigger>
+      <DropdownMenuContent align="end">
+        <DropdownMenuItem onClick={() => handleExport([run.id])}>This run</DropdownMenuItem>
+        <DropdownMenuItem onClick={() => handleExport()}>All runs</DropdownMenuItem>
+        <DropdownMenuSeparator />
+        <DropdownMenuItem onClick={handleReport}>
+          <FileText className="h-4 w-4 mr-2" />
+          Shareable report
+        </DropdownMenuItem>
+      </DropdownMenuContent>
+    </DropdownMenu>
+  )
+}
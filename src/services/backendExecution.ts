@@ -0,0 +1,171 @@
+import { useRunStore } from '@/stores/runStore'
+import { flushLocalDb, listProviders, markBackendRun } from './localDb'
+import type {
+  BackendRunRequest,
+  CassetteMode,
+  OpenRouterModel,
+  ProviderModel,
+  RunFinishedEvent,
+  RunProgressEvent,
+  RunQueueEvent,
+  RunResult,
+  RunResultEvent,
+} from '@/types'
+
+export function isBackendExecutionAvailable(): boolean {
+  return typeof window !== 'undefined' && '__TAURI__' in window
+}
+
+// Resolvers for runs started from this window, and finish events that
+// arrived before their resolver was registered
+const finishWaiters = new Map<string, (event: RunFinishedEvent) => void>()
+const earlyFinishes = new Map<string, RunFinishedEvent>()
+let listening: Promise<void> | null = null
+
+/**
+ * Mirrors backend run events into the run store. Installed once at startup,
+ * so runs that were started before a reload keep updating.
+ */
+export function listenToBackendRuns(): Promise<void> {
+  if (!isBackendExecutionAvailable()) return Promise.resolve()
+
+  if (!listening) {
+    listening = (async () => {
+      const { listen } = await import('@tauri-apps/api/event')
+
+      await listen<RunProgressEvent>('run-progress', ({ payload }) => {
+        markBackendRun(payload.runId)
+        useRunStore.getState().updateResult(payload.runId, payload.testCaseId, payload.modelId, {
+          status: 'running',
+          streamedContent: payload.content,
+        })
+      })
+
+      await listen<RunResultEvent>('run-result', ({ payload }) => {
+        markBackendRun(payload.runId)
+        const { result } = payload
+        useRunStore.getState().updateResult(payload.runId, result.testCaseId, result.modelId, {
+          ...result,
+          streamedContent: result.response,
+        })
+      })
+
+      await listen<RunQueueEvent>('run-queue', ({ payload }) => {
+        useRunStore.getState().setQueueState(payload.runId, payload.queue)
+      })
+
+      await listen<RunFinishedEvent>('run-finished', ({ payload }) => {
+        markBackendRun(payload.runId)
+        const { upsertRun, updateRunStatus, setQueueState } = useRunStore.getState()
+        setQueueState(payload.runId, null)
+        if (payload.run) {
+          upsertRun(payload.run)
+        } else {
+          updateRunStatus(payload.runId, 'failed')
+        }
+
+        const waiter = finishWaiters.get(payload.runId)
+        if (waiter) {
+          finishWaiters.delete(payload.runId)
+          waiter(payload)
+        } else {
+          earlyFinishes.set(payload.runId, payload)
+        }
+      })
+    })()
+  }
+
+  return listening
+}
+
+/**
+ * Starts a run in the backend and resolves with the saved run once it
+ * finishes. The run keeps going if the window reloads; `onStarted` receives
+ * the pending run as soon as it exists. With `cassette`, model calls are
+ * recorded in the app database or replayed from it; a replay fails on judge
+ * grades that were not recorded unless `allowLiveJudge` is set.
+ */
+export async function executeRunInBackend(
+  request: BackendRunRequest,
+  apiKey: string | null,
+  onStarted?: (run: RunResult) => void,
+  cassette?: CassetteMode,
+  allowLiveJudge?: boolean
+): Promise<RunResult> {
+  await listenToBackendRuns()
+  // The backend reads the suite from the database
+  await flushLocalDb()
+
+  const { invoke } = await import('@tauri-apps/api/tauri')
+  const run = await invoke<RunResult>('start_run', {
+    request,
+    apiKey,
+    cassette: cassette ?? null,
+    allowLiveJudge: allowLiveJudge ?? null,
+  })
+
+  const { upsertRun, setCurrentRun } = useRunStore.getState()
+  markBackendRun(run.id)
+  upsertRun(run)
+  setCurrentRun(run.id)
+  onStarted?.(run)
+
+  const finished = await new Promise<RunFinishedEvent>((resolve) => {
+    const early = earlyFinishes.get(run.id)
+    if (early) {
+      earlyFinishes.delete(run.id)
+      resolve(early)
+    } else {
+      finishWaiters.set(run.id, resolve)
+    }
+  })
+
+  if (!finished.run) {
+    throw new Error(finished.error || 'Run failed')
+  }
+  return finished.run
+}
+
+/**
+ * Models of one backend provider, shaped like OpenRouter models so they can
+ * sit in the same picker. Throws when the provider is not set up or can't be
+ * reached.
+ */
+export async function listProviderModels(providerId: string): Promise<OpenRouterModel[]> {
+  if (!isBackendExecutionAvailable()) return []
+
+  const { invoke } = await import('@tauri-apps/api/tauri')
+  const models = await invoke<ProviderModel[]>('list_provider_models', { providerId })
+  return models.map((model) => ({
+    id: model.id,
+    name: model.name,
+    description: model.description ?? undefined,
+    context_length: model.contextLength ?? 0,
+    pricing: model.pricing ?? { prompt: '0', completion: '0' },
+  }))
+}
+
+/**
+ * Models of every configured provider except OpenRouter, whose catalogue the
+ * app fetches itself. Providers that can't list their models (no key, server
+ * not running) are skipped, so mock models work offline.
+ */
+export async function listBackendModels(): Promise<OpenRouterModel[]> {
+  if (!isBackendExecutionAvailable()) return []
+
+  const providers = await listProviders()
+  const lists = await Promise.all(
+    providers
+      .filter((provider) => provider.id !== 'openrouter')
+      .map((provider) => listProviderModels(provider.id).catch(() => []))
+  )
+  return lists.flat()
+}
+
+// Stops the run from starting new cases; requests in flight still finish
+export async function cancelBackendRun(runId: string): Promise<boolean> {
+  if (!isBackendExecutionAvailable()) return false
+
+  const { invoke } = await import('@tauri-apps/api/tauri')
+  return invoke<boolean>('cancel_run', { runId })
+}
@@ -2,6 +2,7 @@ import { getOpenRouterClient, type StreamResult } from './openrouter'
 import { useRunStore } from '@/stores/runStore'
 import { useModelStore } from '@/stores/modelStore'
 import { scoreResponse } from '@/scoring'
+import { flushLocalDb, upsertTestCaseResult } from './localDb'
 import type { TestSuite, TestCaseResult, ChatMessage, ModelParameters, OpenRouterModel } from '@/types'
 
 function calculateCost(
@@ -41,6 +42,7 @@ export async function executeRun(
       const initialResult: TestCaseResult = {
         testCaseId: testCase.id,
         modelId,
+        provider: 'openrouter',
         response: '',
         status: 'idle',
         streamedContent: '',
@@ -49,6 +51,17 @@ export async function executeRun(
     }
   }
 
+  // Save the run with its pending results so each result can be saved on its own
+  await flushLocalDb()
+  const saveResult = (testCaseId: string, modelId: string) => {
+    const result = useRunStore.getState().getRunById(runId)?.results.find(
+      (r) => r.testCaseId === testCaseId && r.modelId === modelId
+    )
+    if (result) {
+      void upsertTestCaseResult(runId, result)
+    }
+  }
+
   // Execute all combinations in parallel batches
   const concurrencyLimit = 5 // Limit concurrent requests
   const tasks: Array<() => Promise<void>> = []
@@ -114,9 +127,11 @@ export async function executeRun(
           )
 
           setResultScore(runId, testCase.id, modelId, score)
+          saveResult(testCase.id, modelId)
         } catch (error) {
           if (error instanceof DOMException && error.name === 'AbortError') {
             updateResult(runId, testCase.id, modelId, { status: 'cancelled' })
+            saveResult(testCase.id, modelId)
             throw error
           }
 
@@ -124,6 +139,7 @@ export async function executeRun(
             status: 'failed',
             error: error instanceof Error ? error.message : 'Unknown error',
           })
+          saveResult(testCase.id, modelId)
         }
       })
     }
@@ -0,0 +1,26 @@
+// Native file pickers for commands that read or write files by path.
+// Both resolve to null when the picker is cancelled or outside the desktop app.
+
+export interface FileFilter {
+  name: string
+  extensions: string[]
+}
+
+function isTauriRuntime(): boolean {
+  return typeof window !== 'undefined' && '__TAURI__' in window
+}
+
+export async function pickFile(filters: FileFilter[]): Promise<string | null> {
+  if (!isTauriRuntime()) return null
+
+  const { open } = await import('@tauri-apps/api/dialog')
+  const selected = await open({ multiple: false, directory: false, filters })
+  return typeof selected === 'string' ? selected : null
+}
+
+export async function pickSavePath(defaultPath: string, filters: FileFilter[]): Promise<string | null> {
+  if (!isTauriRuntime()) return null
+
+  const { save } = await import('@tauri-apps/api/dialog')
+  return await save({ defaultPath, filters })
+}
@@ -1,4 +1,42 @@
-import type { BenchmakerDb } from '@/types'
+import type {
+  BackupInfo,
+  BackupListing,
+  BackupSettings,
+  BenchmakerDb,
+  BundleFormat,
+  CacheSettings,
+  CodeArenaRun,
+  CacheStatus,
+  CollisionStrategy,
+  DatabaseCheckReport,
+  DatabaseRepairReport,
+  LmEvalImportRequest,
+  OpenAiEvalsImportRequest,
+  ProviderConfig,
+  ProviderListing,
+  QuarantinedRow,
+  RateLimits,
+  ReportFormat,
+  RunExportReport,
+  RunExportRequest,
+  RunListPage,
+  RunListQuery,
+  RunResult,
+  RunSummary,
+  SearchResults,
+  SearchQuery,
+  SuiteImportReport,
+  SuiteRevision,
+  SuiteRevisionDiff,
+  SuiteRevisionSummary,
+  TestSuite,
+  TestCaseImportReport,
+  TestCaseImportRequest,
+  TestCaseResult,
+  TrashKind,
+  TrashListing,
+  TrashRef,
+} from '@/types'
 import { useTestSuiteStore } from '@/stores/testSuiteStore'
 import { useRunStore } from '@/stores/runStore'
 import { useCodeArenaRunStore } from '@/stores/codeArenaRunStore'
@@ -9,7 +47,24 @@ const WRITE_THROTTLE_MS = 300
 let initialized = false
 let writeTimer: number | null = null
 let writeInFlight = false
-let pendingSnapshot: BenchmakerDb | null = null
+let writePending = false
+let allowMassDeleteOnNextWrite = false
+let hydrating = false
+// Ids the database holds live, per kind. Only these can be reported as deleted,
+// so items the stores never loaded are never trashed by a write.
+const persistedIds: Record<TrashKind, Set<string>> = {
+  testSuite: new Set(),
+  run: new Set(),
+  codeArenaRun: new Set(),
+}
+// Runs executed by the backend, which saves them itself; the stores only mirror them
+const backendRunIds = new Set<string>()
+// Ids changed in the stores since they were last written; writes send only these
+const dirtyIds: Record<TrashKind, Set<string>> = {
+  testSuite: new Set(),
+  run: new Set(),
+  codeArenaRun: new Set(),
+}
 
 function isTauriRuntime(): boolean {
   return typeof window !== 'undefined' && '__TAURI__' in window
@@ -51,18 +106,97 @@ export async function initLocalDb(): Promise<void> {
 
   initialized = true
 
-  const existing = await readLocalDb()
-  if (existing) {
-    hydrateFromDb(existing)
-  } else {
-    await writeLocalDb(buildSnapshot())
+  await reloadFromDb()
+
+  useTestSuiteStore.subscribe((state, prev) => {
+    trackChanges('testSuite', state.testSuites, prev.testSuites)
+    scheduleWrite()
+  })
+  useRunStore.subscribe((state, prev) => {
+    trackChanges('run', state.runs, prev.runs, (run, before) => backendRunIds.has(run.id) || onlyResultsChanged(run, before))
+    scheduleWrite()
+  })
+  useCodeArenaRunStore.subscribe((state, prev) => {
+    trackChanges('codeArenaRun', state.runs, prev.runs)
+    scheduleWrite()
+  })
+}
+
+type AppStateRow = Pick<BenchmakerDb, 'activeTestSuiteId' | 'currentRunId' | 'currentCodeArenaRunId'>
+
+// Placeholder for a run listed in history; its results are fetched by loadRunResults
+function runFromSummary(summary: RunSummary): RunResult {
+  return {
+    id: summary.id,
+    testSuiteId: summary.testSuiteId,
+    testSuiteName: summary.testSuiteName,
+    testSuiteRevision: summary.testSuiteRevision,
+    models: summary.models,
+    parameters: summary.parameters,
+    results: [],
+    status: summary.status,
+    startedAt: summary.startedAt,
+    completedAt: summary.completedAt,
+    judgeModel: summary.judgeModel,
+    errorCount: summary.errorCount,
+    errorSummary: summary.errorSummary,
+    summary,
   }
+}
 
-  useTestSuiteStore.subscribe(() => scheduleWrite())
-  useRunStore.subscribe(() => scheduleWrite())
-  useCodeArenaRunStore.subscribe(() => scheduleWrite())
+async function listAllRunSummaries(): Promise<RunSummary[]> {
+  const summaries: RunSummary[] = []
+  for (;;) {
+    const page = await tauriInvoke<RunListPage>('list_runs', { query: { limit: 500, offset: summaries.length } })
+    summaries.push(...page.runs)
+    if (page.runs.length === 0 || summaries.length >= page.total) return summaries
+  }
 }
 
+// Replaces the stores with what the database holds. Suites and code arena runs
+// load in full; runs load as summaries, except the current one.
+export async function reloadFromDb(): Promise<void> {
+  if (typeof window === 'undefined' || !isTauriRuntime()) return
+
+  try {
+    const [testSuites, summaries, codeArenaRuns, appState] = await Promise.all([
+      tauriInvoke<TestSuite[]>('get_all_test_suites'),
+      listAllRunSummaries(),
+      tauriInvoke<CodeArenaRun[]>('get_all_code_arena_runs'),
+      tauriInvoke<AppStateRow>('get_app_state'),
+    ])
+    const currentRun = appState.currentRunId ? await getRun(appState.currentRunId) : null
+    const runs = summaries.map((summary) => (summary.id === currentRun?.id ? currentRun : runFromSummary(summary)))
+
+    hydrateFromDb({
+      version: DB_VERSION,
+      updatedAt: Date.now(),
+      testSuites,
+      runs,
+      codeArenaRuns,
+      activeTestSuiteId: appState.activeTestSuiteId ?? null,
+      currentRunId: appState.currentRunId ?? null,
+      currentCodeArenaRunId: appState.currentCodeArenaRunId ?? null,
+    })
+  } catch (error) {
+    console.error('Failed to read from SQLite database:', error)
+  }
+}
+
+// Fetches the results of runs that are only loaded as history summaries
+export async function loadRunResults(runIds: string[]): Promise<void> {
+  const pending = useRunStore.getState().runs.filter((run) => run.summary && runIds.includes(run.id))
+  for (const stub of pending) {
+    const run = await getRun(stub.id)
+    // Skip runs deleted or replaced while the request was in flight
+    const current = useRunStore.getState().runs.find((r) => r.id === stub.id)
+    if (run && current?.summary) {
+      useRunStore.getState().upsertRun(run)
+    }
+  }
+}
+
+// Full copy of the database, including results of runs the stores haven't loaded
 export async function readLocalDb(): Promise<BenchmakerDb | null> {
   if (typeof window === 'undefined' || !isTauriRuntime()) return null
 
@@ -75,16 +209,426 @@ export async function readLocalDb(): Promise<BenchmakerDb | null> {
   }
 }
 
-export async function writeLocalDb(snapshot: BenchmakerDb): Promise<void> {
+export interface WriteOptions {
+  // Required when the snapshot intentionally drops most suites or runs
+  allowMassDelete?: boolean
+}
+
+export async function writeLocalDb(snapshot: BenchmakerDb, options: WriteOptions = {}): Promise<void> {
   if (typeof window === 'undefined' || !isTauriRuntime()) return
 
   try {
-    await tauriInvoke<void>('write_snapshot', { snapshot })
+    await tauriInvoke<void>('write_snapshot', { snapshot, allowMassDelete: options.allowMassDelete ?? false })
+    markPersisted(snapshot)
   } catch (error) {
+    markUnsaved(snapshot)
     console.error('Failed to write to SQLite database:', error)
   }
 }
 
+// Writes pending store changes now instead of waiting for the throttle, for
+// backend commands that read what the UI just edited
+export async function flushLocalDb(): Promise<void> {
+  if (typeof window === 'undefined' || !isTauriRuntime() || !initialized) return
+
+  if (writeTimer !== null) {
+    window.clearTimeout(writeTimer)
+    writeTimer = null
+  }
+  const allowMassDelete = allowMassDeleteOnNextWrite
+  allowMassDeleteOnNextWrite = false
+  await writeLocalDb(buildWriteSnapshot(), { allowMassDelete })
+}
+
+export async function upsertTestCaseResult(runId: string, result: TestCaseResult): Promise<void> {
+  if (typeof window === 'undefined' || !isTauriRuntime()) return
+
+  try {
+    await tauriInvoke<void>('upsert_test_case_result', { runId, result })
+  } catch (error) {
+    console.error('Failed to save test case result:', error)
+  }
+}
+
+export async function listRuns(query: RunListQuery = {}): Promise<RunListPage | null> {
+  if (typeof window === 'undefined' || !isTauriRuntime()) return null
+
+  try {
+    return await tauriInvoke<RunListPage>('list_runs', { query })
+  } catch (error) {
+    console.error('Failed to list runs:', error)
+    return null
+  }
+}
+
+export async function getRun(id: string): Promise<RunResult | null> {
+  if (typeof window === 'undefined' || !isTauriRuntime()) return null
+
+  try {
+    return await tauriInvoke<RunResult | null>('get_run', { id })
+  } catch (error) {
+    console.error('Failed to load run:', error)
+    return null
+  }
+}
+
+export async function searchDb(query: SearchQuery): Promise<SearchResults> {
+  const empty: SearchResults = { testCases: [], results: [] }
+  if (typeof window === 'undefined' || !isTauriRuntime() || !query.text.trim()) return empty
+
+  try {
+    return await tauriInvoke<SearchResults>('search', { query })
+  } catch (error) {
+    console.error('Failed to search database:', error)
+    return empty
+  }
+}
+
+export async function listSuiteRevisions(testSuiteId: string): Promise<SuiteRevisionSummary[]> {
+  if (typeof window === 'undefined' || !isTauriRuntime()) return []
+
+  try {
+    return await tauriInvoke<SuiteRevisionSummary[]>('list_suite_revisions', { testSuiteId })
+  } catch (error) {
+    console.error('Failed to list suite revisions:', error)
+    return []
+  }
+}
+
+export async function getSuiteRevision(testSuiteId: string, revision: number): Promise<SuiteRevision | null> {
+  if (typeof window === 'undefined' || !isTauriRuntime()) return null
+
+  try {
+    return await tauriInvoke<SuiteRevision | null>('get_suite_revision', { testSuiteId, revision })
+  } catch (error) {
+    console.error('Failed to load suite revision:', error)
+    return null
+  }
+}
+
+export async function diffSuiteRevisions(
+  testSuiteId: string,
+  fromRevision: number,
+  toRevision: number
+): Promise<SuiteRevisionDiff | null> {
+  if (typeof window === 'undefined' || !isTauriRuntime()) return null
+
+  try {
+    return await tauriInvoke<SuiteRevisionDiff>('diff_suite_revisions', { testSuiteId, fromRevision, toRevision })
+  } catch (error) {
+    console.error('Failed to diff suite revisions:', error)
+    return null
+  }
+}
+
+// Format defaults to the file extension (.yaml/.yml, otherwise JSON)
+export async function exportTestSuite(id: string, path: string, format?: BundleFormat): Promise<void> {
+  if (typeof window === 'undefined' || !isTauriRuntime()) return
+
+  // Pending edits are saved first so the bundle matches what is on screen
+  await flushLocalDb()
+  await tauriInvoke<void>('export_test_suite', { id, path, format: format ?? null })
+}
+
+// Throws with the validation message when the bundle is rejected
+export async function importTestSuite(
+  path: string,
+  onConflict: CollisionStrategy = 'rename'
+): Promise<SuiteImportReport | null> {
+  if (typeof window === 'undefined' || !isTauriRuntime()) return null
+
+  await flushLocalDb()
+  const report = await tauriInvoke<SuiteImportReport>('import_test_suite', { path, onConflict })
+  await reloadFromDb()
+  return report
+}
+
+// Invalid rows are skipped and listed in the report; throws if the file can't be read at all
+export async function importTestCases(request: TestCaseImportRequest): Promise<TestCaseImportReport | null> {
+  if (typeof window === 'undefined' || !isTauriRuntime()) return null
+
+  await flushLocalDb()
+  const report = await tauriInvoke<TestCaseImportReport>('import_test_cases', { request })
+  if (report.imported > 0) {
+    await reloadFromDb()
+  }
+  return report
+}
+
+export async function importOpenAiEvals(request: OpenAiEvalsImportRequest): Promise<TestCaseImportReport | null> {
+  if (typeof window === 'undefined' || !isTauriRuntime()) return null
+
+  await flushLocalDb()
+  const report = await tauriInvoke<TestCaseImportReport>('import_openai_evals', { request })
+  if (report.imported > 0) {
+    await reloadFromDb()
+  }
+  return report
+}
+
+export async function importLmEvalTask(request: LmEvalImportRequest): Promise<TestCaseImportReport | null> {
+  if (typeof window === 'undefined' || !isTauriRuntime()) return null
+
+  await flushLocalDb()
+  const report = await tauriInvoke<TestCaseImportReport>('import_lm_eval_task', { request })
+  if (report.imported > 0) {
+    await reloadFromDb()
+  }
+  return report
+}
+
+export async function exportRuns(request: RunExportRequest): Promise<RunExportReport | null> {
+  if (typeof window === 'undefined' || !isTauriRuntime()) return null
+
+  await flushLocalDb()
+  return await tauriInvoke<RunExportReport>('export_runs', { request })
+}
+
+export async function generateReport(runId: string, path: string, format?: ReportFormat): Promise<void> {
+  if (typeof window === 'undefined' || !isTauriRuntime()) return
+
+  await flushLocalDb()
+  await tauriInvoke<void>('generate_report', { runId, format: format ?? null, path })
+}
+
+export async function listProviders(): Promise<ProviderListing[]> {
+  if (typeof window === 'undefined' || !isTauriRuntime()) return []
+
+  try {
+    return await tauriInvoke<ProviderListing[]>('list_providers')
+  } catch (error) {
+    console.error('Failed to list providers:', error)
+    return []
+  }
+}
+
+// Without an API key the stored one is kept
+export async function saveProvider(config: ProviderConfig): Promise<void> {
+  if (typeof window === 'undefined' || !isTauriRuntime()) return
+
+  await tauriInvoke<void>('save_provider', { config })
+}
+
+// Built-in providers (openrouter, anthropic, ollama, mock) fall back to their defaults
+export async function deleteProvider(id: string): Promise<boolean> {
+  if (typeof window === 'undefined' || !isTauriRuntime()) return false
+
+  return await tauriInvoke<boolean>('delete_provider', { id })
+}
+
+export async function getCacheStatus(): Promise<CacheStatus | null> {
+  if (typeof window === 'undefined' || !isTauriRuntime()) return null
+
+  try {
+    return await tauriInvoke<CacheStatus>('get_cache_status')
+  } catch (error) {
+    console.error('Failed to read cache status:', error)
+    return null
+  }
+}
+
+// Throws on invalid limits so the caller can show why
+export async function setCacheSettings(settings: CacheSettings): Promise<CacheStatus | null> {
+  if (typeof window === 'undefined' || !isTauriRuntime()) return null
+
+  return await tauriInvoke<CacheStatus>('set_cache_settings', { settings })
+}
+
+// Returns the number of cached responses removed
+export async function clearCache(): Promise<number> {
+  if (typeof window === 'undefined' || !isTauriRuntime()) return 0
+
+  return await tauriInvoke<number>('clear_cache')
+}
+
+export async function getRateLimits(): Promise<RateLimits | null> {
+  if (typeof window === 'undefined' || !isTauriRuntime()) return null
+
+  try {
+    return await tauriInvoke<RateLimits>('get_rate_limits')
+  } catch (error) {
+    console.error('Failed to read rate limits:', error)
+    return null
+  }
+}
+
+// Throws on invalid limits; resolves with the limits as saved
+export async function setRateLimits(limits: RateLimits): Promise<RateLimits | null> {
+  if (typeof window === 'undefined' || !isTauriRuntime()) return null
+
+  return await tauriInvoke<RateLimits>('set_rate_limits', { limits })
+}
+
+export async function listTrash(): Promise<TrashListing | null> {
+  if (typeof window === 'undefined' || !isTauriRuntime()) return null
+
+  try {
+    return await tauriInvoke<TrashListing>('list_trash')
+  } catch (error) {
+    console.error('Failed to list trash:', error)
+    return null
+  }
+}
+
+// Restored items are re-read from the database so the stores pick them up
+export async function restoreFromTrash(items: TrashRef[]): Promise<number> {
+  if (typeof window === 'undefined' || !isTauriRuntime() || items.length === 0) return 0
+
+  try {
+    await flushLocalDb()
+    const restored = await tauriInvoke<number>('restore', { items })
+    await reloadFromDb()
+    return restored
+  } catch (error) {
+    console.error('Failed to restore from trash:', error)
+    return 0
+  }
+}
+
+// Permanently deletes the given items, or everything in the trash when omitted
+export async function purgeTrash(items?: TrashRef[]): Promise<number> {
+  if (typeof window === 'undefined' || !isTauriRuntime()) return 0
+
+  try {
+    return await tauriInvoke<number>('purge_trash', { items: items ?? null })
+  } catch (error) {
+    console.error('Failed to purge trash:', error)
+    return 0
+  }
+}
+
+export async function setTrashRetentionDays(days: number): Promise<void> {
+  if (typeof window === 'undefined' || !isTauriRuntime()) return
+
+  try {
+    await tauriInvoke<void>('set_trash_retention_days', { days })
+  } catch (error) {
+    console.error('Failed to update trash retention:', error)
+  }
+}
+
+export async function createBackup(): Promise<BackupInfo | null> {
+  if (typeof window === 'undefined' || !isTauriRuntime()) return null
+
+  try {
+    return await tauriInvoke<BackupInfo | null>('create_backup')
+  } catch (error) {
+    console.error('Failed to create backup:', error)
+    return null
+  }
+}
+
+export async function listBackups(): Promise<BackupListing | null> {
+  if (typeof window === 'undefined' || !isTauriRuntime()) return null
+
+  try {
+    return await tauriInvoke<BackupListing>('list_backups')
+  } catch (error) {
+    console.error('Failed to list backups:', error)
+    return null
+  }
+}
+
+// Throws on failure so the caller can tell the user why the restore was refused.
+// Returns the safety copy taken beforehand.
+export async function restoreBackup(fileName: string): Promise<BackupInfo | null> {
+  if (typeof window === 'undefined' || !isTauriRuntime()) return null
+
+  if (writeTimer !== null) {
+    window.clearTimeout(writeTimer)
+    writeTimer = null
+  }
+  writePending = false
+
+  const safetyCopy = await tauriInvoke<BackupInfo | null>('restore_backup', { fileName })
+  await reloadFromDb()
+  return safetyCopy
+}
+
+export async function deleteBackup(fileName: string): Promise<void> {
+  if (typeof window === 'undefined' || !isTauriRuntime()) return
+
+  try {
+    await tauriInvoke<void>('delete_backup', { fileName })
+  } catch (error) {
+    console.error('Failed to delete backup:', error)
+  }
+}
+
+export async function setBackupSettings(settings: BackupSettings): Promise<void> {
+  if (typeof window === 'undefined' || !isTauriRuntime()) return
+
+  try {
+    await tauriInvoke<void>('set_backup_settings', { settings })
+  } catch (error) {
+    console.error('Failed to update backup settings:', error)
+  }
+}
+
+export async function checkDatabase(): Promise<DatabaseCheckReport | null> {
+  if (typeof window === 'undefined' || !isTauriRuntime()) return null
+
+  try {
+    return await tauriInvoke<DatabaseCheckReport>('check_database')
+  } catch (error) {
+    console.error('Failed to check database:', error)
+    return null
+  }
+}
+
+// Repairs rewrite rows underneath the stores, so reload them afterwards
+export async function repairDatabase(): Promise<DatabaseRepairReport | null> {
+  if (typeof window === 'undefined' || !isTauriRuntime()) return null
+
+  try {
+    await flushLocalDb()
+    const report = await tauriInvoke<DatabaseRepairReport>('repair_database')
+    await reloadFromDb()
+    return report
+  } catch (error) {
+    console.error('Failed to repair database:', error)
+    return null
+  }
+}
+
+export async function listQuarantine(): Promise<QuarantinedRow[]> {
+  if (typeof window === 'undefined' || !isTauriRuntime()) return []
+
+  try {
+    return await tauriInvoke<QuarantinedRow[]>('list_quarantine')
+  } catch (error) {
+    console.error('Failed to list quarantined rows:', error)
+    return []
+  }
+}
+
+// Restored rows change data the stores hold, so reload them afterwards
+export async function restoreQuarantined(ids: number[]): Promise<number> {
+  if (typeof window === 'undefined' || !isTauriRuntime()) return 0
+
+  try {
+    await flushLocalDb()
+    const restored = await tauriInvoke<number>('restore_quarantined', { ids })
+    await reloadFromDb()
+    return restored
+  } catch (error) {
+    console.error('Failed to restore quarantined rows:', error)
+    return 0
+  }
+}
+
+// Keeps store writes away from a run the backend executes and saves itself,
+// so a stale copy in the webview can't overwrite it
+export function markBackendRun(runId: string): void {
+  backendRunIds.add(runId)
+  dirtyIds.run.delete(runId)
+}
+
+// Lets the next throttled store write drop most suites or runs, e.g. after "Clear All Runs"
+export function allowNextMassDelete(): void {
+  allowMassDeleteOnNextWrite = true
+}
+
 export function buildSnapshot(): BenchmakerDb {
   const testSuiteState = useTestSuiteStore.getState()
   const runState = useRunStore.getState()
@@ -102,7 +646,77 @@ export function buildSnapshot(): BenchmakerDb {
   }
 }
 
-export function hydrateFromDb(snapshot: BenchmakerDb): void {
+function trackChanges<T extends { id: string }>(
+  kind: TrashKind,
+  items: T[],
+  previous: T[],
+  isClean?: (item: T, before: T | undefined) => boolean
+): void {
+  if (hydrating || items === previous) return
+
+  const before = new Map(previous.map((item) => [item.id, item]))
+  for (const item of items) {
+    const old = before.get(item.id)
+    if (old === item || isClean?.(item, old)) continue
+    dirtyIds[kind].add(item.id)
+  }
+}
+
+// The executor saves results as they finish, and fetching a run's results
+// changes nothing on disk, so neither makes the run dirty
+function onlyResultsChanged(run: RunResult, before: RunResult | undefined): boolean {
+  if (!before) return false
+  if (before.summary && !run.summary) return true
+  const keys = new Set([...Object.keys(run), ...Object.keys(before)] as (keyof RunResult)[])
+  return [...keys].every((key) => key === 'results' || run[key] === before[key])
+}
+
+function takeDirty<T extends { id: string }>(kind: TrashKind, items: T[]): T[] {
+  const dirty = items.filter((item) => dirtyIds[kind].has(item.id))
+  dirty.forEach((item) => dirtyIds[kind].delete(item.id))
+  return dirty
+}
+
+// What a write sends: only items changed since the last write, without runs
+// still waiting for their results, and only items the stores dropped are
+// listed as deleted
+function buildWriteSnapshot(): BenchmakerDb {
+  const snapshot = buildSnapshot()
+  return {
+    ...snapshot,
+    testSuites: takeDirty('testSuite', snapshot.testSuites),
+    runs: takeDirty('run', snapshot.runs.filter((run) => !run.summary)),
+    codeArenaRuns: takeDirty('codeArenaRun', snapshot.codeArenaRuns),
+    deleted: [
+      ...missingIds('testSuite', snapshot.testSuites),
+      ...missingIds('run', snapshot.runs),
+      ...missingIds('codeArenaRun', snapshot.codeArenaRuns),
+    ],
+  }
+}
+
+function missingIds(kind: TrashKind, items: { id: string }[]): TrashRef[] {
+  const present = new Set(items.map((item) => item.id))
+  return [...persistedIds[kind]].filter((id) => !present.has(id)).map((id) => ({ kind, id }))
+}
+
+function markPersisted(snapshot: BenchmakerDb): void {
+  for (const item of snapshot.deleted ?? []) {
+    persistedIds[item.kind].delete(item.id)
+  }
+  snapshot.testSuites.forEach((suite) => persistedIds.testSuite.add(suite.id))
+  snapshot.runs.forEach((run) => persistedIds.run.add(run.id))
+  snapshot.codeArenaRuns.forEach((run) => persistedIds.codeArenaRun.add(run.id))
+}
+
+// A failed write is retried with the next one
+function markUnsaved(snapshot: BenchmakerDb): void {
+  snapshot.testSuites.forEach((suite) => dirtyIds.testSuite.add(suite.id))
+  snapshot.runs.forEach((run) => dirtyIds.run.add(run.id))
+  snapshot.codeArenaRuns.forEach((run) => dirtyIds.codeArenaRun.add(run.id))
+}
+
+function setStores(snapshot: BenchmakerDb): void {
   useTestSuiteStore.setState({
     testSuites: snapshot.testSuites || [],
     activeTestSuiteId: snapshot.activeTestSuiteId ?? null,
@@ -117,30 +731,54 @@ export function hydrateFromDb(snapshot: BenchmakerDb): void {
   })
 }
 
+function hydrateFromDb(snapshot: BenchmakerDb): void {
+  persistedIds.testSuite = new Set(snapshot.testSuites.map((suite) => suite.id))
+  persistedIds.run = new Set(snapshot.runs.map((run) => run.id))
+  persistedIds.codeArenaRun = new Set(snapshot.codeArenaRuns.map((run) => run.id))
+  Object.values(dirtyIds).forEach((ids) => ids.clear())
+  hydrating = true
+  try {
+    setStores(snapshot)
+  } finally {
+    hydrating = false
+  }
+}
+
+// Replaces the stores with an imported snapshot and saves it right away;
+// whatever the stores held before and the import lacks is moved to the trash
+export async function importSnapshot(snapshot: BenchmakerDb): Promise<void> {
+  setStores(snapshot)
+  allowNextMassDelete()
+  await flushLocalDb()
+}
+
 function scheduleWrite(): void {
   if (!isTauriRuntime()) return
   if (writeTimer !== null) return
   writeTimer = window.setTimeout(() => {
     writeTimer = null
-    void queueWrite(buildSnapshot())
+    void queueWrite()
   }, WRITE_THROTTLE_MS)
 }
 
-async function queueWrite(snapshot: BenchmakerDb): Promise<void> {
+// The snapshot is built when the write starts, so changes made while another
+// write is in flight are picked up by the next one
+async function queueWrite(): Promise<void> {
   if (writeInFlight) {
-    pendingSnapshot = snapshot
+    writePending = true
     return
   }
 
   writeInFlight = true
+  const allowMassDelete = allowMassDeleteOnNextWrite
+  allowMassDeleteOnNextWrite = false
   try {
-    await writeLocalDb(snapshot)
+    await writeLocalDb(buildWriteSnapshot(), { allowMassDelete })
   } finally {
     writeInFlight = false
-    if (pendingSnapshot) {
-      const next = pendingSnapshot
-      pendingSnapshot = null
-      void queueWrite(next)
+    if (writePending) {
+      writePending = false
+      void queueWrite()
     }
   }
 }
@@ -1,5 +1,5 @@
 import { create } from 'zustand'
-import type { RunResult, TestCaseResult, ExecutionStatus, ScoringResult, TestCase, AggregateScore, MultiRunStats } from '@/types'
+import type { RunResult, TestCaseResult, ExecutionStatus, ScoringResult, TestCase, AggregateScore, MultiRunStats, QueueState } from '@/types'
 
 export interface ModelComparison {
   modelA: string
@@ -22,14 +22,18 @@ interface ErrorInfo {
 interface RunState {
   runs: RunResult[]
   currentRunId: string | null
+  // Live scheduler state of backend runs that are still going
+  queues: Record<string, QueueState>
 
   // Run Actions
   createRun: (run: Omit<RunResult, 'id'>) => RunResult
+  upsertRun: (run: RunResult) => void
   updateRunStatus: (runId: string, status: ExecutionStatus) => void
   completeRun: (runId: string, errorInfo?: ErrorInfo) => void
   deleteRun: (runId: string) => void
   clearAllRuns: () => void
   setCurrentRun: (runId: string | null) => void
+  setQueueState: (runId: string, queue: QueueState | null) => void
 
   // Result Actions
   addResult: (runId: string, result: TestCaseResult) => void
@@ -60,6 +64,7 @@ function generateId(): string {
 export const useRunStore = create<RunState>()((set, get) => ({
   runs: [],
   currentRunId: null,
+  queues: {},
 
   createRun: (runData) => {
     const newRun: RunResult = {
@@ -73,6 +78,14 @@ export const useRunStore = create<RunState>()((set, get) => ({
     return newRun
   },
 
+  upsertRun: (run) => {
+    set((state) => ({
+      runs: state.runs.some((existing) => existing.id === run.id)
+        ? state.runs.map((existing) => (existing.id === run.id ? run : existing))
+        : [run, ...state.runs],
+    }))
+  },
+
   updateRunStatus: (runId, status) => {
     set((state) => ({
       runs: state.runs.map((run) => (run.id === runId ? { ...run, status } : run)),
@@ -107,6 +120,18 @@ export const useRunStore = create<RunState>()((set, get) => ({
 
   setCurrentRun: (runId) => set({ currentRunId: runId }),
 
+  setQueueState: (runId, queue) => {
+    set((state) => {
+      const queues = { ...state.queues }
+      if (queue) {
+        queues[runId] = queue
+      } else {
+        delete queues[runId]
+      }
+      return { queues }
+    })
+  },
+
   addResult: (runId, result) => {
     set((state) => ({
       runs: state.runs.map((run) =>
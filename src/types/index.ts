@@ -23,6 +23,118 @@ export interface TestSuite {
   updatedAt: number
 }
 
+// Immutable suite history; a revision is added whenever prompts or cases change
+export interface SuiteRevisionSummary {
+  testSuiteId: string
+  revision: number
+  contentHash: string
+  name: string
+  testCaseCount: number
+  createdAt: number
+}
+
+export interface SuiteRevision {
+  testSuiteId: string
+  revision: number
+  contentHash: string
+  name: string
+  description?: string
+  systemPrompt: string
+  judgeSystemPrompt?: string
+  testCases: TestCase[]
+  createdAt: number
+}
+
+export interface FieldChange {
+  before?: string
+  after?: string
+}
+
+export interface SuiteRevisionDiff {
+  testSuiteId: string
+  fromRevision: number
+  toRevision: number
+  systemPrompt?: FieldChange
+  judgeSystemPrompt?: FieldChange
+  added: TestCase[]
+  removed: TestCase[]
+  changed: {
+    id: string
+    changedFields: string[]
+    before: TestCase
+    after: TestCase
+  }[]
+  reordered: boolean
+}
+
+// Portable single-suite bundle (JSON or YAML)
+export type BundleFormat = 'json' | 'yaml'
+export type CollisionStrategy = 'keep' | 'rename' | 'overwrite'
+
+export interface SuiteImportReport {
+  outcome: 'created' | 'kept' | 'renamed' | 'overwritten'
+  testSuiteId: string
+  testSuiteName: string
+  sourceId: string // Suite id as written in the bundle
+  revision?: number
+  hashVerified: boolean // False when the bundle was edited after export
+  changes?: SuiteRevisionDiff // Set when an existing suite was overwritten
+  renamedTestCases: { from: string; to: string }[]
+  warnings: string[]
+}
+
+// Bulk test case import from CSV/JSONL datasets
+export type DatasetFormat = 'csv' | 'jsonl' | 'json' // json: one array of row objects
+
+// Source column per TestCase field; JSONL columns may use dotted paths (input.question)
+export interface ColumnMapping {
+  prompt: string
+  expectedOutput?: string
+  scoringMethod?: string
+  weight?: string
+  category?: string
+  difficulty?: string
+  tags?: string
+}
+
+export type ImportTarget =
+  | { mode: 'append'; testSuiteId: string }
+  | { mode: 'create'; name: string; description?: string; systemPrompt?: string }
+
+export interface TestCaseImportRequest {
+  path: string
+  format?: DatasetFormat // Defaults to the file extension
+  mapping: ColumnMapping
+  target: ImportTarget
+  defaultScoringMethod?: ScoringMethod
+  extraTags?: string[]
+}
+
+export interface TestCaseImportReport {
+  testSuiteId?: string
+  testSuiteName?: string
+  createdSuite: boolean
+  imported: number
+  skipped: number
+  revision?: number
+  errors: { row: number; message: string }[]
+}
+
+// Standard benchmark importers; cases are tagged openai-evals:<name> / lm-eval:<task>
+export interface OpenAiEvalsImportRequest {
+  path: string // Samples JSONL
+  sourceName?: string // Defaults to the file name
+  target: ImportTarget // An empty name for a new suite uses the source name
+  scoringMethod?: ScoringMethod
+}
+
+export interface LmEvalImportRequest {
+  taskPath: string // Task YAML
+  dataPath: string // Local JSONL/CSV export of the split
+  target: ImportTarget
+  limit?: number
+}
+
 // Scoring Types
 export type ScoringMethod =
   | 'exact-match'
@@ -105,6 +217,8 @@ export type ExecutionStatus = 'idle' | 'running' | 'completed' | 'failed' | 'can
 export interface TestCaseResult {
   testCaseId: string
   modelId: string
+  provider?: string // Provider namespace that served modelId, e.g. 'openrouter' or 'ollama'
+  attempt?: number // Distinguishes repeated samples of the same test case/model
   response: string
   tokenCount?: number
   promptTokens?: number
@@ -115,12 +229,14 @@ export interface TestCaseResult {
   error?: string
   score?: ScoringResult
   streamedContent?: string
+  cached?: boolean // Served from the backend's response cache
 }
 
 export interface RunResult {
   id: string
   testSuiteId: string
   testSuiteName: string
+  testSuiteRevision?: number // Suite revision the run was scored against
   models: string[]
   parameters: ModelParameters
   results: TestCaseResult[]
@@ -131,6 +247,288 @@ export interface RunResult {
   // Error tracking for surfacing in UI
   errorCount?: number
   errorSummary?: string
+  // Set while only the history listing is loaded; `results` is empty until fetched
+  summary?: RunSummary
+}
+
+// Lightweight run listing (history views load full results lazily)
+export interface RunSummary {
+  id: string
+  testSuiteId: string
+  testSuiteName: string
+  testSuiteRevision?: number
+  models: string[]
+  parameters: ModelParameters
+  status: ExecutionStatus
+  startedAt: number
+  completedAt?: number
+  judgeModel?: string
+  errorCount?: number
+  errorSummary?: string
+  resultCount: number
+  completedCount: number
+  failedCount: number
+  meanScore?: number // Unweighted mean of scored results
+  totalCost?: number
+}
+
+export interface RunListQuery {
+  limit?: number
+  offset?: number
+  sortBy?: 'startedAt' | 'completedAt' | 'suiteName' | 'status' | 'meanScore' | 'totalCost'
+  sortDir?: 'asc' | 'desc'
+  testSuiteId?: string
+  modelId?: string
+  status?: ExecutionStatus
+  startedAfter?: number
+  startedBefore?: number
+}
+
+// Flat result export; column names are snake_case in every format
+export type RunExportFormat = 'csv' | 'jsonl' | 'parquet'
+
+export interface RunExportRequest {
+  path: string
+  format?: RunExportFormat // Defaults to the file extension
+  runIds?: string[] // Combined with filter when both are set
+  filter?: RunListQuery // Paging and sorting are ignored
+}
+
+export interface RunExportReport {
+  path: string
+  format: RunExportFormat
+  runs: number
+  rows: number
+}
+
+// Shareable run report; the format defaults to the file extension
+export type ReportFormat = 'html' | 'markdown'
+
+// Run executed by the backend (start_run); progress arrives as events
+export interface BackendRunRequest {
+  testSuiteId: string
+  models: string[]
+  judgeModel?: string
+  parameters: ModelParameters
+  concurrency?: number // Defaults to 5
+  bypassCache?: boolean // Ignore cached responses; fresh ones still refresh the cache
+}
+
+// Record a backend run's model calls, or replay them (re-scoring without new responses)
+export type CassetteMode = 'record' | 'replay'
+
+// `run-progress`: response streamed so far for a running case
+export interface RunProgressEvent {
+  runId: string
+  testCaseId: string
+  modelId: string
+  content: string
+}
+
+// `run-result`: a finished (or cancelled) result, already saved
+export interface RunResultEvent {
+  runId: string
+  result: TestCaseResult
+}
+
+// `run-finished`: the saved run, or the error that stopped it
+export interface RunFinishedEvent {
+  runId: string
+  run?: RunResult
+  error?: string
+}
+
+// `run-queue`: live scheduler state, sent when it changes
+export interface RunQueueEvent {
+  runId: string
+  queue: QueueState
+}
+
+export interface QueueState {
+  providers: ProviderQueueState[]
+  models: ModelQueueState[]
+}
+
+export interface ProviderQueueState {
+  provider: string
+  concurrency: number // Adaptive limit; drops while the provider returns errors
+  inFlight: number
+}
+
+export interface ModelQueueState {
+  modelId: string
+  queued: number // Cases not started yet
+  running: number
+  waiting: number // Calls held back by a rate limit or a full provider
+  retries: number
+  pausedUntil?: number | null // Epoch ms when a rate-limit pause of the model or its provider ends
+}
+
+// Per-minute limits the backend scheduler keeps to; a missing value is unlimited
+export interface RateLimit {
+  requestsPerMinute?: number | null
+  tokensPerMinute?: number | null
+}
+
+export interface RateLimits {
+  providers: Record<string, RateLimit> // By provider id, shared by its models
+  models: Record<string, RateLimit> // By model id as passed to a run
+}
+
+// Model providers; backend runs address models as `<provider id>:<model>`,
+// and ids without a known prefix go to OpenRouter
+export type ProviderKind = 'openrouter' | 'openai-compatible' | 'ollama' | 'anthropic' | 'mock'
+
+export interface ProviderConfig {
+  id: string
+  kind: ProviderKind
+  baseUrl?: string | null // Required for openai-compatible
+  apiKey?: string | null // Left empty on save to keep the stored key
+  fixture?: string | null // Mock only: JSON or YAML file of scripted responses
+}
+
+// Entry of list_providers; the stored API key is never sent to the webview
+export interface ProviderListing extends ProviderConfig {
+  apiKeySet: boolean
+}
+
+// Entry of list_provider_models; ids are namespaced (`<provider id>:<model>`)
+export interface ProviderModel {
+  id: string
+  name: string
+  description?: string | null
+  contextLength?: number | null
+  pricing?: { prompt: string; completion: string } | null // USD per token
+}
+
+export interface RunListPage {
+  runs: RunSummary[]
+  total: number
+  limit: number
+  offset: number
+}
+
+// Full-text search
+export type SearchHitKind = 'testCase' | 'result'
+
+export interface SearchQuery {
+  text: string
+  limit?: number
+  kinds?: SearchHitKind[]
+  testSuiteId?: string
+  modelId?: string
+}
+
+export interface SearchHit {
+  kind: SearchHitKind
+  rank: number // bm25 within its group, lower is better
+  snippet: string // Matched terms wrapped in « »
+  testSuiteId?: string
+  testSuiteName?: string
+  testCaseId: string
+  runId?: string
+  runStartedAt?: number
+  modelId?: string
+}
+
+// Each index ranks its own hits; bm25 scores of different indexes don't compare
+export interface SearchResults {
+  testCases: SearchHit[]
+  results: SearchHit[]
+}
+
+// Trash (soft-deleted items awaiting restore or purge)
+export type TrashKind = 'testSuite' | 'run' | 'codeArenaRun'
+
+export interface TrashRef {
+  kind: TrashKind
+  id: string
+}
+
+export interface TrashItem extends TrashRef {
+  label: string
+  deletedAt: number
+  purgeAt?: number // Unset when retention is disabled
+}
+
+export interface TrashListing {
+  items: TrashItem[]
+  retentionDays: number
+}
+
+// Database backups (files live in a `backups` folder next to the database)
+export type BackupKind = 'manual' | 'startup' | 'scheduled' | 'preMigration' | 'preRestore' | 'preRepair'
+
+export interface BackupInfo {
+  fileName: string
+  path: string
+  kind: BackupKind
+  sizeBytes: number
+  createdAt: number
+}
+
+export interface BackupSettings {
+  retentionCount: number // Startup and scheduled backups to keep; 0 keeps all
+  intervalHours: number // Hours between scheduled backups; 0 disables
+}
+
+export interface BackupListing {
+  backups: BackupInfo[]
+  settings: BackupSettings
+}
+
+// Opt-in cache of backend model responses
+export interface CacheSettings {
+  enabled: boolean
+  ttlHours: number // Hours a response stays valid; 0 never expires
+  maxSizeMb: number // Size limit of the stored responses; 0 is unlimited
+}
+
+export interface CacheStatus {
+  settings: CacheSettings
+  entries: number
+  sizeBytes: number
+}
+
+// Database health check and repair
+export type DatabaseIssueKind =
+  | 'integrity'
+  | 'missingParent'
+  | 'orphanedResult'
+  | 'invalidJson'
+  | 'danglingReference'
+
+export interface DatabaseIssue {
+  kind: DatabaseIssueKind
+  table: string
+  rowId?: number
+  column?: string
+  detail: string
+}
+
+export interface DatabaseCheckReport {
+  checkedAt: number
+  issues: DatabaseIssue[]
+}
+
+export interface DatabaseRepairReport {
+  backup?: BackupInfo // Taken before anything was changed
+  actions: {
+    issue: DatabaseIssue
+    outcome: 'quarantined' | 'reset' | 'cleared' | 'rebuilt' | 'kept' | 'unrepaired'
+  }[]
+  after: DatabaseCheckReport
+}
+
+// Row or value set aside by a repair; restoring puts it back
+export interface QuarantinedRow {
+  id: number
+  sourceTable: string
+  sourceRowid?: number
+  columnName?: string // Set when only this column was reset
+  reason: string
+  payload: string
+  quarantinedAt: number
 }
 
 // Code Arena Types
@@ -180,6 +578,7 @@ export interface BenchmakerDb {
   activeTestSuiteId: string | null
   currentRunId: string | null
   currentCodeArenaRunId: string | null
+  deleted?: TrashRef[] // Items deleted since the last write; nothing else is removed
 }
 
 // OpenRouter API Types